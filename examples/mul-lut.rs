@@ -0,0 +1,36 @@
+#![allow(missing_docs)]
+
+//! Compares arithmetic and table-lookup 8-bit multiplies.
+//!
+//! Run with `cargo run --release --example mul-lut --features mul-lut,std`. On desktop CPUs the
+//! two are close, and the table competes with the rest of the program for cache; it pays off
+//! where multiplies and divisions are slow, such as on small microcontrollers.
+
+extern crate std;
+use alpha_blend::math;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ROUNDS: u32 = 200;
+
+fn main() {
+    let round = time(math::mul_div_255_round);
+    let lut = time(math::mul_div_255_lut);
+    println!("mul_div_255_round: {round:.2} ns/op");
+    println!("mul_div_255_lut:   {lut:.2} ns/op");
+}
+
+/// Returns the mean time, in nanoseconds, of `mul` over every pair of 8-bit values.
+fn time(mul: fn(u8, u8) -> u8) -> f64 {
+    let start = Instant::now();
+    let mut acc = 0_u8;
+    for _ in 0..ROUNDS {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                acc ^= mul(black_box(a), black_box(b));
+            }
+        }
+    }
+    black_box(acc);
+    start.elapsed().as_secs_f64() * 1e9 / f64::from(ROUNDS * 256 * 256)
+}
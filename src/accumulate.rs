@@ -0,0 +1,187 @@
+//! Weighted accumulation of colors across many inputs.
+//!
+//! Temporal anti-aliasing, multi-sample resolve and exposure stacking combine `N` inputs into
+//! one color at once, which no pairwise Porter-Duff operator expresses. An [`Accumulator`] sums
+//! weighted, premultiplied contributions in any order, and resolves them either to their
+//! weighted average ([`resolve`](Accumulator::resolve)) or to their total light
+//! ([`resolve_sum`](Accumulator::resolve_sum)).
+//!
+//! Summing premultiplied colors keeps transparent inputs from tinting the result: averaging
+//! opaque red with a transparent pixel gives half-transparent red, not dark red.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{accumulate::Accumulator, rgba::F32x4Rgba};
+//!
+//! // Resolve four samples of a pixel, one of them missing.
+//! let mut acc = Accumulator::CLEAR;
+//! for sample in [F32x4Rgba::RED, F32x4Rgba::RED, F32x4Rgba::BLUE, F32x4Rgba::TRANSPARENT] {
+//!     acc.add(sample, 1.0);
+//! }
+//! assert_eq!(acc.resolve(), F32x4Rgba::new(2.0 / 3.0, 0.0, 1.0 / 3.0, 0.75));
+//! ```
+
+use crate::{rgba::F32x4Rgba, vec4::F32x4};
+
+/// A running weighted sum of premultiplied colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accumulator {
+    /// Sum of premultiplied colors, each multiplied by its weight.
+    pub sum: F32x4Rgba,
+
+    /// Sum of the weights.
+    pub weight: f32,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self::CLEAR
+    }
+}
+
+impl Accumulator {
+    /// An accumulator with no contributions.
+    pub const CLEAR: Self = Self {
+        sum: F32x4Rgba::TRANSPARENT,
+        weight: 0.0,
+    };
+
+    /// Adds a straight-alpha `color` with `weight`, which should not be negative.
+    pub fn add(&mut self, color: F32x4Rgba, weight: f32) {
+        let premultiplied = F32x4::from(color.premultiply());
+        self.sum = (F32x4::from(self.sum) + premultiplied * weight).into_rgba();
+        self.weight += weight;
+    }
+
+    /// Adds the contributions of `other`, as if they had been added to this accumulator.
+    ///
+    /// Accumulators filled in parallel, such as per tile or per thread, can be merged in any
+    /// order.
+    pub fn merge(&mut self, other: Self) {
+        self.sum = (F32x4::from(self.sum) + F32x4::from(other.sum)).into_rgba();
+        self.weight += other.weight;
+    }
+
+    /// Returns the weighted average of the contributions, in straight alpha.
+    ///
+    /// If the total weight is zero or less, returns [`F32x4Rgba::TRANSPARENT`].
+    #[must_use]
+    pub fn resolve(self) -> F32x4Rgba {
+        if self.weight <= 0.0 {
+            return F32x4Rgba::TRANSPARENT;
+        }
+        (F32x4::from(self.sum) * (1.0 / self.weight))
+            .into_rgba()
+            .unpremultiply()
+    }
+
+    /// Returns the weighted sum of the contributions, in straight alpha, as when stacking
+    /// exposures.
+    ///
+    /// Alpha is clamped to `[0.0, 1.0]`, but colors are not, so the result can be brighter than
+    /// white; clamp or tone-map it before converting it to a narrower format.
+    #[must_use]
+    pub fn resolve_sum(self) -> F32x4Rgba {
+        let a = self.sum.a.min(1.0);
+        if a <= 0.0 {
+            return F32x4Rgba::TRANSPARENT;
+        }
+        let F32x4Rgba { r, g, b, .. } = self.sum;
+        F32x4Rgba::new(r / a, g / a, b / a, a)
+    }
+}
+
+/// Adds straight-alpha `colors`, one per accumulator of `targets`, with the same `weight`.
+///
+/// ## Panics
+///
+/// If `targets` and `colors` do not have the same length.
+pub fn accumulate_slice(targets: &mut [Accumulator], colors: &[F32x4Rgba], weight: f32) {
+    assert_eq!(
+        targets.len(),
+        colors.len(),
+        "targets and colors must have the same length"
+    );
+    for (target, &color) in targets.iter_mut().zip(colors) {
+        target.add(color, weight);
+    }
+}
+
+/// Resolves each accumulator of `targets` to its weighted average in `dst`.
+///
+/// ## Panics
+///
+/// If `targets` and `dst` do not have the same length.
+pub fn resolve_slice(targets: &[Accumulator], dst: &mut [F32x4Rgba]) {
+    assert_eq!(
+        targets.len(),
+        dst.len(),
+        "targets and dst must have the same length"
+    );
+    for (target, d) in targets.iter().zip(dst) {
+        *d = target.resolve();
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn average_of_opaque_frames() {
+        let mut acc = Accumulator::CLEAR;
+        acc.add(F32x4Rgba::WHITE, 1.0);
+        acc.add(F32x4Rgba::BLACK, 3.0);
+        assert_eq!(acc.weight, 4.0);
+        assert_eq!(acc.resolve(), F32x4Rgba::new(0.25, 0.25, 0.25, 1.0));
+    }
+
+    #[test]
+    fn transparent_contributions_do_not_tint() {
+        let mut acc = Accumulator::default();
+        acc.add(F32x4Rgba::RED, 1.0);
+        acc.add(F32x4Rgba::BLUE.with_alpha(0.0), 1.0);
+        assert_eq!(acc.resolve(), F32x4Rgba::RED.with_alpha(0.5));
+    }
+
+    #[test]
+    fn empty_resolves_to_transparent() {
+        assert_eq!(Accumulator::CLEAR.resolve(), F32x4Rgba::TRANSPARENT);
+        assert_eq!(Accumulator::CLEAR.resolve_sum(), F32x4Rgba::TRANSPARENT);
+    }
+
+    #[test]
+    fn sum_adds_light() {
+        let gray = F32x4Rgba::new(0.5, 0.25, 0.75, 1.0);
+        let mut acc = Accumulator::CLEAR;
+        acc.add(gray, 1.0);
+        acc.add(gray, 1.0);
+        assert_eq!(acc.resolve_sum(), F32x4Rgba::new(1.0, 0.5, 1.5, 1.0));
+        assert_eq!(acc.resolve(), gray);
+    }
+
+    #[test]
+    fn merge_matches_sequential() {
+        let colors = [0.0, 0.25, 0.5, 1.0].map(|t| F32x4Rgba::new(t, 1.0 - t, 0.5, t));
+        let mut all = Accumulator::CLEAR;
+        let (mut left, mut right) = (Accumulator::CLEAR, Accumulator::CLEAR);
+        for (i, &c) in colors.iter().enumerate() {
+            all.add(c, 0.5);
+            if i % 2 == 0 { &mut left } else { &mut right }.add(c, 0.5);
+        }
+        right.merge(left);
+        assert_eq!(right.resolve(), all.resolve());
+    }
+
+    #[test]
+    fn slices() {
+        let mut targets = [Accumulator::CLEAR; 2];
+        accumulate_slice(&mut targets, &[F32x4Rgba::WHITE, F32x4Rgba::RED], 1.0);
+        accumulate_slice(&mut targets, &[F32x4Rgba::BLACK, F32x4Rgba::RED], 1.0);
+        let mut dst = [F32x4Rgba::TRANSPARENT; 2];
+        resolve_slice(&targets, &mut dst);
+        assert_eq!(dst, [F32x4Rgba::new(0.5, 0.5, 0.5, 1.0), F32x4Rgba::RED]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Wide accumulation for additive (`Plus`) stacking.
+//!
+//! Compositing many layers with [`BlendMode::Plus`](crate::BlendMode::Plus) onto a narrow buffer
+//! clips at every step: once a channel saturates, later layers are lost, and with `u8` channels
+//! the rounding error grows with every layer. An [`AdditiveSurface`] instead sums layers into
+//! wider per-channel accumulators ([`u32`] for integer channels) and clamps only once, in
+//! [`resolve`](AdditiveSurface::resolve). This suits light maps, particle glow and other
+//! additive effects.
+//!
+//! _This module requires the `alloc` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{additive::AdditiveSurface, rgba::U8x4Rgba};
+//!
+//! let glow = [U8x4Rgba::new(200, 100, 0, 128)];
+//! let mut acc = AdditiveSurface::<u8>::new(1, 1);
+//! acc.add(&glow);
+//! acc.add(&glow);
+//!
+//! // The accumulators keep the full sum; resolving saturates.
+//! assert_eq!(acc.sums()[0], [400, 200, 0, 256]);
+//! let mut out = [U8x4Rgba::TRANSPARENT];
+//! acc.resolve(&mut out);
+//! assert_eq!(out, [U8x4Rgba::new(255, 200, 0, 255)]);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{math, rgba::Rgba};
+
+/// A channel type that can be summed into a wider accumulator.
+pub trait AdditiveChannel: Copy {
+    /// Accumulator type, wide enough to sum many channel values without overflowing.
+    type Sum: Copy + Default;
+
+    /// Adds `value` to `sum`, saturating at the accumulator's maximum.
+    #[must_use]
+    fn accumulate(sum: Self::Sum, value: Self) -> Self::Sum;
+
+    /// Converts a sum back to a channel value, clamping to the channel's range.
+    #[must_use]
+    fn resolve(sum: Self::Sum) -> Self;
+}
+
+impl AdditiveChannel for u8 {
+    type Sum = u32;
+
+    fn accumulate(sum: u32, value: Self) -> u32 {
+        sum.saturating_add(u32::from(value))
+    }
+
+    fn resolve(sum: u32) -> Self {
+        Self::try_from(sum).unwrap_or(Self::MAX)
+    }
+}
+
+impl AdditiveChannel for u16 {
+    type Sum = u32;
+
+    fn accumulate(sum: u32, value: Self) -> u32 {
+        sum.saturating_add(u32::from(value))
+    }
+
+    fn resolve(sum: u32) -> Self {
+        Self::try_from(sum).unwrap_or(Self::MAX)
+    }
+}
+
+impl AdditiveChannel for f32 {
+    type Sum = Self;
+
+    fn accumulate(sum: Self, value: Self) -> Self {
+        sum + value
+    }
+
+    fn resolve(sum: Self) -> Self {
+        math::clamp01(sum)
+    }
+}
+
+/// A buffer of wide per-channel accumulators for additive stacking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdditiveSurface<C: AdditiveChannel> {
+    width: usize,
+    height: usize,
+    sums: Vec<[C::Sum; 4]>,
+}
+
+impl<C: AdditiveChannel> AdditiveSurface<C> {
+    /// Creates a new `AdditiveSurface` with all accumulators at zero.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            sums: vec![[C::Sum::default(); 4]; width * height],
+        }
+    }
+
+    /// Returns the width, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the per-pixel `[r, g, b, a]` sums, row-major.
+    #[must_use]
+    pub fn sums(&self) -> &[[C::Sum; 4]] {
+        &self.sums
+    }
+
+    /// Resets all accumulators to zero.
+    pub fn clear(&mut self) {
+        self.sums.fill([C::Sum::default(); 4]);
+    }
+
+    /// Adds every channel of `layer` to the accumulators, as [`BlendMode::Plus`] does.
+    ///
+    /// [`BlendMode::Plus`]: crate::BlendMode::Plus
+    ///
+    /// ## Panics
+    ///
+    /// If `layer` does not have `width * height` pixels.
+    pub fn add(&mut self, layer: &[Rgba<C>]) {
+        assert_eq!(
+            layer.len(),
+            self.sums.len(),
+            "layer must have width * height pixels"
+        );
+        for (sum, p) in self.sums.iter_mut().zip(layer) {
+            let [r, g, b, a] = *sum;
+            *sum = [
+                C::accumulate(r, p.r),
+                C::accumulate(g, p.g),
+                C::accumulate(b, p.b),
+                C::accumulate(a, p.a),
+            ];
+        }
+    }
+
+    /// Writes the clamped sums to `out`.
+    ///
+    /// ## Panics
+    ///
+    /// If `out` does not have `width * height` pixels.
+    pub fn resolve(&self, out: &mut [Rgba<C>]) {
+        assert_eq!(
+            out.len(),
+            self.sums.len(),
+            "out must have width * height pixels"
+        );
+        for (o, &[r, g, b, a]) in out.iter_mut().zip(&self.sums) {
+            *o = Rgba::new(C::resolve(r), C::resolve(g), C::resolve(b), C::resolve(a));
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::{
+        BlendMode, RgbaBlend,
+        rgba::{F32x4Rgba, U8x4Rgba},
+    };
+
+    #[test]
+    fn u8_sums_do_not_clip_until_resolve() {
+        let mut acc = AdditiveSurface::<u8>::new(2, 1);
+        for _ in 0..3 {
+            acc.add(&[U8x4Rgba::new(100, 0, 0, 0), U8x4Rgba::new(1, 2, 3, 4)]);
+        }
+        assert_eq!(acc.sums(), &[[300, 0, 0, 0], [3, 6, 9, 12]]);
+        let mut out = [U8x4Rgba::TRANSPARENT; 2];
+        acc.resolve(&mut out);
+        assert_eq!(
+            out,
+            [U8x4Rgba::new(255, 0, 0, 0), U8x4Rgba::new(3, 6, 9, 12)]
+        );
+    }
+
+    #[test]
+    fn f32_matches_plus_then_clamp() {
+        let layers = [
+            F32x4Rgba::new(0.75, 0.25, 0.0, 0.5),
+            F32x4Rgba::new(0.5, 0.25, 0.125, 0.25),
+        ];
+        let mut acc = AdditiveSurface::<f32>::new(1, 1);
+        let mut expected = F32x4Rgba::TRANSPARENT;
+        for layer in layers {
+            acc.add(&[layer]);
+            expected = BlendMode::Plus.apply(layer, expected);
+        }
+        let mut out = [F32x4Rgba::TRANSPARENT];
+        acc.resolve(&mut out);
+        assert_eq!(out, [expected.clamp()]);
+    }
+
+    #[test]
+    fn u16_saturates_at_max() {
+        let mut acc = AdditiveSurface::<u16>::new(1, 1);
+        acc.add(&[Rgba::new(u16::MAX, 1, 0, 0)]);
+        acc.add(&[Rgba::new(1, 1, 0, 0)]);
+        let mut out = [Rgba::new(0, 0, 0, 0)];
+        acc.resolve(&mut out);
+        assert_eq!(out, [Rgba::new(u16::MAX, 2, 0, 0)]);
+    }
+
+    #[test]
+    fn clear_resets_sums() {
+        let mut acc = AdditiveSurface::<u8>::new(1, 1);
+        acc.add(&[U8x4Rgba::WHITE]);
+        acc.clear();
+        assert_eq!(acc.sums(), &[[0; 4]]);
+        assert_eq!((acc.width(), acc.height()), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "width * height pixels")]
+    fn add_panics_on_wrong_size() {
+        AdditiveSurface::<u8>::new(2, 2).add(&[U8x4Rgba::WHITE]);
+    }
+}
@@ -0,0 +1,144 @@
+//! Pre-blend exposure and white-point adjustment.
+//!
+//! An [`Exposure`] scales the color channels of a layer by an exposure multiplier and divides
+//! them by a white point, so that layers captured or rendered at different brightnesses can be
+//! balanced before compositing. Both are plain multiplications, so they are only meaningful on
+//! **linear-light** values; decode sRGB-encoded layers first.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{adjust::Exposure, rgba::F32x4Rgba};
+//!
+//! // One stop brighter, with a warm white point mapped to neutral.
+//! let exposure = Exposure::from_stops(1.0).with_white_point([1.0, 0.8, 0.5]);
+//!
+//! let adjusted = exposure.apply(F32x4Rgba::new(0.25, 0.2, 0.125, 0.5));
+//! assert_eq!(adjusted, F32x4Rgba::new(0.5, 0.5, 0.5, 0.5));
+//! ```
+
+use crate::{math, rgba::F32x4Rgba};
+
+/// An exposure multiplier and white point applied to the color channels of a layer.
+///
+/// Alpha is left unchanged. Because the adjustment is a per-channel scale, it gives the same
+/// result on straight and premultiplied colors. Results may exceed `1.0`; see
+/// [`F32x4Rgba::clamp`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    /// Linear multiplier applied to every color channel.
+    pub multiplier: f32,
+
+    /// Color mapped to white; each color channel is divided by the matching component.
+    pub white_point: [f32; 3],
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Exposure {
+    /// Leaves colors unchanged.
+    pub const IDENTITY: Self = Self::new(1.0);
+
+    /// Creates a new `Exposure` with the given linear `multiplier` and a neutral white point.
+    #[must_use]
+    pub const fn new(multiplier: f32) -> Self {
+        Self {
+            multiplier,
+            white_point: [1.0; 3],
+        }
+    }
+
+    /// Creates a new `Exposure` adjusting by `stops` (the multiplier is `2^stops`).
+    #[must_use]
+    pub fn from_stops(stops: f32) -> Self {
+        Self::new(math::exp2(stops))
+    }
+
+    /// Returns a copy of this adjustment mapping `white_point` to white.
+    #[must_use]
+    pub const fn with_white_point(self, white_point: [f32; 3]) -> Self {
+        Self {
+            multiplier: self.multiplier,
+            white_point,
+        }
+    }
+
+    /// Returns the combined per-channel scale factors.
+    #[must_use]
+    pub fn scale(&self) -> [f32; 3] {
+        self.white_point.map(|w| self.multiplier / w)
+    }
+
+    /// Adjusts the color channels of `color`, keeping its alpha.
+    #[must_use]
+    pub fn apply(&self, color: F32x4Rgba) -> F32x4Rgba {
+        let [r, g, b] = self.scale();
+        F32x4Rgba::new(color.r * r, color.g * g, color.b * b, color.a)
+    }
+
+    /// Adjusts each pixel of `layer` in place.
+    pub fn apply_slice(&self, layer: &mut [F32x4Rgba]) {
+        let [r, g, b] = self.scale();
+        for p in layer {
+            *p = F32x4Rgba::new(p.r * r, p.g * g, p.b * b, p.a);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    const COLOR: F32x4Rgba = F32x4Rgba::new(0.1, 0.2, 0.4, 0.5);
+
+    #[test]
+    fn identity_keeps_colors() {
+        assert_eq!(Exposure::default().apply(COLOR), COLOR);
+        assert_eq!(Exposure::from_stops(0.0), Exposure::IDENTITY);
+    }
+
+    #[test]
+    fn stops_double_per_stop() {
+        assert_eq!(Exposure::from_stops(2.0).multiplier, 4.0);
+        assert_eq!(
+            Exposure::from_stops(-1.0).apply(COLOR),
+            F32x4Rgba::new(0.05, 0.1, 0.2, 0.5)
+        );
+    }
+
+    #[test]
+    fn white_point_maps_to_white() {
+        let white = [0.5, 0.25, 2.0];
+        let exposure = Exposure::new(1.0).with_white_point(white);
+        let [r, g, b] = white;
+        assert_eq!(
+            exposure.apply(F32x4Rgba::new(r, g, b, 0.25)),
+            F32x4Rgba::new(1.0, 1.0, 1.0, 0.25)
+        );
+    }
+
+    #[test]
+    fn same_on_premultiplied() {
+        let exposure = Exposure::new(1.5).with_white_point([0.5, 1.0, 2.0]);
+        assert_eq!(
+            exposure.apply(COLOR.premultiply()),
+            exposure.apply(COLOR).premultiply()
+        );
+    }
+
+    #[test]
+    fn slice_matches_pixels() {
+        let exposure = Exposure::new(3.0).with_white_point([1.0, 2.0, 4.0]);
+        let mut layer = [COLOR, F32x4Rgba::WHITE];
+        exposure.apply_slice(&mut layer);
+        assert_eq!(
+            layer,
+            [exposure.apply(COLOR), exposure.apply(F32x4Rgba::WHITE)]
+        );
+    }
+}
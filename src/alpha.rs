@@ -0,0 +1,209 @@
+//! Type-level separation of straight and premultiplied colors.
+//!
+//! [`RgbaBlend`] takes plain [`Rgba`] values and assumes straight alpha, so nothing stops a
+//! premultiplied buffer from being passed in by mistake. [`StraightRgba`] and
+//! [`PremulRgba`] tag a color with its alpha representation, and [`AlphaBlend`] is generic over
+//! that tag: every [`RgbaBlend`] blends straight colors, every [`PremulBlend`] blends
+//! premultiplied ones, and mixing them up is a compile error.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     alpha::{AlphaBlend, StraightRgba},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let src = StraightRgba::new(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5));
+//! let dst = StraightRgba::new(F32x4Rgba::new(0.0, 0.0, 1.0, 1.0));
+//!
+//! let straight = BlendMode::Multiply.blend_pixel(src, dst);
+//! let premul = BlendMode::SourceOver.blend_pixel(src.premultiply(), dst.premultiply());
+//! assert_eq!(straight.into_rgba(), F32x4Rgba::new(0.0, 0.0, 0.5, 1.0));
+//! assert_eq!(premul.into_rgba(), F32x4Rgba::new(0.5, 0.0, 0.5, 1.0));
+//! ```
+//!
+//! Blending a premultiplied color with a straight one does not compile:
+//!
+//! ```rust,compile_fail
+//! use alpha_blend::{BlendMode, alpha::{AlphaBlend, StraightRgba}, rgba::F32x4Rgba};
+//!
+//! let src = StraightRgba::new(F32x4Rgba::WHITE);
+//! let _ = BlendMode::SourceOver.blend_pixel(src.premultiply(), src);
+//! ```
+//!
+//! [`Rgba`]: crate::rgba::Rgba
+
+use crate::{
+    RgbaBlend,
+    premul::{F32x4PremulRgba, PremulBlend, PremulRgba, U8x4PremulRgba},
+    rgba::{F32x4Rgba, Rgba, U8x4Rgba},
+};
+
+/// A color with straight (un-premultiplied) alpha.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(transparent)]
+pub struct StraightRgba<C: Copy>(Rgba<C>);
+
+impl<C: Copy> StraightRgba<C> {
+    /// Wraps a color with straight alpha.
+    #[must_use]
+    pub const fn new(color: Rgba<C>) -> Self {
+        Self(color)
+    }
+
+    /// Returns the straight channels.
+    #[must_use]
+    pub const fn into_rgba(self) -> Rgba<C> {
+        self.0
+    }
+}
+
+impl StraightRgba<f32> {
+    /// Converts to premultiplied alpha.
+    #[must_use]
+    pub fn premultiply(self) -> F32x4PremulRgba {
+        F32x4PremulRgba::from_straight(self.0)
+    }
+}
+
+impl StraightRgba<u8> {
+    /// Converts to premultiplied alpha.
+    #[must_use]
+    pub const fn premultiply(self) -> U8x4PremulRgba {
+        U8x4PremulRgba::from_straight(self.0)
+    }
+}
+
+impl From<StraightRgba<f32>> for F32x4PremulRgba {
+    fn from(color: StraightRgba<f32>) -> Self {
+        color.premultiply()
+    }
+}
+
+impl From<F32x4PremulRgba> for StraightRgba<f32> {
+    fn from(color: F32x4PremulRgba) -> Self {
+        Self(color.to_straight())
+    }
+}
+
+impl From<StraightRgba<u8>> for U8x4PremulRgba {
+    fn from(color: StraightRgba<u8>) -> Self {
+        color.premultiply()
+    }
+}
+
+impl From<U8x4PremulRgba> for StraightRgba<u8> {
+    fn from(color: U8x4PremulRgba) -> Self {
+        Self(color.to_straight())
+    }
+}
+
+impl From<F32x4Rgba> for StraightRgba<f32> {
+    fn from(color: F32x4Rgba) -> Self {
+        Self(color)
+    }
+}
+
+impl From<U8x4Rgba> for StraightRgba<u8> {
+    fn from(color: U8x4Rgba) -> Self {
+        Self(color)
+    }
+}
+
+/// Blends colors of one alpha representation, `P`.
+///
+/// Implemented for [`StraightRgba`] by every [`RgbaBlend`], and for [`PremulRgba`] by every
+/// [`PremulBlend`].
+pub trait AlphaBlend<P: Copy> {
+    /// Blends two colors together.
+    fn blend_pixel(&self, src: P, dst: P) -> P;
+
+    /// Blend `src` over `dst` in place, pixel by pixel.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    fn blend_pixels(&self, src: &[P], dst: &mut [P]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        for (s, d) in src.iter().zip(dst.iter_mut()) {
+            *d = self.blend_pixel(*s, *d);
+        }
+    }
+}
+
+impl<B: RgbaBlend + ?Sized> AlphaBlend<StraightRgba<B::Channel>> for B {
+    fn blend_pixel(
+        &self,
+        src: StraightRgba<B::Channel>,
+        dst: StraightRgba<B::Channel>,
+    ) -> StraightRgba<B::Channel> {
+        StraightRgba(self.apply(src.0, dst.0))
+    }
+}
+
+impl<B: PremulBlend + ?Sized> AlphaBlend<PremulRgba<B::Channel>> for B {
+    fn blend_pixel(
+        &self,
+        src: PremulRgba<B::Channel>,
+        dst: PremulRgba<B::Channel>,
+    ) -> PremulRgba<B::Channel> {
+        self.apply_premul(src, dst)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    #[test]
+    fn straight_blend_matches_apply() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let dst = F32x4Rgba::new(0.0, 1.0, 0.0, 1.0);
+        let out = BlendMode::Multiply.blend_pixel(StraightRgba::new(src), StraightRgba::new(dst));
+        assert_eq!(out.into_rgba(), BlendMode::Multiply.apply(src, dst));
+    }
+
+    #[test]
+    fn premul_blend_matches_apply_premul() {
+        let src = StraightRgba::new(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)).premultiply();
+        let dst = StraightRgba::new(F32x4Rgba::new(0.0, 1.0, 0.0, 0.5)).premultiply();
+        assert_eq!(
+            BlendMode::Xor.blend_pixel(src, dst),
+            BlendMode::Xor.apply_premul(src, dst)
+        );
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let c = StraightRgba::from(U8x4Rgba::new(255, 128, 0, 128));
+        let p = U8x4PremulRgba::from(c);
+        assert_eq!(p.into_rgba(), U8x4Rgba::new(128, 64, 0, 128));
+        assert_eq!(StraightRgba::from(p), c);
+
+        let c = StraightRgba::from(F32x4Rgba::new(0.5, 0.25, 1.0, 0.5));
+        assert_eq!(StraightRgba::from(c.premultiply()), c);
+    }
+
+    #[test]
+    fn blend_pixels_matches_individual() {
+        let src = [StraightRgba::new(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)); 2];
+        let mut dst = [
+            StraightRgba::new(F32x4Rgba::WHITE),
+            StraightRgba::new(F32x4Rgba::BLACK),
+        ];
+        let expected = [
+            BlendMode::Screen.blend_pixel(src[0], dst[0]),
+            BlendMode::Screen.blend_pixel(src[1], dst[1]),
+        ];
+        BlendMode::Screen.blend_pixels(&src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+}
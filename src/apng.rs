@@ -0,0 +1,374 @@
+//! APNG frame composition.
+//!
+//! Implements the `blend_op` and `dispose_op` semantics of the [APNG] frame control (`fcTL`)
+//! chunk, so decoders and encoders can reuse this crate to reconstruct each output frame from
+//! the sub-region a frame updates.
+//!
+//! The canvas is a row-major buffer of straight-alpha [`U8x4Rgba`] pixels, `canvas_width` pixels
+//! wide, as produced by most PNG decoders.
+//!
+//! [APNG]: https://wiki.mozilla.org/APNG_Specification
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     apng::{BlendOp, DisposeOp, FrameControl},
+//!     rgba::U8x4Rgba,
+//! };
+//!
+//! let mut canvas = [U8x4Rgba::TRANSPARENT; 4 * 4];
+//! let frame = FrameControl {
+//!     width: 2,
+//!     height: 2,
+//!     x_offset: 1,
+//!     y_offset: 1,
+//!     dispose_op: DisposeOp::Background,
+//!     blend_op: BlendOp::Over,
+//! };
+//!
+//! frame.blend(&mut canvas, 4, &[U8x4Rgba::WHITE; 2 * 2]);
+//! assert_eq!(canvas[4 + 1], U8x4Rgba::WHITE);
+//!
+//! // After the frame is displayed, its region is cleared to transparent black.
+//! frame.dispose(&mut canvas, 4, &[]);
+//! assert_eq!(canvas[4 + 1], U8x4Rgba::TRANSPARENT);
+//! ```
+
+use crate::rgba::U8x4Rgba;
+
+/// How a frame is blended onto the canvas (`blend_op` in the `fcTL` chunk).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendOp {
+    /// `APNG_BLEND_OP_SOURCE`: the frame replaces the region it covers, including alpha.
+    #[default]
+    Source,
+
+    /// `APNG_BLEND_OP_OVER`: the frame is composited over the region using its alpha.
+    Over,
+}
+
+/// How the canvas is prepared after a frame is displayed (`dispose_op` in the `fcTL` chunk).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DisposeOp {
+    /// `APNG_DISPOSE_OP_NONE`: the region is left as-is.
+    #[default]
+    None,
+
+    /// `APNG_DISPOSE_OP_BACKGROUND`: the region is cleared to fully transparent black.
+    Background,
+
+    /// `APNG_DISPOSE_OP_PREVIOUS`: the region is reverted to its contents before the frame.
+    ///
+    /// Per the specification, if the first frame uses this op it is treated as
+    /// [`Background`](DisposeOp::Background); see [`FrameControl::dispose_op_for`].
+    Previous,
+}
+
+/// Region and compositing ops of a single APNG frame.
+///
+/// Mirrors the fields of the `fcTL` chunk that affect composition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FrameControl {
+    /// Width of the frame, in pixels.
+    pub width: u32,
+
+    /// Height of the frame, in pixels.
+    pub height: u32,
+
+    /// X position at which to render the frame.
+    pub x_offset: u32,
+
+    /// Y position at which to render the frame.
+    pub y_offset: u32,
+
+    /// Disposal to apply to the frame region after it is displayed.
+    pub dispose_op: DisposeOp,
+
+    /// How the frame is blended onto the canvas.
+    pub blend_op: BlendOp,
+}
+
+impl FrameControl {
+    /// Returns the dispose op to apply for the frame at `index` in the animation.
+    ///
+    /// This is [`dispose_op`](FrameControl::dispose_op), except that
+    /// [`DisposeOp::Previous`] on the first frame is treated as [`DisposeOp::Background`].
+    #[must_use]
+    pub const fn dispose_op_for(&self, index: usize) -> DisposeOp {
+        match self.dispose_op {
+            DisposeOp::Previous if index == 0 => DisposeOp::Background,
+            op => op,
+        }
+    }
+
+    /// Number of pixels covered by the frame (`width * height`).
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Returns `true` if the frame covers no pixels.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Copies the region covered by this frame from `canvas` into `saved`.
+    ///
+    /// Call this before [`blend`](FrameControl::blend) when the frame uses
+    /// [`DisposeOp::Previous`], and pass `saved` to [`dispose`](FrameControl::dispose).
+    ///
+    /// ## Panics
+    ///
+    /// If the frame does not fit within the canvas, or `saved.len() != self.len()`.
+    pub fn save(&self, canvas: &[U8x4Rgba], canvas_width: usize, saved: &mut [U8x4Rgba]) {
+        assert_eq!(
+            saved.len(),
+            self.len(),
+            "saved must have one pixel per frame pixel"
+        );
+        if self.is_empty() {
+            return;
+        }
+        for (row, out) in self
+            .rows(canvas.len(), canvas_width)
+            .zip(saved.chunks_exact_mut(self.width as usize))
+        {
+            out.copy_from_slice(&canvas[row]);
+        }
+    }
+
+    /// Blends the frame `pixels` onto `canvas` according to [`blend_op`](FrameControl::blend_op).
+    ///
+    /// ## Panics
+    ///
+    /// If the frame does not fit within the canvas, or `pixels.len() != self.len()`.
+    pub fn blend(&self, canvas: &mut [U8x4Rgba], canvas_width: usize, pixels: &[U8x4Rgba]) {
+        assert_eq!(
+            pixels.len(),
+            self.len(),
+            "pixels must have one pixel per frame pixel"
+        );
+        if self.is_empty() {
+            return;
+        }
+        for (row, src) in self
+            .rows(canvas.len(), canvas_width)
+            .zip(pixels.chunks_exact(self.width as usize))
+        {
+            let dst = &mut canvas[row];
+            match self.blend_op {
+                BlendOp::Source => dst.copy_from_slice(src),
+                BlendOp::Over => {
+                    for (d, s) in dst.iter_mut().zip(src) {
+                        *d = over(*s, *d);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Disposes of the frame region on `canvas` according to [`dispose_op`](FrameControl::dispose_op).
+    ///
+    /// `saved` is only read for [`DisposeOp::Previous`], and must hold the region as captured by
+    /// [`save`](FrameControl::save); otherwise it may be empty. To honor the first-frame rule,
+    /// dispose a copy with `dispose_op: frame.dispose_op_for(index)`.
+    ///
+    /// ## Panics
+    ///
+    /// If the frame does not fit within the canvas, or the op is [`DisposeOp::Previous`] and
+    /// `saved.len() != self.len()`.
+    pub fn dispose(&self, canvas: &mut [U8x4Rgba], canvas_width: usize, saved: &[U8x4Rgba]) {
+        match self.dispose_op {
+            DisposeOp::None => {}
+            DisposeOp::Background => {
+                for row in self.rows(canvas.len(), canvas_width) {
+                    canvas[row].fill(U8x4Rgba::TRANSPARENT);
+                }
+            }
+            DisposeOp::Previous => {
+                assert_eq!(
+                    saved.len(),
+                    self.len(),
+                    "saved must have one pixel per frame pixel"
+                );
+                if self.is_empty() {
+                    return;
+                }
+                for (row, src) in self
+                    .rows(canvas.len(), canvas_width)
+                    .zip(saved.chunks_exact(self.width as usize))
+                {
+                    canvas[row].copy_from_slice(src);
+                }
+            }
+        }
+    }
+
+    /// Returns the canvas index range of each row covered by the frame.
+    fn rows(
+        &self,
+        canvas_len: usize,
+        canvas_width: usize,
+    ) -> impl Iterator<Item = core::ops::Range<usize>> {
+        let (x, y) = (self.x_offset as usize, self.y_offset as usize);
+        let (w, h) = (self.width as usize, self.height as usize);
+        let canvas_height = canvas_len.checked_div(canvas_width).unwrap_or(0);
+        assert!(
+            x + w <= canvas_width && y + h <= canvas_height,
+            "frame must fit within the canvas"
+        );
+        (y..y + h).map(move |row| {
+            let start = row * canvas_width + x;
+            start..start + w
+        })
+    }
+}
+
+/// Composites straight-alpha `src` over `dst`, as specified by the PNG alpha channel processing.
+///
+/// Unlike [`U8x4Rgba::source_over`], the color channels are divided by the output alpha, so
+/// compositing onto transparent regions of the canvas does not darken the frame.
+fn over(src: U8x4Rgba, dst: U8x4Rgba) -> U8x4Rgba {
+    match src.a {
+        255 => return src,
+        0 => return dst,
+        _ => {}
+    }
+    let sa = u32::from(src.a) * 255;
+    let da = u32::from(dst.a) * (255 - u32::from(src.a));
+    let out = sa + da;
+    let channel = |s: u8, d: u8| -> u8 {
+        let v = u32::from(s) * sa + u32::from(d) * da;
+        #[allow(clippy::cast_possible_truncation)]
+        let c = ((v + out / 2) / out) as u8;
+        c
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let a = ((out + 127) / 255) as u8;
+    U8x4Rgba::new(
+        channel(src.r, dst.r),
+        channel(src.g, dst.g),
+        channel(src.b, dst.b),
+        a,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: U8x4Rgba = U8x4Rgba::new(255, 0, 0, 255);
+    const HALF_BLUE: U8x4Rgba = U8x4Rgba::new(0, 0, 255, 128);
+
+    fn frame(blend_op: BlendOp, dispose_op: DisposeOp) -> FrameControl {
+        FrameControl {
+            width: 2,
+            height: 1,
+            x_offset: 1,
+            y_offset: 1,
+            dispose_op,
+            blend_op,
+        }
+    }
+
+    #[test]
+    fn blend_source_replaces_region() {
+        let mut canvas = [RED; 3 * 3];
+        frame(BlendOp::Source, DisposeOp::None).blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        assert_eq!(canvas[3], RED);
+        assert_eq!(canvas[4], HALF_BLUE);
+        assert_eq!(canvas[5], HALF_BLUE);
+        assert_eq!(canvas[7], RED);
+    }
+
+    #[test]
+    fn blend_over_composites_region() {
+        let mut canvas = [RED; 3 * 3];
+        frame(BlendOp::Over, DisposeOp::None).blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        assert_eq!(canvas[4], U8x4Rgba::new(127, 0, 128, 255));
+        assert_eq!(canvas[8], RED);
+    }
+
+    #[test]
+    fn blend_over_transparent_keeps_color() {
+        let mut canvas = [U8x4Rgba::TRANSPARENT; 3 * 3];
+        frame(BlendOp::Over, DisposeOp::None).blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        assert_eq!(canvas[4], HALF_BLUE);
+    }
+
+    #[test]
+    fn over_fully_transparent_src_keeps_dst() {
+        assert_eq!(over(U8x4Rgba::TRANSPARENT, HALF_BLUE), HALF_BLUE);
+        assert_eq!(
+            over(U8x4Rgba::TRANSPARENT, U8x4Rgba::TRANSPARENT),
+            U8x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn dispose_none_keeps_region() {
+        let mut canvas = [RED; 3 * 3];
+        let f = frame(BlendOp::Source, DisposeOp::None);
+        f.blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        f.dispose(&mut canvas, 3, &[]);
+        assert_eq!(canvas[4], HALF_BLUE);
+    }
+
+    #[test]
+    fn dispose_background_clears_region() {
+        let mut canvas = [RED; 3 * 3];
+        let f = frame(BlendOp::Source, DisposeOp::Background);
+        f.blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        f.dispose(&mut canvas, 3, &[]);
+        assert_eq!(canvas[4], U8x4Rgba::TRANSPARENT);
+        assert_eq!(canvas[5], U8x4Rgba::TRANSPARENT);
+        assert_eq!(canvas[3], RED);
+    }
+
+    #[test]
+    fn dispose_previous_restores_region() {
+        let mut canvas = [RED; 3 * 3];
+        let mut saved = [U8x4Rgba::TRANSPARENT; 2];
+        let f = frame(BlendOp::Over, DisposeOp::Previous);
+        f.save(&canvas, 3, &mut saved);
+        f.blend(&mut canvas, 3, &[HALF_BLUE; 2]);
+        f.dispose(&mut canvas, 3, &saved);
+        assert_eq!(canvas, [RED; 3 * 3]);
+    }
+
+    #[test]
+    fn dispose_op_for_first_frame() {
+        let f = frame(BlendOp::Source, DisposeOp::Previous);
+        assert_eq!(f.dispose_op_for(0), DisposeOp::Background);
+        assert_eq!(f.dispose_op_for(1), DisposeOp::Previous);
+    }
+
+    #[test]
+    fn empty_frame_is_noop() {
+        let mut canvas = [RED; 3 * 3];
+        let f = FrameControl {
+            width: 0,
+            ..frame(BlendOp::Source, DisposeOp::Previous)
+        };
+        f.save(&canvas, 3, &mut []);
+        f.blend(&mut canvas, 3, &[]);
+        f.dispose(&mut canvas, 3, &[]);
+        assert_eq!(canvas, [RED; 3 * 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must fit within the canvas")]
+    fn blend_panics_when_out_of_bounds() {
+        let mut canvas = [RED; 2 * 2];
+        frame(BlendOp::Source, DisposeOp::None).blend(&mut canvas, 2, &[HALF_BLUE; 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one pixel per frame pixel")]
+    fn blend_panics_on_pixel_count_mismatch() {
+        let mut canvas = [RED; 3 * 3];
+        frame(BlendOp::Source, DisposeOp::None).blend(&mut canvas, 3, &[HALF_BLUE; 3]);
+    }
+}
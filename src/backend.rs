@@ -0,0 +1,315 @@
+//! Execution backend selection for bulk blending.
+//!
+//! [`blend_slice`] blends whole buffers using the [`Backend`] resolved for their length: very
+//! short buffers stay on a per-pixel loop, where setup costs would dominate, and longer buffers
+//! use the blend's own slice kernel, [`RgbaBlend::apply_slice`]. The built-in blends vectorize
+//! theirs: Porter-Duff modes blend two pixels per step in eight `f32` lanes, and
+//! [`U8x4Rgba::source_over_slice`](crate::rgba::U8x4Rgba::source_over_slice) uses SSE2, AVX2 or
+//! `simd128` kernels where they are compiled in.
+//!
+//! With the `rayon` feature, `par_blend_slice` also splits very long buffers across the `rayon`
+//! thread pool, for blends that can be shared between threads. [`blend_slice`] always blends on
+//! the calling thread, since its blend need not be [`Sync`].
+//!
+//! Selection is automatic by default; [`set_backend`] overrides it process-wide, for example to
+//! benchmark backends against each other or to keep a real-time thread off the thread pool. A
+//! requested backend that is unavailable falls back to [`Backend::Simd`].
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, backend::{self, Backend}, rgba::F32x4Rgba};
+//!
+//! let src = [F32x4Rgba::WHITE; 4];
+//! let mut dst = [F32x4Rgba::BLACK; 4];
+//! backend::blend_slice(&BlendMode::SourceOver, &src, &mut dst);
+//! assert_eq!(dst, [F32x4Rgba::WHITE; 4]);
+//!
+//! // Short buffers always use the per-pixel loop.
+//! assert_eq!(Backend::Auto.resolve(4), Backend::Scalar);
+//! ```
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{RgbaBlend, rgba::Rgba};
+
+/// Minimum buffer length, in pixels, for which [`Backend::Auto`] uses the blend's slice kernel.
+pub const SIMD_THRESHOLD: usize = 16;
+
+/// Minimum buffer length, in pixels, for which [`Backend::Auto`] splits work across threads in
+/// `par_blend_slice`.
+pub const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+/// How bulk blends are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+pub enum Backend {
+    /// Chooses a backend by buffer length and what is available; see [`Backend::resolve`].
+    #[default]
+    Auto = 0,
+
+    /// A per-pixel loop calling [`RgbaBlend::apply`].
+    Scalar = 1,
+
+    /// The blend's slice kernel, [`RgbaBlend::apply_slice`], which the built-in blends
+    /// vectorize.
+    Simd = 2,
+
+    /// Splits the buffer across the `rayon` thread pool in `par_blend_slice`, blending each part
+    /// with [`Simd`](Backend::Simd). [`blend_slice`] treats it as [`Simd`](Backend::Simd).
+    Parallel = 3,
+}
+
+impl Backend {
+    /// Returns `true` if this backend can run in this build, on this CPU.
+    #[must_use]
+    pub const fn is_available(self) -> bool {
+        match self {
+            Self::Auto | Self::Scalar | Self::Simd => true,
+            Self::Parallel => cfg!(feature = "rayon"),
+        }
+    }
+
+    /// Returns the concrete backend used to blend a buffer of `len` pixels.
+    ///
+    /// [`Auto`](Backend::Auto) prefers [`Parallel`](Backend::Parallel) from
+    /// [`PARALLEL_THRESHOLD`] pixels, then [`Simd`](Backend::Simd) from [`SIMD_THRESHOLD`]
+    /// pixels, then [`Scalar`](Backend::Scalar), skipping unavailable backends. Any other
+    /// backend resolves to itself if available, or to [`Simd`](Backend::Simd) otherwise.
+    #[must_use]
+    pub const fn resolve(self, len: usize) -> Self {
+        match self {
+            Self::Auto if len >= PARALLEL_THRESHOLD && Self::Parallel.is_available() => {
+                Self::Parallel
+            }
+            Self::Auto if len >= SIMD_THRESHOLD && Self::Simd.is_available() => Self::Simd,
+            Self::Auto => Self::Scalar,
+            _ if self.is_available() => self,
+            _ => Self::Simd,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Scalar,
+            2 => Self::Simd,
+            3 => Self::Parallel,
+            _ => Self::Auto,
+        }
+    }
+}
+
+static OVERRIDE: AtomicU8 = AtomicU8::new(Backend::Auto as u8);
+
+/// Overrides the backend used by [`blend_slice`], process-wide.
+///
+/// Pass [`Backend::Auto`] to restore automatic selection.
+pub fn set_backend(backend: Backend) {
+    OVERRIDE.store(backend as u8, Ordering::Relaxed);
+}
+
+/// Returns the backend set with [`set_backend`], [`Backend::Auto`] by default.
+#[must_use]
+pub fn backend() -> Backend {
+    Backend::from_u8(OVERRIDE.load(Ordering::Relaxed))
+}
+
+/// Blends `src` onto `dst` in place on the calling thread, using the [`backend`] resolved for the
+/// buffer length.
+///
+/// [`Scalar`](Backend::Scalar) calls [`RgbaBlend::apply`] for each pixel; every other backend
+/// calls [`RgbaBlend::apply_slice`] once.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_slice<B>(blend: &B, src: &[Rgba<B::Channel>], dst: &mut [Rgba<B::Channel>])
+where
+    B: RgbaBlend + ?Sized,
+{
+    match backend().resolve(dst.len()) {
+        Backend::Scalar => {
+            assert_eq!(
+                src.len(),
+                dst.len(),
+                "src and dst slices must have the same length"
+            );
+            for (s, d) in src.iter().zip(dst) {
+                *d = blend.apply(*s, *d);
+            }
+        }
+        Backend::Auto | Backend::Simd | Backend::Parallel => blend.apply_slice(src, dst),
+    }
+}
+
+#[cfg(feature = "rayon")]
+pub use parallel::{PARALLEL_CHUNK, par_blend_slice};
+
+#[cfg(feature = "rayon")]
+mod parallel {
+    use rayon::prelude::*;
+
+    use super::{Backend, backend, blend_slice};
+    use crate::{RgbaBlend, rgba::Rgba};
+
+    /// Number of pixels blended by each task of [`par_blend_slice`].
+    pub const PARALLEL_CHUNK: usize = 4096;
+
+    /// Blends `src` onto `dst` in place, splitting the buffers across the [`rayon`] thread pool
+    /// when the [`backend`] resolves to [`Parallel`](Backend::Parallel).
+    ///
+    /// Each chunk of [`PARALLEL_CHUNK`] pixels is blended with [`blend_slice`], so it still uses
+    /// the best single-threaded backend; any other backend blends the whole buffer with
+    /// [`blend_slice`] on the calling thread. Unlike [`blend_slice`], `blend` must be [`Sync`].
+    ///
+    /// _This function requires the `rayon` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn par_blend_slice<B>(blend: &B, src: &[Rgba<B::Channel>], dst: &mut [Rgba<B::Channel>])
+    where
+        B: RgbaBlend + Sync + ?Sized,
+        B::Channel: Send + Sync,
+    {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        if backend().resolve(dst.len()) != Backend::Parallel {
+            blend_slice(blend, src, dst);
+            return;
+        }
+        dst.par_chunks_mut(PARALLEL_CHUNK)
+            .zip(src.par_chunks(PARALLEL_CHUNK))
+            .for_each(|(dst, src)| blend_slice(blend, src, dst));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::{BlendMode, rgba::F32x4Rgba};
+
+    const ALL: [Backend; 4] = [
+        Backend::Auto,
+        Backend::Scalar,
+        Backend::Simd,
+        Backend::Parallel,
+    ];
+
+    #[test]
+    fn resolve_is_concrete_and_available() {
+        for backend in ALL {
+            for len in [0, SIMD_THRESHOLD, PARALLEL_THRESHOLD] {
+                let resolved = backend.resolve(len);
+                assert_ne!(resolved, Backend::Auto);
+                assert!(resolved.is_available(), "{backend:?} -> {resolved:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn short_buffers_are_scalar() {
+        assert_eq!(Backend::Auto.resolve(SIMD_THRESHOLD - 1), Backend::Scalar);
+        assert_eq!(Backend::Scalar.resolve(PARALLEL_THRESHOLD), Backend::Scalar);
+    }
+
+    #[test]
+    fn parallel_requires_rayon() {
+        assert_eq!(Backend::Parallel.is_available(), cfg!(feature = "rayon"));
+        let long = Backend::Auto.resolve(PARALLEL_THRESHOLD);
+        if cfg!(feature = "rayon") {
+            assert_eq!(long, Backend::Parallel);
+        } else {
+            assert_eq!(long, Backend::Simd);
+        }
+    }
+
+    #[test]
+    fn u8_round_trip() {
+        for backend in ALL {
+            assert_eq!(Backend::from_u8(backend as u8), backend);
+        }
+    }
+
+    /// `SourceOver`, counting calls to each [`RgbaBlend`] method.
+    #[derive(Default)]
+    struct Counting {
+        apply: AtomicUsize,
+        apply_slice: AtomicUsize,
+    }
+
+    impl Counting {
+        fn calls(&self) -> (usize, usize) {
+            (
+                self.apply.swap(0, Ordering::Relaxed),
+                self.apply_slice.swap(0, Ordering::Relaxed),
+            )
+        }
+    }
+
+    impl RgbaBlend for Counting {
+        type Channel = f32;
+
+        fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+            self.apply.fetch_add(1, Ordering::Relaxed);
+            BlendMode::SourceOver.apply(src, dst)
+        }
+
+        fn apply_slice(&self, src: &[F32x4Rgba], dst: &mut [F32x4Rgba]) {
+            self.apply_slice.fetch_add(1, Ordering::Relaxed);
+            BlendMode::SourceOver.apply_slice(src, dst);
+        }
+    }
+
+    #[test]
+    fn override_selects_code_path() {
+        // The only test that sets the override; other tests get identical results either way.
+        let blend = Counting::default();
+        let src = [F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); 3];
+        let mut expected = [F32x4Rgba::BLACK; 3];
+        BlendMode::SourceOver.apply_slice(&src, &mut expected);
+
+        for (backend, calls) in [
+            (Backend::Scalar, (3, 0)),
+            (Backend::Simd, (0, 1)),
+            (Backend::Auto, (3, 0)),
+        ] {
+            set_backend(backend);
+            let mut dst = [F32x4Rgba::BLACK; 3];
+            blend_slice(&blend, &src, &mut dst);
+            assert_eq!(dst, expected, "{backend:?}");
+            assert_eq!(blend.calls(), calls, "{backend:?}");
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            let src = vec![F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); PARALLEL_CHUNK * 3];
+            for (backend, slices) in [(Backend::Simd, 1), (Backend::Parallel, 3)] {
+                set_backend(backend);
+                let mut dst = vec![F32x4Rgba::BLACK; src.len()];
+                par_blend_slice(&blend, &src, &mut dst);
+                assert_eq!(blend.calls(), (0, slices), "{backend:?}");
+            }
+        }
+        set_backend(Backend::Auto);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_blend_slice_matches_blend_slice() {
+        let src: Vec<F32x4Rgba> = (0..PARALLEL_CHUNK * 2 + 7)
+            .map(|i| F32x4Rgba::new(1.0, 0.0, 0.0, [0.0, 0.25, 0.5, 0.75, 1.0][i % 5]))
+            .collect();
+        let mut expected = vec![F32x4Rgba::BLACK; src.len()];
+        BlendMode::SourceOver.apply_slice(&src, &mut expected);
+
+        let mut dst = vec![F32x4Rgba::BLACK; src.len()];
+        par_blend_slice(&BlendMode::SourceOver, &src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+}
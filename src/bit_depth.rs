@@ -0,0 +1,285 @@
+//! Blending across channel bit depths.
+//!
+//! Layers and canvases do not always share a bit depth; for example, an 8-bit sticker may be
+//! composited onto a 16-bit photo. Rather than converting every input up front, the functions in
+//! this module promote each pixel to an `f32` working depth, blend, and demote once at the end,
+//! scaling by the exact range of each depth (`255` for [`u8`], `65535` for [`u16`]; a [`u8`]
+//! value `v` widens to the [`u16`] value `v * 257`).
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, bit_depth, rgba::Rgba};
+//!
+//! let sticker = [Rgba::<u8>::new(255, 0, 0, 255)];
+//! let mut photo = [Rgba::<u16>::new(0, 0, 0xFFFF, 0xFFFF)];
+//! bit_depth::blend_mixed(&BlendMode::SourceOver, &sticker, &mut photo);
+//! assert_eq!(photo, [Rgba::new(0xFFFF, 0, 0, 0xFFFF)]);
+//!
+//! // Widening is exact.
+//! assert_eq!(bit_depth::convert::<u8, u16>(Rgba::new(1, 2, 3, 4)), Rgba::new(257, 514, 771, 1028));
+//! ```
+
+use crate::{
+    RgbaBlend, format, math,
+    rgba::{F32x4Rgba, Rgba},
+};
+
+/// A channel type that can be promoted to, and demoted from, the `f32` working depth.
+pub trait BitDepth: Copy {
+    /// Converts the channel to `[0.0, 1.0]` (for integers) by dividing by its maximum value.
+    fn to_unit(self) -> f32;
+
+    /// Converts a working-depth value back, scaling, clamping and rounding for integer types.
+    fn from_unit(value: f32) -> Self;
+}
+
+impl BitDepth for u8 {
+    fn to_unit(self) -> f32 {
+        f32::from(self) / f32::from(Self::MAX)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_unit(value: f32) -> Self {
+        math::round(value.clamp(0.0, 1.0) * f32::from(Self::MAX)) as Self
+    }
+}
+
+impl BitDepth for u16 {
+    fn to_unit(self) -> f32 {
+        f32::from(self) / f32::from(Self::MAX)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_unit(value: f32) -> Self {
+        math::round(value.clamp(0.0, 1.0) * f32::from(Self::MAX)) as Self
+    }
+}
+
+/// Half floats are not clamped, like [`f32`].
+///
+/// _This implementation requires the `half` feature._
+#[cfg(feature = "half")]
+impl BitDepth for half::f16 {
+    fn to_unit(self) -> f32 {
+        self.to_f32()
+    }
+
+    fn from_unit(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl BitDepth for f32 {
+    fn to_unit(self) -> f32 {
+        self
+    }
+
+    fn from_unit(value: f32) -> Self {
+        value
+    }
+}
+
+/// Doubles are not clamped, like [`f32`].
+impl BitDepth for f64 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_unit(self) -> f32 {
+        self as f32
+    }
+
+    fn from_unit(value: f32) -> Self {
+        Self::from(value)
+    }
+}
+
+/// Promotes a color to the `f32` working depth.
+#[must_use]
+pub fn promote<C: BitDepth>(color: Rgba<C>) -> F32x4Rgba {
+    F32x4Rgba::new(
+        color.r.to_unit(),
+        color.g.to_unit(),
+        color.b.to_unit(),
+        color.a.to_unit(),
+    )
+}
+
+/// Demotes a color from the `f32` working depth.
+#[must_use]
+pub fn demote<C: BitDepth>(color: F32x4Rgba) -> Rgba<C> {
+    Rgba::new(
+        C::from_unit(color.r),
+        C::from_unit(color.g),
+        C::from_unit(color.b),
+        C::from_unit(color.a),
+    )
+}
+
+/// Converts a color between bit depths.
+#[must_use]
+pub fn convert<S: BitDepth, D: BitDepth>(color: Rgba<S>) -> Rgba<D> {
+    demote(promote(color))
+}
+
+/// Promotes each pixel of `src` into the working buffer `dst`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn promote_slice<C: BitDepth>(src: &[Rgba<C>], dst: &mut [F32x4Rgba]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = promote(*s);
+    }
+}
+
+/// Demotes each pixel of the working buffer `src` into `dst`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn demote_slice<C: BitDepth>(src: &[F32x4Rgba], dst: &mut [Rgba<C>]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = demote(*s);
+    }
+}
+
+/// Blends a layer of any bit depth onto an `f32` working canvas, in place.
+///
+/// Use this to composite several layers of mixed depths without rounding in between, then
+/// [`demote_slice`] the canvas once at the end.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_promoted<B, C>(blend: &B, src: &[Rgba<C>], dst: &mut [F32x4Rgba])
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    C: BitDepth,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = blend.apply(promote(*s), *d);
+    }
+}
+
+/// Blends a layer of one bit depth onto a canvas of another, in place.
+///
+/// Each pixel is promoted, blended in `f32`, and demoted once; see also
+/// [`format::blend_converted`] for other pixel formats.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_mixed<B, S, D>(blend: &B, src: &[Rgba<S>], dst: &mut [Rgba<D>])
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: BitDepth,
+    D: BitDepth,
+{
+    format::blend_converted(blend, src, dst);
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, rgba::U8x4Rgba};
+
+    #[test]
+    fn u8_widens_exactly_to_u16() {
+        for v in 0..=u8::MAX {
+            assert_eq!(
+                <u16 as BitDepth>::from_unit(v.to_unit()),
+                u16::from(v) * 257
+            );
+        }
+    }
+
+    #[test]
+    fn u16_narrows_with_rounding() {
+        for v in 0..=u8::MAX {
+            let wide = u16::from(v) * 257;
+            assert_eq!(<u8 as BitDepth>::from_unit(wide.to_unit()), v);
+            assert_eq!(
+                <u8 as BitDepth>::from_unit(wide.saturating_add(128).to_unit()),
+                v
+            );
+        }
+        assert_eq!(<u8 as BitDepth>::from_unit(129_u16.to_unit()), 1);
+    }
+
+    #[test]
+    fn demote_clamps() {
+        let c = demote::<u16>(F32x4Rgba::new(-1.0, 0.5, 2.0, f32::NAN));
+        assert_eq!(c, Rgba::new(0, 32768, 0xFFFF, 0));
+    }
+
+    #[test]
+    fn promote_matches_u8_conversion() {
+        let c = U8x4Rgba::new(12, 34, 56, 78);
+        assert_eq!(promote(c), F32x4Rgba::from(c));
+        assert_eq!(demote::<u8>(F32x4Rgba::from(c)), c);
+    }
+
+    #[test]
+    fn blend_mixed_u8_onto_u16() {
+        let src = [Rgba::<u8>::new(255, 255, 255, 128)];
+        let mut dst = [Rgba::<u16>::new(0, 0, 0, 0xFFFF)];
+        blend_mixed(&BlendMode::SourceOver, &src, &mut dst);
+        // 128 / 255 of white over black, kept at 16-bit precision.
+        assert_eq!(dst[0].r, 32896);
+    }
+
+    #[test]
+    fn blend_promoted_defers_rounding() {
+        let half = [Rgba::<u8>::new(255, 255, 255, 128)];
+        let mut canvas = [F32x4Rgba::BLACK];
+        blend_promoted(&BlendMode::SourceOver, &half, &mut canvas);
+        blend_promoted(
+            &BlendMode::SourceOver,
+            &[Rgba::<u16>::new(0, 0, 0, 0)],
+            &mut canvas,
+        );
+        let mut out = [Rgba::<u8>::new(0, 0, 0, 0)];
+        demote_slice(&canvas, &mut out);
+        assert_eq!(out[0].r, 128);
+
+        let mut promoted = [F32x4Rgba::TRANSPARENT];
+        promote_slice(&half, &mut promoted);
+        assert_eq!(promoted[0].a, 128.0 / 255.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn blend_mixed_panics_on_length_mismatch() {
+        blend_mixed(
+            &BlendMode::Source,
+            &[Rgba::<u8>::new(0, 0, 0, 0)],
+            &mut [Rgba::<u16>::new(0, 0, 0, 0); 2],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn f16_blends_through_f32() {
+        use crate::rgba::F16x4Rgba;
+
+        let src = [F16x4Rgba::from(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5))];
+        let mut dst = [F16x4Rgba::from(F32x4Rgba::new(0.0, 0.0, 1.0, 1.0))];
+        blend_mixed(&BlendMode::SourceOver, &src, &mut dst);
+        assert_eq!(F32x4Rgba::from(dst[0]), F32x4Rgba::new(0.5, 0.0, 0.5, 0.75));
+    }
+}
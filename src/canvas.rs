@@ -0,0 +1,450 @@
+//! Canvases that own their pixels.
+//!
+//! A [`FixedCanvas`] stores a `W × H` image inline, with its size fixed at compile time, so
+//! firmware driving a small display can keep a framebuffer in a `static` or on the stack and
+//! composite layers into it without an allocator. A `Canvas` has the same API, with its size
+//! chosen at runtime and its pixels in a `Vec`, for `no_std` targets that have an allocator;
+//! it requires the `alloc` feature. A `surface::Surface` is a `Canvas<f32>`, with extra methods
+//! for compositing whole surfaces and trimming transparent borders.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{canvas::FixedCanvas, porter_duff::U8PorterDuff, rgba::U8x4Rgba};
+//!
+//! // A 4×3 framebuffer with a 2×2 sprite hanging off its right edge.
+//! let mut frame = FixedCanvas::<u8, 4, 3>::filled(U8x4Rgba::BLACK);
+//! let sprite = [U8x4Rgba::WHITE; 4];
+//! frame.composite_at(&sprite, 2, 3, 1, &U8PorterDuff::SRC_OVER);
+//!
+//! assert_eq!(frame.get(3, 1), Some(U8x4Rgba::WHITE));
+//! assert_eq!(frame.get(2, 1), Some(U8x4Rgba::BLACK));
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{RgbaBlend, channel::Channel, composite, rect::Rect, rgba::Rgba};
+
+/// A `W × H` image of [`Rgba<C>`] pixels stored inline, row-major.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedCanvas<C: Copy, const W: usize, const H: usize> {
+    pixels: [[Rgba<C>; W]; H],
+}
+
+impl<C: Channel, const W: usize, const H: usize> Default for FixedCanvas<C, W, H> {
+    fn default() -> Self {
+        Self::filled(Rgba::new(C::ZERO, C::ZERO, C::ZERO, C::ZERO))
+    }
+}
+
+impl<C: Copy, const W: usize, const H: usize> FixedCanvas<C, W, H> {
+    /// Creates a canvas with every pixel set to `color`.
+    #[must_use]
+    pub const fn filled(color: Rgba<C>) -> Self {
+        Self {
+            pixels: [[color; W]; H],
+        }
+    }
+
+    /// Width of the canvas, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        W
+    }
+
+    /// Height of the canvas, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        H
+    }
+
+    /// Returns the rectangle covering the whole canvas.
+    #[must_use]
+    pub const fn bounds(&self) -> Rect {
+        Rect::new(0, 0, W, H)
+    }
+
+    /// Returns the pixels of the canvas, row-major.
+    #[must_use]
+    pub fn pixels(&self) -> &[Rgba<C>] {
+        self.pixels.as_flattened()
+    }
+
+    /// Returns the pixels of the canvas for modification, row-major.
+    pub fn pixels_mut(&mut self) -> &mut [Rgba<C>] {
+        self.pixels.as_flattened_mut()
+    }
+
+    /// Returns the pixels of row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    #[must_use]
+    pub const fn row(&self, y: usize) -> &[Rgba<C>; W] {
+        assert!(y < H, "row out of bounds");
+        &self.pixels[y]
+    }
+
+    /// Returns the pixels of row `y` for modification.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    pub const fn row_mut(&mut self, y: usize) -> &mut [Rgba<C>; W] {
+        assert!(y < H, "row out of bounds");
+        &mut self.pixels[y]
+    }
+
+    /// Returns the pixel at `(x, y)`, or `None` if out of bounds.
+    #[must_use]
+    pub const fn get(&self, x: usize, y: usize) -> Option<Rgba<C>> {
+        if x < W && y < H {
+            Some(self.pixels[y][x])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the pixel at `(x, y)`.
+    ///
+    /// ## Panics
+    ///
+    /// If `(x, y)` is out of bounds.
+    pub const fn set(&mut self, x: usize, y: usize, color: Rgba<C>) {
+        assert!(x < W && y < H, "pixel out of bounds");
+        self.pixels[y][x] = color;
+    }
+
+    /// Sets every pixel to `color`.
+    pub fn fill(&mut self, color: Rgba<C>) {
+        self.pixels_mut().fill(color);
+    }
+
+    /// Composites `src`, an image `src_width` pixels wide, onto the canvas with its top-left
+    /// corner at `(x, y)`, using `blend`.
+    ///
+    /// Offsets may be negative, or place `src` partially or entirely outside the canvas; only
+    /// the overlapping pixels are blended.
+    ///
+    /// ## Panics
+    ///
+    /// If `src_width` is zero or does not divide the length of `src`.
+    pub fn composite_at<B>(
+        &mut self,
+        src: &[Rgba<C>],
+        src_width: usize,
+        x: isize,
+        y: isize,
+        blend: &B,
+    ) where
+        B: RgbaBlend<Channel = C> + ?Sized,
+    {
+        composite_pixels_at(self.pixels_mut(), W, src, src_width, x, y, blend);
+    }
+}
+
+/// A `width × height` image of [`Rgba<C>`] pixels on the heap, row-major.
+///
+/// This is [`FixedCanvas`] with its size chosen at runtime. A `Canvas<f32>` is a
+/// [`Surface`](crate::surface::Surface).
+///
+/// _This type requires the `alloc` feature._
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas<C: Copy> {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba<C>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<C: Channel> Canvas<C> {
+    /// Creates a new, fully transparent canvas.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::filled(width, height, Rgba::new(C::ZERO, C::ZERO, C::ZERO, C::ZERO))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: Copy> Canvas<C> {
+    /// Creates a canvas with every pixel set to `color`.
+    #[must_use]
+    pub fn filled(width: usize, height: usize, color: Rgba<C>) -> Self {
+        Self {
+            width,
+            height,
+            pixels: alloc::vec![color; width * height],
+        }
+    }
+
+    /// Creates a canvas from row-major `pixels`.
+    ///
+    /// ## Panics
+    ///
+    /// If `pixels.len() != width * height`.
+    #[must_use]
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Rgba<C>>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "canvas must have width * height pixels"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Width of the canvas, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the canvas, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the rectangle covering the whole canvas.
+    #[must_use]
+    pub const fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    /// Returns the pixels of the canvas, row-major.
+    #[must_use]
+    pub fn pixels(&self) -> &[Rgba<C>] {
+        &self.pixels
+    }
+
+    /// Returns the pixels of the canvas for modification, row-major.
+    pub fn pixels_mut(&mut self) -> &mut [Rgba<C>] {
+        &mut self.pixels
+    }
+
+    /// Consumes the canvas, returning its pixels.
+    #[must_use]
+    pub fn into_pixels(self) -> Vec<Rgba<C>> {
+        self.pixels
+    }
+
+    /// Returns the pixels of row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &[Rgba<C>] {
+        assert!(y < self.height, "row out of bounds");
+        &self.pixels[y * self.width..][..self.width]
+    }
+
+    /// Returns the pixels of row `y` for modification.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [Rgba<C>] {
+        assert!(y < self.height, "row out of bounds");
+        &mut self.pixels[y * self.width..][..self.width]
+    }
+
+    /// Returns the pixel at `(x, y)`, or `None` if out of bounds.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgba<C>> {
+        self.bounds()
+            .contains(x, y)
+            .then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Sets the pixel at `(x, y)`.
+    ///
+    /// ## Panics
+    ///
+    /// If `(x, y)` is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, color: Rgba<C>) {
+        assert!(self.bounds().contains(x, y), "pixel out of bounds");
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Sets every pixel to `color`.
+    pub fn fill(&mut self, color: Rgba<C>) {
+        self.pixels.fill(color);
+    }
+
+    /// Composites `src`, an image `src_width` pixels wide, onto the canvas with its top-left
+    /// corner at `(x, y)`, using `blend`; see [`FixedCanvas::composite_at`].
+    ///
+    /// ## Panics
+    ///
+    /// If `src_width` is zero or does not divide the length of `src`.
+    pub fn composite_at<B>(
+        &mut self,
+        src: &[Rgba<C>],
+        src_width: usize,
+        x: isize,
+        y: isize,
+        blend: &B,
+    ) where
+        B: RgbaBlend<Channel = C> + ?Sized,
+    {
+        composite_pixels_at(&mut self.pixels, self.width, src, src_width, x, y, blend);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: Copy, const W: usize, const H: usize> From<FixedCanvas<C, W, H>> for Canvas<C> {
+    fn from(canvas: FixedCanvas<C, W, H>) -> Self {
+        Self::from_pixels(W, H, canvas.pixels().to_vec())
+    }
+}
+
+/// Composites `src`, an image `src_width` pixels wide, onto `dst`, an image `dst_width` pixels
+/// wide, with its top-left corner at `(x, y)`, clipped to `dst`.
+fn composite_pixels_at<C, B>(
+    dst: &mut [Rgba<C>],
+    dst_width: usize,
+    src: &[Rgba<C>],
+    src_width: usize,
+    x: isize,
+    y: isize,
+    blend: &B,
+) where
+    C: Copy,
+    B: RgbaBlend<Channel = C> + ?Sized,
+{
+    assert!(
+        src_width != 0 && src.len() % src_width == 0,
+        "src length must be a multiple of src_width"
+    );
+    let (src_height, dst_height) = (src.len() / src_width, dst.len() / dst_width.max(1));
+    let Some((src_rect, dst_rect)) =
+        composite::clip((src_width, src_height), (dst_width, dst_height), x, y)
+    else {
+        return;
+    };
+    let src_rows = src.chunks_exact(src_width).skip(src_rect.y);
+    let dst_rows = dst.chunks_exact_mut(dst_width).skip(dst_rect.y);
+    for (s, d) in src_rows.zip(dst_rows).take(dst_rect.height) {
+        blend.apply_slice(
+            &s[src_rect.x..src_rect.right()],
+            &mut d[dst_rect.x..dst_rect.right()],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BlendMode,
+        composite::{View, ViewMut},
+        porter_duff::U8PorterDuff,
+        rgba::{F32x4Rgba, U8x4Rgba},
+    };
+
+    #[test]
+    fn stores_pixels_inline() {
+        assert_eq!(
+            size_of::<FixedCanvas<u8, 8, 4>>(),
+            8 * 4 * size_of::<U8x4Rgba>()
+        );
+        let canvas = FixedCanvas::<u8, 3, 2>::default();
+        assert_eq!(canvas.pixels(), &[U8x4Rgba::TRANSPARENT; 6]);
+        assert_eq!((canvas.width(), canvas.height()), (3, 2));
+    }
+
+    #[test]
+    fn get_set_and_fill() {
+        let mut canvas = FixedCanvas::<u8, 2, 2>::filled(U8x4Rgba::BLACK);
+        canvas.set(1, 0, U8x4Rgba::WHITE);
+        assert_eq!(canvas.get(1, 0), Some(U8x4Rgba::WHITE));
+        assert_eq!(canvas.row(0), &[U8x4Rgba::BLACK, U8x4Rgba::WHITE]);
+        assert_eq!(canvas.get(2, 0), None);
+        canvas.fill(U8x4Rgba::RED);
+        assert_eq!(canvas.pixels(), &[U8x4Rgba::RED; 4]);
+    }
+
+    #[test]
+    fn composite_at_clips_to_edges() {
+        let sprite: [F32x4Rgba; 6] = core::array::from_fn(|i| {
+            F32x4Rgba::new(1.0, 0.0, 0.0, f32::from(u8::try_from(i).unwrap()) / 5.0)
+        });
+        for (x, y) in [(0, 0), (-1, 1), (2, -1), (4, 4), (-3, 0)] {
+            let mut expected = [F32x4Rgba::BLUE; 12];
+            composite::composite_at(
+                &BlendMode::Multiply,
+                View::new(&sprite, 3, 2),
+                ViewMut::new(&mut expected, 4, 3),
+                x,
+                y,
+            );
+
+            let mut canvas = FixedCanvas::<f32, 4, 3>::filled(F32x4Rgba::BLUE);
+            canvas.composite_at(&sprite, 3, x, y, &BlendMode::Multiply);
+            assert_eq!(canvas.pixels(), expected, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn composite_at_u8() {
+        let mut canvas = FixedCanvas::<u8, 2, 1>::filled(U8x4Rgba::BLUE);
+        canvas.composite_at(&[U8x4Rgba::RED], 1, 1, 0, &U8PorterDuff::SRC_OVER);
+        assert_eq!(canvas.pixels(), &[U8x4Rgba::BLUE, U8x4Rgba::RED]);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of src_width")]
+    fn composite_at_panics_on_bad_width() {
+        let mut canvas = FixedCanvas::<u8, 2, 2>::default();
+        canvas.composite_at(&[U8x4Rgba::RED; 3], 2, 0, 0, &U8PorterDuff::SRC_OVER);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn canvas_matches_fixed_canvas() {
+        let sprite = [U8x4Rgba::RED.with_alpha(128), U8x4Rgba::GREEN];
+        let mut fixed = FixedCanvas::<u8, 3, 2>::filled(U8x4Rgba::BLUE);
+        let mut canvas = Canvas::filled(3, 2, U8x4Rgba::BLUE);
+        for (x, y) in [(0, 0), (2, 1), (-1, 0), (5, 5)] {
+            fixed.composite_at(&sprite, 1, x, y, &U8PorterDuff::SRC_OVER);
+            canvas.composite_at(&sprite, 1, x, y, &U8PorterDuff::SRC_OVER);
+        }
+        assert_eq!(canvas, Canvas::from(fixed));
+        assert_eq!(canvas.row(1), fixed.row(1));
+
+        canvas.fill(U8x4Rgba::WHITE);
+        canvas.set(2, 1, U8x4Rgba::BLACK);
+        assert_eq!(canvas.get(2, 1), Some(U8x4Rgba::BLACK));
+        assert_eq!(canvas.get(3, 1), None);
+        assert_eq!(
+            Canvas::<f32>::new(2, 1).into_pixels(),
+            [F32x4Rgba::TRANSPARENT; 2]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn surface_is_a_canvas() {
+        use crate::surface::Surface;
+
+        let fixed = FixedCanvas::<f32, 2, 1>::filled(F32x4Rgba::BLUE);
+        let mut surface = Surface::from(fixed);
+        surface.composite_at(&[F32x4Rgba::RED], 1, 1, 0, &BlendMode::SourceOver);
+        let mut expected = Surface::from(fixed);
+        expected.composite(
+            &Surface::filled(1, 1, F32x4Rgba::RED),
+            1,
+            0,
+            BlendMode::SourceOver,
+        );
+        assert_eq!(surface, expected);
+        assert_eq!(surface.pixels(), &[F32x4Rgba::BLUE, F32x4Rgba::RED]);
+    }
+}
@@ -0,0 +1,263 @@
+//! Numeric channel types that colors can be blended in.
+//!
+//! [`PorterDuff`] coefficients and [`RgbaBlend`] are generic over a [`Channel`]: a normalized
+//! number with a zero, a one, and the arithmetic needed to weigh and sum two colors. The crate
+//! implements it for [`f32`], [`f64`], [`u8`] and [`u16`]; implement it for a fixed-point or SIMD
+//! lane type of your own to blend in it without going through `f32`.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     RgbaBlend, bit_depth::BitDepth, channel::Channel, porter_duff::PorterDuff, rgba::Rgba,
+//! };
+//!
+//! /// A 4-bit channel in `[0, 15]`.
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! struct U4(u8);
+//!
+//! impl BitDepth for U4 {
+//!     fn to_unit(self) -> f32 {
+//!         f32::from(self.0) / 15.0
+//!     }
+//!
+//!     fn from_unit(value: f32) -> Self {
+//!         Self((value.clamp(0.0, 1.0) * 15.0 + 0.5) as u8)
+//!     }
+//! }
+//!
+//! impl Channel for U4 {
+//!     const ZERO: Self = Self(0);
+//!     const ONE: Self = Self(15);
+//!
+//!     fn mul(self, rhs: Self) -> Self {
+//!         Self((self.0 * rhs.0 + 7) / 15)
+//!     }
+//!
+//!     fn add(self, rhs: Self) -> Self {
+//!         Self((self.0 + rhs.0).min(15))
+//!     }
+//!
+//!     fn sub(self, rhs: Self) -> Self {
+//!         Self(self.0.saturating_sub(rhs.0))
+//!     }
+//! }
+//!
+//! let src = Rgba::new(U4(15), U4(0), U4(0), U4(15));
+//! let dst = Rgba::new(U4(0), U4(0), U4(15), U4(15));
+//! assert_eq!(PorterDuff::SRC_OVER.apply(src, dst), src);
+//! ```
+//!
+//! [`PorterDuff`]: crate::porter_duff::PorterDuff
+//! [`RgbaBlend`]: crate::RgbaBlend
+
+use crate::{
+    bit_depth::BitDepth,
+    math,
+    rgba::{F32x4Rgba, Rgba},
+    vec4::{F32x4, F32x8},
+};
+
+/// A normalized channel value that can be blended.
+///
+/// [`ONE`](Channel::ONE) is full intensity (and full opacity for alpha). Conversion to and from
+/// `f32`, in `[0.0, 1.0]`, comes from the [`BitDepth`] supertrait.
+pub trait Channel: BitDepth {
+    /// No intensity.
+    const ZERO: Self;
+
+    /// Full intensity.
+    const ONE: Self;
+
+    /// Multiplies two normalized values, so that `x.mul(ONE) == x`.
+    #[must_use]
+    fn mul(self, rhs: Self) -> Self;
+
+    /// Adds two values; integer types saturate at [`ONE`](Channel::ONE).
+    #[must_use]
+    fn add(self, rhs: Self) -> Self;
+
+    /// Subtracts `rhs`; integer types saturate at [`ZERO`](Channel::ZERO).
+    #[must_use]
+    fn sub(self, rhs: Self) -> Self;
+
+    /// Returns `src * fa + dst * fb` for every channel, including alpha.
+    ///
+    /// Default impl calls [`mul`](Channel::mul) and [`add`](Channel::add) per channel.
+    /// Implementations may override with SIMD or other optimized paths.
+    #[must_use]
+    fn weighted_sum(src: Rgba<Self>, fa: Self, dst: Rgba<Self>, fb: Self) -> Rgba<Self> {
+        let channel = |s: Self, d: Self| fa.mul(s).add(fb.mul(d));
+        Rgba::new(
+            channel(src.r, dst.r),
+            channel(src.g, dst.g),
+            channel(src.b, dst.b),
+            channel(src.a, dst.a),
+        )
+    }
+
+    /// Computes [`weighted_sum`](Channel::weighted_sum) for two pixels at once.
+    #[must_use]
+    fn weighted_sum_pair(
+        src: [Rgba<Self>; 2],
+        fa: [Self; 2],
+        dst: [Rgba<Self>; 2],
+        fb: [Self; 2],
+    ) -> [Rgba<Self>; 2] {
+        [
+            Self::weighted_sum(src[0], fa[0], dst[0], fb[0]),
+            Self::weighted_sum(src[1], fa[1], dst[1], fb[1]),
+        ]
+    }
+}
+
+/// Sums are computed four or eight lanes at a time, and are not clamped.
+impl Channel for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn weighted_sum(src: F32x4Rgba, fa: Self, dst: F32x4Rgba, fb: Self) -> F32x4Rgba {
+        let blend = F32x4::splat(fa) * F32x4::from(src) + F32x4::splat(fb) * F32x4::from(dst);
+        blend.into_rgba()
+    }
+
+    fn weighted_sum_pair(
+        src: [F32x4Rgba; 2],
+        fa: [Self; 2],
+        dst: [F32x4Rgba; 2],
+        fb: [Self; 2],
+    ) -> [F32x4Rgba; 2] {
+        let fa = F32x8::splat_halves(fa[0], fa[1]);
+        let fb = F32x8::splat_halves(fb[0], fb[1]);
+        let blend = fa * F32x8::from(src) + fb * F32x8::from(dst);
+        blend.into_pixels()
+    }
+}
+
+/// Sums are not clamped.
+impl Channel for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+}
+
+/// Products are rounded to nearest with [`math::mul_div_255_round`], or looked up in a table
+/// with the `mul-lut` feature.
+impl Channel for u8 {
+    const ZERO: Self = 0;
+    const ONE: Self = Self::MAX;
+
+    fn mul(self, rhs: Self) -> Self {
+        #[cfg(feature = "mul-lut")]
+        use math::mul_div_255_lut as mul_div;
+        #[cfg(not(feature = "mul-lut"))]
+        use math::mul_div_255_round as mul_div;
+
+        mul_div(self, rhs)
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self.saturating_add(rhs)
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self.saturating_sub(rhs)
+    }
+}
+
+/// Products are computed in `u32` and rounded to nearest.
+impl Channel for u16 {
+    const ZERO: Self = 0;
+    const ONE: Self = Self::MAX;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn mul(self, rhs: Self) -> Self {
+        const MAX: u32 = u16::MAX as u32;
+        ((u32::from(self) * u32::from(rhs) + MAX / 2) / MAX) as Self
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self.saturating_add(rhs)
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self.saturating_sub(rhs)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_is_multiplicative_identity() {
+        for v in 0..=u8::MAX {
+            assert_eq!(v.mul(u8::ONE), v);
+            assert_eq!(u16::from(v).mul(u16::ONE), u16::from(v));
+        }
+        assert_eq!(0.25_f32.mul(f32::ONE), 0.25);
+        assert_eq!(0.25_f64.mul(f64::ONE), 0.25);
+    }
+
+    #[test]
+    fn integers_saturate() {
+        assert_eq!(200_u8.add(100), u8::ONE);
+        assert_eq!(100_u8.sub(200), u8::ZERO);
+        assert_eq!(u16::ONE.add(1), u16::ONE);
+        assert_eq!(u16::ZERO.sub(1), u16::ZERO);
+    }
+
+    #[test]
+    fn u16_mul_rounds_to_nearest() {
+        assert_eq!(32768_u16.mul(32768), 16384);
+        assert_eq!(1_u16.mul(32768), 1);
+        assert_eq!(1_u16.mul(32767), 0);
+    }
+
+    #[test]
+    fn f32_weighted_sum_matches_default() {
+        let src = [
+            F32x4Rgba::new(1.0, 0.5, 0.25, 0.5),
+            F32x4Rgba::new(0.0, 0.75, 1.0, 0.25),
+        ];
+        let dst = [F32x4Rgba::BLACK, F32x4Rgba::WHITE];
+        let pair = f32::weighted_sum_pair(src, [0.5, 0.25], dst, [0.5, 0.75]);
+        for i in 0..2 {
+            let fa = [0.5, 0.25][i];
+            let fb = [0.5, 0.75][i];
+            let scalar = |s: f32, d: f32| fa * s + fb * d;
+            let expected = F32x4Rgba::new(
+                scalar(src[i].r, dst[i].r),
+                scalar(src[i].g, dst[i].g),
+                scalar(src[i].b, dst[i].b),
+                scalar(src[i].a, dst[i].a),
+            );
+            assert_eq!(pair[i], expected);
+            assert_eq!(f32::weighted_sum(src[i], fa, dst[i], fb), expected);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Colors tagged with the color space they are encoded in.
+//!
+//! Blending a Display P3 color with an sRGB one treats both sets of numbers as if they meant the
+//! same thing, which shifts saturated colors. A [`SpaceRgba`] carries its [`ColorSpace`] as a
+//! type parameter, so colors of different spaces cannot be blended together by mistake; convert
+//! one to the other first with [`SpaceRgba::convert`], through linear light and CIE XYZ (D65).
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     alpha::AlphaBlend,
+//!     color_space::{DisplayP3, SpaceRgba, Srgb},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let screenshot = SpaceRgba::<DisplayP3>::new(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5));
+//! let ui = SpaceRgba::<Srgb>::new(F32x4Rgba::WHITE);
+//!
+//! let out = BlendMode::SourceOver.blend_pixel(screenshot.convert::<Srgb>(), ui);
+//! // P3 red is outside sRGB, so its sRGB red channel exceeds `1.0`.
+//! assert!(out.into_rgba().r > 1.0);
+//! ```
+//!
+//! Blending colors of different spaces does not compile:
+//!
+//! ```rust,compile_fail
+//! use alpha_blend::{
+//!     BlendMode,
+//!     alpha::AlphaBlend,
+//!     color_space::{DisplayP3, SpaceRgba, Srgb},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let p3 = SpaceRgba::<DisplayP3>::new(F32x4Rgba::WHITE);
+//! let srgb = SpaceRgba::<Srgb>::new(F32x4Rgba::WHITE);
+//! let _ = BlendMode::SourceOver.blend_pixel(p3, srgb);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{RgbaBlend, alpha::AlphaBlend, linear, rgba::F32x4Rgba};
+
+/// An RGB color space: its primaries, as matrices to and from CIE XYZ, and its transfer function.
+///
+/// All spaces here use the D65 white point, so no chromatic adaptation is needed between them.
+pub trait ColorSpace: Copy {
+    /// Converts linear RGB in this space to CIE XYZ, row by row.
+    const TO_XYZ: [[f32; 3]; 3];
+
+    /// Converts CIE XYZ to linear RGB in this space, row by row.
+    const FROM_XYZ: [[f32; 3]; 3];
+
+    /// Decodes a stored channel to linear light.
+    fn to_linear(c: f32) -> f32;
+
+    /// Encodes a linear-light channel for storage.
+    fn from_linear(c: f32) -> f32;
+}
+
+/// sRGB (IEC 61966-2-1): Rec. 709 primaries with the sRGB transfer function.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Srgb;
+
+/// Linear sRGB: Rec. 709 primaries, without a transfer function.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LinearSrgb;
+
+/// Display P3: DCI-P3 primaries with the D65 white point and the sRGB transfer function.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayP3;
+
+const SRGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.412_390_8, 0.357_584_3, 0.180_480_8],
+    [0.212_639, 0.715_168_7, 0.072_192_3],
+    [0.019_330_8, 0.119_194_8, 0.950_532_2],
+];
+
+const XYZ_TO_SRGB: [[f32; 3]; 3] = [
+    [3.240_97, -1.537_383_2, -0.498_610_8],
+    [-0.969_243_6, 1.875_967_5, 0.041_555_1],
+    [0.055_630_1, -0.203_977, 1.056_971_5],
+];
+
+impl ColorSpace for Srgb {
+    const TO_XYZ: [[f32; 3]; 3] = SRGB_TO_XYZ;
+    const FROM_XYZ: [[f32; 3]; 3] = XYZ_TO_SRGB;
+
+    fn to_linear(c: f32) -> f32 {
+        linear::srgb_to_linear(c)
+    }
+
+    fn from_linear(c: f32) -> f32 {
+        linear::linear_to_srgb(c)
+    }
+}
+
+impl ColorSpace for LinearSrgb {
+    const TO_XYZ: [[f32; 3]; 3] = SRGB_TO_XYZ;
+    const FROM_XYZ: [[f32; 3]; 3] = XYZ_TO_SRGB;
+
+    fn to_linear(c: f32) -> f32 {
+        c
+    }
+
+    fn from_linear(c: f32) -> f32 {
+        c
+    }
+}
+
+impl ColorSpace for DisplayP3 {
+    const TO_XYZ: [[f32; 3]; 3] = [
+        [0.486_570_9, 0.265_667_7, 0.198_217_3],
+        [0.228_974_6, 0.691_738_5, 0.079_286_9],
+        [0.0, 0.045_113_4, 1.043_944_4],
+    ];
+    const FROM_XYZ: [[f32; 3]; 3] = [
+        [2.493_497, -0.931_383_6, -0.402_710_8],
+        [-0.829_489, 1.762_664_1, 0.023_624_7],
+        [0.035_845_8, -0.076_172_4, 0.956_884_5],
+    ];
+
+    fn to_linear(c: f32) -> f32 {
+        linear::srgb_to_linear(c)
+    }
+
+    fn from_linear(c: f32) -> f32 {
+        linear::linear_to_srgb(c)
+    }
+}
+
+/// Multiplies the column vector `v` by `m`.
+#[allow(clippy::suboptimal_flops)]
+pub(crate) fn mul3(m: [[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// A straight-alpha color encoded in the color space `S`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(transparent)]
+pub struct SpaceRgba<S: ColorSpace> {
+    color: F32x4Rgba,
+    _space: PhantomData<S>,
+}
+
+impl<S: ColorSpace> SpaceRgba<S> {
+    /// Tags a color as encoded in `S`.
+    #[must_use]
+    pub const fn new(color: F32x4Rgba) -> Self {
+        Self {
+            color,
+            _space: PhantomData,
+        }
+    }
+
+    /// Returns the encoded channels.
+    #[must_use]
+    pub const fn into_rgba(self) -> F32x4Rgba {
+        self.color
+    }
+
+    /// Converts the color to the space `D`, keeping alpha.
+    ///
+    /// Colors outside the gamut of `D` are not clipped: their channels fall outside
+    /// `[0.0, 1.0]`.
+    #[must_use]
+    pub fn convert<D: ColorSpace>(self) -> SpaceRgba<D> {
+        let c = self.color;
+        let linear = [c.r, c.g, c.b].map(S::to_linear);
+        let [r, g, b] = mul3(D::FROM_XYZ, mul3(S::TO_XYZ, linear)).map(D::from_linear);
+        SpaceRgba::new(F32x4Rgba::new(r, g, b, c.a))
+    }
+}
+
+/// Every `f32` blend mode blends colors of one color space, as stored.
+impl<B, S> AlphaBlend<SpaceRgba<S>> for B
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: ColorSpace,
+{
+    fn blend_pixel(&self, src: SpaceRgba<S>, dst: SpaceRgba<S>) -> SpaceRgba<S> {
+        SpaceRgba::new(self.apply(src.color, dst.color))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, test_util::assert_close};
+
+    #[test]
+    fn matrices_are_inverses() {
+        fn check<S: ColorSpace>() {
+            for column in 0..3 {
+                let mut v = [0.0; 3];
+                v[column] = 1.0;
+                let back = mul3(S::FROM_XYZ, mul3(S::TO_XYZ, v));
+                for (x, y) in back.into_iter().zip(v) {
+                    assert!((x - y).abs() < 1e-5);
+                }
+            }
+        }
+        check::<Srgb>();
+        check::<DisplayP3>();
+    }
+
+    #[test]
+    fn white_is_shared() {
+        let white = SpaceRgba::<DisplayP3>::new(F32x4Rgba::WHITE);
+        assert_close(white.convert::<Srgb>().into_rgba(), F32x4Rgba::WHITE, 1e-4);
+        assert_close(
+            white.convert::<LinearSrgb>().into_rgba(),
+            F32x4Rgba::WHITE,
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn srgb_to_linear_srgb_only_decodes() {
+        let c = F32x4Rgba::new(0.5, 0.25, 1.0, 0.5);
+        let converted = SpaceRgba::<Srgb>::new(c).convert::<LinearSrgb>();
+        assert_close(converted.into_rgba(), linear::decode(c), 1e-4);
+    }
+
+    #[test]
+    fn p3_round_trips_through_srgb() {
+        let c = F32x4Rgba::new(1.0, 0.2, 0.6, 0.75);
+        let p3 = SpaceRgba::<DisplayP3>::new(c);
+        let srgb = p3.convert::<Srgb>();
+        assert!(srgb.into_rgba().r > 1.0, "P3 red is outside sRGB");
+        assert_close(srgb.convert::<DisplayP3>().into_rgba(), c, 1e-4);
+    }
+
+    #[test]
+    fn blends_within_a_space() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let dst = F32x4Rgba::new(0.0, 0.0, 1.0, 1.0);
+        let out =
+            BlendMode::Multiply.blend_pixel(SpaceRgba::<DisplayP3>::new(src), SpaceRgba::new(dst));
+        assert_eq!(out.into_rgba(), BlendMode::Multiply.apply(src, dst));
+    }
+}
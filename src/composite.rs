@@ -0,0 +1,560 @@
+//! Whole-image compositing operators.
+//!
+//! The [`Composite`] trait describes an operator that composites a source image onto a
+//! destination image of the same size, given as borrowed, possibly strided [`View`]s and
+//! [`ViewMut`]s. Every per-pixel [`RgbaBlend`], including the built-in [`BlendMode`]s and custom
+//! blends, implements it by blending row by row through [`backend::blend_slice`]; operators that
+//! need the whole image (blurs, effects reading neighbouring pixels, …) can implement it directly.
+//!
+//! Either way, [`composite_at`] positions the source within the destination and clips it to the
+//! destination bounds before invoking the operator, and [`blit`] does the same for a
+//! sub-rectangle of the source.
+//!
+//! [`BlendMode`]: crate::BlendMode
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     composite::{self, View, ViewMut},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let sprite = [F32x4Rgba::WHITE; 4];
+//! let mut canvas = [F32x4Rgba::BLACK; 9];
+//!
+//! // A 2×2 sprite hanging off the top-left corner of a 3×3 canvas.
+//! composite::composite_at(
+//!     &BlendMode::SourceOver,
+//!     View::new(&sprite, 2, 2),
+//!     ViewMut::new(&mut canvas, 3, 3),
+//!     -1,
+//!     -1,
+//! );
+//! assert_eq!(canvas[0], F32x4Rgba::WHITE);
+//! assert_eq!(canvas[1..], [F32x4Rgba::BLACK; 8]);
+//! ```
+
+use crate::{RgbaBlend, backend, rect::Rect, rgba::F32x4Rgba};
+
+/// Returns the number of elements spanned by `height` rows of `width` pixels, `stride` apart.
+const fn span(width: usize, height: usize, stride: usize) -> usize {
+    if height == 0 {
+        0
+    } else {
+        (height - 1) * stride + width
+    }
+}
+
+/// Validates the dimensions of a view over `len` pixels.
+fn check_layout(len: usize, width: usize, height: usize, stride: usize) {
+    assert!(stride >= width, "stride must be at least width");
+    assert!(
+        len >= span(width, height, stride),
+        "buffer is too small for the view"
+    );
+}
+
+/// A borrowed, read-only `width × height` image within a row-major pixel buffer.
+///
+/// Consecutive rows start `stride` pixels apart, so a view can describe a sub-rectangle of a
+/// larger buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a> {
+    pixels: &'a [F32x4Rgba],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> View<'a> {
+    /// Creates a view of a tightly packed `width × height` buffer.
+    ///
+    /// ## Panics
+    ///
+    /// If `pixels` has fewer than `width * height` pixels.
+    #[must_use]
+    pub fn new(pixels: &'a [F32x4Rgba], width: usize, height: usize) -> Self {
+        Self::with_stride(pixels, width, height, width)
+    }
+
+    /// Creates a view whose rows start `stride` pixels apart.
+    ///
+    /// ## Panics
+    ///
+    /// If `stride` is less than `width`, or `pixels` is too small for the view.
+    #[must_use]
+    pub fn with_stride(
+        pixels: &'a [F32x4Rgba],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Self {
+        check_layout(pixels.len(), width, height, stride);
+        Self {
+            pixels,
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Returns the width, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the distance between the starts of consecutive rows, in pixels.
+    #[must_use]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the pixels of row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &'a [F32x4Rgba] {
+        assert!(y < self.height, "row out of bounds");
+        &self.pixels[y * self.stride..][..self.width]
+    }
+
+    /// Returns a view of the sub-rectangle `rect`.
+    ///
+    /// ## Panics
+    ///
+    /// If `rect` does not fit within the view.
+    #[must_use]
+    pub fn subview(self, rect: Rect) -> Self {
+        assert!(
+            rect.right() <= self.width && rect.bottom() <= self.height,
+            "rect must fit within the view"
+        );
+        let start = rect.y * self.stride + rect.x;
+        let len = span(rect.width, rect.height, self.stride);
+        Self::with_stride(
+            &self.pixels[start..][..len],
+            rect.width,
+            rect.height,
+            self.stride,
+        )
+    }
+}
+
+/// A borrowed, mutable `width × height` image within a row-major pixel buffer.
+///
+/// See [`View`].
+#[derive(Debug)]
+pub struct ViewMut<'a> {
+    pixels: &'a mut [F32x4Rgba],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> ViewMut<'a> {
+    /// Creates a view of a tightly packed `width × height` buffer.
+    ///
+    /// ## Panics
+    ///
+    /// If `pixels` has fewer than `width * height` pixels.
+    #[must_use]
+    pub fn new(pixels: &'a mut [F32x4Rgba], width: usize, height: usize) -> Self {
+        Self::with_stride(pixels, width, height, width)
+    }
+
+    /// Creates a view whose rows start `stride` pixels apart.
+    ///
+    /// ## Panics
+    ///
+    /// If `stride` is less than `width`, or `pixels` is too small for the view.
+    #[must_use]
+    pub fn with_stride(
+        pixels: &'a mut [F32x4Rgba],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Self {
+        check_layout(pixels.len(), width, height, stride);
+        Self {
+            pixels,
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Returns the width, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the distance between the starts of consecutive rows, in pixels.
+    #[must_use]
+    pub const fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the pixels of row `y`.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &[F32x4Rgba] {
+        assert!(y < self.height, "row out of bounds");
+        &self.pixels[y * self.stride..][..self.width]
+    }
+
+    /// Returns the pixels of row `y` for modification.
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [F32x4Rgba] {
+        assert!(y < self.height, "row out of bounds");
+        &mut self.pixels[y * self.stride..][..self.width]
+    }
+
+    /// Returns a read-only view of the same pixels.
+    #[must_use]
+    pub fn as_view(&self) -> View<'_> {
+        View::with_stride(self.pixels, self.width, self.height, self.stride)
+    }
+
+    /// Returns a shorter-lived view of the same pixels, leaving `self` usable afterwards.
+    #[must_use]
+    pub fn reborrow(&mut self) -> ViewMut<'_> {
+        ViewMut::with_stride(self.pixels, self.width, self.height, self.stride)
+    }
+
+    /// Returns a view of the sub-rectangle `rect`.
+    ///
+    /// ## Panics
+    ///
+    /// If `rect` does not fit within the view.
+    #[must_use]
+    pub fn subview(self, rect: Rect) -> Self {
+        assert!(
+            rect.right() <= self.width && rect.bottom() <= self.height,
+            "rect must fit within the view"
+        );
+        let start = rect.y * self.stride + rect.x;
+        let len = span(rect.width, rect.height, self.stride);
+        Self::with_stride(
+            &mut self.pixels[start..][..len],
+            rect.width,
+            rect.height,
+            self.stride,
+        )
+    }
+}
+
+/// An operator compositing a whole source image onto a destination image.
+pub trait Composite {
+    /// Composites `src` onto `dst` in place.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same dimensions.
+    fn composite(&self, src: View<'_>, dst: ViewMut<'_>);
+}
+
+impl<B> Composite for B
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    fn composite(&self, src: View<'_>, mut dst: ViewMut<'_>) {
+        assert_eq!(
+            (src.width(), src.height()),
+            (dst.width(), dst.height()),
+            "src and dst views must have the same dimensions"
+        );
+        for y in 0..src.height() {
+            backend::blend_slice(self, src.row(y), dst.row_mut(y));
+        }
+    }
+}
+
+/// Composites `src` onto `dst` with its top-left corner at `(x, y)`, clipping to `dst`.
+///
+/// Offsets may be negative, or place `src` partially or entirely outside `dst`; only the
+/// overlapping region is passed to `op`, which is not called at all if the images do not overlap.
+pub fn composite_at<O>(op: &O, src: View<'_>, dst: ViewMut<'_>, x: isize, y: isize)
+where
+    O: Composite + ?Sized,
+{
+    let Some((src_rect, dst_rect)) = clip(
+        (src.width(), src.height()),
+        (dst.width(), dst.height()),
+        x,
+        y,
+    ) else {
+        return;
+    };
+    op.composite(src.subview(src_rect), dst.subview(dst_rect));
+}
+
+/// Clips a `src` image placed at `(x, y)` on a `dst` image, returning the overlapping regions of
+/// each, or `None` if they do not overlap.
+pub(crate) fn clip(
+    (src_width, src_height): (usize, usize),
+    (dst_width, dst_height): (usize, usize),
+    x: isize,
+    y: isize,
+) -> Option<(Rect, Rect)> {
+    let (src_x, dst_x) = if x < 0 {
+        (x.unsigned_abs(), 0)
+    } else {
+        (0, x.unsigned_abs())
+    };
+    let (src_y, dst_y) = if y < 0 {
+        (y.unsigned_abs(), 0)
+    } else {
+        (0, y.unsigned_abs())
+    };
+    let width = src_width
+        .saturating_sub(src_x)
+        .min(dst_width.saturating_sub(dst_x));
+    let height = src_height
+        .saturating_sub(src_y)
+        .min(dst_height.saturating_sub(dst_y));
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some((
+        Rect::new(src_x, src_y, width, height),
+        Rect::new(dst_x, dst_y, width, height),
+    ))
+}
+
+/// Composites the `src_rect` region of `src` onto `dst` with its top-left corner at `(x, y)`.
+///
+/// This is [`composite_at`] on a sub-rectangle: `src_rect` is first clipped to `src`, then the
+/// region is clipped to `dst`. Both views may be strided, for example to blit a sprite from an
+/// atlas into a framebuffer with padded rows.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{
+///     BlendMode,
+///     composite::{self, View, ViewMut},
+///     rect::Rect,
+///     rgba::F32x4Rgba,
+/// };
+///
+/// // A 2×1 atlas, and a 2×2 framebuffer with rows 3 pixels apart.
+/// let atlas = [F32x4Rgba::WHITE, F32x4Rgba::TRANSPARENT];
+/// let mut framebuffer = [F32x4Rgba::BLACK; 5];
+///
+/// composite::blit(
+///     &BlendMode::SourceOver,
+///     View::new(&atlas, 2, 1),
+///     Rect::new(0, 0, 1, 1),
+///     ViewMut::with_stride(&mut framebuffer, 2, 2, 3),
+///     1,
+///     1,
+/// );
+/// assert_eq!(framebuffer[4], F32x4Rgba::WHITE);
+/// ```
+pub fn blit<O>(op: &O, src: View<'_>, src_rect: Rect, dst: ViewMut<'_>, x: isize, y: isize)
+where
+    O: Composite + ?Sized,
+{
+    let src_x = src_rect.x.min(src.width());
+    let src_y = src_rect.y.min(src.height());
+    let width = src_rect.width.min(src.width() - src_x);
+    let height = src_rect.height.min(src.height() - src_y);
+    composite_at(
+        op,
+        src.subview(Rect::new(src_x, src_y, width, height)),
+        dst,
+        x,
+        y,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    /// A whole-image operator that mirrors the source horizontally.
+    struct Mirror;
+
+    impl Composite for Mirror {
+        fn composite(&self, src: View<'_>, mut dst: ViewMut<'_>) {
+            for y in 0..src.height() {
+                for (d, s) in dst.row_mut(y).iter_mut().zip(src.row(y).iter().rev()) {
+                    *d = *s;
+                }
+            }
+        }
+    }
+
+    const RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+
+    #[test]
+    fn blend_modes_composite_row_by_row() {
+        let src = [RED, F32x4Rgba::TRANSPARENT, F32x4Rgba::WHITE, RED];
+        let mut expected = [F32x4Rgba::BLACK; 4];
+        BlendMode::SourceOver.apply_slice(&src, &mut expected);
+
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        BlendMode::SourceOver.composite(View::new(&src, 2, 2), ViewMut::new(&mut dst, 2, 2));
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn strided_views_skip_padding() {
+        let src = [RED, RED, F32x4Rgba::WHITE, RED, RED, F32x4Rgba::WHITE];
+        let mut dst = [F32x4Rgba::BLACK; 6];
+        BlendMode::Source.composite(
+            View::with_stride(&src, 2, 2, 3),
+            ViewMut::with_stride(&mut dst, 2, 2, 3),
+        );
+        assert_eq!(
+            dst,
+            [RED, RED, F32x4Rgba::BLACK, RED, RED, F32x4Rgba::BLACK]
+        );
+    }
+
+    #[test]
+    fn composite_at_clips_to_destination() {
+        let src = [RED; 4];
+        let mut dst = [F32x4Rgba::BLACK; 9];
+        composite_at(
+            &BlendMode::Source,
+            View::new(&src, 2, 2),
+            ViewMut::new(&mut dst, 3, 3),
+            2,
+            1,
+        );
+        let painted: [bool; 9] = core::array::from_fn(|i| dst[i] == RED);
+        assert_eq!(
+            painted,
+            [false, false, false, false, false, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn composite_at_skips_disjoint_images() {
+        let src = [RED; 4];
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        let mut view = ViewMut::new(&mut dst, 2, 2);
+        composite_at(&Mirror, View::new(&src, 2, 2), view.reborrow(), 2, 0);
+        composite_at(&Mirror, View::new(&src, 2, 2), view.reborrow(), -2, 0);
+        assert_eq!(dst, [F32x4Rgba::BLACK; 4]);
+    }
+
+    #[test]
+    fn custom_operator_sees_clipped_views() {
+        let src = [RED, F32x4Rgba::WHITE, F32x4Rgba::BLACK];
+        let mut dst = [F32x4Rgba::TRANSPARENT; 2];
+        composite_at(
+            &Mirror,
+            View::new(&src, 3, 1),
+            ViewMut::new(&mut dst, 2, 1),
+            -1,
+            0,
+        );
+        assert_eq!(dst, [F32x4Rgba::BLACK, F32x4Rgba::WHITE]);
+    }
+
+    #[test]
+    fn blit_copies_sub_rectangle_into_strided_destination() {
+        // A 3×2 atlas with the sprite in its right two columns.
+        let atlas = [
+            F32x4Rgba::BLACK,
+            RED,
+            F32x4Rgba::WHITE,
+            F32x4Rgba::BLACK,
+            F32x4Rgba::WHITE,
+            RED,
+        ];
+        // A 3×2 framebuffer with one padding pixel per row.
+        let mut dst = [F32x4Rgba::TRANSPARENT; 8];
+        blit(
+            &BlendMode::Source,
+            View::new(&atlas, 3, 2),
+            Rect::new(1, 0, 2, 2),
+            ViewMut::with_stride(&mut dst, 3, 2, 4),
+            1,
+            0,
+        );
+        let t = F32x4Rgba::TRANSPARENT;
+        assert_eq!(
+            dst,
+            [t, RED, F32x4Rgba::WHITE, t, t, F32x4Rgba::WHITE, RED, t]
+        );
+    }
+
+    #[test]
+    fn blit_clips_source_rect_and_destination() {
+        let atlas = [RED; 4];
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        blit(
+            &BlendMode::Source,
+            View::new(&atlas, 2, 2),
+            Rect::new(1, 1, 5, 5),
+            ViewMut::new(&mut dst, 2, 2),
+            -1,
+            1,
+        );
+        assert_eq!(dst, [F32x4Rgba::BLACK; 4], "clipped region is off-screen");
+
+        blit(
+            &BlendMode::Source,
+            View::new(&atlas, 2, 2),
+            Rect::new(1, 1, 5, 5),
+            ViewMut::new(&mut dst, 2, 2),
+            1,
+            1,
+        );
+        assert_eq!(
+            dst,
+            [F32x4Rgba::BLACK, F32x4Rgba::BLACK, F32x4Rgba::BLACK, RED]
+        );
+
+        blit(
+            &BlendMode::Source,
+            View::new(&atlas, 2, 2),
+            Rect::new(3, 0, 1, 1),
+            ViewMut::new(&mut dst, 2, 2),
+            0,
+            0,
+        );
+        assert_eq!(dst[0], F32x4Rgba::BLACK, "rect outside the source is empty");
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimensions")]
+    fn composite_panics_on_size_mismatch() {
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        BlendMode::Source.composite(View::new(&[RED; 2], 1, 2), ViewMut::new(&mut dst, 2, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn view_panics_on_short_buffer() {
+        let _ = View::with_stride(&[RED; 4], 2, 2, 3);
+    }
+}
@@ -0,0 +1,968 @@
+//! Layer-stack compositing.
+//!
+//! A [`LayerStack`] holds equally-sized layers of straight-alpha [`F32x4Rgba`] pixels, each with
+//! a [`BlendMode`] and an opacity, and flattens them bottom-to-top onto a transparent background.
+//!
+//! Interactive applications typically edit one layer at a time; a [`FlattenCache`] keeps the
+//! flattened result of the unchanged layers below the edit, so each frame only re-composites the
+//! layers that changed and those above them. Stacks with large opaque top layers can instead use
+//! [`LayerStack::flatten_front_to_back`], which skips everything hidden below them.
+//!
+//! A [`Group`] composites a whole stack onto a backdrop as one element, as PDF transparency
+//! groups do: isolated from the backdrop or blending with it, and optionally knocking out the
+//! layers below each layer.
+//!
+//! _This module requires the `alloc` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     compositor::{FlattenCache, LayerStack},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let mut stack = LayerStack::new(2, 1);
+//! stack.push(vec![F32x4Rgba::BLACK; 2], BlendMode::SourceOver);
+//! let top = stack.push(vec![F32x4Rgba::TRANSPARENT; 2], BlendMode::SourceOver);
+//!
+//! let mut cache = FlattenCache::new();
+//! assert_eq!(cache.flatten(&stack), &[F32x4Rgba::BLACK; 2]);
+//!
+//! // Editing the top layer only re-composites the top layer.
+//! stack.layer_mut(top).unwrap().pixels_mut()[0] = F32x4Rgba::WHITE;
+//! assert_eq!(cache.flatten(&stack), &[F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+//! ```
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{BlendMode, RgbaBlend, math, rgba::F32x4Rgba, vec4::F32x4};
+
+/// Identifies the content of a [`Layer`] at a point in time.
+///
+/// Every layer has a unique id, and a version that changes whenever its pixels, blend mode or
+/// opacity may have changed; two equal keys always refer to identical layer content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerKey {
+    id: usize,
+    version: u64,
+}
+
+impl LayerKey {
+    /// Returns a key with a new, process-wide unique id.
+    fn unique() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            version: 0,
+        }
+    }
+}
+
+/// A single layer of a [`LayerStack`].
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pixels: Vec<F32x4Rgba>,
+    mode: BlendMode,
+    opacity: f32,
+    key: LayerKey,
+}
+
+impl Layer {
+    /// Returns the pixels of the layer.
+    #[must_use]
+    pub fn pixels(&self) -> &[F32x4Rgba] {
+        &self.pixels
+    }
+
+    /// Returns the pixels of the layer for modification.
+    ///
+    /// The layer is [invalidated](Layer::invalidate), as its content may change.
+    pub fn pixels_mut(&mut self) -> &mut [F32x4Rgba] {
+        self.invalidate();
+        &mut self.pixels
+    }
+
+    /// Returns the blend mode used to composite the layer onto the layers below it.
+    #[must_use]
+    pub const fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    /// Sets the blend mode used to composite the layer onto the layers below it.
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.invalidate();
+        }
+    }
+
+    /// Returns the opacity the layer's alpha is multiplied by when compositing, in `[0.0, 1.0]`.
+    ///
+    /// New layers are fully opaque (`1.0`).
+    #[must_use]
+    pub const fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Sets the opacity the layer's alpha is multiplied by when compositing, clamped to
+    /// `[0.0, 1.0]`.
+    pub const fn set_opacity(&mut self, opacity: f32) {
+        let opacity = math::clamp01(opacity);
+        if self.opacity.to_bits() != opacity.to_bits() {
+            self.opacity = opacity;
+            self.invalidate();
+        }
+    }
+
+    /// Returns the pixel at `index`, with its alpha multiplied by the layer opacity.
+    fn pixel(&self, index: usize) -> F32x4Rgba {
+        let p = self.pixels[index];
+        F32x4Rgba::new(p.r, p.g, p.b, p.a * self.opacity)
+    }
+
+    /// Returns the key identifying the current content of the layer.
+    #[must_use]
+    pub const fn key(&self) -> LayerKey {
+        self.key
+    }
+
+    /// Marks the layer as changed, so any cached result that includes it is recomputed.
+    ///
+    /// Called automatically by [`pixels_mut`](Layer::pixels_mut), [`set_mode`](Layer::set_mode)
+    /// and [`set_opacity`](Layer::set_opacity).
+    pub const fn invalidate(&mut self) {
+        self.key.version = self.key.version.wrapping_add(1);
+    }
+}
+
+/// An ordered stack of equally-sized layers, from bottom (index `0`) to top.
+#[derive(Debug, Clone)]
+pub struct LayerStack {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+}
+
+impl LayerStack {
+    /// Creates an empty stack of `width` × `height` layers.
+    #[must_use]
+    pub const fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Width of each layer, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of each layer, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of layers in the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if the stack has no layers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns the layers, from bottom to top.
+    #[must_use]
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the layer at `index`, or `None` if out of bounds.
+    #[must_use]
+    pub fn layer(&self, index: usize) -> Option<&Layer> {
+        self.layers.get(index)
+    }
+
+    /// Returns the layer at `index` for modification, or `None` if out of bounds.
+    #[must_use]
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Layer> {
+        self.layers.get_mut(index)
+    }
+
+    /// Adds a layer on top of the stack, returning its index.
+    ///
+    /// ## Panics
+    ///
+    /// If `pixels.len() != width * height`.
+    pub fn push(&mut self, pixels: Vec<F32x4Rgba>, mode: BlendMode) -> usize {
+        assert_eq!(
+            pixels.len(),
+            self.width * self.height,
+            "layer must have width * height pixels"
+        );
+        self.layers.push(Layer {
+            pixels,
+            mode,
+            opacity: 1.0,
+            key: LayerKey::unique(),
+        });
+        self.layers.len() - 1
+    }
+
+    /// Removes and returns the layer at `index`, shifting the layers above it down.
+    ///
+    /// ## Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Layer {
+        self.layers.remove(index)
+    }
+
+    /// Flattens all layers bottom-to-top into `out`, without caching.
+    ///
+    /// ## Panics
+    ///
+    /// If `out.len() != width * height`.
+    pub fn flatten(&self, out: &mut [F32x4Rgba]) {
+        assert_eq!(
+            out.len(),
+            self.width * self.height,
+            "out must have width * height pixels"
+        );
+        out.fill(F32x4Rgba::TRANSPARENT);
+        composite(&self.layers, out);
+    }
+
+    /// Flattens all layers into `out` front-to-back, stopping per pixel at the first layer that
+    /// hides everything below it.
+    ///
+    /// Each layer is applied with the "under" form of its blend mode: the accumulated result of
+    /// the layers above is kept along with the fraction of the layers below that still shows
+    /// through, and once that fraction reaches zero (e.g. at an opaque
+    /// [`SourceOver`](BlendMode::SourceOver) pixel) the remaining layers are skipped. This is
+    /// much faster than [`flatten`](LayerStack::flatten) for stacks with large opaque top layers.
+    ///
+    /// Only modes whose destination coefficient depends solely on the source pixel can be
+    /// reversed ([`Clear`](BlendMode::Clear), [`Source`](BlendMode::Source),
+    /// [`Destination`](BlendMode::Destination), [`SourceOver`](BlendMode::SourceOver),
+    /// [`DestinationIn`](BlendMode::DestinationIn), [`DestinationOut`](BlendMode::DestinationOut)
+    /// and [`Plus`](BlendMode::Plus)). Layers up to and including the topmost layer using any
+    /// other mode are flattened back-to-front first, and the remaining layers front-to-back on
+    /// top of that result.
+    ///
+    /// The result matches [`flatten`](LayerStack::flatten), up to floating-point rounding.
+    ///
+    /// ## Panics
+    ///
+    /// If `out.len() != width * height`.
+    pub fn flatten_front_to_back(&self, out: &mut [F32x4Rgba]) {
+        assert_eq!(
+            out.len(),
+            self.width * self.height,
+            "out must have width * height pixels"
+        );
+        let split = self
+            .layers
+            .iter()
+            .rposition(|layer| under_coefficients(layer.mode, 0.0).is_none())
+            .map_or(0, |index| index + 1);
+        let (below, above) = self.layers.split_at(split);
+
+        out.fill(F32x4Rgba::TRANSPARENT);
+        composite(below, out);
+
+        for (i, dst) in out.iter_mut().enumerate() {
+            let mut acc = F32x4::zeroed();
+            let mut transmittance = 1.0;
+            for layer in above.iter().rev() {
+                let src = layer.pixel(i);
+                let Some((src_f, dst_f)) = under_coefficients(layer.mode, src.a) else {
+                    unreachable!("layers above the split are reversible");
+                };
+                acc = acc + F32x4::from(src) * (transmittance * src_f);
+                transmittance *= dst_f;
+                if transmittance == 0.0 {
+                    break;
+                }
+            }
+            *dst = (acc + F32x4::from(*dst) * transmittance).into_rgba();
+        }
+    }
+}
+
+/// Composites `layers` bottom-to-top onto `dst`.
+fn composite(layers: &[Layer], dst: &mut [F32x4Rgba]) {
+    composite_range(layers, dst, 0);
+}
+
+/// Composites the pixels `start..start + dst.len()` of `layers` bottom-to-top onto `dst`.
+pub(crate) fn composite_range(layers: &[Layer], dst: &mut [F32x4Rgba], start: usize) {
+    for layer in layers {
+        if layer.opacity >= 1.0 {
+            layer
+                .mode
+                .apply_slice(&layer.pixels[start..start + dst.len()], dst);
+        } else {
+            for (i, d) in dst.iter_mut().enumerate() {
+                *d = layer.mode.apply(layer.pixel(start + i), *d);
+            }
+        }
+    }
+}
+
+/// Returns the Porter-Duff `(src, dst)` coefficients of `mode` for a source alpha of `src_a`,
+/// or `None` if they depend on the destination alpha and so cannot be applied front-to-back.
+fn under_coefficients(mode: BlendMode, src_a: f32) -> Option<(f32, f32)> {
+    match mode {
+        BlendMode::Clear => Some((0.0, 0.0)),
+        BlendMode::Source => Some((1.0, 0.0)),
+        BlendMode::Destination => Some((0.0, 1.0)),
+        BlendMode::SourceOver => Some((src_a, 1.0 - src_a)),
+        BlendMode::DestinationIn => Some((0.0, src_a)),
+        BlendMode::DestinationOut => Some((0.0, 1.0 - src_a)),
+        BlendMode::Plus => Some((1.0, 1.0)),
+        _ => None,
+    }
+}
+
+/// Caches flattened sub-stacks of a [`LayerStack`] between calls to
+/// [`flatten`](FlattenCache::flatten).
+///
+/// The cache keeps two buffers: the last flattened output, and the flattened result of the
+/// layers below the lowest layer that changed between the last two calls. While edits stay on
+/// the same layer (the common case when painting), only that layer and the layers above it are
+/// re-composited; if nothing changed, the previous output is returned as-is.
+///
+/// Changes are detected through each layer's [`LayerKey`]; adding, removing or reordering layers
+/// is handled the same way as editing them.
+#[derive(Debug, Clone, Default)]
+pub struct FlattenCache {
+    prefix: Vec<F32x4Rgba>,
+    prefix_keys: Vec<LayerKey>,
+    output: Vec<F32x4Rgba>,
+    output_keys: Vec<LayerKey>,
+}
+
+impl FlattenCache {
+    /// Creates an empty cache.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            prefix: Vec::new(),
+            prefix_keys: Vec::new(),
+            output: Vec::new(),
+            output_keys: Vec::new(),
+        }
+    }
+
+    /// Discards all cached results, so the next call to [`flatten`](FlattenCache::flatten)
+    /// re-composites every layer.
+    pub fn invalidate(&mut self) {
+        self.prefix_keys.clear();
+        self.output_keys.clear();
+    }
+
+    /// Discards cached results that include the layer at `index` or any layer above it.
+    pub fn invalidate_from(&mut self, index: usize) {
+        if self.prefix_keys.len() > index {
+            self.prefix_keys.clear();
+        }
+        self.output_keys.truncate(index);
+    }
+
+    /// Flattens `stack`, re-compositing only the layers that changed since the last call.
+    ///
+    /// The result is identical to [`LayerStack::flatten`].
+    pub fn flatten(&mut self, stack: &LayerStack) -> &[F32x4Rgba] {
+        let len = stack.width * stack.height;
+        if self.output.len() != len {
+            self.invalidate();
+            self.prefix.resize(len, F32x4Rgba::TRANSPARENT);
+            self.output.resize(len, F32x4Rgba::TRANSPARENT);
+        }
+
+        let keys = stack.layers.iter().map(Layer::key);
+        let unchanged = self
+            .output_keys
+            .iter()
+            .zip(keys.clone())
+            .take_while(|(cached, key)| *cached == key)
+            .count();
+        if unchanged == self.output_keys.len() && unchanged == stack.layers.len() {
+            return &self.output;
+        }
+
+        // The prefix only ever covers layers that were unchanged in the previous output, so it
+        // is still valid if it does not extend past the first changed layer.
+        if self.prefix_keys.len() > unchanged {
+            self.prefix_keys.clear();
+        }
+        if self.prefix_keys.is_empty() {
+            self.prefix.fill(F32x4Rgba::TRANSPARENT);
+        }
+        let below = &stack.layers[self.prefix_keys.len()..unchanged];
+        composite(below, &mut self.prefix);
+        self.prefix_keys.extend(below.iter().map(Layer::key));
+
+        self.output.copy_from_slice(&self.prefix);
+        composite(&stack.layers[unchanged..], &mut self.output);
+        self.output_keys.clear();
+        self.output_keys.extend(keys);
+        &self.output
+    }
+}
+
+/// A [`LayerStack`] composited as a single element, with the transparency group semantics of
+/// PDF.
+///
+/// An _isolated_ group composites its layers onto a transparent backdrop, so their blend modes
+/// only interact with each other, and then blends the result onto the backdrop with the group's
+/// mode and opacity. A non-isolated group (the default, as in PDF) composites its layers onto the
+/// backdrop itself, so a [`Multiply`](BlendMode::Multiply) layer darkens what is below the group,
+/// and removes the backdrop's contribution from the result before blending it.
+///
+/// In a _knockout_ group, each layer composites with the group's initial backdrop rather than
+/// with the layers below it in the group, and replaces them wherever its pixels have non-zero
+/// alpha (their shape, regardless of the layer opacity). Otherwise layers accumulate as in
+/// [`LayerStack::flatten`].
+///
+/// Backdrop removal uses the formulas of ISO 32000-1, 11.4.8, which assume layers blend with
+/// separable or non-separable modes; other Porter-Duff modes have no PDF equivalent, and only
+/// behave predictably in isolated groups.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{
+///     BlendMode,
+///     compositor::{Group, LayerStack},
+///     rgba::F32x4Rgba,
+/// };
+///
+/// let mut stack = LayerStack::new(1, 1);
+/// stack.push(vec![F32x4Rgba::RED], BlendMode::Multiply);
+/// let gray = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+///
+/// // The layer multiplies the backdrop below the group...
+/// let mut backdrop = [gray];
+/// Group::new(stack.clone()).composite(&mut backdrop);
+/// assert_eq!(backdrop, [F32x4Rgba::new(0.5, 0.0, 0.0, 1.0)]);
+///
+/// // ...unless the group is isolated, where there is nothing below it to multiply.
+/// let mut backdrop = [gray];
+/// Group::new(stack).with_isolated(true).composite(&mut backdrop);
+/// assert_eq!(backdrop, [F32x4Rgba::RED]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Group {
+    stack: LayerStack,
+    mode: BlendMode,
+    opacity: f32,
+    isolated: bool,
+    knockout: bool,
+}
+
+impl Group {
+    /// Creates a non-isolated, non-knockout group of the layers of `stack`, blended onto the
+    /// backdrop with [`SourceOver`](BlendMode::SourceOver) at full opacity.
+    #[must_use]
+    pub const fn new(stack: LayerStack) -> Self {
+        Self {
+            stack,
+            mode: BlendMode::SourceOver,
+            opacity: 1.0,
+            isolated: false,
+            knockout: false,
+        }
+    }
+
+    /// Returns this group, blended onto the backdrop with `mode`.
+    #[must_use]
+    pub const fn with_mode(mut self, mode: BlendMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns this group, with its alpha multiplied by `opacity` clamped to `[0.0, 1.0]`.
+    #[must_use]
+    pub const fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = math::clamp01(opacity);
+        self
+    }
+
+    /// Returns this group, isolated from the backdrop or not.
+    #[must_use]
+    pub const fn with_isolated(mut self, isolated: bool) -> Self {
+        self.isolated = isolated;
+        self
+    }
+
+    /// Returns this group, with knockout or not.
+    #[must_use]
+    pub const fn with_knockout(mut self, knockout: bool) -> Self {
+        self.knockout = knockout;
+        self
+    }
+
+    /// Returns the layers of the group.
+    #[must_use]
+    pub const fn stack(&self) -> &LayerStack {
+        &self.stack
+    }
+
+    /// Returns the layers of the group for modification.
+    #[must_use]
+    pub const fn stack_mut(&mut self) -> &mut LayerStack {
+        &mut self.stack
+    }
+
+    /// Returns the blend mode used to composite the group onto the backdrop.
+    #[must_use]
+    pub const fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    /// Returns the opacity the group's alpha is multiplied by, in `[0.0, 1.0]`.
+    #[must_use]
+    pub const fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Returns `true` if the layers of the group composite onto a transparent backdrop.
+    #[must_use]
+    pub const fn is_isolated(&self) -> bool {
+        self.isolated
+    }
+
+    /// Returns `true` if each layer of the group replaces the layers below it.
+    #[must_use]
+    pub const fn is_knockout(&self) -> bool {
+        self.knockout
+    }
+
+    /// Composites the group onto `backdrop`.
+    ///
+    /// ## Panics
+    ///
+    /// If `backdrop.len() != width * height` of the group's layers.
+    pub fn composite(&self, backdrop: &mut [F32x4Rgba]) {
+        assert_eq!(
+            backdrop.len(),
+            self.stack.width * self.stack.height,
+            "backdrop must have width * height pixels"
+        );
+        self.composite_range(backdrop, 0);
+    }
+
+    /// Composites the pixels `start..start + dst.len()` of the group onto `dst`.
+    pub(crate) fn composite_range(&self, dst: &mut [F32x4Rgba], start: usize) {
+        for (i, d) in dst.iter_mut().enumerate() {
+            *d = self.composite_pixel(start + i, *d);
+        }
+    }
+
+    /// Returns the pixel at `index` of the group composited onto `backdrop`.
+    #[allow(clippy::suboptimal_flops)]
+    fn composite_pixel(&self, index: usize, backdrop: F32x4Rgba) -> F32x4Rgba {
+        let initial = if self.isolated {
+            F32x4Rgba::TRANSPARENT
+        } else {
+            backdrop
+        };
+        let mut result = initial;
+        // The alpha of the layers alone, without the backdrop.
+        let mut alpha = 0.0;
+        for layer in &self.stack.layers {
+            let src = layer.pixel(index);
+            if !self.knockout {
+                result = layer.mode.apply(src, result);
+                alpha = alpha + src.a - alpha * src.a;
+            } else if layer.pixels[index].a > 0.0 {
+                result = layer.mode.apply(src, initial);
+                alpha = src.a;
+            }
+        }
+        let group = if self.isolated {
+            result
+        } else {
+            remove_backdrop(result, backdrop, alpha)
+        };
+        let group = F32x4Rgba::new(group.r, group.g, group.b, group.a * self.opacity);
+        self.mode.apply(group, backdrop)
+    }
+}
+
+/// Returns the color of a group alone, from `result`, the group composited onto `backdrop`, and
+/// `alpha`, the alpha of the group alone.
+#[allow(clippy::suboptimal_flops)]
+fn remove_backdrop(result: F32x4Rgba, backdrop: F32x4Rgba, alpha: f32) -> F32x4Rgba {
+    if alpha <= 0.0 {
+        return F32x4Rgba::TRANSPARENT;
+    }
+    let k = backdrop.a / alpha - backdrop.a;
+    let channel = |c: f32, c0: f32| math::clamp01(c + (c - c0) * k);
+    F32x4Rgba::new(
+        channel(result.r, backdrop.r),
+        channel(result.g, backdrop.g),
+        channel(result.b, backdrop.b),
+        alpha,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn stack_3x1() -> LayerStack {
+        let mut stack = LayerStack::new(3, 1);
+        stack.push(vec![F32x4Rgba::BLACK; 3], BlendMode::SourceOver);
+        stack.push(
+            vec![F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); 3],
+            BlendMode::SourceOver,
+        );
+        stack.push(vec![F32x4Rgba::TRANSPARENT; 3], BlendMode::SourceOver);
+        stack
+    }
+
+    fn uncached(stack: &LayerStack) -> Vec<F32x4Rgba> {
+        let mut out = vec![F32x4Rgba::WHITE; stack.width() * stack.height()];
+        stack.flatten(&mut out);
+        out
+    }
+
+    #[test]
+    fn flatten_composites_bottom_to_top() {
+        let out = uncached(&stack_3x1());
+        assert_eq!(out, vec![F32x4Rgba::new(0.5, 0.0, 0.0, 0.75); 3]);
+    }
+
+    #[test]
+    fn flatten_empty_stack_is_transparent() {
+        let out = uncached(&LayerStack::new(2, 2));
+        assert_eq!(out, vec![F32x4Rgba::TRANSPARENT; 4]);
+    }
+
+    fn assert_approx_eq(a: &[F32x4Rgba], b: &[F32x4Rgba]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            let close = x
+                .as_ref()
+                .iter()
+                .zip(y.as_ref())
+                .all(|(p, q)| (p - q).abs() < 1e-6);
+            assert!(close, "{x} != {y}");
+        }
+    }
+
+    fn front_to_back(stack: &LayerStack) -> Vec<F32x4Rgba> {
+        let mut out = vec![F32x4Rgba::WHITE; stack.width() * stack.height()];
+        stack.flatten_front_to_back(&mut out);
+        out
+    }
+
+    #[test]
+    fn front_to_back_matches_flatten() {
+        let mut stack = stack_3x1();
+        stack.layer_mut(2).unwrap().pixels_mut()[0] = F32x4Rgba::new(0.0, 1.0, 0.0, 0.25);
+        assert_approx_eq(&front_to_back(&stack), &uncached(&stack));
+
+        stack.push(vec![F32x4Rgba::new(0.2, 0.4, 0.6, 0.8); 3], BlendMode::Plus);
+        stack.push(
+            vec![F32x4Rgba::new(0.0, 0.0, 0.0, 0.5); 3],
+            BlendMode::DestinationOut,
+        );
+        stack.push(
+            vec![F32x4Rgba::new(0.0, 0.0, 0.0, 0.5); 3],
+            BlendMode::DestinationIn,
+        );
+        assert_approx_eq(&front_to_back(&stack), &uncached(&stack));
+    }
+
+    #[test]
+    fn front_to_back_falls_back_below_irreversible_modes() {
+        let mut stack = stack_3x1();
+        stack.layer_mut(1).unwrap().set_mode(BlendMode::SourceAtop);
+        stack.push(
+            vec![F32x4Rgba::new(0.0, 0.0, 1.0, 0.5); 3],
+            BlendMode::SourceOver,
+        );
+        assert_approx_eq(&front_to_back(&stack), &uncached(&stack));
+
+        stack.push(vec![F32x4Rgba::new(0.0, 0.0, 1.0, 0.5); 3], BlendMode::Xor);
+        assert_approx_eq(&front_to_back(&stack), &uncached(&stack));
+    }
+
+    #[test]
+    fn front_to_back_stops_at_opaque_layer() {
+        let mut stack = LayerStack::new(2, 1);
+        stack.push(
+            vec![F32x4Rgba::new(f32::NAN, 0.0, 0.0, 1.0); 2],
+            BlendMode::SourceOver,
+        );
+        stack.push(
+            vec![F32x4Rgba::BLACK, F32x4Rgba::TRANSPARENT],
+            BlendMode::SourceOver,
+        );
+        let out = front_to_back(&stack);
+        assert_eq!(out[0], F32x4Rgba::BLACK);
+        assert!(out[1].r.is_nan());
+    }
+
+    #[test]
+    fn front_to_back_empty_stack_is_transparent() {
+        let out = front_to_back(&LayerStack::new(2, 2));
+        assert_eq!(out, vec![F32x4Rgba::TRANSPARENT; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "width * height pixels")]
+    fn push_panics_on_wrong_size() {
+        LayerStack::new(2, 2).push(vec![F32x4Rgba::BLACK; 3], BlendMode::SourceOver);
+    }
+
+    #[test]
+    fn pixels_mut_and_set_mode_change_key() {
+        let mut stack = stack_3x1();
+        let layer = stack.layer_mut(1).unwrap();
+        let key = layer.key();
+        layer.set_mode(BlendMode::SourceOver);
+        assert_eq!(layer.key(), key);
+        layer.set_mode(BlendMode::Plus);
+        assert_ne!(layer.key(), key);
+        let key = layer.key();
+        let _ = layer.pixels_mut();
+        assert_ne!(layer.key(), key);
+    }
+
+    #[test]
+    fn opacity_scales_layer_alpha() {
+        let mut stack = LayerStack::new(2, 1);
+        stack.push(vec![F32x4Rgba::BLACK; 2], BlendMode::SourceOver);
+        let top = stack.push(vec![F32x4Rgba::WHITE; 2], BlendMode::SourceOver);
+        assert_eq!(stack.layer(top).unwrap().opacity(), 1.0);
+
+        stack.layer_mut(top).unwrap().set_opacity(0.5);
+        let out = uncached(&stack);
+        assert_eq!(out, vec![F32x4Rgba::new(0.5, 0.5, 0.5, 0.75); 2]);
+        assert_approx_eq(&front_to_back(&stack), &out);
+
+        stack.layer_mut(top).unwrap().set_opacity(-1.0);
+        assert_eq!(stack.layer(top).unwrap().opacity(), 0.0);
+        assert_eq!(uncached(&stack), vec![F32x4Rgba::BLACK; 2]);
+    }
+
+    #[test]
+    fn set_opacity_changes_key() {
+        let mut stack = stack_3x1();
+        let mut cache = FlattenCache::new();
+        let _ = cache.flatten(&stack);
+        let layer = stack.layer_mut(1).unwrap();
+        let key = layer.key();
+        layer.set_opacity(1.0);
+        assert_eq!(layer.key(), key);
+        layer.set_opacity(0.25);
+        assert_ne!(layer.key(), key);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+    }
+
+    #[test]
+    fn keys_are_unique_across_stacks() {
+        let (a, b) = (stack_3x1(), stack_3x1());
+        assert_ne!(a.layers()[0].key(), b.layers()[0].key());
+    }
+
+    #[test]
+    fn cache_matches_uncached() {
+        let mut stack = stack_3x1();
+        let mut cache = FlattenCache::new();
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+
+        stack.layer_mut(2).unwrap().pixels_mut()[1] = F32x4Rgba::WHITE;
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+
+        stack.layer_mut(0).unwrap().set_mode(BlendMode::Clear);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+
+        stack.remove(1);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+
+        stack.push(vec![F32x4Rgba::WHITE; 3], BlendMode::Xor);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+    }
+
+    #[test]
+    fn cache_keeps_layers_below_edit() {
+        let mut stack = stack_3x1();
+        let mut cache = FlattenCache::new();
+        let _ = cache.flatten(&stack);
+        assert!(cache.prefix_keys.is_empty());
+
+        stack.layer_mut(2).unwrap().pixels_mut()[0] = F32x4Rgba::WHITE;
+        let _ = cache.flatten(&stack);
+        assert_eq!(cache.prefix_keys.len(), 2);
+
+        stack.layer_mut(2).unwrap().pixels_mut()[1] = F32x4Rgba::WHITE;
+        let _ = cache.flatten(&stack);
+        assert_eq!(cache.prefix_keys.len(), 2);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+    }
+
+    #[test]
+    fn cache_restarts_when_lower_layer_changes() {
+        let mut stack = stack_3x1();
+        let mut cache = FlattenCache::new();
+        let _ = cache.flatten(&stack);
+        stack.layer_mut(2).unwrap().invalidate();
+        let _ = cache.flatten(&stack);
+
+        stack.layer_mut(0).unwrap().pixels_mut()[0] = F32x4Rgba::WHITE;
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+        assert!(cache.prefix_keys.is_empty());
+    }
+
+    #[test]
+    fn cache_invalidate_from() {
+        let mut stack = stack_3x1();
+        let mut cache = FlattenCache::new();
+        stack.layer_mut(2).unwrap().invalidate();
+        let _ = cache.flatten(&stack);
+        cache.invalidate_from(1);
+        assert_eq!(cache.output_keys.len(), 1);
+        assert!(cache.prefix_keys.is_empty());
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+
+        cache.invalidate();
+        assert!(cache.output_keys.is_empty());
+        stack.layer_mut(1).unwrap().set_mode(BlendMode::Plus);
+        assert_eq!(cache.flatten(&stack), uncached(&stack));
+    }
+
+    #[test]
+    fn cache_handles_resized_stack() {
+        let mut cache = FlattenCache::new();
+        let _ = cache.flatten(&stack_3x1());
+        let mut stack = LayerStack::new(1, 1);
+        stack.push(vec![F32x4Rgba::WHITE], BlendMode::SourceOver);
+        assert_eq!(cache.flatten(&stack), &[F32x4Rgba::WHITE]);
+    }
+
+    fn group_stack(pixels: [F32x4Rgba; 2], mode: BlendMode) -> LayerStack {
+        let mut stack = LayerStack::new(1, 1);
+        for p in pixels {
+            stack.push(vec![p], mode);
+        }
+        stack
+    }
+
+    fn composite_group(group: &Group, backdrop: F32x4Rgba) -> F32x4Rgba {
+        let mut out = [backdrop];
+        group.composite(&mut out);
+        out[0]
+    }
+
+    #[test]
+    fn isolated_group_blends_flattened_layers() {
+        let stack = group_stack(
+            [F32x4Rgba::RED.with_alpha(0.5), F32x4Rgba::BLUE],
+            BlendMode::Screen,
+        );
+        let mut flattened = [F32x4Rgba::TRANSPARENT];
+        stack.flatten(&mut flattened);
+        let backdrop = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+        let group = Group::new(stack)
+            .with_isolated(true)
+            .with_mode(BlendMode::Multiply);
+        assert_eq!(
+            composite_group(&group, backdrop),
+            BlendMode::Multiply.apply(flattened[0], backdrop)
+        );
+    }
+
+    #[test]
+    fn non_isolated_group_on_transparent_matches_isolated() {
+        let stack = group_stack(
+            [
+                F32x4Rgba::RED.with_alpha(0.5),
+                F32x4Rgba::CYAN.with_alpha(0.25),
+            ],
+            BlendMode::Difference,
+        );
+        let group = Group::new(stack);
+        let isolated = group.clone().with_isolated(true);
+        assert_eq!(
+            composite_group(&group, F32x4Rgba::TRANSPARENT),
+            composite_group(&isolated, F32x4Rgba::TRANSPARENT)
+        );
+    }
+
+    #[test]
+    fn non_isolated_group_removes_backdrop() {
+        // A source-over layer over an opaque backdrop adds nothing to remove.
+        let stack = group_stack(
+            [F32x4Rgba::RED, F32x4Rgba::TRANSPARENT],
+            BlendMode::SourceOver,
+        );
+        let group = Group::new(stack).with_opacity(0.5);
+        let out = composite_group(&group, F32x4Rgba::BLUE);
+        assert_eq!(
+            out,
+            BlendMode::SourceOver.apply(F32x4Rgba::RED.with_alpha(0.5), F32x4Rgba::BLUE)
+        );
+
+        // A fully transparent group leaves the backdrop unchanged.
+        let empty = group_stack([F32x4Rgba::TRANSPARENT; 2], BlendMode::Multiply);
+        let backdrop = F32x4Rgba::new(0.25, 0.5, 0.75, 0.5);
+        assert_eq!(composite_group(&Group::new(empty), backdrop), backdrop);
+    }
+
+    #[test]
+    fn knockout_group_replaces_lower_layers() {
+        let stack = group_stack(
+            [
+                F32x4Rgba::RED.with_alpha(0.5),
+                F32x4Rgba::BLUE.with_alpha(0.5),
+            ],
+            BlendMode::SourceOver,
+        );
+        let group = Group::new(stack)
+            .with_isolated(true)
+            .with_knockout(true)
+            .with_mode(BlendMode::Source);
+        assert!(group.is_knockout() && group.is_isolated());
+        assert_eq!(
+            composite_group(&group, F32x4Rgba::TRANSPARENT),
+            BlendMode::SourceOver.apply(F32x4Rgba::BLUE.with_alpha(0.5), F32x4Rgba::TRANSPARENT)
+        );
+
+        // Where the top layer has no shape, the layer below it shows.
+        let mut group = group;
+        group.stack_mut().layer_mut(1).unwrap().pixels_mut()[0] = F32x4Rgba::TRANSPARENT;
+        assert_eq!(
+            composite_group(&group, F32x4Rgba::TRANSPARENT),
+            BlendMode::SourceOver.apply(F32x4Rgba::RED.with_alpha(0.5), F32x4Rgba::TRANSPARENT)
+        );
+    }
+
+    #[test]
+    fn knockout_layers_blend_with_initial_backdrop() {
+        let gray = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+        let stack = group_stack([F32x4Rgba::BLACK, F32x4Rgba::WHITE], BlendMode::Multiply);
+        let group = Group::new(stack).with_knockout(true);
+        assert_eq!(composite_group(&group, gray), gray);
+        let group = group.with_knockout(false);
+        assert_eq!(composite_group(&group, gray), F32x4Rgba::BLACK);
+    }
+}
@@ -0,0 +1,268 @@
+//! Depth-tested compositing.
+//!
+//! Mirrors the GPU depth test: each source pixel has a depth, and is only blended onto the
+//! destination where its depth passes a [`DepthTest`] against the destination depth buffer. This
+//! allows mixing layers rendered with depth, such as sprites composited into a 3D scene render.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     depth::{self, DepthState},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let sprite = [F32x4Rgba::WHITE; 2];
+//! let mut scene = [F32x4Rgba::BLACK; 2];
+//! let mut scene_depth = [0.5, 0.5];
+//!
+//! // The sprite is in front of the scene on the left, and behind it on the right.
+//! depth::blend_depth_tested(
+//!     &BlendMode::SourceOver,
+//!     &sprite,
+//!     &[0.25, 0.75],
+//!     &mut scene,
+//!     &mut scene_depth,
+//!     DepthState::default(),
+//! );
+//!
+//! assert_eq!(scene, [F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+//! assert_eq!(scene_depth, [0.25, 0.5]);
+//! ```
+
+use crate::{RgbaBlend, rgba::F32x4Rgba};
+
+/// Comparison a source depth must pass against the destination depth to be drawn.
+///
+/// As on GPUs, comparisons involving `NaN` fail, except for [`NotEqual`](DepthTest::NotEqual).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DepthTest {
+    /// Never passes.
+    Never,
+
+    /// Passes if the source is nearer (`src < dst`).
+    #[default]
+    Less,
+
+    /// Passes if `src <= dst`.
+    LessEqual,
+
+    /// Passes if `src == dst`.
+    Equal,
+
+    /// Passes if `src != dst`.
+    NotEqual,
+
+    /// Passes if `src >= dst`.
+    GreaterEqual,
+
+    /// Passes if the source is farther (`src > dst`).
+    Greater,
+
+    /// Always passes.
+    Always,
+}
+
+impl DepthTest {
+    /// Returns `true` if a source depth of `src` passes the test against `dst`.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn passes(self, src: f32, dst: f32) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Less => src < dst,
+            Self::LessEqual => src <= dst,
+            Self::Equal => src == dst,
+            Self::NotEqual => src != dst,
+            Self::GreaterEqual => src >= dst,
+            Self::Greater => src > dst,
+            Self::Always => true,
+        }
+    }
+}
+
+/// Depth test and write configuration for [`blend_depth_tested`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DepthState {
+    /// Test each source depth must pass to be blended.
+    pub test: DepthTest,
+
+    /// Whether the depth of passing source pixels replaces the destination depth.
+    pub write: bool,
+}
+
+impl Default for DepthState {
+    /// Returns [`DepthTest::Less`] with depth writes enabled, as is the GPU default.
+    fn default() -> Self {
+        Self::new(DepthTest::Less)
+    }
+}
+
+impl DepthState {
+    /// Creates a new `DepthState` with the given test and depth writes enabled.
+    #[must_use]
+    pub const fn new(test: DepthTest) -> Self {
+        Self { test, write: true }
+    }
+
+    /// Returns a copy of this state with depth writes disabled, as is typical for translucent
+    /// layers that should not occlude what is drawn after them.
+    #[must_use]
+    pub const fn read_only(self) -> Self {
+        Self {
+            test: self.test,
+            write: false,
+        }
+    }
+}
+
+/// Blends `src` onto `dst` in place, only where `src_depth` passes the depth test against
+/// `dst_depth`.
+///
+/// Where the test passes and [`DepthState::write`] is set, `dst_depth` is updated with the
+/// source depth. Like on GPUs, depth is written for passing pixels regardless of their alpha.
+///
+/// ## Panics
+///
+/// If `src`, `src_depth`, `dst` and `dst_depth` do not all have the same length.
+pub fn blend_depth_tested<B>(
+    blend: &B,
+    src: &[F32x4Rgba],
+    src_depth: &[f32],
+    dst: &mut [F32x4Rgba],
+    dst_depth: &mut [f32],
+    state: DepthState,
+) where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert_eq!(
+        src_depth.len(),
+        src.len(),
+        "src_depth must have one depth per pixel"
+    );
+    assert_eq!(
+        dst_depth.len(),
+        dst.len(),
+        "dst_depth must have one depth per pixel"
+    );
+    let pixels = src.iter().zip(src_depth).zip(dst.iter_mut().zip(dst_depth));
+    for ((s, &sz), (d, dz)) in pixels {
+        if state.test.passes(sz, *dz) {
+            *d = blend.apply(*s, *d);
+            if state.write {
+                *dz = sz;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    const ALL: [DepthTest; 8] = [
+        DepthTest::Never,
+        DepthTest::Less,
+        DepthTest::LessEqual,
+        DepthTest::Equal,
+        DepthTest::NotEqual,
+        DepthTest::GreaterEqual,
+        DepthTest::Greater,
+        DepthTest::Always,
+    ];
+
+    #[test]
+    fn passes_truth_table() {
+        // (less, equal, greater)
+        let expected = [
+            (false, false, false),
+            (true, false, false),
+            (true, true, false),
+            (false, true, false),
+            (true, false, true),
+            (false, true, true),
+            (false, false, true),
+            (true, true, true),
+        ];
+        for (test, (lt, eq, gt)) in ALL.into_iter().zip(expected) {
+            assert_eq!(test.passes(0.0, 1.0), lt, "{test:?} less");
+            assert_eq!(test.passes(1.0, 1.0), eq, "{test:?} equal");
+            assert_eq!(test.passes(2.0, 1.0), gt, "{test:?} greater");
+        }
+    }
+
+    #[test]
+    fn nan_fails_except_not_equal() {
+        for test in ALL {
+            let expected = matches!(test, DepthTest::NotEqual | DepthTest::Always);
+            assert_eq!(test.passes(f32::NAN, 1.0), expected, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn default_state_is_less_with_writes() {
+        assert_eq!(
+            DepthState::default(),
+            DepthState {
+                test: DepthTest::Less,
+                write: true,
+            }
+        );
+        assert!(!DepthState::default().read_only().write);
+    }
+
+    #[test]
+    fn blends_only_passing_pixels() {
+        let src = [F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); 3];
+        let mut dst = [F32x4Rgba::BLACK; 3];
+        let mut dst_depth = [1.0, 1.0, 1.0];
+        blend_depth_tested(
+            &BlendMode::SourceOver,
+            &src,
+            &[0.5, 1.0, 1.5],
+            &mut dst,
+            &mut dst_depth,
+            DepthState::new(DepthTest::LessEqual),
+        );
+        let blended = BlendMode::SourceOver.apply(src[0], F32x4Rgba::BLACK);
+        assert_eq!(dst, [blended, blended, F32x4Rgba::BLACK]);
+        assert_eq!(dst_depth, [0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn read_only_keeps_depth() {
+        let mut dst = [F32x4Rgba::BLACK];
+        let mut dst_depth = [1.0];
+        blend_depth_tested(
+            &BlendMode::Source,
+            &[F32x4Rgba::WHITE],
+            &[0.0],
+            &mut dst,
+            &mut dst_depth,
+            DepthState::default().read_only(),
+        );
+        assert_eq!(dst, [F32x4Rgba::WHITE]);
+        assert_eq!(dst_depth, [1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "dst_depth must have one depth per pixel")]
+    fn panics_on_depth_length_mismatch() {
+        blend_depth_tested(
+            &BlendMode::Source,
+            &[F32x4Rgba::WHITE],
+            &[0.0],
+            &mut [F32x4Rgba::BLACK],
+            &mut [],
+            DepthState::default(),
+        );
+    }
+}
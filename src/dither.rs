@@ -0,0 +1,206 @@
+//! Dithered quantization of `f32` colors to 8 bits.
+//!
+//! Rounding each channel to the nearest of 256 levels turns smooth gradients into visible bands.
+//! Dithering trades the bands for fine noise, so the average of a neighborhood keeps the exact
+//! value: [`Dither::Bayer`] adds a fixed 8×8 threshold pattern, which is fast, stable across
+//! frames and parallelizable, and [`Dither::FloydSteinberg`] diffuses each pixel's rounding error
+//! to its unvisited neighbors, which gives finer noise at the cost of a sequential pass.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     dither::{self, Dither},
+//!     rgba::{F32x4Rgba, U8x4Rgba},
+//! };
+//!
+//! // A flat color halfway between two 8-bit levels.
+//! let mut src = [F32x4Rgba::new(0.5, 0.5, 0.5, 1.0); 64];
+//! let mut dst = [U8x4Rgba::TRANSPARENT; 64];
+//! dither::quantize_dithered(&mut src, &mut dst, 8, Dither::Bayer);
+//!
+//! let sum: u32 = dst.iter().map(|p| u32::from(p.r)).sum();
+//! assert_eq!(sum, 127 * 32 + 128 * 32);
+//! ```
+
+use crate::{
+    math,
+    rgba::{F32x4Rgba, U8x4Rgba},
+};
+
+/// A dithering algorithm for [`quantize_dithered`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dither {
+    /// No dithering: each channel rounds to nearest, as `From<F32x4Rgba>` does.
+    None,
+
+    /// Ordered dithering with an 8×8 Bayer threshold matrix.
+    #[default]
+    Bayer,
+
+    /// Floyd–Steinberg error diffusion, in row-major order.
+    FloydSteinberg,
+}
+
+/// The 8×8 Bayer index matrix, with thresholds `0..64` spread as evenly as possible.
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Quantizes `v`, in 8-bit units, to the nearest level in `[0, 255]`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn quantize(v: f32) -> u8 {
+    math::round(v.clamp(0.0, 255.0)) as u8
+}
+
+/// Quantizes straight-alpha `src` into `dst`, an image `width` pixels wide, with `dither`.
+///
+/// Every channel, including alpha, is dithered. `NaN` channels quantize to `0`, and other
+/// out-of-range values are clamped. Error diffusion accumulates into the pixels of `src` that are
+/// not yet quantized, so `src` is left modified by [`Dither::FloydSteinberg`]; the other
+/// algorithms leave it unchanged.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length, or `width` is zero or does not divide it.
+pub fn quantize_dithered(
+    src: &mut [F32x4Rgba],
+    dst: &mut [U8x4Rgba],
+    width: usize,
+    dither: Dither,
+) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert!(
+        width != 0 && src.len() % width == 0,
+        "buffer length must be a multiple of width"
+    );
+    match dither {
+        Dither::None => {
+            for (s, d) in src.iter().zip(dst) {
+                *d = U8x4Rgba::from(*s);
+            }
+        }
+        #[allow(clippy::suboptimal_flops)]
+        Dither::Bayer => {
+            for (i, (s, d)) in src.iter().zip(dst).enumerate() {
+                let threshold = f32::from(BAYER_8X8[(i / width) % 8][(i % width) % 8]);
+                let offset = (threshold + 0.5) / 64.0 - 0.5;
+                let levels = <[f32; 4]>::from(s.clamp_unit()).map(|c| quantize(c * 255.0 + offset));
+                *d = U8x4Rgba::from(levels);
+            }
+        }
+        Dither::FloydSteinberg => floyd_steinberg(src, dst, width),
+    }
+}
+
+/// Quantizes `src` with Floyd–Steinberg error diffusion, accumulating errors into `src`.
+#[allow(clippy::suboptimal_flops)]
+fn floyd_steinberg(src: &mut [F32x4Rgba], dst: &mut [U8x4Rgba], width: usize) {
+    let len = src.len();
+    for i in 0..len {
+        let (x, below) = (i % width, i + width);
+        let value = <[f32; 4]>::from(src[i].clamp_unit()).map(|c| c * 255.0);
+        let levels = value.map(quantize);
+        dst[i] = U8x4Rgba::from(levels);
+
+        let error: [f32; 4] = core::array::from_fn(|c| (value[c] - f32::from(levels[c])) / 255.0);
+        let mut diffuse = |j: usize, weight: f32| {
+            let p = &mut src[j];
+            p.r += error[0] * weight;
+            p.g += error[1] * weight;
+            p.b += error[2] * weight;
+            p.a += error[3] * weight;
+        };
+        if x + 1 < width {
+            diffuse(i + 1, 7.0 / 16.0);
+        }
+        if below < len {
+            if x > 0 {
+                diffuse(below - 1, 3.0 / 16.0);
+            }
+            diffuse(below, 5.0 / 16.0);
+            if x + 1 < width {
+                diffuse(below + 1, 1.0 / 16.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An 8×8 image, one Bayer tile, of gray `level` in 8-bit units.
+    fn flat(level: f32) -> [F32x4Rgba; 64] {
+        let v = level / 255.0;
+        [F32x4Rgba::new(v, v, v, 1.0); 64]
+    }
+
+    fn mean_red(pixels: &[U8x4Rgba]) -> f32 {
+        let sum: u32 = pixels.iter().map(|p| u32::from(p.r)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let mean = sum as f32 / pixels.len() as f32;
+        mean
+    }
+
+    #[test]
+    fn none_matches_from() {
+        let mut src = flat(100.25);
+        let mut dst = [U8x4Rgba::TRANSPARENT; 64];
+        quantize_dithered(&mut src, &mut dst, 8, Dither::None);
+        assert_eq!(dst, [U8x4Rgba::from(flat(100.25)[0]); 64]);
+    }
+
+    #[test]
+    fn dithering_preserves_the_mean() {
+        for level in [100.125, 100.25, 100.5, 100.75] {
+            for dither in [Dither::Bayer, Dither::FloydSteinberg] {
+                let mut dst = [U8x4Rgba::TRANSPARENT; 64];
+                quantize_dithered(&mut flat(level), &mut dst, 8, dither);
+                let error = (mean_red(&dst) - level).abs();
+                assert!(error < 0.05, "{dither:?} at {level}: {error}");
+                assert!(dst.iter().all(|p| (p.r == 100 || p.r == 101) && p.a == 255));
+            }
+        }
+    }
+
+    #[test]
+    fn exact_levels_are_unchanged() {
+        for dither in [Dither::Bayer, Dither::FloydSteinberg] {
+            let mut src = [F32x4Rgba::new(0.0, 1.0, 128.0 / 255.0, 1.0); 16];
+            let mut dst = [U8x4Rgba::TRANSPARENT; 16];
+            quantize_dithered(&mut src, &mut dst, 4, dither);
+            assert_eq!(dst, [U8x4Rgba::new(0, 255, 128, 255); 16]);
+        }
+    }
+
+    #[test]
+    fn nan_quantizes_to_zero() {
+        let mut src = [F32x4Rgba::new(f32::NAN, 0.0, 0.0, 1.0); 4];
+        let mut dst = [U8x4Rgba::WHITE; 4];
+        quantize_dithered(&mut src, &mut dst, 2, Dither::FloydSteinberg);
+        assert_eq!(dst, [U8x4Rgba::new(0, 0, 0, 255); 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of width")]
+    fn panics_on_bad_width() {
+        quantize_dithered(
+            &mut [F32x4Rgba::WHITE; 3],
+            &mut [U8x4Rgba::WHITE; 3],
+            2,
+            Dither::Bayer,
+        );
+    }
+}
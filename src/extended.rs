@@ -0,0 +1,213 @@
+//! Extended separable blend modes from Photoshop.
+//!
+//! These modes are not part of the W3C specification, but are common in layer-based editors and
+//! PSD files: [`LinearBurn`](crate::BlendMode::LinearBurn),
+//! [`LinearDodge`](crate::BlendMode::LinearDodge), [`VividLight`](crate::BlendMode::VividLight),
+//! [`LinearLight`](crate::BlendMode::LinearLight), [`PinLight`](crate::BlendMode::PinLight) and
+//! [`HardMix`](crate::BlendMode::HardMix), and the arithmetic
+//! [`Subtract`](crate::BlendMode::Subtract) and [`Divide`](crate::BlendMode::Divide). They are [`Separable`] modes, so partially transparent
+//! pixels are composited as described in [`separable`].
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, RgbaBlend, extended, rgba::F32x4Rgba};
+//!
+//! assert_eq!(extended::linear_burn(0.75, 0.5), 0.25);
+//!
+//! let src = F32x4Rgba::new(0.75, 0.25, 0.5, 1.0);
+//! let dst = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+//! assert_eq!(BlendMode::HardMix.apply(src, dst), F32x4Rgba::new(1.0, 0.0, 1.0, 1.0));
+//! ```
+
+use crate::{
+    math,
+    separable::{self, Separable},
+};
+
+impl Separable<fn(f32, f32) -> f32> {
+    /// Source and destination colors are added, and one subtracted, darkening the result.
+    pub const LINEAR_BURN: Self = Self::new(linear_burn);
+
+    /// Source and destination colors are added, lightening the result.
+    pub const LINEAR_DODGE: Self = Self::new(linear_dodge);
+
+    /// Burns or dodges the destination color depending on the source color.
+    pub const VIVID_LIGHT: Self = Self::new(vivid_light);
+
+    /// Linearly burns or dodges the destination color depending on the source color.
+    pub const LINEAR_LIGHT: Self = Self::new(linear_light);
+
+    /// Replaces the destination color depending on the source color.
+    pub const PIN_LIGHT: Self = Self::new(pin_light);
+
+    /// Posterizes each channel to black or white.
+    pub const HARD_MIX: Self = Self::new(hard_mix);
+
+    /// Subtracts the source color from the destination color.
+    pub const SUBTRACT: Self = Self::new(subtract);
+
+    /// Divides the destination color by the source color.
+    pub const DIVIDE: Self = Self::new(divide);
+}
+
+/// Returns `src + dst - 1`, clamped to at least `0.0`.
+#[must_use]
+pub fn linear_burn(src: f32, dst: f32) -> f32 {
+    (src + dst - 1.0).max(0.0)
+}
+
+/// Returns `src + dst`, clamped to at most `1.0`.
+#[must_use]
+pub fn linear_dodge(src: f32, dst: f32) -> f32 {
+    (src + dst).min(1.0)
+}
+
+/// Color-burns the destination by twice the source where the source is at most `0.5`, and
+/// color-dodges it by `2 * src - 1` elsewhere.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn vivid_light(src: f32, dst: f32) -> f32 {
+    if src <= 0.5 {
+        separable::color_burn(2.0 * src, dst)
+    } else {
+        separable::color_dodge(2.0 * src - 1.0, dst)
+    }
+}
+
+/// Returns `dst + 2 * src - 1`, clamped to `[0.0, 1.0]`.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn linear_light(src: f32, dst: f32) -> f32 {
+    math::clamp01(dst + 2.0 * src - 1.0)
+}
+
+/// Darkens the destination to twice the source where the source is at most `0.5`, and lightens
+/// it to `2 * src - 1` elsewhere.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn pin_light(src: f32, dst: f32) -> f32 {
+    if src <= 0.5 {
+        dst.min(2.0 * src)
+    } else {
+        dst.max(2.0 * src - 1.0)
+    }
+}
+
+/// Returns `1.0` where `src + dst` is at least `1.0`, and `0.0` elsewhere.
+#[must_use]
+pub fn hard_mix(src: f32, dst: f32) -> f32 {
+    if src + dst >= 1.0 { 1.0 } else { 0.0 }
+}
+
+/// Returns `dst - src`, clamped to at least `0.0`.
+#[must_use]
+pub fn subtract(src: f32, dst: f32) -> f32 {
+    (dst - src).max(0.0)
+}
+
+/// Returns `dst / src`, clamped to at most `1.0`.
+///
+/// Dividing by a zero source gives `1.0`, except for a zero destination, which stays `0.0`.
+#[must_use]
+pub fn divide(src: f32, dst: f32) -> f32 {
+    if dst <= 0.0 {
+        0.0
+    } else if src <= 0.0 {
+        1.0
+    } else {
+        (dst / src).min(1.0)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, RgbaBlend, rgba::F32x4Rgba};
+
+    #[test]
+    fn linear_reference_values() {
+        assert_eq!(linear_burn(0.5, 0.75), 0.25);
+        assert_eq!(linear_burn(0.25, 0.5), 0.0);
+        assert_eq!(linear_dodge(0.25, 0.5), 0.75);
+        assert_eq!(linear_dodge(0.75, 0.5), 1.0);
+        assert_eq!(linear_light(0.75, 0.25), 0.75);
+        assert_eq!(linear_light(0.125, 0.5), 0.0);
+        assert_eq!(linear_light(1.0, 0.5), 1.0);
+    }
+
+    #[test]
+    fn vivid_light_reference_values() {
+        assert_eq!(vivid_light(0.25, 0.5), 0.0);
+        assert_eq!(vivid_light(0.375, 0.625), 0.5);
+        assert_eq!(vivid_light(0.75, 0.25), 0.5);
+        assert_eq!(vivid_light(0.5, 0.3), 0.3);
+        assert_eq!(vivid_light(1.0, 0.5), 1.0);
+        assert_eq!(vivid_light(0.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn pin_light_reference_values() {
+        assert_eq!(pin_light(0.25, 0.75), 0.5);
+        assert_eq!(pin_light(0.25, 0.25), 0.25);
+        assert_eq!(pin_light(0.75, 0.25), 0.5);
+        assert_eq!(pin_light(0.75, 0.75), 0.75);
+    }
+
+    #[test]
+    fn hard_mix_posterizes() {
+        assert_eq!(hard_mix(0.5, 0.5), 1.0);
+        assert_eq!(hard_mix(0.25, 0.5), 0.0);
+        assert_eq!(hard_mix(0.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn subtract_saturates_at_black() {
+        assert_eq!(subtract(0.25, 0.75), 0.5);
+        assert_eq!(subtract(0.75, 0.25), 0.0);
+    }
+
+    #[test]
+    fn divide_handles_zero() {
+        assert_eq!(divide(0.5, 0.25), 0.5);
+        assert_eq!(divide(0.25, 0.5), 1.0);
+        assert_eq!(divide(0.0, 0.5), 1.0);
+        assert_eq!(divide(0.0, 0.0), 0.0);
+        assert_eq!(divide(0.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn flat_field_correction() {
+        let flat = F32x4Rgba::new(0.5, 0.8, 1.0, 1.0);
+        let image = F32x4Rgba::new(0.25, 0.4, 0.5, 1.0);
+        assert_eq!(
+            BlendMode::Divide.apply(flat, image),
+            F32x4Rgba::new(0.5, 0.5, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn modes_use_their_formulas() {
+        let src = F32x4Rgba::new(0.25, 0.5, 0.75, 1.0);
+        let dst = F32x4Rgba::new(0.75, 0.5, 0.25, 1.0);
+        for (mode, f) in [
+            (BlendMode::LinearBurn, linear_burn as fn(f32, f32) -> f32),
+            (BlendMode::LinearDodge, linear_dodge),
+            (BlendMode::VividLight, vivid_light),
+            (BlendMode::LinearLight, linear_light),
+            (BlendMode::PinLight, pin_light),
+            (BlendMode::HardMix, hard_mix),
+            (BlendMode::Subtract, subtract),
+            (BlendMode::Divide, divide),
+        ] {
+            let out = mode.apply(src, dst);
+            assert_eq!(
+                [out.r, out.g, out.b, out.a],
+                [f(0.25, 0.75), f(0.5, 0.5), f(0.75, 0.25), 1.0],
+                "{mode:?}"
+            );
+            assert_eq!(mode.apply(src, F32x4Rgba::TRANSPARENT), src, "{mode:?}");
+        }
+    }
+}
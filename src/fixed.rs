@@ -0,0 +1,212 @@
+//! Fixed-point channels for targets without a floating-point unit.
+//!
+//! A [`Q15`] stores a channel in `[0.0, 1.0]` as an integer with 15 fractional bits, so `1.0`
+//! is `1 << 15`. Multiplying two values is a widening multiply, a rounding bias and a shift,
+//! with no division and no floating-point math, and every operation is exactly specified: blends
+//! give the same bits on every target.
+//!
+//! [`Q15`] implements [`Channel`], so the [`Q15PorterDuff`] constants blend [`Q15x4Rgba`]
+//! colors.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     RgbaBlend,
+//!     fixed::{Q15PorterDuff, Q15x4Rgba},
+//!     rgba::U8x4Rgba,
+//! };
+//!
+//! let src = Q15x4Rgba::from(U8x4Rgba::new(255, 0, 0, 128));
+//! let dst = Q15x4Rgba::from(U8x4Rgba::new(0, 0, 255, 255));
+//! let out = Q15PorterDuff::SRC_OVER.apply(src, dst);
+//! assert_eq!(U8x4Rgba::from(out), U8x4Rgba::new(128, 0, 127, 191));
+//! ```
+
+use crate::{
+    bit_depth::BitDepth,
+    channel::Channel,
+    math,
+    porter_duff::PorterDuff,
+    rgba::{Rgba, U8x4Rgba},
+};
+
+/// An unsigned fixed-point channel in `[0.0, 1.0]` with 15 fractional bits.
+///
+/// The raw bits are in `[0, 32768]`, where `32768` (`1 << 15`) is `1.0`. Products round to
+/// nearest, with ties rounding up; sums saturate at `1.0` and differences at `0.0`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Q15(u16);
+
+/// An [`Rgba`] with [`Q15`] fixed-point channels.
+pub type Q15x4Rgba = Rgba<Q15>;
+
+/// A [`PorterDuff`] blending [`Q15`] channels with fixed-point integer math.
+pub type Q15PorterDuff = PorterDuff<Q15, fn(Q15, Q15) -> Q15>;
+
+impl Q15 {
+    /// Number of fractional bits.
+    pub const FRAC_BITS: u32 = 15;
+
+    /// Raw bits of `1.0`.
+    const ONE_BITS: u16 = 1 << Self::FRAC_BITS;
+
+    /// Creates a value from its raw bits, saturating at `1.0` (`32768`).
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        if bits > Self::ONE_BITS {
+            Self(Self::ONE_BITS)
+        } else {
+            Self(bits)
+        }
+    }
+
+    /// Returns the raw bits, in `[0, 32768]`.
+    #[must_use]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Converts an 8-bit channel, so that `255` becomes `1.0`, rounding to nearest.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn from_u8(value: u8) -> Self {
+        Self(((value as u32 * Self::ONE_BITS as u32 + 127) / 255) as u16)
+    }
+
+    /// Converts to an 8-bit channel, so that `1.0` becomes `255`, rounding to nearest.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn to_u8(self) -> u8 {
+        ((self.0 as u32 * 255 + (1 << (Self::FRAC_BITS - 1))) >> Self::FRAC_BITS) as u8
+    }
+}
+
+impl From<u8> for Q15 {
+    fn from(value: u8) -> Self {
+        Self::from_u8(value)
+    }
+}
+
+impl From<Q15> for u8 {
+    fn from(value: Q15) -> Self {
+        value.to_u8()
+    }
+}
+
+impl BitDepth for Q15 {
+    fn to_unit(self) -> f32 {
+        f32::from(self.0) / f32::from(Self::ONE_BITS)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_unit(value: f32) -> Self {
+        Self(math::round(value.clamp(0.0, 1.0) * f32::from(Self::ONE_BITS)) as u16)
+    }
+}
+
+impl Channel for Q15 {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(Self::ONE_BITS);
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn mul(self, rhs: Self) -> Self {
+        let product = u32::from(self.0) * u32::from(rhs.0);
+        Self(((product + (1 << (Self::FRAC_BITS - 1))) >> Self::FRAC_BITS) as u16)
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0).min(Self::ONE_BITS))
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl From<U8x4Rgba> for Q15x4Rgba {
+    fn from(rgba: U8x4Rgba) -> Self {
+        Self::new(
+            Q15::from_u8(rgba.r),
+            Q15::from_u8(rgba.g),
+            Q15::from_u8(rgba.b),
+            Q15::from_u8(rgba.a),
+        )
+    }
+}
+
+impl From<Q15x4Rgba> for U8x4Rgba {
+    fn from(rgba: Q15x4Rgba) -> Self {
+        Self::new(
+            rgba.r.to_u8(),
+            rgba.g.to_u8(),
+            rgba.b.to_u8(),
+            rgba.a.to_u8(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RgbaBlend, porter_duff::F32PorterDuff};
+
+    #[test]
+    fn u8_round_trips() {
+        for v in 0..=u8::MAX {
+            assert_eq!(Q15::from_u8(v).to_u8(), v);
+        }
+        assert_eq!(Q15::from_u8(255), Q15::ONE);
+        assert_eq!(u8::from(Q15::ONE), 255);
+    }
+
+    #[test]
+    fn from_bits_saturates() {
+        assert_eq!(Q15::from_bits(u16::MAX), Q15::ONE);
+        assert_eq!(Q15::from_bits(12345).to_bits(), 12345);
+    }
+
+    #[test]
+    fn mul_rounds_to_nearest() {
+        let half = Q15::from_bits(1 << 14);
+        assert_eq!(half.mul(half).to_bits(), 1 << 13);
+        assert_eq!(Q15::ONE.mul(Q15::ONE), Q15::ONE);
+        assert_eq!(Q15::from_bits(1).mul(half).to_bits(), 1);
+        assert_eq!(Q15::from_bits(1).mul(Q15::from_bits(16383)).to_bits(), 0);
+    }
+
+    #[test]
+    fn add_and_sub_saturate() {
+        assert_eq!(Q15::ONE.add(Q15::ONE), Q15::ONE);
+        assert_eq!(Q15::ZERO.sub(Q15::ONE), Q15::ZERO);
+    }
+
+    #[test]
+    fn unit_conversions() {
+        assert_eq!(Q15::from_unit(0.5).to_bits(), 1 << 14);
+        assert_eq!(Q15::from_unit(2.0), Q15::ONE);
+        assert_eq!(Q15::from_unit(-1.0), Q15::ZERO);
+        assert!((Q15::from_bits(1 << 13).to_unit() - 0.25).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn porter_duff_matches_f32_within_one() {
+        let modes = [
+            (Q15PorterDuff::SRC_OVER, F32PorterDuff::SRC_OVER),
+            (Q15PorterDuff::XOR, F32PorterDuff::XOR),
+            (Q15PorterDuff::PLUS, F32PorterDuff::PLUS),
+        ];
+        let dst = U8x4Rgba::new(0, 64, 255, 200);
+        for a in (0..=255).step_by(15) {
+            let src = U8x4Rgba::new(255, 128, 0, a);
+            for (fixed, float) in modes {
+                let fixed: [u8; 4] = U8x4Rgba::from(fixed.apply(src.into(), dst.into())).into();
+                let float: [u8; 4] = U8x4Rgba::from(float.apply(src.into(), dst.into())).into();
+                for (f, e) in fixed.into_iter().zip(float) {
+                    assert!(f.abs_diff(e) <= 1, "{fixed:?} != {float:?}");
+                }
+            }
+        }
+    }
+}
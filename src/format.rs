@@ -0,0 +1,121 @@
+//! Pixel formats and cross-format blending.
+//!
+//! The [`Pixel`] trait describes how a stored pixel format converts to and from the straight-alpha
+//! [`F32x4Rgba`] working format. Blending a source of one format onto a destination of another,
+//! such as an 8-bit sprite onto a 16-bit or float framebuffer, can then fuse the conversions and
+//! the blend into a single pass with [`blend_converted`], instead of converting the source into a
+//! temporary buffer first.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, format, rgba::{F32x4Rgba, Rgba}};
+//!
+//! let sprite = [Rgba::<u8>::new(255, 255, 255, 255), Rgba::new(0, 0, 0, 0)];
+//! let mut framebuffer = [F32x4Rgba::BLACK; 2];
+//! format::blend_converted(&BlendMode::SourceOver, &sprite, &mut framebuffer);
+//! assert_eq!(framebuffer, [F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+//! ```
+
+use crate::{
+    RgbaBlend,
+    bit_depth::{self, BitDepth},
+    rgba::{F32x4Rgba, Rgba},
+};
+
+/// A stored pixel format that can be converted to and from straight-alpha [`F32x4Rgba`].
+///
+/// Formats without an alpha channel are treated as opaque: [`to_rgba`](Pixel::to_rgba) returns
+/// an alpha of `1.0`, and [`from_rgba`](Pixel::from_rgba) discards alpha.
+pub trait Pixel: Copy {
+    /// Converts the pixel to the straight-alpha working format.
+    fn to_rgba(self) -> F32x4Rgba;
+
+    /// Converts a straight-alpha color to this format.
+    fn from_rgba(color: F32x4Rgba) -> Self;
+}
+
+impl<C: BitDepth> Pixel for Rgba<C> {
+    fn to_rgba(self) -> F32x4Rgba {
+        bit_depth::promote(self)
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        bit_depth::demote(color)
+    }
+}
+
+/// Converts a pixel from one format to another.
+#[must_use]
+pub fn convert<S: Pixel, D: Pixel>(pixel: S) -> D {
+    D::from_rgba(pixel.to_rgba())
+}
+
+/// Blends `src` onto `dst` in place, converting each pixel between formats on the fly.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_converted<B, S, D>(blend: &B, src: &[S], dst: &mut [D])
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: Pixel,
+    D: Pixel,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = D::from_rgba(blend.apply(s.to_rgba(), d.to_rgba()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    /// An opaque, alpha-less test format storing only the red channel.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Red(u8);
+
+    impl Pixel for Red {
+        fn to_rgba(self) -> F32x4Rgba {
+            F32x4Rgba::new(self.0.to_unit(), 0.0, 0.0, 1.0)
+        }
+
+        fn from_rgba(color: F32x4Rgba) -> Self {
+            Self(u8::from_unit(color.r))
+        }
+    }
+
+    #[test]
+    fn rgba_pixel_matches_bit_depth() {
+        let c = Rgba::<u16>::new(1, 2, 3, 4);
+        assert_eq!(c.to_rgba(), bit_depth::promote(c));
+        assert_eq!(convert::<_, Rgba<u8>>(c), bit_depth::convert(c));
+    }
+
+    #[test]
+    fn blend_onto_custom_format() {
+        let src = [Rgba::<u8>::new(255, 0, 0, 255), Rgba::new(255, 0, 0, 0)];
+        let mut dst = [Red(0), Red(10)];
+        blend_converted(&BlendMode::SourceOver, &src, &mut dst);
+        assert_eq!(dst, [Red(255), Red(10)]);
+    }
+
+    #[test]
+    fn blend_from_custom_format() {
+        let mut dst = [F32x4Rgba::TRANSPARENT];
+        blend_converted(&BlendMode::Source, &[Red(255)], &mut dst);
+        assert_eq!(dst, [F32x4Rgba::new(1.0, 0.0, 0.0, 1.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn blend_converted_panics_on_length_mismatch() {
+        blend_converted(&BlendMode::Source, &[Red(0)], &mut [Red(0); 2]);
+    }
+}
@@ -0,0 +1,304 @@
+//! Multi-stop color gradients.
+//!
+//! A [`Gradient`] maps a position `t` to a color between its [`ColorStop`]s, interpolating in
+//! premultiplied alpha as CSS gradients do, either on the stored channels or in
+//! [`OkLab`](crate::oklab::OkLab) for perceptually even ramps. A [`Shape`] maps pixels to
+//! positions, so [`Gradient::fill`] renders linear and radial gradients into a buffer that can
+//! be composited like any other source.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     gradient::{ColorStop, Gradient, Shape},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let stops = [
+//!     ColorStop::new(0.0, F32x4Rgba::BLACK),
+//!     ColorStop::new(1.0, F32x4Rgba::WHITE),
+//! ];
+//! let gradient = Gradient::new(&stops);
+//! assert_eq!(gradient.sample(0.5), F32x4Rgba::new(0.5, 0.5, 0.5, 1.0));
+//!
+//! // A horizontal ramp across a 4×1 image.
+//! let mut pixels = [F32x4Rgba::TRANSPARENT; 4];
+//! let shape = Shape::Linear { start: [0.0, 0.0], end: [4.0, 0.0] };
+//! gradient.fill(shape, &mut pixels, 4);
+//! assert_eq!(pixels[0], F32x4Rgba::new(0.125, 0.125, 0.125, 1.0));
+//! ```
+
+use crate::{math, oklab, rgba::F32x4Rgba};
+
+/// A color at a position along a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// Position of the stop, usually in `[0.0, 1.0]`.
+    pub offset: f32,
+
+    /// Straight-alpha color at the stop.
+    pub color: F32x4Rgba,
+}
+
+impl ColorStop {
+    /// Creates a stop of `color` at `offset`.
+    #[must_use]
+    pub const fn new(offset: f32, color: F32x4Rgba) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// How a [`Gradient`] interpolates between two stops.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Interpolates the stored channels, premultiplied, as
+    /// [`F32x4Rgba::lerp_premultiplied`].
+    #[default]
+    Rgb,
+
+    /// Interpolates in `OkLab`, premultiplied, as [`oklab::mix_oklab`].
+    OkLab,
+}
+
+/// Maps a pixel position to a position `t` along a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// `t` goes from `0.0` at `start` to `1.0` at `end`, constant perpendicular to the line
+    /// between them.
+    Linear {
+        /// Point at `t = 0.0`.
+        start: [f32; 2],
+
+        /// Point at `t = 1.0`.
+        end: [f32; 2],
+    },
+
+    /// `t` goes from `0.0` at `center` to `1.0` at `radius` from it.
+    Radial {
+        /// Point at `t = 0.0`.
+        center: [f32; 2],
+
+        /// Distance at which `t = 1.0`.
+        radius: f32,
+    },
+}
+
+impl Shape {
+    /// Returns the gradient position at the point `(x, y)`.
+    ///
+    /// A linear shape with `start == end` returns `0.0` everywhere, and a radial shape with a
+    /// radius of zero or less returns `1.0`.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)]
+    pub fn position(self, x: f32, y: f32) -> f32 {
+        match self {
+            Self::Linear { start, end } => {
+                let (dx, dy) = (end[0] - start[0], end[1] - start[1]);
+                let len_sq = dx * dx + dy * dy;
+                if len_sq <= 0.0 {
+                    return 0.0;
+                }
+                ((x - start[0]) * dx + (y - start[1]) * dy) / len_sq
+            }
+            Self::Radial { center, radius } => {
+                if radius <= 0.0 {
+                    return 1.0;
+                }
+                let (dx, dy) = (x - center[0], y - center[1]);
+                math::sqrt(dx * dx + dy * dy) / radius
+            }
+        }
+    }
+}
+
+/// A color ramp through one or more [`ColorStop`]s.
+///
+/// Positions before the first stop take its color, and positions after the last stop take
+/// that one's; two stops at the same offset make a hard edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient<'a> {
+    stops: &'a [ColorStop],
+    interpolation: Interpolation,
+}
+
+impl<'a> Gradient<'a> {
+    /// Creates a gradient through `stops`, interpolating with [`Interpolation::Rgb`].
+    ///
+    /// ## Panics
+    ///
+    /// If `stops` is empty, or its offsets are not in ascending order.
+    #[must_use]
+    pub fn new(stops: &'a [ColorStop]) -> Self {
+        assert!(!stops.is_empty(), "gradient must have at least one stop");
+        assert!(
+            stops.windows(2).all(|w| w[0].offset <= w[1].offset),
+            "gradient stops must be in ascending order"
+        );
+        Self {
+            stops,
+            interpolation: Interpolation::Rgb,
+        }
+    }
+
+    /// Returns this gradient, interpolating with `interpolation`.
+    #[must_use]
+    pub const fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    /// Returns the stops of this gradient.
+    #[must_use]
+    pub const fn stops(&self) -> &'a [ColorStop] {
+        self.stops
+    }
+
+    /// Returns the color at position `t`; `NaN` takes the color of the first stop.
+    #[must_use]
+    pub fn sample(&self, t: f32) -> F32x4Rgba {
+        let (first, last) = (self.stops[0], self.stops[self.stops.len() - 1]);
+        if t.is_nan() || t <= first.offset {
+            return first.color;
+        }
+        if t >= last.offset {
+            return last.color;
+        }
+        let i = self.stops.partition_point(|stop| stop.offset <= t);
+        let (from, to) = (self.stops[i - 1], self.stops[i]);
+        let local = (t - from.offset) / (to.offset - from.offset);
+        match self.interpolation {
+            Interpolation::Rgb => from.color.lerp_premultiplied(to.color, local),
+            Interpolation::OkLab => oklab::mix_oklab(from.color, to.color, local),
+        }
+    }
+
+    /// Renders the gradient into `dst`, an image `width` pixels wide, sampling `shape` at the
+    /// center of each pixel.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or does not divide the length of `dst`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fill(&self, shape: Shape, dst: &mut [F32x4Rgba], width: usize) {
+        assert!(
+            width != 0 && dst.len() % width == 0,
+            "buffer length must be a multiple of width"
+        );
+        for (y, row) in dst.chunks_exact_mut(width).enumerate() {
+            let py = y as f32 + 0.5;
+            for (x, d) in row.iter_mut().enumerate() {
+                *d = self.sample(shape.position(x as f32 + 0.5, py));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_clamps_to_end_stops() {
+        let stops = [
+            ColorStop::new(0.25, F32x4Rgba::RED),
+            ColorStop::new(0.75, F32x4Rgba::BLUE),
+        ];
+        let gradient = Gradient::new(&stops);
+        assert_eq!(gradient.sample(-1.0), F32x4Rgba::RED);
+        assert_eq!(gradient.sample(0.25), F32x4Rgba::RED);
+        assert_eq!(gradient.sample(f32::NAN), F32x4Rgba::RED);
+        assert_eq!(gradient.sample(0.75), F32x4Rgba::BLUE);
+        assert_eq!(gradient.sample(2.0), F32x4Rgba::BLUE);
+        assert_eq!(gradient.sample(0.5), F32x4Rgba::new(0.5, 0.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn single_stop_is_solid() {
+        let stops = [ColorStop::new(0.5, F32x4Rgba::RED)];
+        let gradient = Gradient::new(&stops);
+        assert_eq!(gradient.sample(0.0), F32x4Rgba::RED);
+        assert_eq!(gradient.sample(1.0), F32x4Rgba::RED);
+    }
+
+    #[test]
+    fn equal_offsets_make_a_hard_edge() {
+        let stops = [
+            ColorStop::new(0.0, F32x4Rgba::RED),
+            ColorStop::new(0.5, F32x4Rgba::RED),
+            ColorStop::new(0.5, F32x4Rgba::BLUE),
+            ColorStop::new(1.0, F32x4Rgba::BLUE),
+        ];
+        let gradient = Gradient::new(&stops);
+        assert_eq!(gradient.sample(0.499), F32x4Rgba::RED);
+        assert_eq!(gradient.sample(0.5), F32x4Rgba::BLUE);
+    }
+
+    #[test]
+    fn transparent_stops_do_not_tint() {
+        let stops = [
+            ColorStop::new(0.0, F32x4Rgba::RED),
+            ColorStop::new(1.0, F32x4Rgba::TRANSPARENT),
+        ];
+        let mid = Gradient::new(&stops).sample(0.5);
+        assert_eq!(mid, F32x4Rgba::new(1.0, 0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn oklab_interpolation() {
+        let stops = [
+            ColorStop::new(0.0, F32x4Rgba::RED),
+            ColorStop::new(1.0, F32x4Rgba::BLUE),
+        ];
+        let gradient = Gradient::new(&stops).with_interpolation(Interpolation::OkLab);
+        assert_eq!(
+            gradient.sample(0.5),
+            oklab::mix_oklab(F32x4Rgba::RED, F32x4Rgba::BLUE, 0.5)
+        );
+        assert_ne!(gradient.sample(0.5), Gradient::new(&stops).sample(0.5));
+    }
+
+    #[test]
+    fn shapes() {
+        let linear = Shape::Linear {
+            start: [0.0, 0.0],
+            end: [0.0, 2.0],
+        };
+        assert_eq!(linear.position(5.0, 1.0), 0.5);
+        let radial = Shape::Radial {
+            center: [1.0, 1.0],
+            radius: 2.0,
+        };
+        assert_eq!(radial.position(1.0, 3.0), 1.0);
+        assert_eq!(radial.position(1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn fill_samples_pixel_centers() {
+        let stops = [
+            ColorStop::new(0.0, F32x4Rgba::WHITE),
+            ColorStop::new(1.0, F32x4Rgba::BLACK),
+        ];
+        let shape = Shape::Radial {
+            center: [1.0, 1.0],
+            radius: 1.0,
+        };
+        let mut pixels = [F32x4Rgba::TRANSPARENT; 4];
+        Gradient::new(&stops).fill(shape, &mut pixels, 2);
+        let corner = 1.0 - math::sqrt(0.5);
+        for p in pixels {
+            assert!((p.r - corner).abs() < 1e-6, "{p:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ascending order")]
+    fn new_rejects_unsorted_stops() {
+        let _ = Gradient::new(&[
+            ColorStop::new(1.0, F32x4Rgba::RED),
+            ColorStop::new(0.0, F32x4Rgba::BLUE),
+        ]);
+    }
+}
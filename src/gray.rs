@@ -0,0 +1,211 @@
+//! Grayscale colors with alpha.
+//!
+//! Font atlases, masks with tint and many UI layers carry a single intensity. An [`La`] stores
+//! it with alpha in two channels, half the memory of an [`Rgba`], and
+//! [`PorterDuff::blend_la`] composites it directly, with the same coefficients and rounding as
+//! the four-channel blend. Converting to RGBA replicates the gray; converting from RGBA keeps its
+//! BT.709 luma.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{gray::U8x2La, porter_duff::U8PorterDuff, rgba::U8x4Rgba};
+//!
+//! let glyph = U8x2La::new(255, 128);
+//! let background = U8x2La::new(0, 255);
+//! let out = U8PorterDuff::SRC_OVER.blend_la(glyph, background);
+//! assert_eq!(out, U8x2La::new(128, 191));
+//! assert_eq!(U8x4Rgba::from(out), U8x4Rgba::new(128, 128, 128, 191));
+//! ```
+
+use crate::{
+    bit_depth::{self, BitDepth},
+    channel::Channel,
+    format::Pixel,
+    porter_duff::PorterDuff,
+    rgba::{F32x4Rgba, Rgba},
+    ycbcr::Standard,
+};
+
+/// Two-component vector type for grayscale colors with straight alpha.
+///
+/// Laid out as a C-style struct of `l` then `a`, like [`Rgba`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd)]
+#[repr(C)]
+pub struct La<C: Copy> {
+    /// Gray level (luminance) component.
+    pub l: C,
+
+    /// Alpha component.
+    pub a: C,
+}
+
+/// An [`La`] with 8-bit channels.
+pub type U8x2La = La<u8>;
+
+/// An [`La`] with 16-bit channels.
+pub type U16x2La = La<u16>;
+
+/// An [`La`] with `f32` channels.
+pub type F32x2La = La<f32>;
+
+impl<C: Copy> La<C> {
+    /// Creates a new color from a gray level and alpha.
+    #[must_use]
+    pub const fn new(l: C, a: C) -> Self {
+        Self { l, a }
+    }
+
+    /// Returns the gray level replicated into the color channels of an RGBA color.
+    #[must_use]
+    pub const fn to_rgba(self) -> Rgba<C> {
+        Rgba::new(self.l, self.l, self.l, self.a)
+    }
+}
+
+impl<C: BitDepth> La<C> {
+    /// Converts an RGBA color to grayscale, keeping the BT.709 luma of its color channels.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)]
+    pub fn from_rgba(color: Rgba<C>) -> Self {
+        let (kr, kb) = Standard::Bt709.coefficients();
+        let luma =
+            kr * color.r.to_unit() + (1.0 - kr - kb) * color.g.to_unit() + kb * color.b.to_unit();
+        Self::new(C::from_unit(luma), color.a)
+    }
+}
+
+impl<C: Copy> From<[C; 2]> for La<C> {
+    fn from([l, a]: [C; 2]) -> Self {
+        Self::new(l, a)
+    }
+}
+
+impl<C: Copy> From<La<C>> for [C; 2] {
+    fn from(color: La<C>) -> Self {
+        [color.l, color.a]
+    }
+}
+
+impl<C: Copy> From<La<C>> for Rgba<C> {
+    fn from(color: La<C>) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl<C: BitDepth> From<Rgba<C>> for La<C> {
+    fn from(color: Rgba<C>) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl<C: BitDepth> Pixel for La<C> {
+    fn to_rgba(self) -> F32x4Rgba {
+        bit_depth::promote(Self::to_rgba(self))
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::from_rgba(bit_depth::demote(color))
+    }
+}
+
+// SAFETY: `La<C>` is `#[repr(C)]` with two fields of the same type, so it has no padding, and
+// every bit pattern is valid whenever it is valid for `C`.
+#[cfg(feature = "bytemuck")]
+unsafe impl<C: bytemuck::Zeroable + Copy> bytemuck::Zeroable for La<C> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<C: bytemuck::Pod> bytemuck::Pod for La<C> {}
+
+impl<C: Channel> PorterDuff<C, fn(C, C) -> C> {
+    /// Returns the result of the blend operation on grayscale colors.
+    ///
+    /// Identical to [`blend`](Self::blend) on the colors' [`La::to_rgba`] expansions.
+    #[must_use]
+    pub fn blend_la(&self, src: La<C>, dst: La<C>) -> La<C> {
+        let (fa, fb) = self.factors(src.a, dst.a);
+        La::new(
+            src.l.mul(fa).add(dst.l.mul(fb)),
+            src.a.mul(fa).add(dst.a.mul(fb)),
+        )
+    }
+
+    /// Blends grayscale `src` onto `dst` in place.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn blend_la_slice(&self, src: &[La<C>], dst: &mut [La<C>]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        for (s, d) in src.iter().zip(dst) {
+            *d = self.blend_la(*s, *d);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::{
+        format,
+        porter_duff::{F32PorterDuff, U8PorterDuff},
+        rgba::U8x4Rgba,
+    };
+
+    #[test]
+    fn rgba_conversions() {
+        assert_eq!(U8x4Rgba::from(U8x2La::new(7, 9)), U8x4Rgba::new(7, 7, 7, 9));
+        assert_eq!(U8x2La::from(U8x4Rgba::new(7, 7, 7, 9)), U8x2La::new(7, 9));
+        assert_eq!(U8x2La::from(U8x4Rgba::WHITE), U8x2La::new(255, 255));
+
+        let green = F32x2La::from(F32x4Rgba::new(0.0, 1.0, 0.0, 0.5));
+        assert!((green.l - 0.7152).abs() < 1e-6);
+        assert_eq!(green.a, 0.5);
+    }
+
+    #[test]
+    fn blend_la_matches_rgba() {
+        let modes = [
+            U8PorterDuff::SRC_OVER,
+            U8PorterDuff::DST_IN,
+            U8PorterDuff::XOR,
+            U8PorterDuff::PLUS,
+        ];
+        let dst = U8x2La::new(64, 200);
+        for a in (0..=255).step_by(15) {
+            let src = U8x2La::new(255, a);
+            for mode in modes {
+                let expected = mode.blend(src.to_rgba(), dst.to_rgba());
+                assert_eq!(mode.blend_la(src, dst).to_rgba(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn blend_la_slice_matches_individual() {
+        let src = [F32x2La::new(1.0, 0.5), F32x2La::new(0.25, 1.0)];
+        let mut dst = [F32x2La::new(0.0, 1.0); 2];
+        F32PorterDuff::SRC_OVER.blend_la_slice(&src, &mut dst);
+        assert_eq!(dst[0], F32x2La::new(0.5, 0.75));
+        assert_eq!(dst[1], F32x2La::new(0.25, 1.0));
+    }
+
+    #[test]
+    fn converts_to_other_formats() {
+        let gray = format::convert::<_, U8x2La>(U8x4Rgba::new(255, 255, 255, 128));
+        assert_eq!(gray, U8x2La::new(255, 128));
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn casts_raw_buffers() {
+        let raw = [10_u8, 255, 20, 128];
+        let pixels: &[U8x2La] = bytemuck::cast_slice(&raw);
+        assert_eq!(pixels, [U8x2La::new(10, 255), U8x2La::new(20, 128)]);
+    }
+}
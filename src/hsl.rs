@@ -0,0 +1,239 @@
+//! HSL and HSV, the cylindrical forms of RGB.
+//!
+//! [`Hsla`] and [`Hsva`] describe a color by its hue angle and saturation, plus its lightness or
+//! value, as CSS `hsl()` and most color pickers do. They are computed directly from the
+//! (usually sRGB-encoded) channels of an [`F32x4Rgba`], without any change of color space, so
+//! hue rotations and saturation tweaks round-trip exactly with the colors they came from.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{hsl::Hsla, rgba::F32x4Rgba};
+//!
+//! let orange = F32x4Rgba::new(1.0, 0.5, 0.0, 1.0);
+//! let mut hsla = orange.to_hsla();
+//! assert_eq!((hsla.h, hsla.s, hsla.l), (30.0, 1.0, 0.5));
+//!
+//! // Rotate the hue by a third of a turn.
+//! hsla.h += 120.0;
+//! assert_eq!(F32x4Rgba::from_hsla(hsla), F32x4Rgba::new(0.0, 1.0, 0.5, 1.0));
+//! ```
+
+use crate::rgba::F32x4Rgba;
+
+/// A color as hue, saturation, lightness and alpha.
+///
+/// The hue `h` is in degrees; conversions accept any angle and return one in `[0.0, 360.0)`.
+/// The other components are in `[0.0, 1.0]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Hsla {
+    /// Hue angle in degrees.
+    pub h: f32,
+
+    /// Saturation.
+    pub s: f32,
+
+    /// Lightness: `0.0` is black, `1.0` is white and `0.5` is the pure hue.
+    pub l: f32,
+
+    /// Straight alpha.
+    pub a: f32,
+}
+
+/// A color as hue, saturation, value and alpha.
+///
+/// The hue `h` is in degrees; conversions accept any angle and return one in `[0.0, 360.0)`.
+/// The other components are in `[0.0, 1.0]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Hsva {
+    /// Hue angle in degrees.
+    pub h: f32,
+
+    /// Saturation.
+    pub s: f32,
+
+    /// Value: `0.0` is black, and `1.0` with full saturation is the pure hue.
+    pub v: f32,
+
+    /// Straight alpha.
+    pub a: f32,
+}
+
+/// Wraps an angle in degrees to `[0.0, 360.0)`.
+fn wrap_degrees(h: f32) -> f32 {
+    let h = h % 360.0;
+    if h < 0.0 { h + 360.0 } else { h }
+}
+
+/// Returns the hue of `color` in degrees, and its largest and smallest color channels.
+#[allow(clippy::float_cmp)]
+fn hue_max_min(color: F32x4Rgba) -> (f32, f32, f32) {
+    let F32x4Rgba { r, g, b, .. } = color;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let h = if d <= 0.0 {
+        0.0
+    } else if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (wrap_degrees(h * 60.0), max, min)
+}
+
+impl F32x4Rgba {
+    /// Converts to hue, saturation and lightness, keeping alpha.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)]
+    pub fn to_hsla(self) -> Hsla {
+        let (h, max, min) = hue_max_min(self);
+        let l = f32::midpoint(max, min);
+        let d = max - min;
+        let s = if d <= 0.0 {
+            0.0
+        } else {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsla { h, s, l, a: self.a }
+    }
+
+    /// Converts from hue, saturation and lightness, keeping alpha.
+    #[must_use]
+    pub fn from_hsla(hsla: Hsla) -> Self {
+        let Hsla { h, s, l, a } = hsla;
+        let h = wrap_degrees(h) / 30.0;
+        let chroma = s * l.min(1.0 - l);
+        let f = |n: f32| {
+            let k = (n + h) % 12.0;
+            l - chroma * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self::new(f(0.0), f(8.0), f(4.0), a)
+    }
+
+    /// Converts to hue, saturation and value, keeping alpha.
+    #[must_use]
+    pub fn to_hsva(self) -> Hsva {
+        let (h, max, min) = hue_max_min(self);
+        let s = if max <= 0.0 { 0.0 } else { (max - min) / max };
+        Hsva {
+            h,
+            s,
+            v: max,
+            a: self.a,
+        }
+    }
+
+    /// Converts from hue, saturation and value, keeping alpha.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)]
+    pub fn from_hsva(hsva: Hsva) -> Self {
+        let Hsva { h, s, v, a } = hsva;
+        let h = wrap_degrees(h) / 60.0;
+        let f = |n: f32| {
+            let k = (n + h) % 6.0;
+            v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Self::new(f(5.0), f(3.0), f(1.0), a)
+    }
+}
+
+impl From<F32x4Rgba> for Hsla {
+    fn from(color: F32x4Rgba) -> Self {
+        color.to_hsla()
+    }
+}
+
+impl From<Hsla> for F32x4Rgba {
+    fn from(hsla: Hsla) -> Self {
+        Self::from_hsla(hsla)
+    }
+}
+
+impl From<F32x4Rgba> for Hsva {
+    fn from(color: F32x4Rgba) -> Self {
+        color.to_hsva()
+    }
+}
+
+impl From<Hsva> for F32x4Rgba {
+    fn from(hsva: Hsva) -> Self {
+        Self::from_hsva(hsva)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn primaries() {
+        let blue = F32x4Rgba::new(0.0, 0.0, 1.0, 0.5);
+        assert_eq!(
+            blue.to_hsla(),
+            Hsla {
+                h: 240.0,
+                s: 1.0,
+                l: 0.5,
+                a: 0.5
+            }
+        );
+        assert_eq!(
+            blue.to_hsva(),
+            Hsva {
+                h: 240.0,
+                s: 1.0,
+                v: 1.0,
+                a: 0.5
+            }
+        );
+        let magenta = F32x4Rgba::new(1.0, 0.0, 1.0, 1.0);
+        assert_eq!(magenta.to_hsla().h, 300.0);
+    }
+
+    #[test]
+    fn grays_have_no_saturation() {
+        for gray in [
+            F32x4Rgba::BLACK,
+            F32x4Rgba::WHITE,
+            F32x4Rgba::new(0.5, 0.5, 0.5, 1.0),
+        ] {
+            assert_eq!(gray.to_hsla().s, 0.0);
+            assert_eq!(gray.to_hsva().s, 0.0);
+            assert_eq!(gray.to_hsla().h, 0.0);
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for i in 0..=64_u8 {
+            let t = f32::from(i) / 64.0;
+            let color = F32x4Rgba::new(t, 1.0 - t, (t * 3.0) % 1.0, t);
+            assert_close(F32x4Rgba::from(Hsla::from(color)), color, 1e-5);
+            assert_close(F32x4Rgba::from(Hsva::from(color)), color, 1e-5);
+        }
+    }
+
+    #[test]
+    fn hue_wraps() {
+        let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+        let below = Hsla {
+            h: -360.0,
+            s: 1.0,
+            l: 0.5,
+            a: 1.0,
+        };
+        assert_close(F32x4Rgba::from_hsla(below), red, 1e-5);
+        let above = Hsva {
+            h: 720.0,
+            s: 1.0,
+            v: 1.0,
+            a: 1.0,
+        };
+        assert_close(F32x4Rgba::from_hsva(above), red, 1e-5);
+    }
+}
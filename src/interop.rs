@@ -0,0 +1,1020 @@
+//! Adapters for image buffers from other crates.
+//!
+//! Most image libraries expose pixels as interleaved RGBA channels in a flat buffer; for example
+//! `zune-image` (via `Image::flatten_to_u8`) and `png`. [`blend_interleaved`] blends such buffers
+//! directly, at any supported [bit depth](crate::bit_depth), without first converting them to
+//! [`Rgba`] slices, and [`blit_rgba8`] positions and clips a sprite onto an RGBA8 framebuffer,
+//! such as the frame of the `pixels` crate.
+//!
+//! With the `exr` feature, RGBA layers of EXR images can be read into and written from
+//! [`Surface`](crate::surface::Surface)s, converting from and to the premultiplied alpha that
+//! EXR uses.
+//!
+//! With the `image` feature, [`ImageBuffer`](::image::ImageBuffer)s of the `image` crate (which
+//! `imageproc` also uses) can be blended with [`blend_image`], and converted to and from
+//! [`Surface`](crate::surface::Surface)s.
+//!
+//! With the `zune-image` feature, RGBA [`zune_image::image::Image`]s of any bit depth can be
+//! blended frame by frame with [`blend_zune_image`], and converted to and from
+//! [`Surface`](crate::surface::Surface)s with [`zune_to_surface`] and [`zune_from_surface`].
+//!
+//! With the `glam` feature, [`glam::Vec4`] converts to and from
+//! [`F32x4Rgba`](crate::rgba::F32x4Rgba) (`x, y, z, w` as `r, g, b, a`), and [`blend_vec4`]
+//! blends colors kept as `Vec4`s by renderers built on `glam`.
+//!
+//! With the `embedded-graphics` feature, `Rgb888` and `Rgb565` colors convert to and from
+//! [`U8x4Rgba`](crate::rgba::U8x4Rgba), which is an `embedded-graphics` `PixelColor`, and
+//! [`BlendTarget`] is a `DrawTarget` that blends what is drawn onto its framebuffer.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, interop};
+//!
+//! let src = [255, 0, 0, 255, 0, 0, 0, 0];
+//! let mut dst = [0, 0, 255, 255, 0, 0, 255, 255];
+//! interop::blend_interleaved::<_, u8, u8>(&BlendMode::SourceOver, &src, &mut dst);
+//! assert_eq!(dst, [255, 0, 0, 255, 0, 0, 255, 255]);
+//! ```
+
+use crate::{
+    RgbaBlend,
+    bit_depth::{self, BitDepth},
+    composite,
+    rgba::Rgba,
+};
+
+/// Blends interleaved RGBA channels `src` onto `dst` in place, pixel by pixel.
+///
+/// Each pixel is promoted to `f32`, blended, and demoted back to the channel type of `dst`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length, or their length is not a multiple of `4`.
+pub fn blend_interleaved<B, S, D>(blend: &B, src: &[S], dst: &mut [D])
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: BitDepth,
+    D: BitDepth,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert_eq!(dst.len() % 4, 0, "buffer must have 4 channels per pixel");
+    for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+        let s = Rgba::new(s[0], s[1], s[2], s[3]);
+        let color = bit_depth::promote(Rgba::new(d[0], d[1], d[2], d[3]));
+        let out: Rgba<D> = bit_depth::demote(blend.apply(bit_depth::promote(s), color));
+        d.copy_from_slice(out.as_ref());
+    }
+}
+
+/// Blends the RGBA8 image `sprite`, `sprite_width` pixels wide, onto the RGBA8 framebuffer
+/// `frame`, `frame_width` pixels wide, with its top-left corner at `(x, y)`.
+///
+/// Both buffers are rows of interleaved `r, g, b, a` bytes with no padding, as used by the
+/// `pixels` crate. Offsets may be negative, or place the sprite partially or entirely outside the
+/// frame; only the overlapping region is blended, as in
+/// [`composite_at`](crate::composite::composite_at).
+///
+/// ## Panics
+///
+/// If either width is zero, or the length of either buffer is not a multiple of `4 × width`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{BlendMode, interop};
+///
+/// // A 1×1 white sprite hanging off the bottom-right corner of a 2×2 black frame.
+/// let mut frame = [0, 0, 0, 255].repeat(4);
+/// interop::blit_rgba8(&mut frame, 2, &[255; 4], 1, 1, 1, &BlendMode::SourceOver);
+/// assert_eq!(frame[12..], [255; 4]);
+/// assert_eq!(frame[..12], [0, 0, 0, 255].repeat(3));
+/// ```
+pub fn blit_rgba8<B>(
+    frame: &mut [u8],
+    frame_width: usize,
+    sprite: &[u8],
+    sprite_width: usize,
+    x: isize,
+    y: isize,
+    mode: &B,
+) where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    let frame_stride = frame_width * 4;
+    let sprite_stride = sprite_width * 4;
+    assert!(
+        frame_stride != 0 && frame.len() % frame_stride == 0,
+        "frame length must be a multiple of 4 × width"
+    );
+    assert!(
+        sprite_stride != 0 && sprite.len() % sprite_stride == 0,
+        "sprite length must be a multiple of 4 × width"
+    );
+    let Some((src, dst)) = composite::clip(
+        (sprite_width, sprite.len() / sprite_stride),
+        (frame_width, frame.len() / frame_stride),
+        x,
+        y,
+    ) else {
+        return;
+    };
+    for row in 0..src.height {
+        let s = (src.y + row) * sprite_stride + src.x * 4;
+        let d = (dst.y + row) * frame_stride + dst.x * 4;
+        let len = src.width * 4;
+        blend_interleaved::<_, u8, u8>(mode, &sprite[s..s + len], &mut frame[d..d + len]);
+    }
+}
+
+#[cfg(feature = "image")]
+pub use image_buffer::{blend_image, from_surface, to_surface};
+
+#[cfg(feature = "image")]
+mod image_buffer {
+    use core::ops::{Deref, DerefMut};
+    use std::vec::Vec;
+
+    use image::{ImageBuffer, Pixel, Primitive, Rgba32FImage};
+
+    use super::blend_interleaved;
+    use crate::{
+        RgbaBlend,
+        bit_depth::{self, BitDepth},
+        rgba::{F32x4Rgba, Rgba},
+        surface::Surface,
+    };
+
+    impl<T: Primitive> From<image::Rgba<T>> for Rgba<T> {
+        fn from(pixel: image::Rgba<T>) -> Self {
+            let [r, g, b, a] = pixel.0;
+            Self::new(r, g, b, a)
+        }
+    }
+
+    impl<T: Primitive> From<Rgba<T>> for image::Rgba<T> {
+        fn from(color: Rgba<T>) -> Self {
+            Self([color.r, color.g, color.b, color.a])
+        }
+    }
+
+    /// Blends RGBA image `src` onto `dst` in place.
+    ///
+    /// The images may have different channel types, such as an `RgbaImage` onto an
+    /// `Rgba32FImage`; see [`blend_interleaved`].
+    ///
+    /// _This function requires the `image` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If the images do not have the same dimensions.
+    pub fn blend_image<B, S, D, CS, CD>(
+        blend: &B,
+        src: &ImageBuffer<image::Rgba<S>, CS>,
+        dst: &mut ImageBuffer<image::Rgba<D>, CD>,
+    ) where
+        B: RgbaBlend<Channel = f32> + ?Sized,
+        S: Primitive + BitDepth,
+        D: Primitive + BitDepth,
+        image::Rgba<S>: Pixel<Subpixel = S>,
+        image::Rgba<D>: Pixel<Subpixel = D>,
+        CS: Deref<Target = [S]>,
+        CD: DerefMut<Target = [D]>,
+    {
+        assert_eq!(
+            src.dimensions(),
+            dst.dimensions(),
+            "src and dst images must have the same dimensions"
+        );
+        blend_interleaved(blend, src.as_raw(), dst);
+    }
+
+    /// Converts an RGBA image of any channel type to a [`Surface`].
+    ///
+    /// _This function requires the `image` feature._
+    #[must_use]
+    pub fn to_surface<T, C>(image: &ImageBuffer<image::Rgba<T>, C>) -> Surface
+    where
+        T: Primitive + BitDepth,
+        image::Rgba<T>: Pixel<Subpixel = T>,
+        C: Deref<Target = [T]>,
+    {
+        let pixels: Vec<F32x4Rgba> = image
+            .pixels()
+            .map(|&p| bit_depth::promote(Rgba::from(p)))
+            .collect();
+        let (width, height) = image.dimensions();
+        Surface::from_pixels(width as usize, height as usize, pixels)
+    }
+
+    /// Converts a [`Surface`] to an `Rgba32FImage`.
+    ///
+    /// _This function requires the `image` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If the surface is larger than `u32::MAX` pixels in either dimension.
+    #[must_use]
+    pub fn from_surface(surface: &Surface) -> Rgba32FImage {
+        let width = u32::try_from(surface.width()).expect("surface width must fit in u32");
+        let height = u32::try_from(surface.height()).expect("surface height must fit in u32");
+        let raw = surface
+            .pixels()
+            .iter()
+            .flat_map(|p| [p.r, p.g, p.b, p.a])
+            .collect();
+        ImageBuffer::from_raw(width, height, raw).expect("buffer matches dimensions")
+    }
+}
+
+#[cfg(feature = "zune-image")]
+pub use zune::{blend_zune_image, zune_from_surface, zune_to_surface};
+
+#[cfg(feature = "zune-image")]
+mod zune {
+    use core::mem;
+    use std::vec::Vec;
+
+    use zune_core::{bit_depth::BitDepth as ZuneDepth, colorspace::ColorSpace};
+    use zune_image::{frame::Frame, image::Image};
+
+    use super::blend_interleaved;
+    use crate::{
+        RgbaBlend,
+        bit_depth::BitDepth,
+        rgba::{F32x4Rgba, Rgba},
+        surface::Surface,
+    };
+
+    /// Returns the interleaved channels of `frame`, converted to `f32` in `[0.0, 1.0]`.
+    fn frame_to_unit(frame: &Frame, depth: ZuneDepth) -> Vec<f32> {
+        match depth {
+            ZuneDepth::Eight => frame
+                .flatten::<u8>(ColorSpace::RGBA)
+                .into_iter()
+                .map(BitDepth::to_unit)
+                .collect(),
+            ZuneDepth::Sixteen => frame
+                .flatten::<u16>(ColorSpace::RGBA)
+                .into_iter()
+                .map(BitDepth::to_unit)
+                .collect(),
+            ZuneDepth::Float32 => frame.flatten::<f32>(ColorSpace::RGBA),
+            _ => panic!("image bit depth must be known"),
+        }
+    }
+
+    /// Panics unless `image` stores RGBA pixels.
+    fn assert_rgba(image: &Image) {
+        assert_eq!(
+            image.colorspace(),
+            ColorSpace::RGBA,
+            "image must be RGBA; convert it with `Image::convert_color` first"
+        );
+    }
+
+    /// Blends each frame of the RGBA image `src` onto the matching frame of `dst` in place.
+    ///
+    /// The images may have different bit depths, such as an 8-bit image onto a 32-bit float one;
+    /// see [`blend_interleaved`]. Frame durations of `dst` are kept.
+    ///
+    /// _This function requires the `zune-image` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If either image is not RGBA or has an unknown bit depth, or the images do not have the
+    /// same dimensions and number of frames.
+    pub fn blend_zune_image<B>(blend: &B, src: &Image, dst: &mut Image)
+    where
+        B: RgbaBlend<Channel = f32> + ?Sized,
+    {
+        assert_rgba(src);
+        assert_rgba(dst);
+        assert_eq!(
+            (src.dimensions(), src.frames_len()),
+            (dst.dimensions(), dst.frames_len()),
+            "src and dst images must have the same dimensions and number of frames"
+        );
+        let (src_depth, dst_depth) = (src.depth(), dst.depth());
+        for (s, d) in src.frames_ref().iter().zip(dst.frames_mut()) {
+            let s = frame_to_unit(s, src_depth);
+            let mut blended = match dst_depth {
+                ZuneDepth::Eight => {
+                    let mut pixels = d.flatten::<u8>(ColorSpace::RGBA);
+                    blend_interleaved(blend, &s, &mut pixels);
+                    Frame::from_u8(&pixels, ColorSpace::RGBA, 0, 0)
+                }
+                ZuneDepth::Sixteen => {
+                    let mut pixels = d.flatten::<u16>(ColorSpace::RGBA);
+                    blend_interleaved(blend, &s, &mut pixels);
+                    Frame::from_u16(&pixels, ColorSpace::RGBA, 0, 0)
+                }
+                ZuneDepth::Float32 => {
+                    let mut pixels = d.flatten::<f32>(ColorSpace::RGBA);
+                    blend_interleaved(blend, &s, &mut pixels);
+                    Frame::from_f32(&pixels, ColorSpace::RGBA, 0, 0)
+                }
+                _ => panic!("image bit depth must be known"),
+            };
+            d.set_channels(mem::take(blended.channels_vec()));
+        }
+    }
+
+    /// Converts the first frame of an RGBA image of any bit depth to a [`Surface`].
+    ///
+    /// _This function requires the `zune-image` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If the image is not RGBA, has an unknown bit depth, or has no frames.
+    #[must_use]
+    pub fn zune_to_surface(image: &Image) -> Surface {
+        assert_rgba(image);
+        let frame = image.frames_ref().first().expect("image must have a frame");
+        let pixels = frame_to_unit(frame, image.depth())
+            .chunks_exact(4)
+            .map(|c| Rgba::new(c[0], c[1], c[2], c[3]))
+            .collect();
+        let (width, height) = image.dimensions();
+        Surface::from_pixels(width, height, pixels)
+    }
+
+    /// Converts a [`Surface`] to a single-frame, 32-bit float RGBA image.
+    ///
+    /// _This function requires the `zune-image` feature._
+    #[must_use]
+    pub fn zune_from_surface(surface: &Surface) -> Image {
+        let raw: Vec<f32> = F32x4Rgba::as_arrays(surface.pixels())
+            .as_flattened()
+            .to_vec();
+        Image::from_f32(&raw, surface.width(), surface.height(), ColorSpace::RGBA)
+    }
+}
+
+#[cfg(feature = "exr")]
+pub use exr_file::{read_exr, read_exr_layers, write_exr, write_exr_layers};
+
+#[cfg(feature = "exr")]
+mod exr_file {
+    use std::{
+        io::{Read, Seek, Write},
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use exr::{
+        error::{Result, UnitResult},
+        image::{Encoding, Image, Layer, SpecificChannels, read, write::channels::GetPixel},
+        math::Vec2,
+        meta::{
+            attribute::{ChannelDescription, IntegerBounds, Text},
+            header::{ImageAttributes, LayerAttributes},
+        },
+        prelude::traits::{ReadChannels as _, ReadLayers as _, WritableImage as _},
+    };
+
+    use crate::{rgba::F32x4Rgba, surface::Surface};
+
+    /// Reads the RGBA layers of an EXR image, with their layer names.
+    fn read_layers(reader: impl Read + Seek, all: bool) -> Result<Vec<(String, Surface)>> {
+        let pixels = read::read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .rgba_channels(
+                |size, _| Surface::new(size.width(), size.height()),
+                |surface: &mut Surface, Vec2(x, y), (r, g, b, a): (f32, f32, f32, f32)| {
+                    surface.set(x, y, F32x4Rgba::new(r, g, b, a).unpremultiply());
+                },
+            );
+        let name = |attributes: &LayerAttributes| {
+            attributes
+                .layer_name
+                .as_ref()
+                .map_or_else(String::new, Text::to_string)
+        };
+        if all {
+            let image = pixels.all_layers().all_attributes().from_buffered(reader)?;
+            Ok(image
+                .layer_data
+                .into_iter()
+                .map(|layer| (name(&layer.attributes), layer.channel_data.pixels))
+                .collect())
+        } else {
+            let image = pixels
+                .first_valid_layer()
+                .all_attributes()
+                .from_buffered(reader)?;
+            let layer = image.layer_data;
+            Ok(vec![(name(&layer.attributes), layer.channel_data.pixels)])
+        }
+    }
+
+    /// Reads the first RGBA layer of an EXR image into a [`Surface`].
+    ///
+    /// EXR stores premultiplied (associated) alpha; pixels are converted to straight alpha,
+    /// so the surface can be composited with any [`BlendMode`](crate::BlendMode). A missing
+    /// alpha channel is read as opaque.
+    ///
+    /// _This function requires the `exr` feature._
+    ///
+    /// ## Errors
+    ///
+    /// If the image cannot be read or has no layer with red, green and blue channels.
+    pub fn read_exr(reader: impl Read + Seek) -> Result<Surface> {
+        let mut layers = read_layers(reader, false)?;
+        Ok(layers.remove(0).1)
+    }
+
+    /// Reads every RGBA layer of an EXR image, such as the AOVs of a render, as named
+    /// [`Surface`]s.
+    ///
+    /// Unnamed layers have an empty name. Pixels are converted as by [`read_exr`].
+    ///
+    /// _This function requires the `exr` feature._
+    ///
+    /// ## Errors
+    ///
+    /// If the image cannot be read, or any layer lacks red, green or blue channels.
+    pub fn read_exr_layers(reader: impl Read + Seek) -> Result<Vec<(String, Surface)>> {
+        read_layers(reader, true)
+    }
+
+    /// A surface whose pixels are written premultiplied.
+    struct Premultiplied<'a>(&'a Surface);
+
+    impl GetPixel for Premultiplied<'_> {
+        type Pixel = (f32, f32, f32, f32);
+
+        fn get_pixel(&self, Vec2(x, y): Vec2<usize>) -> Self::Pixel {
+            let p = self.0.pixels()[y * self.0.width() + x].premultiply();
+            (p.r, p.g, p.b, p.a)
+        }
+    }
+
+    type Channels<'a> = SpecificChannels<
+        Premultiplied<'a>,
+        (
+            ChannelDescription,
+            ChannelDescription,
+            ChannelDescription,
+            ChannelDescription,
+        ),
+    >;
+
+    /// Creates a 32-bit float EXR layer of `surface`.
+    fn layer(attributes: LayerAttributes, surface: &Surface) -> Layer<Channels<'_>> {
+        Layer::new(
+            (surface.width(), surface.height()),
+            attributes,
+            Encoding::FAST_LOSSLESS,
+            SpecificChannels::rgba(Premultiplied(surface)),
+        )
+    }
+
+    /// Writes `surface` as a single-layer, 32-bit float EXR image.
+    ///
+    /// Pixels are premultiplied, following the EXR convention.
+    ///
+    /// _This function requires the `exr` feature._
+    ///
+    /// ## Errors
+    ///
+    /// If the image cannot be written.
+    pub fn write_exr(surface: &Surface, writer: impl Write + Seek) -> UnitResult {
+        let image = Image::from_layer(layer(LayerAttributes::default(), surface));
+        image.write().to_buffered(writer)
+    }
+
+    /// Writes named surfaces as the layers of a multi-layer, 32-bit float EXR image.
+    ///
+    /// Pixels are premultiplied, following the EXR convention.
+    ///
+    /// _This function requires the `exr` feature._
+    ///
+    /// ## Errors
+    ///
+    /// If the image cannot be written, for example because layer names are not unique.
+    pub fn write_exr_layers(layers: &[(&str, &Surface)], writer: impl Write + Seek) -> UnitResult {
+        let width = layers.iter().map(|(_, s)| s.width()).max().unwrap_or(0);
+        let height = layers.iter().map(|(_, s)| s.height()).max().unwrap_or(0);
+        let layers: Vec<_> = layers
+            .iter()
+            .map(|&(name, surface)| layer(LayerAttributes::named(name), surface))
+            .collect();
+        let bounds = IntegerBounds::from_dimensions((width, height));
+        let image = Image::from_layers(ImageAttributes::new(bounds), layers);
+        image.write().to_buffered(writer)
+    }
+}
+
+#[cfg(feature = "glam")]
+pub use glam_vec::{blend_vec4, blend_vec4_slice};
+
+#[cfg(feature = "glam")]
+mod glam_vec {
+    use glam::Vec4;
+
+    use crate::{RgbaBlend, rgba::F32x4Rgba};
+
+    impl From<Vec4> for F32x4Rgba {
+        fn from(v: Vec4) -> Self {
+            Self::from(v.to_array())
+        }
+    }
+
+    impl From<F32x4Rgba> for Vec4 {
+        fn from(color: F32x4Rgba) -> Self {
+            Self::from_array(color.into())
+        }
+    }
+
+    /// Blends `src` with `dst`, straight-alpha colors stored as `Vec4`s.
+    ///
+    /// _This function requires the `glam` feature._
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{BlendMode, interop};
+    /// use glam::Vec4;
+    ///
+    /// let gray = Vec4::new(0.5, 0.5, 0.5, 1.0);
+    /// let out = interop::blend_vec4(&BlendMode::Multiply, gray, Vec4::ONE);
+    /// assert_eq!(out, Vec4::new(0.5, 0.5, 0.5, 1.0));
+    /// ```
+    #[must_use]
+    pub fn blend_vec4<B>(blend: &B, src: Vec4, dst: Vec4) -> Vec4
+    where
+        B: RgbaBlend<Channel = f32> + ?Sized,
+    {
+        blend.apply(src.into(), dst.into()).into()
+    }
+
+    /// Blends `src` onto `dst` in place, straight-alpha colors stored as `Vec4`s.
+    ///
+    /// _This function requires the `glam` feature._
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn blend_vec4_slice<B>(blend: &B, src: &[Vec4], dst: &mut [Vec4])
+    where
+        B: RgbaBlend<Channel = f32> + ?Sized,
+    {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        for (s, d) in src.iter().zip(dst) {
+            *d = blend_vec4(blend, *s, *d);
+        }
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+pub use draw_target::BlendTarget;
+
+#[cfg(feature = "embedded-graphics")]
+mod draw_target {
+    use core::convert::Infallible;
+
+    use embedded_graphics_core::{
+        Pixel,
+        draw_target::DrawTarget,
+        geometry::{OriginDimensions, Size},
+        pixelcolor::{PixelColor, Rgb565, Rgb888, RgbColor, raw::RawU32},
+    };
+
+    use crate::{
+        BlendMode, RgbaBlend,
+        rgba::{F32x4Rgba, U8x4Rgba},
+    };
+
+    impl PixelColor for U8x4Rgba {
+        type Raw = RawU32;
+    }
+
+    impl From<Rgb888> for U8x4Rgba {
+        fn from(color: Rgb888) -> Self {
+            Self::new(color.r(), color.g(), color.b(), u8::MAX)
+        }
+    }
+
+    /// Drops the alpha channel; use [`U8x4Rgba::flatten_over`] first to keep its effect.
+    impl From<U8x4Rgba> for Rgb888 {
+        fn from(color: U8x4Rgba) -> Self {
+            Self::new(color.r, color.g, color.b)
+        }
+    }
+
+    impl From<Rgb565> for U8x4Rgba {
+        fn from(color: Rgb565) -> Self {
+            Self::from(Rgb888::from(color))
+        }
+    }
+
+    /// Drops the alpha channel and the low bits of each color channel.
+    impl From<U8x4Rgba> for Rgb565 {
+        fn from(color: U8x4Rgba) -> Self {
+            Self::from(Rgb888::from(color))
+        }
+    }
+
+    /// An `embedded-graphics` [`DrawTarget`] that blends drawn pixels onto a framebuffer with
+    /// a [`BlendMode`], instead of overwriting them.
+    ///
+    /// Pixels outside the framebuffer are ignored, as other draw targets do. Convert the
+    /// finished framebuffer to the display's color type, such as [`Rgb565`], to flush it.
+    ///
+    /// _This type requires the `embedded-graphics` feature._
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{BlendMode, interop::BlendTarget, rgba::U8x4Rgba};
+    /// use embedded_graphics_core::{Pixel, draw_target::DrawTarget, geometry::Point};
+    ///
+    /// let mut framebuffer = [U8x4Rgba::CYAN; 4];
+    /// let mut target = BlendTarget::new(&mut framebuffer, 2, BlendMode::Multiply);
+    /// let gray = U8x4Rgba::new(128, 128, 128, 255);
+    /// target.draw_iter([Pixel(Point::new(1, 0), gray), Pixel(Point::new(5, 5), gray)])?;
+    ///
+    /// assert_eq!(framebuffer[0], U8x4Rgba::CYAN);
+    /// assert_eq!(framebuffer[1], U8x4Rgba::new(0, 128, 128, 255));
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    #[derive(Debug)]
+    pub struct BlendTarget<'a> {
+        pixels: &'a mut [U8x4Rgba],
+        width: usize,
+        mode: BlendMode,
+    }
+
+    impl<'a> BlendTarget<'a> {
+        /// Creates a target drawing onto `pixels`, an image `width` pixels wide, with `mode`.
+        ///
+        /// ## Panics
+        ///
+        /// If `width` is zero or does not divide the length of `pixels`.
+        #[must_use]
+        pub fn new(pixels: &'a mut [U8x4Rgba], width: usize, mode: BlendMode) -> Self {
+            assert!(
+                width != 0 && pixels.len() % width == 0,
+                "buffer length must be a multiple of width"
+            );
+            Self {
+                pixels,
+                width,
+                mode,
+            }
+        }
+
+        /// Returns the blend mode used for drawn pixels.
+        #[must_use]
+        pub const fn mode(&self) -> BlendMode {
+            self.mode
+        }
+
+        /// Sets the blend mode used for pixels drawn from now on.
+        pub const fn set_mode(&mut self, mode: BlendMode) {
+            self.mode = mode;
+        }
+
+        /// Returns the framebuffer.
+        #[must_use]
+        pub const fn pixels(&self) -> &[U8x4Rgba] {
+            self.pixels
+        }
+    }
+
+    impl OriginDimensions for BlendTarget<'_> {
+        fn size(&self) -> Size {
+            let dimension = |n: usize| u32::try_from(n).unwrap_or(u32::MAX);
+            Size::new(
+                dimension(self.width),
+                dimension(self.pixels.len() / self.width),
+            )
+        }
+    }
+
+    impl DrawTarget for BlendTarget<'_> {
+        type Color = U8x4Rgba;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            let height = self.pixels.len() / self.width;
+            for Pixel(point, color) in pixels {
+                let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                    continue;
+                };
+                if x < self.width && y < height {
+                    let dst = &mut self.pixels[y * self.width + x];
+                    let out = self
+                        .mode
+                        .apply(F32x4Rgba::from(color), F32x4Rgba::from(*dst));
+                    *dst = U8x4Rgba::from(out);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    #[test]
+    fn interleaved_matches_rgba_blend() {
+        let src = [255_u8, 0, 0, 128];
+        let mut dst = [0_u16, 0, 0xFFFF, 0xFFFF];
+        blend_interleaved(&BlendMode::SourceOver, &src, &mut dst);
+
+        let mut expected = [Rgba::<u16>::new(0, 0, 0xFFFF, 0xFFFF)];
+        bit_depth::blend_mixed(
+            &BlendMode::SourceOver,
+            &[Rgba::new(255_u8, 0, 0, 128)],
+            &mut expected,
+        );
+        assert_eq!(dst, <[u16; 4]>::from(expected[0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "4 channels per pixel")]
+    fn interleaved_panics_on_partial_pixel() {
+        blend_interleaved::<_, u8, u8>(&BlendMode::Source, &[0; 3], &mut [0; 3]);
+    }
+
+    #[test]
+    fn blit_rgba8_clips_to_frame() {
+        // A 2×2 sprite with one opaque white pixel per row, placed one pixel up and left.
+        let sprite = [[255; 4], [0; 4], [0; 4], [255; 4]].concat();
+        let mut frame = [0, 0, 0, 255].repeat(6);
+        blit_rgba8(&mut frame, 3, &sprite, 2, -1, -1, &BlendMode::SourceOver);
+        let mut expected = [0, 0, 0, 255].repeat(6);
+        expected[..4].fill(255);
+        assert_eq!(frame, expected);
+
+        blit_rgba8(&mut frame, 3, &sprite, 2, 3, 0, &BlendMode::SourceOver);
+        assert_eq!(frame, expected);
+    }
+
+    #[test]
+    fn blit_rgba8_matches_composite_at() {
+        let sprite = [[255, 0, 0, 128], [0, 255, 0, 255]].concat();
+        let mut frame = [[0, 0, 255, 255]; 4].concat();
+        blit_rgba8(&mut frame, 2, &sprite, 1, 1, 0, &BlendMode::Multiply);
+
+        let mut expected = [0, 0, 255, 255].repeat(4);
+        blend_interleaved::<_, u8, u8>(&BlendMode::Multiply, &sprite[..4], &mut expected[4..8]);
+        blend_interleaved::<_, u8, u8>(&BlendMode::Multiply, &sprite[4..], &mut expected[12..]);
+        assert_eq!(frame, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 4 × width")]
+    fn blit_rgba8_panics_on_partial_row() {
+        blit_rgba8(&mut [0; 12], 2, &[0; 4], 1, 0, 0, &BlendMode::Source);
+    }
+
+    #[cfg(feature = "glam")]
+    mod glam_vec {
+        use glam::Vec4;
+
+        use super::*;
+        use crate::rgba::F32x4Rgba;
+
+        #[test]
+        fn vec4_round_trips() {
+            let color = F32x4Rgba::new(0.1, 0.2, 0.3, 0.4);
+            let v = Vec4::from(color);
+            assert_eq!(v, Vec4::new(0.1, 0.2, 0.3, 0.4));
+            assert_eq!(F32x4Rgba::from(v), color);
+        }
+
+        #[test]
+        fn vec4_slice_matches_rgba_blend() {
+            let src = [Vec4::new(1.0, 0.0, 0.0, 0.5), Vec4::ZERO];
+            let mut dst = [Vec4::new(0.0, 0.0, 1.0, 1.0); 2];
+            let expected = BlendMode::SourceOver.apply(src[0].into(), dst[0].into());
+            blend_vec4_slice(&BlendMode::SourceOver, &src, &mut dst);
+            assert_eq!(F32x4Rgba::from(dst[0]), expected);
+            assert_eq!(dst[1], Vec4::new(0.0, 0.0, 1.0, 1.0));
+        }
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    mod draw_target {
+        use embedded_graphics_core::{
+            Pixel,
+            draw_target::DrawTarget,
+            geometry::{OriginDimensions, Point, Size},
+            pixelcolor::{Rgb565, Rgb888},
+        };
+
+        use super::*;
+        use crate::rgba::U8x4Rgba;
+
+        #[test]
+        fn rgb_conversions() {
+            let orange = U8x4Rgba::new(255, 128, 0, 255);
+            assert_eq!(U8x4Rgba::from(Rgb888::from(orange)), orange);
+            assert_eq!(Rgb888::from(orange.with_alpha(0)), Rgb888::new(255, 128, 0));
+            let rgb565 = Rgb565::from(orange);
+            assert_eq!(rgb565, Rgb565::new(31, 32, 0));
+            assert_eq!(U8x4Rgba::from(rgb565), U8x4Rgba::new(255, 130, 0, 255));
+        }
+
+        #[test]
+        fn blend_target_blends_in_bounds() {
+            let mut pixels = [U8x4Rgba::WHITE; 6];
+            let mut target = BlendTarget::new(&mut pixels, 3, BlendMode::Multiply);
+            assert_eq!(target.size(), Size::new(3, 2));
+            let gray = U8x4Rgba::new(128, 128, 128, 255);
+            let drawn =
+                [(2, 1), (-1, 0), (3, 0), (0, 2)].map(|(x, y)| Pixel(Point::new(x, y), gray));
+            target.draw_iter(drawn).unwrap();
+            target.set_mode(BlendMode::Source);
+            target
+                .draw_iter([Pixel(Point::zero(), U8x4Rgba::RED)])
+                .unwrap();
+            assert_eq!(
+                pixels,
+                [
+                    U8x4Rgba::RED,
+                    U8x4Rgba::WHITE,
+                    U8x4Rgba::WHITE,
+                    U8x4Rgba::WHITE,
+                    U8x4Rgba::WHITE,
+                    gray
+                ]
+            );
+        }
+    }
+
+    #[cfg(feature = "exr")]
+    #[allow(clippy::float_cmp)]
+    mod exr_file {
+        use std::io::Cursor;
+
+        use exr::{
+            image::{Image, SpecificChannels},
+            math::Vec2,
+            prelude::traits::WritableImage as _,
+        };
+
+        use super::*;
+        use crate::{rgba::F32x4Rgba, surface::Surface};
+
+        const HALF_RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+
+        #[test]
+        fn reads_premultiplied_as_straight() {
+            let channels =
+                SpecificChannels::rgba(|_: Vec2<usize>| (0.5_f32, 0.0_f32, 0.0_f32, 0.5_f32));
+            let mut buffer = Cursor::new(Vec::new());
+            Image::from_channels((1, 1), channels)
+                .write()
+                .to_buffered(&mut buffer)
+                .unwrap();
+            buffer.set_position(0);
+            let surface = read_exr(buffer).unwrap();
+            assert_eq!(surface.pixels(), &[HALF_RED]);
+        }
+
+        #[test]
+        fn surface_round_trip() {
+            let surface =
+                Surface::from_pixels(2, 1, vec![HALF_RED, F32x4Rgba::new(0.0, 4.0, 0.0, 1.0)]);
+            let mut buffer = Cursor::new(Vec::new());
+            write_exr(&surface, &mut buffer).unwrap();
+            buffer.set_position(0);
+            assert_eq!(read_exr(buffer).unwrap(), surface);
+        }
+
+        #[test]
+        fn named_layers_round_trip() {
+            let beauty = Surface::filled(2, 2, HALF_RED);
+            let specular = Surface::filled(1, 1, F32x4Rgba::WHITE);
+            let mut buffer = Cursor::new(Vec::new());
+            write_exr_layers(&[("beauty", &beauty), ("specular", &specular)], &mut buffer).unwrap();
+            buffer.set_position(0);
+            let layers = read_exr_layers(buffer).unwrap();
+            assert_eq!(
+                layers,
+                [("beauty".into(), beauty), ("specular".into(), specular)]
+            );
+        }
+
+        #[test]
+        fn invalid_data_is_an_error() {
+            assert!(read_exr(Cursor::new(b"not an exr file")).is_err());
+        }
+    }
+
+    #[cfg(feature = "zune-image")]
+    #[allow(clippy::float_cmp)]
+    mod zune {
+        use zune_core::{bit_depth::BitDepth as ZuneDepth, colorspace::ColorSpace};
+        use zune_image::image::Image;
+
+        use super::*;
+        use crate::rgba::F32x4Rgba;
+
+        #[test]
+        fn blend_u8_onto_f32_image() {
+            let src = Image::from_u8(&[255, 255, 255, 255, 0, 0, 0, 0], 2, 1, ColorSpace::RGBA);
+            let mut dst = Image::from_f32(&[0.0, 0.0, 0.0, 1.0].repeat(2), 2, 1, ColorSpace::RGBA);
+            blend_zune_image(&BlendMode::SourceOver, &src, &mut dst);
+            assert_eq!(dst.depth(), ZuneDepth::Float32);
+            assert_eq!(
+                dst.flatten_frames::<f32>(),
+                [[1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0]]
+            );
+        }
+
+        #[test]
+        fn blend_u16_onto_u8_image() {
+            let src = Image::from_u16(&[0, 0, u16::MAX, u16::MAX], 1, 1, ColorSpace::RGBA);
+            let mut dst = Image::from_u8(&[255, 0, 0, 255], 1, 1, ColorSpace::RGBA);
+            blend_zune_image(&BlendMode::SourceOver, &src, &mut dst);
+            assert_eq!(dst.flatten_to_u8(), [[0, 0, 255, 255]]);
+        }
+
+        #[test]
+        fn surface_round_trip() {
+            let image = Image::from_u8(&[0, 0, 0, 0, 255, 0, 0, 255], 2, 1, ColorSpace::RGBA);
+            let surface = zune_to_surface(&image);
+            assert_eq!(surface.get(1, 0), Some(F32x4Rgba::new(1.0, 0.0, 0.0, 1.0)));
+            assert_eq!(surface.get(0, 0), Some(F32x4Rgba::TRANSPARENT));
+
+            let back = zune_from_surface(&surface);
+            assert_eq!(back.dimensions(), (2, 1));
+            assert_eq!(
+                back.flatten_frames::<f32>(),
+                [[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "must be RGBA")]
+        fn rejects_other_colorspaces() {
+            let image = Image::from_u8(&[1, 2, 3], 1, 1, ColorSpace::RGB);
+            let _ = zune_to_surface(&image);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    #[allow(clippy::float_cmp)]
+    mod image_buffer {
+        use image::{Rgba32FImage, RgbaImage};
+
+        use super::*;
+        use crate::rgba::F32x4Rgba;
+
+        #[test]
+        fn pixel_conversions() {
+            let p = image::Rgba([1_u8, 2, 3, 4]);
+            assert_eq!(Rgba::from(p), Rgba::new(1, 2, 3, 4));
+            assert_eq!(image::Rgba::from(Rgba::new(1_u8, 2, 3, 4)), p);
+        }
+
+        #[test]
+        fn blend_u8_onto_f32_image() {
+            let src = RgbaImage::from_pixel(2, 1, image::Rgba([255, 255, 255, 255]));
+            let mut dst = Rgba32FImage::from_pixel(2, 1, image::Rgba([0.0, 0.0, 0.0, 1.0]));
+            blend_image(&BlendMode::SourceOver, &src, &mut dst);
+            assert!(dst.pixels().all(|p| p.0 == [1.0; 4]));
+        }
+
+        #[test]
+        fn surface_round_trip() {
+            let mut image = RgbaImage::new(2, 2);
+            image.put_pixel(1, 0, image::Rgba([255, 0, 0, 255]));
+            let surface = to_surface(&image);
+            assert_eq!(surface.get(1, 0), Some(F32x4Rgba::new(1.0, 0.0, 0.0, 1.0)));
+            assert_eq!(surface.get(0, 1), Some(F32x4Rgba::TRANSPARENT));
+
+            let back = from_surface(&surface);
+            assert_eq!(back.dimensions(), (2, 2));
+            assert_eq!(back.get_pixel(1, 0).0, [1.0, 0.0, 0.0, 1.0]);
+        }
+
+        #[test]
+        #[should_panic(expected = "same dimensions")]
+        fn blend_image_panics_on_size_mismatch() {
+            let src = RgbaImage::new(2, 1);
+            let mut dst = RgbaImage::new(1, 2);
+            blend_image(&BlendMode::Source, &src, &mut dst);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Colors stored in other channel orders, such as BGRA.
+//!
+//! Windows DIBs, X11 and many GPU swapchains store pixels as BGRA, ARGB or ABGR rather than
+//! RGBA. An [`Ordered`] color keeps its channels in the order given by a [`ChannelOrder`]
+//! marker, so such buffers can be reinterpreted (with the `bytemuck` feature) and blended in
+//! place with [`blend_ordered`]: channels are reordered in registers, pixel by pixel, instead of
+//! swizzling whole buffers before and after blending.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     layout::{self, Bgra8},
+//!     porter_duff::U8PorterDuff,
+//!     rgba::U8x4Rgba,
+//! };
+//!
+//! // Blue, green, red, alpha.
+//! let sprite = [Bgra8::from_channels([0, 0, 255, 255])];
+//! let mut window = [Bgra8::from(U8x4Rgba::new(0, 0, 255, 255))];
+//! layout::blend_ordered(&U8PorterDuff::SRC_OVER, &sprite, &mut window);
+//! assert_eq!(window[0].channels(), [0, 0, 255, 255]);
+//! assert_eq!(window[0].into_rgba(), U8x4Rgba::new(255, 0, 0, 255));
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{
+    RgbaBlend,
+    bit_depth::{self, BitDepth},
+    format::Pixel,
+    rgba::{F32x4Rgba, Rgba},
+};
+
+/// The order in which an [`Ordered`] color stores its channels.
+pub trait ChannelOrder: Copy {
+    /// Storage index of the red, green, blue and alpha channels, in that order.
+    const INDICES: [usize; 4];
+}
+
+/// Blue, green, red, alpha.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bgra;
+
+impl ChannelOrder for Bgra {
+    const INDICES: [usize; 4] = [2, 1, 0, 3];
+}
+
+/// Alpha, red, green, blue.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Argb;
+
+impl ChannelOrder for Argb {
+    const INDICES: [usize; 4] = [1, 2, 3, 0];
+}
+
+/// Alpha, blue, green, red.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Abgr;
+
+impl ChannelOrder for Abgr {
+    const INDICES: [usize; 4] = [3, 2, 1, 0];
+}
+
+/// A straight-alpha color whose channels are stored in the order `O`.
+///
+/// Laid out exactly as `[C; 4]` in storage order, so buffers of it can be cast to and from raw
+/// channel buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Ordered<C: Copy, O: ChannelOrder> {
+    channels: [C; 4],
+    _order: PhantomData<O>,
+}
+
+/// An 8-bit color stored as blue, green, red, alpha.
+pub type Bgra8 = Ordered<u8, Bgra>;
+
+/// An 8-bit color stored as alpha, red, green, blue.
+pub type Argb8 = Ordered<u8, Argb>;
+
+/// An 8-bit color stored as alpha, blue, green, red.
+pub type Abgr8 = Ordered<u8, Abgr>;
+
+impl<C: Copy, O: ChannelOrder> Ordered<C, O> {
+    /// Wraps channels that are already in storage order.
+    #[must_use]
+    pub const fn from_channels(channels: [C; 4]) -> Self {
+        Self {
+            channels,
+            _order: PhantomData,
+        }
+    }
+
+    /// Returns the channels in storage order.
+    #[must_use]
+    pub const fn channels(self) -> [C; 4] {
+        self.channels
+    }
+
+    /// Reorders an RGBA color into storage order.
+    #[must_use]
+    pub fn new(color: Rgba<C>) -> Self {
+        let mut channels = [color.r; 4];
+        for (&index, channel) in O::INDICES.iter().zip([color.r, color.g, color.b, color.a]) {
+            channels[index] = channel;
+        }
+        Self::from_channels(channels)
+    }
+
+    /// Reorders the channels into an RGBA color.
+    #[must_use]
+    pub fn into_rgba(self) -> Rgba<C> {
+        let [r, g, b, a] = O::INDICES.map(|index| self.channels[index]);
+        Rgba::new(r, g, b, a)
+    }
+}
+
+impl<C: Copy, O: ChannelOrder> From<Rgba<C>> for Ordered<C, O> {
+    fn from(color: Rgba<C>) -> Self {
+        Self::new(color)
+    }
+}
+
+impl<C: Copy, O: ChannelOrder> From<Ordered<C, O>> for Rgba<C> {
+    fn from(color: Ordered<C, O>) -> Self {
+        color.into_rgba()
+    }
+}
+
+impl<C: BitDepth, O: ChannelOrder> Pixel for Ordered<C, O> {
+    fn to_rgba(self) -> F32x4Rgba {
+        bit_depth::promote(self.into_rgba())
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::new(bit_depth::demote(color))
+    }
+}
+
+// SAFETY: `Ordered<C, O>` is `#[repr(transparent)]` over `[C; 4]`; the marker is zero-sized.
+#[cfg(feature = "bytemuck")]
+unsafe impl<C: bytemuck::Zeroable + Copy, O: ChannelOrder> bytemuck::Zeroable for Ordered<C, O> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<C: bytemuck::Pod, O: ChannelOrder + 'static> bytemuck::Pod for Ordered<C, O> {}
+
+/// Blends `src` onto `dst` in place, both stored in the channel order `O`.
+///
+/// Each pixel is reordered to RGBA in registers, blended, and written back in storage order.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_ordered<B, O>(
+    blend: &B,
+    src: &[Ordered<B::Channel, O>],
+    dst: &mut [Ordered<B::Channel, O>],
+) where
+    B: RgbaBlend + ?Sized,
+    O: ChannelOrder,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = Ordered::new(blend.apply(s.into_rgba(), d.into_rgba()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, format, porter_duff::U8PorterDuff, rgba::U8x4Rgba};
+
+    const COLOR: U8x4Rgba = U8x4Rgba::new(1, 2, 3, 4);
+
+    #[test]
+    fn storage_orders() {
+        assert_eq!(Bgra8::from(COLOR).channels(), [3, 2, 1, 4]);
+        assert_eq!(Argb8::from(COLOR).channels(), [4, 1, 2, 3]);
+        assert_eq!(Abgr8::from(COLOR).channels(), [4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(Bgra8::from(COLOR).into_rgba(), COLOR);
+        assert_eq!(Argb8::from(COLOR).into_rgba(), COLOR);
+        assert_eq!(U8x4Rgba::from(Abgr8::from(COLOR)), COLOR);
+    }
+
+    #[test]
+    fn blend_ordered_matches_rgba() {
+        let src = U8x4Rgba::new(255, 128, 0, 100);
+        let dst = U8x4Rgba::new(0, 64, 255, 200);
+        let expected = U8PorterDuff::SRC_OVER.apply(src, dst);
+        let mut argb = [Argb8::from(dst)];
+        blend_ordered(&U8PorterDuff::SRC_OVER, &[Argb8::from(src)], &mut argb);
+        assert_eq!(argb[0].into_rgba(), expected);
+    }
+
+    #[test]
+    fn converts_to_other_formats() {
+        let mut dst = [F32x4Rgba::TRANSPARENT];
+        format::blend_converted(
+            &BlendMode::Source,
+            &[Bgra8::from(U8x4Rgba::WHITE)],
+            &mut dst,
+        );
+        assert_eq!(dst, [F32x4Rgba::WHITE]);
+        assert_eq!(
+            format::convert::<_, Bgra8>(F32x4Rgba::new(1.0, 0.0, 0.0, 1.0)).channels(),
+            [0, 0, 255, 255]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn casts_raw_buffers() {
+        let raw = [0_u8, 0, 255, 255, 255, 0, 0, 128];
+        let pixels: &[Bgra8] = bytemuck::cast_slice(&raw);
+        assert_eq!(pixels[0].into_rgba(), U8x4Rgba::new(255, 0, 0, 255));
+        assert_eq!(pixels[1].into_rgba(), U8x4Rgba::new(0, 0, 255, 128));
+    }
+}
@@ -11,7 +11,7 @@
 //! example [`BlendMode::SourceOver`] blends the source color over the destination color using
 //! [`PorterDuff::SRC_OVER`][].
 //!
-//! [`PorterDuff::SRC_OVER`]: `crate::porter_duff::PorterDuff::SRC_OVER`
+//! [`PorterDuff::SRC_OVER`]: `crate::porter_duff::F32PorterDuff::SRC_OVER`
 //!
 //! ```rust
 //! use alpha_blend::{rgba::F32x4Rgba, BlendMode, RgbaBlend};
@@ -30,9 +30,57 @@
 //!
 //! Either `std` or `libm` must be enabled.
 //!
+//! ### `alloc`
+//!
+//! Enables the heap-allocating [`additive`], [`compositor`], [`surface`] and [`tiled`] modules,
+//! and `canvas::Canvas`, on `no_std` targets with an allocator. Implied by `std`.
+//!
+//! ### `arbitrary`
+//!
+//! Implements `Arbitrary` from the [`arbitrary`] crate for `U8x4Rgba`, `F32x4Rgba` (with
+//! channels in `[0.0, 1.0]`) and [`BlendMode`], for fuzzing and property tests. Implies `std`.
+//!
 //! ### `bytemuck`
 //!
-//! Enables the `bytemuck` crate for zero-copy conversions between types.
+//! Enables the `bytemuck` crate for zero-copy conversions between types: [`Rgba<C>`] is `Pod`
+//! for every `Pod` channel type `C`, including `f16` with the `half` feature.
+//!
+//! ### `cube`
+//!
+//! Enables parsing 3D LUTs in the `.cube` format with [`lut::Lut3d::parse_cube`]. Implies `std`.
+//!
+//! ### `defmt`
+//!
+//! Implements `Format` from the [`defmt`] crate for `U8x4Rgba`, `F32x4Rgba` and [`BlendMode`],
+//! so `no_std` firmware can log them without `core::fmt`.
+//!
+//! ### `embedded-graphics`
+//!
+//! Converts between `U8x4Rgba` and the `Rgb888` / `Rgb565` colors of [`embedded-graphics`],
+//! and adds `interop::BlendTarget`, a `DrawTarget` that alpha-blends drawn pixels onto its
+//! framebuffer.
+//!
+//! [`embedded-graphics`]: https://docs.rs/embedded-graphics
+//!
+//! ### `exr`
+//!
+//! Enables reading and writing EXR images in [`interop`], with the [`exr`] crate. Implies
+//! `std`.
+//!
+//! ### `glam`
+//!
+//! Converts `glam::Vec4` to and from `F32x4Rgba`, and blends `Vec4` colors
+//! with `interop::blend_vec4`, for renderers built on the [`glam`] crate.
+//!
+//! ### `half`
+//!
+//! Adds `rgba::F16x4Rgba` half-float colors using the [`half`] crate, converting to and from
+//! `F32x4Rgba` and blending through `bit_depth::blend_mixed`.
+//!
+//! ### `image`
+//!
+//! Enables adapters for `ImageBuffer`s of the [`image`] crate in [`interop`]. Implies
+//! `std`.
 //!
 //! ### `libm`
 //!
@@ -46,18 +94,132 @@
 //!
 //! Enables the `arch` feature of `libm`.
 //!
+//! ### `mul-lut`
+//!
+//! Replaces the 8-bit multiplies of the `u8` Porter-Duff blends with a 64 KiB lookup table
+//! built at compile time, for targets without a fast hardware multiplier. See
+//! `math::mul_div_255_lut`.
+//!
+//! ### `portable-simd`
+//!
+//! Stores [`vec4::F32x4`], the four-lane `f32` vector used by Porter-Duff and other blends, in a
+//! [`core::simd`] vector, so they are vectorized on every target. Requires a nightly compiler,
+//! and takes precedence over `wide`.
+//!
+//! ### `rayon`
+//!
+//! Enables parallel blending on the [`rayon`] thread pool, with `backend::par_blend_slice` and
+//! `surface::Surface::par_composite`. Implies `std`.
+//!
+//! ### `simd`
+//!
+//! Enables SSE2 and AVX2 kernels for `rgba::U8x4Rgba::source_over_slice` on x86-64, selected
+//! by runtime CPU feature detection. Implies `std`.
+//!
 //! ### `std`
 //!
 //! Uses the standard library for math operations, such as `f32::round`.
+//!
+//! Also enables the [`shader`] module, and everything `alloc` does.
+//!
+//! ### `test-vectors`
+//!
+//! Enables the `test_vectors` module, reference results of every blend mode for validating SIMD,
+//! GPU or other ports: the W3C separable and non-separable modes are evaluated from the W3C
+//! Compositing and Blending formulas outside this crate, and the other modes are regression
+//! results generated by it.
+//!
+//! ### `wasm_simd`
+//!
+//! Enables a WebAssembly `simd128` kernel for `rgba::U8x4Rgba::source_over_slice` on wasm32.
+//! WebAssembly has no runtime feature detection, so the kernel is only used when compiling with
+//! `-C target-feature=+simd128`.
+//!
+//! ### `wide`
+//!
+//! Stores [`vec4::F32x4`], the four-lane `f32` vector used by Porter-Duff and other blends, in a
+//! [`wide`] vector, vectorizing them on stable Rust. Without it (or `portable-simd`), the vector
+//! is four scalar fields and the crate has no SIMD dependencies.
+//!
+//! ### `zune-image`
+//!
+//! Blends RGBA images of the [`zune-image`] crate with `interop::blend_zune_image`, and converts
+//! them to and from `surface::Surface`s. Implies `std`.
+//!
+//! [`zune-image`]: https://docs.rs/zune-image
 
 #![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use crate::{porter_duff::PorterDuff, rgba::Rgba};
+use core::fmt;
 
-pub(crate) mod math;
+use crate::{
+    nonseparable::NonSeparable, porter_duff::F32PorterDuff, rgba::Rgba, separable::Separable,
+};
+
+pub mod accumulate;
+#[cfg(feature = "alloc")]
+pub mod additive;
+pub mod adjust;
+pub mod alpha;
+pub mod apng;
+pub mod backend;
+pub mod bit_depth;
+pub mod canvas;
+pub mod channel;
+pub mod color_space;
+pub mod composite;
+#[cfg(feature = "alloc")]
+pub mod compositor;
+pub mod depth;
+pub mod dither;
+pub mod extended;
+pub mod fixed;
+pub mod format;
+pub mod gradient;
+pub mod gray;
+pub mod hsl;
+pub mod interop;
+pub mod layout;
+pub mod linear;
+pub mod lut;
+pub mod mask;
+pub mod math;
+pub mod modes;
+pub mod nonseparable;
+pub mod oit;
+pub mod oklab;
+pub mod packed;
+pub mod planar;
+pub mod policy;
 pub mod porter_duff;
+pub mod premul;
+pub mod rect;
 pub mod rgba;
-pub(crate) mod vec4;
+pub mod rows;
+pub mod separable;
+#[cfg(feature = "std")]
+pub mod shader;
+#[cfg(any(feature = "simd", feature = "wasm_simd"))]
+mod simd;
+pub mod stencil;
+#[cfg(feature = "alloc")]
+pub mod surface;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+pub mod threshold;
+#[cfg(feature = "alloc")]
+pub mod tiled;
+pub mod transition;
+pub mod vec4;
+#[cfg(kani)]
+mod verify;
+pub mod ycbcr;
 
 /// Supported blend modes by this crate.
 ///
@@ -68,72 +230,495 @@ pub(crate) mod vec4;
 ///
 /// [`Plus`](BlendMode::Plus) can produce channel values > 1.0.  Call
 /// [`clamp()`](crate::rgba::F32x4Rgba::clamp) on the result when using `Plus`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+///
+/// ## FFI
+///
+/// `BlendMode` is `#[repr(u8)]` with stable, explicit discriminants, so it can be passed across
+/// FFI or stored in binary formats as a [`u8`]; use [`TryFrom<u8>`] to convert back safely.
+/// [`Custom`](BlendMode::Custom) modes carry a function, and cannot be converted back.
+///
+/// ```rust
+/// use alpha_blend::BlendMode;
+///
+/// assert_eq!(u8::from(BlendMode::SourceOver), 3);
+/// assert_eq!(BlendMode::try_from(3), Ok(BlendMode::SourceOver));
+/// assert!(BlendMode::try_from(255).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(u8)]
 pub enum BlendMode {
     /// Destination pixels covered by the source pixels are cleared.
-    Clear,
+    Clear = 0,
 
     /// Source pixels are copied to the destination.
-    Source,
+    Source = 1,
 
     /// Destination pixels are copied to the source.
-    Destination,
+    Destination = 2,
 
     /// Source pixels are copied to the destination, ignoring the alpha channel.
     #[default]
-    SourceOver,
+    SourceOver = 3,
 
     /// Destination pixels are copied to the source, ignoring the alpha channel.
-    DestinationOver,
+    DestinationOver = 4,
 
     /// Source pixels are copied to the destination, only where the destination is opaque.
-    SourceIn,
+    SourceIn = 5,
 
     /// Destination pixels are copied to the source, only where the source is opaque.
-    DestinationIn,
+    DestinationIn = 6,
 
     /// Source pixels are copied to the destination, only where the source is opaque.
-    SourceOut,
+    SourceOut = 7,
 
     /// Destination pixels are copied to the source, only where the destination is opaque.
-    DestinationOut,
+    DestinationOut = 8,
 
     /// Source pixels are copied to the destination, where both source and destination are opaque.
-    SourceAtop,
+    SourceAtop = 9,
 
     /// Destination pixels are copied to the source, where both source and destination are opaque.
-    DestinationAtop,
+    DestinationAtop = 10,
 
     /// Source pixels are blended with the destination using the source's alpha channel.
-    Xor,
+    Xor = 11,
 
     /// Source pixels are added to the destination.
     ///
     /// **Note**: can produce channel values > 1.0.  Call
-    /// [`clamp()`](crate::rgba::F32x4Rgba::clamp) on the result when clamping is needed.
-    Plus,
+    /// [`clamp()`](crate::rgba::F32x4Rgba::clamp) on the result when clamping is needed, or use
+    /// [`PlusLighter`](BlendMode::PlusLighter).
+    Plus = 12,
+
+    /// Source and destination colors are multiplied, darkening the result.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    Multiply = 13,
+
+    /// Complements of the source and destination colors are multiplied, lightening the result.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    Screen = 14,
+
+    /// Multiplies or screens the colors depending on the destination color, preserving its
+    /// highlights and shadows.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    Overlay = 15,
+
+    /// Multiplies or screens the colors depending on the source color, as if shining a harsh
+    /// spotlight on the destination.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    HardLight = 16,
+
+    /// Darkens or lightens the colors depending on the source color, as if shining a diffused
+    /// spotlight on the destination.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    SoftLight = 17,
+
+    /// Brightens the destination color to reflect the source color.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    ColorDodge = 18,
+
+    /// Darkens the destination color to reflect the source color.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    ColorBurn = 19,
+
+    /// Subtracts the darker of the source and destination colors from the lighter one.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    Difference = 20,
+
+    /// Like [`Difference`](BlendMode::Difference), but with lower contrast.
+    ///
+    /// See [`separable`] for how partially transparent pixels are blended.
+    Exclusion = 21,
+
+    /// Hue of the source with the saturation and luminosity of the destination.
+    ///
+    /// See [`nonseparable`] for how colors are mixed.
+    Hue = 22,
+
+    /// Saturation of the source with the hue and luminosity of the destination.
+    ///
+    /// See [`nonseparable`] for how colors are mixed.
+    Saturation = 23,
+
+    /// Hue and saturation of the source with the luminosity of the destination.
+    ///
+    /// See [`nonseparable`] for how colors are mixed.
+    Color = 24,
+
+    /// Luminosity of the source with the hue and saturation of the destination.
+    ///
+    /// See [`nonseparable`] for how colors are mixed.
+    Luminosity = 25,
+
+    /// Source and destination colors are added, and one subtracted, darkening the result.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    LinearBurn = 26,
+
+    /// Source and destination colors are added, lightening the result.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    LinearDodge = 27,
+
+    /// Burns or dodges the destination color depending on the source color.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    VividLight = 28,
+
+    /// Linearly burns or dodges the destination color depending on the source color.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    LinearLight = 29,
+
+    /// Replaces the destination color depending on the source color.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    PinLight = 30,
+
+    /// Posterizes each channel to black or white.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    HardMix = 31,
+
+    /// Subtracts the source color from the destination color, clamping at black.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    Subtract = 32,
+
+    /// Divides the destination color by the source color, clamping at white.
+    ///
+    /// See [`extended`] for these Photoshop modes.
+    Divide = 33,
+
+    /// All four channels, including alpha, are multiplied, as Skia's `kModulate`.
+    ///
+    /// Unlike [`Multiply`](BlendMode::Multiply), the result is no more opaque than either input,
+    /// which makes it suitable for tinting or masking a sprite by a texture.
+    Modulate = 34,
+
+    /// Source pixels are added to the destination, clamping colors and alpha to `1.0`, as the CSS
+    /// `plus-lighter` operator.
+    PlusLighter = 35,
+
+    /// Source and destination pixels are darkened by the sum of their distances from white,
+    /// clamping to `0.0`, as the CSS `plus-darker` operator.
+    PlusDarker = 36,
+
+    /// A user-defined blend function, so APIs accepting a `BlendMode` also accept extensions.
+    ///
+    /// Modes compare equal when their functions are the same pointer, which the compiler does
+    /// not guarantee for the same function. [`u8::from`] returns `37` for every custom mode,
+    /// which [`TryFrom<u8>`] rejects.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{BlendMode, RgbaBlend, rgba::F32x4Rgba};
+    ///
+    /// fn average(src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+    ///     F32x4Rgba::new(
+    ///         f32::midpoint(src.r, dst.r),
+    ///         f32::midpoint(src.g, dst.g),
+    ///         f32::midpoint(src.b, dst.b),
+    ///         src.a.max(dst.a),
+    ///     )
+    /// }
+    ///
+    /// let mode = BlendMode::Custom(average);
+    /// let out = mode.apply(F32x4Rgba::WHITE, F32x4Rgba::BLACK);
+    /// assert_eq!(out, F32x4Rgba::new(0.5, 0.5, 0.5, 1.0));
+    /// ```
+    Custom(fn(Rgba<f32>, Rgba<f32>) -> Rgba<f32>) = 37,
+}
+
+/// Compares discriminants, and custom modes with [`core::ptr::fn_addr_eq`].
+impl PartialEq for BlendMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => core::ptr::fn_addr_eq(*a, *b),
+            _ => u8::from(*self) == u8::from(*other),
+        }
+    }
+}
+
+impl Eq for BlendMode {}
+
+impl core::hash::Hash for BlendMode {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+        if let Self::Custom(blend) = self {
+            (*blend as usize).hash(state);
+        }
+    }
+}
+
+impl From<BlendMode> for u8 {
+    fn from(mode: BlendMode) -> Self {
+        // SAFETY: `BlendMode` is `#[repr(u8)]`, so every variant starts with its `u8`
+        // discriminant, including `Custom`, which carries a field.
+        unsafe { *(&raw const mode).cast::<Self>() }
+    }
+}
+
+impl TryFrom<u8> for BlendMode {
+    type Error = InvalidBlendMode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Clear),
+            1 => Ok(Self::Source),
+            2 => Ok(Self::Destination),
+            3 => Ok(Self::SourceOver),
+            4 => Ok(Self::DestinationOver),
+            5 => Ok(Self::SourceIn),
+            6 => Ok(Self::DestinationIn),
+            7 => Ok(Self::SourceOut),
+            8 => Ok(Self::DestinationOut),
+            9 => Ok(Self::SourceAtop),
+            10 => Ok(Self::DestinationAtop),
+            11 => Ok(Self::Xor),
+            12 => Ok(Self::Plus),
+            13 => Ok(Self::Multiply),
+            14 => Ok(Self::Screen),
+            15 => Ok(Self::Overlay),
+            16 => Ok(Self::HardLight),
+            17 => Ok(Self::SoftLight),
+            18 => Ok(Self::ColorDodge),
+            19 => Ok(Self::ColorBurn),
+            20 => Ok(Self::Difference),
+            21 => Ok(Self::Exclusion),
+            22 => Ok(Self::Hue),
+            23 => Ok(Self::Saturation),
+            24 => Ok(Self::Color),
+            25 => Ok(Self::Luminosity),
+            26 => Ok(Self::LinearBurn),
+            27 => Ok(Self::LinearDodge),
+            28 => Ok(Self::VividLight),
+            29 => Ok(Self::LinearLight),
+            30 => Ok(Self::PinLight),
+            31 => Ok(Self::HardMix),
+            32 => Ok(Self::Subtract),
+            33 => Ok(Self::Divide),
+            34 => Ok(Self::Modulate),
+            35 => Ok(Self::PlusLighter),
+            36 => Ok(Self::PlusDarker),
+            _ => Err(InvalidBlendMode(value)),
+        }
+    }
+}
+
+/// Names of the built-in blend modes, indexed by discriminant.
+#[cfg(feature = "defmt")]
+const NAMES: [&str; 37] = [
+    "Clear",
+    "Source",
+    "Destination",
+    "SourceOver",
+    "DestinationOver",
+    "SourceIn",
+    "DestinationIn",
+    "SourceOut",
+    "DestinationOut",
+    "SourceAtop",
+    "DestinationAtop",
+    "Xor",
+    "Plus",
+    "Multiply",
+    "Screen",
+    "Overlay",
+    "HardLight",
+    "SoftLight",
+    "ColorDodge",
+    "ColorBurn",
+    "Difference",
+    "Exclusion",
+    "Hue",
+    "Saturation",
+    "Color",
+    "Luminosity",
+    "LinearBurn",
+    "LinearDodge",
+    "VividLight",
+    "LinearLight",
+    "PinLight",
+    "HardMix",
+    "Subtract",
+    "Divide",
+    "Modulate",
+    "PlusLighter",
+    "PlusDarker",
+];
+
+/// Logs the name of the mode; custom modes are logged without their function.
+#[cfg(feature = "defmt")]
+impl defmt::Format for BlendMode {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        if let Some(name) = NAMES.get(usize::from(u8::from(*self))) {
+            defmt::write!(f, "{=str}", name);
+        } else {
+            defmt::write!(f, "Custom(..)");
+        }
+    }
+}
+
+/// Picks any built-in blend mode, uniformly.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for BlendMode {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let value = u.int_in_range(0..=u8::from(Self::PlusDarker))?;
+        Self::try_from(value).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+/// Error returned when converting a [`u8`] that is not a [`BlendMode`] discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidBlendMode(pub u8);
+
+impl fmt::Display for InvalidBlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid blend mode: {}", self.0)
+    }
+}
+
+impl core::error::Error for InvalidBlendMode {}
+
+/// Number of pixels scaled into a stack buffer at a time by
+/// [`BlendMode::apply_slice_with_opacity`].
+const OPACITY_CHUNK: usize = 64;
+
+impl BlendMode {
+    /// Blends `src` with `dst` as a layer at `opacity`, clamped to `[0.0, 1.0]`.
+    ///
+    /// The source alpha is scaled by `opacity` before blending; see
+    /// [`RgbaBlend::apply_with_coverage`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{BlendMode, rgba::F32x4Rgba};
+    ///
+    /// let out = BlendMode::Screen.apply_with_opacity(F32x4Rgba::WHITE, F32x4Rgba::BLACK, 0.4);
+    /// assert!((out.r - 0.4).abs() < 1e-6);
+    /// ```
+    #[must_use]
+    pub fn apply_with_opacity(self, src: Rgba<f32>, dst: Rgba<f32>, opacity: f32) -> Rgba<f32> {
+        self.apply_with_coverage(src, dst, opacity)
+    }
+
+    /// Blends the layer `src` onto `dst` in place at `opacity`, clamped to `[0.0, 1.0]`.
+    ///
+    /// Chunks of `src` are scaled into a stack buffer and blended with
+    /// [`apply_slice`](RgbaBlend::apply_slice), so Porter-Duff modes keep their two-pixel steps.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn apply_slice_with_opacity(self, src: &[Rgba<f32>], dst: &mut [Rgba<f32>], opacity: f32) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        let opacity = math::clamp01(opacity);
+        let mut buffer = [Rgba::new(0.0, 0.0, 0.0, 0.0); OPACITY_CHUNK];
+        for (s, d) in src.chunks(OPACITY_CHUNK).zip(dst.chunks_mut(OPACITY_CHUNK)) {
+            let scaled = &mut buffer[..s.len()];
+            for (b, s) in scaled.iter_mut().zip(s) {
+                *b = Rgba::new(s.r, s.g, s.b, s.a * opacity);
+            }
+            self.apply_slice(scaled, d);
+        }
+    }
 }
 
 impl RgbaBlend for BlendMode {
     type Channel = f32;
 
     fn apply(&self, src: Rgba<Self::Channel>, dst: Rgba<Self::Channel>) -> Rgba<Self::Channel> {
-        let pd: PorterDuff<f32, fn(f32, f32) -> f32> = match self {
-            Self::Clear => PorterDuff::CLEAR,
-            Self::Source => PorterDuff::SRC,
-            Self::Destination => PorterDuff::DST,
-            Self::SourceOver => PorterDuff::SRC_OVER,
-            Self::DestinationOver => PorterDuff::DST_OVER,
-            Self::SourceIn => PorterDuff::SRC_IN,
-            Self::DestinationIn => PorterDuff::DST_IN,
-            Self::SourceOut => PorterDuff::SRC_OUT,
-            Self::DestinationOut => PorterDuff::DST_OUT,
-            Self::SourceAtop => PorterDuff::SRC_ATOP,
-            Self::DestinationAtop => PorterDuff::DST_ATOP,
-            Self::Xor => PorterDuff::XOR,
-            Self::Plus => PorterDuff::PLUS,
+        match self {
+            Self::Clear => F32PorterDuff::CLEAR.blend(src, dst),
+            Self::Source => F32PorterDuff::SRC.blend(src, dst),
+            Self::Destination => F32PorterDuff::DST.blend(src, dst),
+            Self::SourceOver => F32PorterDuff::SRC_OVER.blend(src, dst),
+            Self::DestinationOver => F32PorterDuff::DST_OVER.blend(src, dst),
+            Self::SourceIn => F32PorterDuff::SRC_IN.blend(src, dst),
+            Self::DestinationIn => F32PorterDuff::DST_IN.blend(src, dst),
+            Self::SourceOut => F32PorterDuff::SRC_OUT.blend(src, dst),
+            Self::DestinationOut => F32PorterDuff::DST_OUT.blend(src, dst),
+            Self::SourceAtop => F32PorterDuff::SRC_ATOP.blend(src, dst),
+            Self::DestinationAtop => F32PorterDuff::DST_ATOP.blend(src, dst),
+            Self::Xor => F32PorterDuff::XOR.blend(src, dst),
+            Self::Plus => F32PorterDuff::PLUS.blend(src, dst),
+            Self::Multiply => Separable::MULTIPLY.blend(src, dst),
+            Self::Screen => Separable::SCREEN.blend(src, dst),
+            Self::Overlay => Separable::OVERLAY.blend(src, dst),
+            Self::HardLight => Separable::HARD_LIGHT.blend(src, dst),
+            Self::SoftLight => Separable::SOFT_LIGHT.blend(src, dst),
+            Self::ColorDodge => Separable::COLOR_DODGE.blend(src, dst),
+            Self::ColorBurn => Separable::COLOR_BURN.blend(src, dst),
+            Self::Difference => Separable::DIFFERENCE.blend(src, dst),
+            Self::Exclusion => Separable::EXCLUSION.blend(src, dst),
+            Self::Hue => NonSeparable::HUE.blend(src, dst),
+            Self::Saturation => NonSeparable::SATURATION.blend(src, dst),
+            Self::Color => NonSeparable::COLOR.blend(src, dst),
+            Self::Luminosity => NonSeparable::LUMINOSITY.blend(src, dst),
+            Self::LinearBurn => Separable::LINEAR_BURN.blend(src, dst),
+            Self::LinearDodge => Separable::LINEAR_DODGE.blend(src, dst),
+            Self::VividLight => Separable::VIVID_LIGHT.blend(src, dst),
+            Self::LinearLight => Separable::LINEAR_LIGHT.blend(src, dst),
+            Self::PinLight => Separable::PIN_LIGHT.blend(src, dst),
+            Self::HardMix => Separable::HARD_MIX.blend(src, dst),
+            Self::Subtract => Separable::SUBTRACT.blend(src, dst),
+            Self::Divide => Separable::DIVIDE.blend(src, dst),
+            Self::Modulate => src.modulate(dst),
+            Self::PlusLighter => porter_duff::plus_lighter(src, dst),
+            Self::PlusDarker => porter_duff::plus_darker(src, dst),
+            Self::Custom(blend) => blend(src, dst),
+        }
+    }
+
+    /// Porter-Duff modes blend two pixels per step; other modes blend pixel by pixel.
+    fn apply_slice(&self, src: &[Rgba<Self::Channel>], dst: &mut [Rgba<Self::Channel>]) {
+        let porter_duff = match self {
+            Self::Clear => F32PorterDuff::CLEAR,
+            Self::Source => F32PorterDuff::SRC,
+            Self::Destination => F32PorterDuff::DST,
+            Self::SourceOver => F32PorterDuff::SRC_OVER,
+            Self::DestinationOver => F32PorterDuff::DST_OVER,
+            Self::SourceIn => F32PorterDuff::SRC_IN,
+            Self::DestinationIn => F32PorterDuff::DST_IN,
+            Self::SourceOut => F32PorterDuff::SRC_OUT,
+            Self::DestinationOut => F32PorterDuff::DST_OUT,
+            Self::SourceAtop => F32PorterDuff::SRC_ATOP,
+            Self::DestinationAtop => F32PorterDuff::DST_ATOP,
+            Self::Xor => F32PorterDuff::XOR,
+            Self::Plus => F32PorterDuff::PLUS,
+            _ => {
+                assert_eq!(
+                    src.len(),
+                    dst.len(),
+                    "src and dst slices must have the same length"
+                );
+                for (s, d) in src.iter().zip(dst.iter_mut()) {
+                    *d = self.apply(*s, *d);
+                }
+                return;
+            }
         };
-        pd.apply(src, dst)
+        porter_duff.apply_slice(src, dst);
     }
 }
 
@@ -141,11 +726,10 @@ impl RgbaBlend for BlendMode {
 pub trait RgbaBlend {
     /// What type of channel this blend mode operates on.
     ///
-    /// **Note**: only `f32` is currently supported via the provided
-    /// [`BlendMode`] / [`PorterDuff`] implementations.  `u8` blending is
-    /// available directly on [`U8x4Rgba`](crate::rgba::U8x4Rgba) via
-    /// [`source_over`](crate::rgba::U8x4Rgba::source_over).
-    type Channel: Copy;
+    /// **Note**: [`BlendMode`] blends `f32` channels. [`PorterDuff`](porter_duff::PorterDuff)
+    /// constants blend any [`Channel`](channel::Channel); for example, `u8` channels are blended
+    /// with integer math by the [`U8PorterDuff`](porter_duff::U8PorterDuff) constants.
+    type Channel: channel::Channel;
 
     /// Blends two colors together using this blend mode.
     fn apply(&self, src: Rgba<Self::Channel>, dst: Rgba<Self::Channel>) -> Rgba<Self::Channel>;
@@ -164,6 +748,105 @@ pub trait RgbaBlend {
             *d = self.apply(*s, *d);
         }
     }
+
+    /// Blends `src` with `dst` after scaling the source alpha by `coverage`, clamped to
+    /// `[0.0, 1.0]`, as rasterizers do for partially covered edge pixels.
+    ///
+    /// For `SourceOver` and other modes where transparent source pixels leave the destination
+    /// unchanged, this antialiases exactly. Modes such as `Source` or `Clear` affect the
+    /// destination regardless of source alpha; interpolate those with
+    /// [`mask::blend_masked`] instead.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{BlendMode, RgbaBlend, rgba::F32x4Rgba};
+    ///
+    /// let edge = BlendMode::SourceOver.apply_with_coverage(
+    ///     F32x4Rgba::WHITE,
+    ///     F32x4Rgba::BLACK,
+    ///     0.25,
+    /// );
+    /// assert_eq!((edge.r, edge.g, edge.b), (0.25, 0.25, 0.25));
+    /// ```
+    fn apply_with_coverage(
+        &self,
+        src: Rgba<Self::Channel>,
+        dst: Rgba<Self::Channel>,
+        coverage: f32,
+    ) -> Rgba<Self::Channel> {
+        use bit_depth::BitDepth as _;
+        use channel::Channel as _;
+
+        let coverage = Self::Channel::from_unit(math::clamp01(coverage));
+        self.apply(Rgba::new(src.r, src.g, src.b, src.a.mul(coverage)), dst)
+    }
+
+    /// Blends `src` onto `dst` in place, scaling each source alpha by the matching `coverage`;
+    /// see [`apply_with_coverage`](RgbaBlend::apply_with_coverage).
+    ///
+    /// ## Panics
+    ///
+    /// If `src`, `dst` and `coverage` do not all have the same length.
+    fn apply_slice_with_coverage(
+        &self,
+        src: &[Rgba<Self::Channel>],
+        dst: &mut [Rgba<Self::Channel>],
+        coverage: &[f32],
+    ) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        assert_eq!(
+            coverage.len(),
+            dst.len(),
+            "mask must have one coverage value per pixel"
+        );
+        for ((s, d), &c) in src.iter().zip(dst.iter_mut()).zip(coverage) {
+            *d = self.apply_with_coverage(*s, *d, c);
+        }
+    }
+}
+
+/// Blends pixel colors into an existing destination, without producing a separate output.
+///
+/// Implemented for every [`RgbaBlend`].
+pub trait RgbaBlendAssign: RgbaBlend {
+    /// Blends `src` onto `dst` in place.
+    fn apply_in_place(&self, src: Rgba<Self::Channel>, dst: &mut Rgba<Self::Channel>);
+}
+
+impl<B: RgbaBlend + ?Sized> RgbaBlendAssign for B {
+    fn apply_in_place(&self, src: Rgba<Self::Channel>, dst: &mut Rgba<Self::Channel>) {
+        *dst = self.apply(src, *dst);
+    }
+}
+
+/// Blends `src` onto an existing framebuffer `dst` in place, using `mode`.
+///
+/// Uses [`backend::blend_slice`], so long buffers take the fastest available backend.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{BlendMode, blend_slice_in_place, rgba::F32x4Rgba};
+///
+/// let mut framebuffer = [F32x4Rgba::BLACK; 3];
+/// blend_slice_in_place(&mut framebuffer, &[F32x4Rgba::WHITE; 3], &BlendMode::SourceOver);
+/// assert_eq!(framebuffer, [F32x4Rgba::WHITE; 3]);
+/// ```
+pub fn blend_slice_in_place<B: RgbaBlend + ?Sized>(
+    dst: &mut [Rgba<B::Channel>],
+    src: &[Rgba<B::Channel>],
+    mode: &B,
+) {
+    backend::blend_slice(mode, src, dst);
 }
 
 #[cfg(test)]
@@ -171,6 +854,94 @@ mod tests {
     use super::*;
     use crate::rgba::F32x4Rgba;
 
+    #[test]
+    fn apply_with_coverage_scales_source_alpha() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let dst = F32x4Rgba::new(0.0, 0.0, 1.0, 1.0);
+        let scaled = F32x4Rgba::new(1.0, 0.0, 0.0, 0.25);
+        assert_eq!(
+            BlendMode::Multiply.apply_with_coverage(src, dst, 0.5),
+            BlendMode::Multiply.apply(scaled, dst)
+        );
+        assert_eq!(
+            BlendMode::SourceOver.apply_with_coverage(src, dst, 0.0),
+            dst
+        );
+        assert_eq!(
+            BlendMode::SourceOver.apply_with_coverage(src, dst, 2.0),
+            BlendMode::SourceOver.apply(src, dst)
+        );
+    }
+
+    #[test]
+    fn apply_with_coverage_u8() {
+        use crate::{porter_duff::U8PorterDuff, rgba::U8x4Rgba};
+
+        let out = U8PorterDuff::SRC_OVER.apply_with_coverage(U8x4Rgba::WHITE, U8x4Rgba::BLACK, 0.5);
+        assert_eq!(out, U8x4Rgba::new(128, 128, 128, 191));
+    }
+
+    #[test]
+    fn apply_slice_with_coverage_matches_individual() {
+        let src = [F32x4Rgba::new(1.0, 0.5, 0.0, 0.75); 3];
+        let mut dst = [F32x4Rgba::new(0.0, 0.0, 1.0, 1.0); 3];
+        let coverage = [0.0, 0.5, 1.0];
+        let expected: [F32x4Rgba; 3] = core::array::from_fn(|i| {
+            BlendMode::Screen.apply_with_coverage(src[i], dst[i], coverage[i])
+        });
+        BlendMode::Screen.apply_slice_with_coverage(&src, &mut dst, &coverage);
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn apply_slice_with_opacity_matches_individual() {
+        let src: [F32x4Rgba; 131] = core::array::from_fn(|i| {
+            let v = f32::from(u8::try_from(i).unwrap()) / 130.0;
+            F32x4Rgba::new(v, 1.0 - v, 0.5, v)
+        });
+        for mode in [BlendMode::SourceOver, BlendMode::Overlay] {
+            let mut dst = [F32x4Rgba::new(0.25, 0.5, 0.75, 1.0); 131];
+            let expected: [F32x4Rgba; 131] =
+                core::array::from_fn(|i| mode.apply_with_opacity(src[i], dst[i], 0.4));
+            mode.apply_slice_with_opacity(&src, &mut dst, 0.4);
+            assert_eq!(dst, expected);
+        }
+    }
+
+    #[test]
+    fn zero_opacity_keeps_dst_for_source_over() {
+        let mut dst = [F32x4Rgba::new(0.25, 0.5, 0.75, 1.0); 2];
+        let before = dst;
+        BlendMode::SourceOver.apply_slice_with_opacity(&[F32x4Rgba::WHITE; 2], &mut dst, -1.0);
+        assert_eq!(dst, before);
+    }
+
+    #[test]
+    fn apply_in_place_matches_apply() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let mut dst = F32x4Rgba::new(0.0, 0.0, 1.0, 1.0);
+        let expected = BlendMode::Screen.apply(src, dst);
+        BlendMode::Screen.apply_in_place(src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn blend_slice_in_place_matches_apply_slice() {
+        let src = [F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); 3];
+        let mut dst = [F32x4Rgba::new(0.0, 1.0, 0.0, 1.0); 3];
+        let mut expected = dst;
+        BlendMode::Overlay.apply_slice(&src, &mut expected);
+        blend_slice_in_place(&mut dst, &src, &BlendMode::Overlay);
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the same length")]
+    fn blend_slice_in_place_rejects_length_mismatch() {
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        blend_slice_in_place(&mut dst, &[F32x4Rgba::WHITE; 3], &BlendMode::SourceOver);
+    }
+
     #[test]
     fn blend_mode_default_is_source_over() {
         assert_eq!(BlendMode::default(), BlendMode::SourceOver);
@@ -196,6 +967,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn apply_slice_matches_individual_for_every_mode() {
+        let src: [F32x4Rgba; 5] = core::array::from_fn(|i| {
+            let t = f32::from(u8::try_from(i).unwrap()) * 0.25;
+            F32x4Rgba::new(t, 1.0 - t, 0.5, 1.0 - t)
+        });
+        let dst: [F32x4Rgba; 5] = core::array::from_fn(|i| src[4 - i]);
+        for value in 0..=u8::MAX {
+            let Ok(mode) = BlendMode::try_from(value) else {
+                continue;
+            };
+            let mut batch = dst;
+            mode.apply_slice(&src, &mut batch);
+            for i in 0..src.len() {
+                assert_eq!(batch[i], mode.apply(src[i], dst[i]), "{mode:?}[{i}]");
+            }
+        }
+    }
+
     #[test]
     #[should_panic(expected = "must have the same length")]
     fn apply_slice_panics_on_mismatched_lengths() {
@@ -204,6 +994,23 @@ mod tests {
         BlendMode::SourceOver.apply_slice(&src, &mut dst);
     }
 
+    #[test]
+    fn custom_mode_applies_its_function() {
+        fn darken(src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+            F32x4Rgba::new(src.r.min(dst.r), src.g.min(dst.g), src.b.min(dst.b), dst.a)
+        }
+        let mode = BlendMode::Custom(darken);
+        let src = [F32x4Rgba::new(0.25, 1.0, 0.5, 1.0); 3];
+        let mut dst = [F32x4Rgba::new(0.5, 0.5, 0.5, 0.75); 3];
+        mode.apply_slice(&src, &mut dst);
+        assert_eq!(dst, [F32x4Rgba::new(0.25, 0.5, 0.5, 0.75); 3]);
+
+        assert_eq!(mode, mode);
+        assert_ne!(mode, BlendMode::SourceOver);
+        assert_eq!(u8::from(mode), 37);
+        assert!(BlendMode::try_from(37).is_err());
+    }
+
     #[test]
     fn blend_mode_hash() {
         use std::collections::HashSet;
@@ -214,4 +1021,54 @@ mod tests {
         set.insert(BlendMode::Clear);
         assert_eq!(set.len(), 2);
     }
+
+    #[test]
+    fn blend_mode_u8_round_trip() {
+        for value in 0..=u8::MAX {
+            match BlendMode::try_from(value) {
+                Ok(mode) => assert_eq!(u8::from(mode), value),
+                Err(err) => {
+                    assert!(value > 36, "{value}");
+                    assert_eq!(err, InvalidBlendMode(value));
+                }
+            }
+        }
+        assert_eq!(BlendMode::try_from(12), Ok(BlendMode::Plus));
+        assert_eq!(BlendMode::try_from(14), Ok(BlendMode::Screen));
+    }
+
+    #[test]
+    fn invalid_blend_mode_display() {
+        assert_eq!(InvalidBlendMode(42).to_string(), "invalid blend mode: 42");
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    #[allow(clippy::cast_possible_truncation)]
+    fn arbitrary_premul_source_over_never_lowers_alpha() {
+        use arbitrary::{Arbitrary, Unstructured};
+        use premul::PremulBlend;
+
+        let bytes: [u8; 4096] =
+            core::array::from_fn(|i| ((i as u32).wrapping_mul(2_654_435_761) >> 13) as u8);
+        let mut u = Unstructured::new(&bytes);
+        for _ in 0..100 {
+            let src = F32x4Rgba::arbitrary(&mut u).unwrap();
+            let dst = F32x4Rgba::arbitrary(&mut u).unwrap();
+            assert!(
+                [src.r, src.g, src.b, src.a]
+                    .iter()
+                    .all(|c| (0.0..=1.0).contains(c))
+            );
+            let out = BlendMode::SourceOver.apply_premul(
+                premul::F32x4PremulRgba::from_straight(src),
+                premul::F32x4PremulRgba::from_straight(dst),
+            );
+            assert!(out.alpha() >= dst.a);
+
+            let mode = BlendMode::arbitrary(&mut u).unwrap();
+            assert_eq!(BlendMode::try_from(u8::from(mode)), Ok(mode));
+            let _ = rgba::U8x4Rgba::arbitrary(&mut u).unwrap();
+        }
+    }
 }
@@ -0,0 +1,173 @@
+//! Gamma-correct blending in linear light.
+//!
+//! Colors are usually stored sRGB-encoded, but blending the encoded values directly darkens
+//! mid-tones: 50% white over black comes out at about 21% luminance instead of 50%. The
+//! functions here convert between sRGB and linear sRGB with the IEC 61966-2-1 transfer
+//! functions, and [`LinearBlend`] wraps any blend mode so that it decodes, blends in linear
+//! light, and re-encodes. Alpha is never encoded, and passes through unchanged.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, RgbaBlend, linear::LinearBlend, rgba::F32x4Rgba};
+//!
+//! let src = F32x4Rgba::new(1.0, 1.0, 1.0, 0.5);
+//! let out = LinearBlend(BlendMode::SourceOver).apply(src, F32x4Rgba::BLACK);
+//! assert!((out.r - 0.7354).abs() < 1e-4);
+//! assert_eq!(out, BlendMode::SourceOver.apply_linear(src, F32x4Rgba::BLACK));
+//! ```
+
+use crate::{BlendMode, RgbaBlend, math, rgba::F32x4Rgba};
+
+/// Decodes an sRGB-encoded channel in `[0.0, 1.0]` to linear light.
+#[must_use]
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        math::powf((c + 0.055) / 1.055, 2.4)
+    }
+}
+
+/// Encodes a linear-light channel in `[0.0, 1.0]` to sRGB.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * math::powf(c, 1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decodes the color channels of an sRGB-encoded color to linear light, keeping alpha.
+#[must_use]
+pub fn decode(color: F32x4Rgba) -> F32x4Rgba {
+    F32x4Rgba::new(
+        srgb_to_linear(color.r),
+        srgb_to_linear(color.g),
+        srgb_to_linear(color.b),
+        color.a,
+    )
+}
+
+/// Encodes the color channels of a linear-light color to sRGB, keeping alpha.
+#[must_use]
+pub fn encode(color: F32x4Rgba) -> F32x4Rgba {
+    F32x4Rgba::new(
+        linear_to_srgb(color.r),
+        linear_to_srgb(color.g),
+        linear_to_srgb(color.b),
+        color.a,
+    )
+}
+
+/// Decodes every color in `colors` to linear light, in place.
+pub fn decode_slice(colors: &mut [F32x4Rgba]) {
+    for c in colors {
+        *c = decode(*c);
+    }
+}
+
+/// Encodes every color in `colors` to sRGB, in place.
+pub fn encode_slice(colors: &mut [F32x4Rgba]) {
+    for c in colors {
+        *c = encode(*c);
+    }
+}
+
+/// Blends sRGB-encoded colors in linear light, using the wrapped blend mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LinearBlend<B>(pub B);
+
+/// Number of pixels decoded into a stack buffer at a time by [`LinearBlend::apply_slice`].
+const CHUNK: usize = 64;
+
+impl<B: RgbaBlend<Channel = f32>> RgbaBlend for LinearBlend<B> {
+    type Channel = f32;
+
+    fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        encode(self.0.apply(decode(src), decode(dst)))
+    }
+
+    /// Decodes chunks of `src` into a stack buffer and `dst` in place, then blends each chunk
+    /// with the wrapped mode's own [`apply_slice`](RgbaBlend::apply_slice).
+    fn apply_slice(&self, src: &[F32x4Rgba], dst: &mut [F32x4Rgba]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        let mut buffer = [F32x4Rgba::TRANSPARENT; CHUNK];
+        for (s, d) in src.chunks(CHUNK).zip(dst.chunks_mut(CHUNK)) {
+            let linear = &mut buffer[..s.len()];
+            linear.copy_from_slice(s);
+            decode_slice(linear);
+            decode_slice(d);
+            self.0.apply_slice(linear, d);
+            encode_slice(d);
+        }
+    }
+}
+
+impl BlendMode {
+    /// Blends sRGB-encoded colors in linear light; see [`LinearBlend`].
+    #[must_use]
+    pub fn apply_linear(self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        LinearBlend(self).apply(src, dst)
+    }
+
+    /// Blends sRGB-encoded `src` over `dst` in place, in linear light; see [`LinearBlend`].
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn apply_linear_slice(self, src: &[F32x4Rgba], dst: &mut [F32x4Rgba]) {
+        LinearBlend(self).apply_slice(src, dst);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_functions_round_trip() {
+        for i in 0..=u8::MAX {
+            let c = f32::from(i) / 255.0;
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5, "{c}");
+        }
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        assert!((srgb_to_linear(0.5) - 0.214_041).abs() < 1e-5);
+    }
+
+    #[test]
+    fn decode_keeps_alpha() {
+        let c = decode(F32x4Rgba::new(0.5, 0.5, 0.5, 0.5));
+        assert_eq!(c.a, 0.5);
+        assert!((encode(c).r - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn opaque_endpoints_are_unchanged() {
+        let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+        let out = BlendMode::SourceOver.apply_linear(red, F32x4Rgba::BLACK);
+        assert!((out.r - 1.0).abs() < 1e-6);
+        assert_eq!(out.g, 0.0);
+    }
+
+    #[test]
+    fn apply_slice_matches_individual() {
+        let src: [F32x4Rgba; 131] = core::array::from_fn(|i| {
+            let v = f32::from(u8::try_from(i).unwrap()) / 130.0;
+            F32x4Rgba::new(v, 1.0 - v, 0.5, v)
+        });
+        let mut dst = [F32x4Rgba::new(0.25, 0.5, 0.75, 1.0); 131];
+        let expected: [F32x4Rgba; 131] =
+            core::array::from_fn(|i| BlendMode::Multiply.apply_linear(src[i], dst[i]));
+        BlendMode::Multiply.apply_linear_slice(&src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+}
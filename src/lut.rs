@@ -0,0 +1,487 @@
+//! 3D lookup table (LUT) color grading.
+//!
+//! A [`Lut3d`] maps RGB colors through a cubic lattice of output colors, using trilinear
+//! interpolation between lattice points. It is typically applied to a layer before compositing,
+//! as a color grading stage in video and photo pipelines.
+//!
+//! With the `cube` feature, LUTs can be parsed from the common Adobe/Resolve `.cube` format; see
+//! [`Lut3d::parse_cube`].
+//!
+//! ## Examples
+//!
+//! A 2×2×2 LUT that inverts colors:
+//!
+//! ```rust
+//! use alpha_blend::{lut::Lut3d, rgba::F32x4Rgba};
+//!
+//! let mut table = [[0.0; 3]; 8];
+//! for (i, entry) in table.iter_mut().enumerate() {
+//!     // Red varies fastest, then green, then blue.
+//!     let (r, g, b) = (i & 1, (i >> 1) & 1, (i >> 2) & 1);
+//!     *entry = [1.0 - r as f32, 1.0 - g as f32, 1.0 - b as f32];
+//! }
+//! let lut = Lut3d::new(2, table);
+//!
+//! let graded = lut.apply(F32x4Rgba::new(0.25, 0.5, 1.0, 0.5));
+//! assert_eq!(graded, F32x4Rgba::new(0.75, 0.5, 0.0, 0.5));
+//! ```
+
+use crate::rgba::F32x4Rgba;
+
+/// A 3D color lookup table with trilinear interpolation.
+///
+/// The table holds `size³` RGB entries, with red varying fastest and blue slowest, as in the
+/// `.cube` format. Input colors are mapped from the `[domain_min, domain_max]` range onto the
+/// lattice, clamping values outside of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lut3d<T> {
+    size: usize,
+    table: T,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+}
+
+impl<T: AsRef<[[f32; 3]]>> Lut3d<T> {
+    /// Creates a new LUT with `size` lattice points per axis over the `[0.0, 1.0]` domain.
+    ///
+    /// ## Panics
+    ///
+    /// If `size < 2`, or `table` does not have exactly `size³` entries.
+    pub fn new(size: usize, table: T) -> Self {
+        assert!(size >= 2, "LUT size must be at least 2");
+        assert_eq!(
+            Some(table.as_ref().len()),
+            size.checked_pow(3),
+            "table must have size³ entries"
+        );
+        Self {
+            size,
+            table,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+        }
+    }
+
+    /// Returns a copy of this LUT mapping inputs from `[min, max]` (per channel) onto the lattice.
+    #[must_use]
+    pub fn with_domain(self, min: [f32; 3], max: [f32; 3]) -> Self {
+        Self {
+            domain_min: min,
+            domain_max: max,
+            ..self
+        }
+    }
+
+    /// Number of lattice points per axis.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the lattice entries, with red varying fastest.
+    #[must_use]
+    pub fn table(&self) -> &[[f32; 3]] {
+        self.table.as_ref()
+    }
+
+    /// Returns the lower bound of the input domain.
+    #[must_use]
+    pub const fn domain_min(&self) -> [f32; 3] {
+        self.domain_min
+    }
+
+    /// Returns the upper bound of the input domain.
+    #[must_use]
+    pub const fn domain_max(&self) -> [f32; 3] {
+        self.domain_max
+    }
+
+    /// Maps an RGB triple through the LUT.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        clippy::suboptimal_flops
+    )]
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let last = self.size - 1;
+        let mut index = [0; 3];
+        let mut frac = [0.0; 3];
+        for c in 0..3 {
+            let range = self.domain_max[c] - self.domain_min[c];
+            let x = ((rgb[c] - self.domain_min[c]) / range).clamp(0.0, 1.0) * last as f32;
+            // Truncation is flooring, as `x` is non-negative; NaN maps to `0`.
+            let i = (x as usize).min(last - 1);
+            index[c] = i;
+            frac[c] = x - i as f32;
+        }
+
+        let table = self.table.as_ref();
+        let at = |r: usize, g: usize, b: usize| {
+            table[(index[0] + r)
+                + (index[1] + g) * self.size
+                + (index[2] + b) * self.size * self.size]
+        };
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| {
+            [
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+            ]
+        };
+        let [fr, fg, fb] = frac;
+        let c00 = lerp(at(0, 0, 0), at(1, 0, 0), fr);
+        let c10 = lerp(at(0, 1, 0), at(1, 1, 0), fr);
+        let c01 = lerp(at(0, 0, 1), at(1, 0, 1), fr);
+        let c11 = lerp(at(0, 1, 1), at(1, 1, 1), fr);
+        lerp(lerp(c00, c10, fg), lerp(c01, c11, fg), fb)
+    }
+
+    /// Maps the color channels of a straight-alpha `color` through the LUT, keeping its alpha.
+    #[must_use]
+    pub fn apply(&self, color: F32x4Rgba) -> F32x4Rgba {
+        let [r, g, b] = self.sample([color.r, color.g, color.b]);
+        F32x4Rgba::new(r, g, b, color.a)
+    }
+
+    /// Maps each straight-alpha pixel of `layer` through the LUT, in place.
+    pub fn apply_slice(&self, layer: &mut [F32x4Rgba]) {
+        for p in layer {
+            *p = self.apply(*p);
+        }
+    }
+}
+
+#[cfg(feature = "cube")]
+pub use cube::{CubeErrorKind, ParseCubeError};
+
+#[cfg(feature = "cube")]
+mod cube {
+    use core::{error::Error, fmt};
+
+    use super::Lut3d;
+
+    /// Largest `LUT_3D_SIZE` accepted by the parser, as recommended by the format specification.
+    const MAX_SIZE: usize = 256;
+
+    /// What went wrong while parsing a `.cube` file.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub enum CubeErrorKind {
+        /// The `LUT_3D_SIZE` keyword is missing.
+        MissingSize,
+
+        /// The `LUT_3D_SIZE` value is not an integer in `2..=256`.
+        InvalidSize,
+
+        /// The file describes a 1D LUT (`LUT_1D_SIZE`), which is not supported.
+        Unsupported1d,
+
+        /// A keyword or table line does not have the expected number of values.
+        WrongValueCount,
+
+        /// A value is not a valid number.
+        InvalidNumber,
+
+        /// The table does not have exactly `size³` entries.
+        WrongEntryCount,
+    }
+
+    /// Error returned by [`Lut3d::parse_cube`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ParseCubeError {
+        kind: CubeErrorKind,
+        line: usize,
+    }
+
+    impl ParseCubeError {
+        /// Returns what went wrong.
+        #[must_use]
+        pub const fn kind(&self) -> CubeErrorKind {
+            self.kind
+        }
+
+        /// Returns the 1-based line number at which the error was detected.
+        ///
+        /// For errors about the file as a whole, this is the last line.
+        #[must_use]
+        pub const fn line(&self) -> usize {
+            self.line
+        }
+    }
+
+    impl fmt::Display for ParseCubeError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let message = match self.kind {
+                CubeErrorKind::MissingSize => "missing LUT_3D_SIZE",
+                CubeErrorKind::InvalidSize => "LUT_3D_SIZE must be an integer in 2..=256",
+                CubeErrorKind::Unsupported1d => "1D LUTs are not supported",
+                CubeErrorKind::WrongValueCount => "wrong number of values",
+                CubeErrorKind::InvalidNumber => "invalid number",
+                CubeErrorKind::WrongEntryCount => "table must have LUT_3D_SIZE³ entries",
+            };
+            write!(f, "line {}: {message}", self.line)
+        }
+    }
+
+    impl Error for ParseCubeError {}
+
+    impl Lut3d<Vec<[f32; 3]>> {
+        /// Parses a 3D LUT in the Adobe/Resolve `.cube` text format.
+        ///
+        /// `TITLE`, `DOMAIN_MIN`, `DOMAIN_MAX`, `LUT_3D_SIZE`, comments (`#`) and blank lines are
+        /// understood; other keywords are ignored.
+        ///
+        /// _This function requires the `cube` feature._
+        ///
+        /// ## Errors
+        ///
+        /// Returns a [`ParseCubeError`] if the text is not a valid 3D `.cube` LUT.
+        ///
+        /// ## Examples
+        ///
+        /// ```rust
+        /// use alpha_blend::lut::Lut3d;
+        ///
+        /// let text = "
+        /// TITLE \"identity\"
+        /// LUT_3D_SIZE 2
+        /// 0 0 0
+        /// 1 0 0
+        /// 0 1 0
+        /// 1 1 0
+        /// 0 0 1
+        /// 1 0 1
+        /// 0 1 1
+        /// 1 1 1
+        /// ";
+        /// let lut = Lut3d::parse_cube(text)?;
+        /// assert_eq!(lut.sample([0.25, 0.5, 0.75]), [0.25, 0.5, 0.75]);
+        /// # Ok::<(), alpha_blend::lut::ParseCubeError>(())
+        /// ```
+        pub fn parse_cube(text: &str) -> Result<Self, ParseCubeError> {
+            let mut size = None;
+            let mut domain_min = [0.0; 3];
+            let mut domain_max = [1.0; 3];
+            let mut table = Vec::new();
+            let mut line_number = 0;
+
+            for (i, line) in text.lines().enumerate() {
+                line_number = i + 1;
+                let error = |kind| ParseCubeError {
+                    kind,
+                    line: line_number,
+                };
+                let line = line.split('#').next().unwrap_or_default().trim();
+                let mut words = line.split_ascii_whitespace();
+                let Some(first) = words.next() else {
+                    continue;
+                };
+                match first {
+                    "TITLE" => {}
+                    "LUT_1D_SIZE" => return Err(error(CubeErrorKind::Unsupported1d)),
+                    "LUT_3D_SIZE" => {
+                        let (Some(n), None) = (words.next(), words.next()) else {
+                            return Err(error(CubeErrorKind::WrongValueCount));
+                        };
+                        let n = n
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|n| (2..=MAX_SIZE).contains(n))
+                            .ok_or_else(|| error(CubeErrorKind::InvalidSize))?;
+                        table.reserve(n * n * n);
+                        size = Some(n);
+                    }
+                    "DOMAIN_MIN" => domain_min = parse_triple(words).map_err(error)?,
+                    "DOMAIN_MAX" => domain_max = parse_triple(words).map_err(error)?,
+                    _ if first.starts_with(|c: char| c.is_ascii_alphabetic()) => {}
+                    _ => {
+                        let rest = line.split_ascii_whitespace();
+                        table.push(parse_triple(rest).map_err(error)?);
+                    }
+                }
+            }
+
+            let error = |kind| ParseCubeError {
+                kind,
+                line: line_number,
+            };
+            let size = size.ok_or_else(|| error(CubeErrorKind::MissingSize))?;
+            if table.len() != size * size * size {
+                return Err(error(CubeErrorKind::WrongEntryCount));
+            }
+            Ok(Self::new(size, table).with_domain(domain_min, domain_max))
+        }
+    }
+
+    /// Parses exactly three whitespace-separated numbers.
+    fn parse_triple<'a>(
+        mut words: impl Iterator<Item = &'a str>,
+    ) -> Result<[f32; 3], CubeErrorKind> {
+        let mut out = [0.0; 3];
+        for value in &mut out {
+            let word = words.next().ok_or(CubeErrorKind::WrongValueCount)?;
+            *value = word.parse().map_err(|_| CubeErrorKind::InvalidNumber)?;
+        }
+        if words.next().is_some() {
+            return Err(CubeErrorKind::WrongValueCount);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    /// Returns an identity LUT table with `N` points per axis.
+    fn identity<const N: usize>() -> [[[[f32; 3]; N]; N]; N] {
+        let step = |i: usize| i as f32 / (N - 1) as f32;
+        core::array::from_fn(|b| {
+            core::array::from_fn(|g| core::array::from_fn(|r| [step(r), step(g), step(b)]))
+        })
+    }
+
+    fn flat<const N: usize>(table: &[[[[f32; 3]; N]; N]; N]) -> &[[f32; 3]] {
+        table.as_flattened().as_flattened()
+    }
+
+    #[test]
+    fn identity_preserves_colors() {
+        let table = identity::<5>();
+        let lut = Lut3d::new(5, flat(&table));
+        for rgb in [
+            [0.0, 0.0, 0.0],
+            [1.0, 1.0, 1.0],
+            [0.1, 0.5, 0.9],
+            [0.3, 0.7, 0.2],
+        ] {
+            let out = lut.sample(rgb);
+            for c in 0..3 {
+                assert!((out[c] - rgb[c]).abs() < 1e-6, "{rgb:?} -> {out:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn lattice_points_are_exact() {
+        let mut table = identity::<3>();
+        table[1][2][0] = [0.25, 0.5, 0.75];
+        let lut = Lut3d::new(3, flat(&table));
+        assert_eq!(lut.sample([0.0, 1.0, 0.5]), [0.25, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn interpolates_between_lattice_points() {
+        let mut table = identity::<2>();
+        table[0][0][1] = [0.0, 0.0, 0.0];
+        let lut = Lut3d::new(2, flat(&table));
+        assert_eq!(lut.sample([0.5, 0.0, 0.0]), [0.0, 0.0, 0.0]);
+        assert_eq!(lut.sample([0.5, 0.5, 0.0]), [0.25, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn clamps_outside_domain() {
+        let table = identity::<2>();
+        let lut = Lut3d::new(2, flat(&table));
+        assert_eq!(lut.sample([-1.0, 2.0, 0.5]), [0.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn custom_domain() {
+        let table = identity::<2>();
+        let lut = Lut3d::new(2, flat(&table)).with_domain([0.0; 3], [2.0; 3]);
+        assert_eq!(lut.sample([1.0, 2.0, 0.0]), [0.5, 1.0, 0.0]);
+        assert_eq!(lut.domain_max(), [2.0; 3]);
+    }
+
+    #[test]
+    fn apply_keeps_alpha() {
+        let mut table = identity::<2>();
+        table[1][1][1] = [0.0, 0.0, 0.0];
+        let lut = Lut3d::new(2, flat(&table));
+        let mut layer = [F32x4Rgba::WHITE, F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)];
+        lut.apply_slice(&mut layer);
+        assert_eq!(
+            layer,
+            [F32x4Rgba::BLACK, F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "size³ entries")]
+    fn new_panics_on_wrong_table_size() {
+        let _ = Lut3d::new(2, [[0.0; 3]; 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2")]
+    fn new_panics_on_size_one() {
+        let _ = Lut3d::new(1, [[0.0; 3]; 1]);
+    }
+
+    #[cfg(feature = "cube")]
+    mod cube {
+        use super::*;
+
+        const IDENTITY_2: &str = "0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+
+        fn parse(text: &str) -> Result<Lut3d<Vec<[f32; 3]>>, ParseCubeError> {
+            Lut3d::parse_cube(text)
+        }
+
+        #[test]
+        fn parses_keywords_and_comments() {
+            let text = format!(
+                "# comment\nTITLE \"grade\"\n\nLUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2 # max\n{IDENTITY_2}"
+            );
+            let lut = parse(&text).unwrap();
+            assert_eq!(lut.size(), 2);
+            assert_eq!(lut.domain_max(), [2.0; 3]);
+            assert_eq!(lut.table()[1], [1.0, 0.0, 0.0]);
+            assert_eq!(lut.sample([1.0, 1.0, 1.0]), [0.5, 0.5, 0.5]);
+        }
+
+        #[test]
+        fn errors() {
+            let cases = [
+                (IDENTITY_2.into(), CubeErrorKind::MissingSize, 8),
+                ("LUT_3D_SIZE 1\n".into(), CubeErrorKind::InvalidSize, 1),
+                ("LUT_3D_SIZE x\n".into(), CubeErrorKind::InvalidSize, 1),
+                (
+                    "LUT_3D_SIZE 2 2\n".into(),
+                    CubeErrorKind::WrongValueCount,
+                    1,
+                ),
+                ("LUT_1D_SIZE 2\n".into(), CubeErrorKind::Unsupported1d, 1),
+                (
+                    format!("LUT_3D_SIZE 2\n0 0\n{IDENTITY_2}"),
+                    CubeErrorKind::WrongValueCount,
+                    2,
+                ),
+                (
+                    format!("LUT_3D_SIZE 2\n0 0 x\n{IDENTITY_2}"),
+                    CubeErrorKind::InvalidNumber,
+                    2,
+                ),
+                (
+                    format!("LUT_3D_SIZE 2\n0 0 0\n{IDENTITY_2}"),
+                    CubeErrorKind::WrongEntryCount,
+                    10,
+                ),
+            ];
+            for (text, kind, line) in cases {
+                let err = parse(&text).unwrap_err();
+                assert_eq!((err.kind(), err.line()), (kind, line), "{text:?}");
+            }
+        }
+
+        #[test]
+        fn error_display() {
+            let err = parse("LUT_1D_SIZE 2\n").unwrap_err();
+            assert_eq!(format!("{err}"), "line 1: 1D LUTs are not supported");
+        }
+    }
+}
@@ -0,0 +1,621 @@
+//! Compositing through per-pixel coverage masks.
+//!
+//! A mask is a plane of coverage values in `[0.0, 1.0]`, one per pixel, laid out in the same
+//! order as the pixel buffers it is applied to. A coverage of `0.0` leaves the destination
+//! untouched, `1.0` writes the full blend result, and values in between interpolate linearly.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, mask, rgba::F32x4Rgba};
+//!
+//! let src = [F32x4Rgba::WHITE; 2];
+//! let mut dst = [F32x4Rgba::BLACK; 2];
+//! mask::blend_masked(&BlendMode::Source, &src, &mut dst, &[1.0, 0.0]);
+//!
+//! assert_eq!(dst, [F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+//! ```
+//!
+//! Masks can also be generated, such as a [`LinearRamp`] that fades a layer's edge:
+//!
+//! ```rust
+//! use alpha_blend::{mask::LinearRamp, rgba::F32x4Rgba};
+//!
+//! let mut layer = [F32x4Rgba::WHITE; 4];
+//! LinearRamp::new((0.0, 0.0), (4.0, 0.0), 1.0, 0.0).apply(&mut layer, 4);
+//!
+//! assert_eq!(layer[0].a, 0.875);
+//! assert_eq!(layer[3].a, 0.125);
+//! ```
+
+use crate::{
+    RgbaBlend, math,
+    rgba::{F32x4Rgba, U8x4Rgba},
+};
+
+/// Blends `src` onto `dst` in place, weighting each result by the matching `mask` coverage.
+///
+/// Each output pixel is `dst.lerp(blend.apply(src, dst), coverage)`, with coverage clamped to
+/// `[0.0, 1.0]`.
+///
+/// ## Panics
+///
+/// If `src`, `dst` and `mask` do not all have the same length.
+pub fn blend_masked<B>(blend: &B, src: &[F32x4Rgba], dst: &mut [F32x4Rgba], mask: &[f32])
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert_eq!(
+        mask.len(),
+        dst.len(),
+        "mask must have one coverage value per pixel"
+    );
+    for ((s, d), &m) in src.iter().zip(dst.iter_mut()).zip(mask) {
+        *d = d.lerp(blend.apply(*s, *d), m);
+    }
+}
+
+/// Composites `src` over `dst` with per-channel (LCD subpixel) `coverage`, as component-alpha
+/// `SourceOver`.
+///
+/// Subpixel-antialiased glyph rasterizers produce a separate coverage for the red, green and blue
+/// subpixels of each pixel. Each color channel is composited as `SourceOver` with its own source
+/// alpha, `src.a * coverage`, with coverage clamped to `[0.0, 1.0]`; the output alpha uses the
+/// largest of the three. Channels are composited premultiplied, so translucent destinations
+/// blend correctly, and the result is straight alpha.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{mask, rgba::F32x4Rgba};
+///
+/// // A glyph edge covering only the rightmost (blue) subpixel.
+/// let out = mask::subpixel_over(F32x4Rgba::WHITE, F32x4Rgba::BLACK, [0.0, 0.0, 1.0]);
+/// assert_eq!(out, F32x4Rgba::new(0.0, 0.0, 1.0, 1.0));
+/// ```
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn subpixel_over(src: F32x4Rgba, dst: F32x4Rgba, coverage: [f32; 3]) -> F32x4Rgba {
+    let [r, g, b] = coverage.map(|c| src.a * math::clamp01(c));
+    let alpha = r.max(g).max(b) + dst.a * (1.0 - r.max(g).max(b));
+    if alpha <= 0.0 {
+        return F32x4Rgba::TRANSPARENT;
+    }
+    let channel = |s: f32, d: f32, a: f32| (s * a + d * dst.a * (1.0 - a)) / alpha;
+    F32x4Rgba::new(
+        channel(src.r, dst.r, r),
+        channel(src.g, dst.g, g),
+        channel(src.b, dst.b, b),
+        alpha,
+    )
+}
+
+/// Composites `src` over `dst` in place with per-pixel subpixel `coverage`; see
+/// [`subpixel_over`].
+///
+/// ## Panics
+///
+/// If `src`, `dst` and `coverage` do not all have the same length.
+pub fn blend_subpixel(src: &[F32x4Rgba], dst: &mut [F32x4Rgba], coverage: &[[f32; 3]]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert_eq!(
+        coverage.len(),
+        dst.len(),
+        "mask must have one coverage value per pixel"
+    );
+    for ((s, d), &c) in src.iter().zip(dst.iter_mut()).zip(coverage) {
+        *d = subpixel_over(*s, *d, c);
+    }
+}
+
+/// A borrowed coverage plane, one value per pixel, such as a rasterized glyph or a clip path.
+///
+/// Coverage is either 8-bit (A8), where `255` is full coverage, or `f32`, clamped to
+/// `[0.0, 1.0]`. The operations panic if the mask does not have one value per pixel.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{mask::AlphaMask, rgba::U8x4Rgba};
+///
+/// let glyph = [0, 128, 255];
+/// let mut dst = [U8x4Rgba::BLACK; 3];
+/// AlphaMask::from(&glyph[..]).fill_over_u8(U8x4Rgba::WHITE, &mut dst);
+/// assert_eq!(dst[0], U8x4Rgba::BLACK);
+/// assert_eq!(dst[1], U8x4Rgba::new(128, 128, 128, 255));
+/// assert_eq!(dst[2], U8x4Rgba::WHITE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMask<'a> {
+    /// 8-bit coverage, where `255` is full coverage.
+    A8(&'a [u8]),
+
+    /// Coverage in `[0.0, 1.0]`; values outside are clamped.
+    F32(&'a [f32]),
+}
+
+impl AlphaMask<'_> {
+    /// Returns the number of coverage values.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        match self {
+            Self::A8(mask) => mask.len(),
+            Self::F32(mask) => mask.len(),
+        }
+    }
+
+    /// Returns `true` if the mask has no coverage values.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the coverage of pixel `i`, in `[0.0, 1.0]`.
+    ///
+    /// ## Panics
+    ///
+    /// If `i` is out of bounds.
+    #[must_use]
+    pub fn coverage(&self, i: usize) -> f32 {
+        match self {
+            Self::A8(mask) => f32::from(mask[i]) / 255.0,
+            Self::F32(mask) => math::clamp01(mask[i]),
+        }
+    }
+
+    /// Returns the coverage of pixel `i` as 8 bits, rounding to nearest.
+    ///
+    /// ## Panics
+    ///
+    /// If `i` is out of bounds.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn coverage_u8(&self, i: usize) -> u8 {
+        match self {
+            Self::A8(mask) => mask[i],
+            Self::F32(mask) => math::round(math::clamp01(mask[i]) * 255.0) as u8,
+        }
+    }
+
+    /// Panics unless the mask has exactly one coverage value for each of `pixels` pixels.
+    fn assert_covers(&self, pixels: usize) {
+        assert_eq!(
+            self.len(),
+            pixels,
+            "mask must have one coverage value per pixel"
+        );
+    }
+
+    /// Clips straight-alpha `dst` to the mask in place, as `DstIn`: each alpha is multiplied by
+    /// its coverage.
+    ///
+    /// ## Panics
+    ///
+    /// If the mask and `dst` do not have the same length.
+    pub fn clip(&self, dst: &mut [F32x4Rgba]) {
+        self.assert_covers(dst.len());
+        for (i, d) in dst.iter_mut().enumerate() {
+            d.a *= self.coverage(i);
+        }
+    }
+
+    /// Clips straight-alpha 8-bit `dst` to the mask in place; see [`clip`](Self::clip).
+    ///
+    /// ## Panics
+    ///
+    /// If the mask and `dst` do not have the same length.
+    pub fn clip_u8(&self, dst: &mut [U8x4Rgba]) {
+        self.assert_covers(dst.len());
+        for (i, d) in dst.iter_mut().enumerate() {
+            d.a = math::mul_div_255_round(d.a, self.coverage_u8(i));
+        }
+    }
+
+    /// Blends the solid `color` onto `dst` in place through the mask.
+    ///
+    /// Each output pixel is `dst.lerp(blend.apply(color, dst), coverage)`, as in
+    /// [`blend_masked`].
+    ///
+    /// ## Panics
+    ///
+    /// If the mask and `dst` do not have the same length.
+    pub fn fill<B>(&self, blend: &B, color: F32x4Rgba, dst: &mut [F32x4Rgba])
+    where
+        B: RgbaBlend<Channel = f32> + ?Sized,
+    {
+        self.assert_covers(dst.len());
+        for (i, d) in dst.iter_mut().enumerate() {
+            *d = d.lerp(blend.apply(color, *d), self.coverage(i));
+        }
+    }
+
+    /// Composites the solid 8-bit `color` over `dst` in place, with its alpha scaled by the
+    /// coverage, using only integer math.
+    ///
+    /// This is how text and other coverage-rasterized shapes are usually drawn.
+    ///
+    /// ## Panics
+    ///
+    /// If the mask and `dst` do not have the same length.
+    pub fn fill_over_u8(&self, color: U8x4Rgba, dst: &mut [U8x4Rgba]) {
+        self.assert_covers(dst.len());
+        for (i, d) in dst.iter_mut().enumerate() {
+            let alpha = math::mul_div_255_round(color.a, self.coverage_u8(i));
+            *d = U8x4Rgba::new(color.r, color.g, color.b, alpha).source_over(*d);
+        }
+    }
+}
+
+impl<'a> From<&'a [u8]> for AlphaMask<'a> {
+    fn from(mask: &'a [u8]) -> Self {
+        Self::A8(mask)
+    }
+}
+
+impl<'a> From<&'a [f32]> for AlphaMask<'a> {
+    fn from(mask: &'a [f32]) -> Self {
+        Self::F32(mask)
+    }
+}
+
+/// A linear ramp of alpha between two points, such as a fade-out edge or a scrim.
+///
+/// Pixels are sampled at their centers and projected onto the line from `start` to `end`;
+/// alpha is `start_alpha` at or before `start`, `end_alpha` at or past `end`, and linearly
+/// interpolated in between. If `start == end`, every pixel has `end_alpha`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRamp {
+    /// Point, in pixels, at which the ramp has `start_alpha`.
+    pub start: (f32, f32),
+
+    /// Point, in pixels, at which the ramp has `end_alpha`.
+    pub end: (f32, f32),
+
+    /// Alpha at (and before) `start`.
+    pub start_alpha: f32,
+
+    /// Alpha at (and past) `end`.
+    pub end_alpha: f32,
+}
+
+impl LinearRamp {
+    /// Creates a new `LinearRamp` from `start_alpha` at `start` to `end_alpha` at `end`.
+    #[must_use]
+    pub const fn new(start: (f32, f32), end: (f32, f32), start_alpha: f32, end_alpha: f32) -> Self {
+        Self {
+            start,
+            end,
+            start_alpha,
+            end_alpha,
+        }
+    }
+
+    /// Returns the ramp alpha for the pixel at `(x, y)`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::suboptimal_flops)]
+    pub fn alpha(&self, x: usize, y: usize) -> f32 {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let length_sq = dx * dx + dy * dy;
+        if length_sq == 0.0 {
+            return self.end_alpha;
+        }
+        let (px, py) = (x as f32 + 0.5 - self.start.0, y as f32 + 0.5 - self.start.1);
+        let t = ((px * dx + py * dy) / length_sq).clamp(0.0, 1.0);
+        self.start_alpha + (self.end_alpha - self.start_alpha) * t
+    }
+
+    /// Fills `mask` with the ramp as a coverage plane, `width` pixels wide.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `mask.len()` is not a multiple of `width`.
+    pub fn fill_mask(&self, mask: &mut [f32], width: usize) {
+        for_each_pixel(mask, width, |x, y, m| *m = self.alpha(x, y));
+    }
+
+    /// Fills `mask` with the ramp as an 8-bit (A8) alpha plane, `width` pixels wide.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `mask.len()` is not a multiple of `width`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn fill_mask_u8(&self, mask: &mut [u8], width: usize) {
+        for_each_pixel(mask, width, |x, y, m| {
+            *m = math::round(self.alpha(x, y).clamp(0.0, 1.0) * 255.0) as u8;
+        });
+    }
+
+    /// Fills `layer` with straight-alpha `color`, its alpha scaled by the ramp.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `layer.len()` is not a multiple of `width`.
+    pub fn fill_layer(&self, layer: &mut [F32x4Rgba], width: usize, color: F32x4Rgba) {
+        for_each_pixel(layer, width, |x, y, p| {
+            *p = F32x4Rgba::new(color.r, color.g, color.b, color.a * self.alpha(x, y));
+        });
+    }
+
+    /// Fades a straight-alpha `layer` in place by scaling each pixel's alpha by the ramp.
+    ///
+    /// Color channels are left untouched; see [`apply_premultiplied`](Self::apply_premultiplied)
+    /// for premultiplied layers.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `layer.len()` is not a multiple of `width`.
+    pub fn apply(&self, layer: &mut [F32x4Rgba], width: usize) {
+        for_each_pixel(layer, width, |x, y, p| p.a *= self.alpha(x, y));
+    }
+
+    /// Fades a premultiplied `layer` in place by scaling all four channels by the ramp.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `layer.len()` is not a multiple of `width`.
+    pub fn apply_premultiplied(&self, layer: &mut [F32x4Rgba], width: usize) {
+        for_each_pixel(layer, width, |x, y, p| {
+            let alpha = self.alpha(x, y);
+            *p = F32x4Rgba::new(p.r * alpha, p.g * alpha, p.b * alpha, p.a * alpha);
+        });
+    }
+
+    /// Fades a straight-alpha 8-bit `layer` in place by scaling each pixel's alpha by the ramp.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `layer.len()` is not a multiple of `width`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn apply_u8(&self, layer: &mut [U8x4Rgba], width: usize) {
+        for_each_pixel(layer, width, |x, y, p| {
+            let alpha = self.alpha(x, y).clamp(0.0, 1.0);
+            p.a = math::round(f32::from(p.a) * alpha) as u8;
+        });
+    }
+}
+
+/// Calls `f` with the coordinates of, and a reference to, each element of a row-major plane.
+fn for_each_pixel<T>(plane: &mut [T], width: usize, mut f: impl FnMut(usize, usize, &mut T)) {
+    assert!(
+        width != 0 && plane.len() % width == 0,
+        "buffer length must be a multiple of width"
+    );
+    for (y, row) in plane.chunks_exact_mut(width).enumerate() {
+        for (x, p) in row.iter_mut().enumerate() {
+            f(x, y, p);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    #[test]
+    fn zero_coverage_keeps_dst() {
+        let src = [F32x4Rgba::WHITE; 3];
+        let mut dst = [F32x4Rgba::BLACK; 3];
+        blend_masked(&BlendMode::SourceOver, &src, &mut dst, &[0.0; 3]);
+        assert_eq!(dst, [F32x4Rgba::BLACK; 3]);
+    }
+
+    #[test]
+    fn full_coverage_matches_apply() {
+        let src = [F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)];
+        let mut dst = [F32x4Rgba::new(0.0, 0.0, 1.0, 1.0)];
+        let expected = BlendMode::SourceOver.apply(src[0], dst[0]);
+        blend_masked(&BlendMode::SourceOver, &src, &mut dst, &[1.0]);
+        assert_eq!(dst[0], expected);
+    }
+
+    #[test]
+    fn partial_coverage_interpolates() {
+        let src = [F32x4Rgba::WHITE];
+        let mut dst = [F32x4Rgba::BLACK];
+        blend_masked(&BlendMode::Source, &src, &mut dst, &[0.25]);
+        assert_eq!(dst[0], F32x4Rgba::new(0.25, 0.25, 0.25, 1.0));
+    }
+
+    #[test]
+    fn coverage_is_clamped() {
+        let src = [F32x4Rgba::WHITE; 2];
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        blend_masked(&BlendMode::Source, &src, &mut dst, &[2.0, -1.0]);
+        assert_eq!(dst, [F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one coverage value per pixel")]
+    fn panics_on_mask_length_mismatch() {
+        let src = [F32x4Rgba::WHITE; 2];
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        blend_masked(&BlendMode::Source, &src, &mut dst, &[1.0]);
+    }
+
+    // --- AlphaMask ---
+
+    #[test]
+    fn alpha_mask_coverage() {
+        let a8 = AlphaMask::from(&[0_u8, 51, 255][..]);
+        assert_eq!(a8.len(), 3);
+        assert_eq!(a8.coverage(1), 0.2);
+        let f32 = AlphaMask::from(&[-1.0, 0.5, 2.0][..]);
+        assert_eq!([f32.coverage(0), f32.coverage(2)], [0.0, 1.0]);
+        assert_eq!(f32.coverage_u8(1), 128);
+        assert!(AlphaMask::A8(&[]).is_empty());
+    }
+
+    #[test]
+    fn alpha_mask_clip_scales_alpha() {
+        let mut dst = [F32x4Rgba::WHITE; 2];
+        AlphaMask::F32(&[0.5, 0.0]).clip(&mut dst);
+        assert_eq!(dst[0], F32x4Rgba::new(1.0, 1.0, 1.0, 0.5));
+        assert_eq!(dst[1].a, 0.0);
+
+        let mut dst = [U8x4Rgba::new(10, 20, 30, 200); 2];
+        AlphaMask::A8(&[128, 255]).clip_u8(&mut dst);
+        assert_eq!(dst[0], U8x4Rgba::new(10, 20, 30, 100));
+        assert_eq!(dst[1].a, 200);
+    }
+
+    #[test]
+    fn alpha_mask_fill_matches_blend_masked() {
+        let color = F32x4Rgba::new(1.0, 0.5, 0.0, 0.75);
+        let mask = [0.0, 0.25, 1.0];
+        let mut filled = [F32x4Rgba::new(0.0, 0.0, 1.0, 1.0); 3];
+        let mut masked = filled;
+        AlphaMask::F32(&mask).fill(&BlendMode::Multiply, color, &mut filled);
+        blend_masked(&BlendMode::Multiply, &[color; 3], &mut masked, &mask);
+        assert_eq!(filled, masked);
+    }
+
+    #[test]
+    fn alpha_mask_fill_over_u8_keeps_uncovered_pixels() {
+        let mut dst = [U8x4Rgba::new(10, 20, 30, 40); 2];
+        AlphaMask::F32(&[0.0, 1.0]).fill_over_u8(U8x4Rgba::new(255, 0, 0, 255), &mut dst);
+        assert_eq!(dst[0], U8x4Rgba::new(10, 20, 30, 40));
+        assert_eq!(dst[1], U8x4Rgba::new(255, 0, 0, 255));
+    }
+
+    #[test]
+    #[should_panic(expected = "one coverage value per pixel")]
+    fn alpha_mask_panics_on_length_mismatch() {
+        AlphaMask::A8(&[255]).clip(&mut [F32x4Rgba::WHITE; 2]);
+    }
+
+    // --- LinearRamp ---
+
+    const FADE_RIGHT: LinearRamp = LinearRamp::new((0.0, 0.0), (4.0, 0.0), 1.0, 0.0);
+
+    #[test]
+    fn ramp_interpolates_along_axis() {
+        let mut mask = [0.0; 4];
+        FADE_RIGHT.fill_mask(&mut mask, 4);
+        assert_eq!(mask, [0.875, 0.625, 0.375, 0.125]);
+    }
+
+    #[test]
+    fn ramp_clamps_outside_endpoints() {
+        let ramp = LinearRamp::new((1.0, 0.0), (2.0, 0.0), 1.0, 0.0);
+        let mut mask = [0.5; 4];
+        ramp.fill_mask(&mut mask, 4);
+        assert_eq!(mask, [1.0, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn ramp_is_constant_across_perpendicular_axis() {
+        let mut mask = [0.0; 8];
+        FADE_RIGHT.fill_mask(&mut mask, 4);
+        assert_eq!(mask[..4], mask[4..]);
+    }
+
+    #[test]
+    fn ramp_degenerate_uses_end_alpha() {
+        let ramp = LinearRamp::new((1.0, 1.0), (1.0, 1.0), 1.0, 0.25);
+        assert_eq!(ramp.alpha(0, 0), 0.25);
+        assert_eq!(ramp.alpha(3, 3), 0.25);
+    }
+
+    #[test]
+    fn ramp_fill_mask_u8() {
+        let mut mask = [0; 4];
+        FADE_RIGHT.fill_mask_u8(&mut mask, 4);
+        assert_eq!(mask, [223, 159, 96, 32]);
+    }
+
+    #[test]
+    fn ramp_fill_layer_keeps_color() {
+        let mut layer = [F32x4Rgba::TRANSPARENT; 4];
+        FADE_RIGHT.fill_layer(&mut layer, 4, F32x4Rgba::new(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(layer[0], F32x4Rgba::new(1.0, 0.5, 0.0, 0.4375));
+        assert_eq!(layer[3], F32x4Rgba::new(1.0, 0.5, 0.0, 0.0625));
+    }
+
+    #[test]
+    fn ramp_apply_straight_scales_alpha_only() {
+        let mut layer = [F32x4Rgba::WHITE; 4];
+        FADE_RIGHT.apply(&mut layer, 4);
+        assert_eq!(layer[1], F32x4Rgba::new(1.0, 1.0, 1.0, 0.625));
+    }
+
+    #[test]
+    fn ramp_apply_premultiplied_scales_all_channels() {
+        let mut layer = [F32x4Rgba::WHITE; 4];
+        FADE_RIGHT.apply_premultiplied(&mut layer, 4);
+        assert_eq!(layer[1], F32x4Rgba::new(0.625, 0.625, 0.625, 0.625));
+        assert_eq!(
+            layer[1].unpremultiply(),
+            F32x4Rgba::new(1.0, 1.0, 1.0, 0.625)
+        );
+    }
+
+    #[test]
+    fn ramp_apply_u8() {
+        let mut layer = [U8x4Rgba::WHITE; 4];
+        FADE_RIGHT.apply_u8(&mut layer, 4);
+        assert_eq!(layer[0], U8x4Rgba::new(255, 255, 255, 223));
+        assert_eq!(layer[3], U8x4Rgba::new(255, 255, 255, 32));
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of width")]
+    fn ramp_panics_on_bad_width() {
+        FADE_RIGHT.fill_mask(&mut [0.0; 5], 4);
+    }
+
+    #[test]
+    fn subpixel_equal_coverage_matches_blend_masked() {
+        let src = [F32x4Rgba::new(1.0, 0.5, 0.0, 0.5)];
+        let dst = [F32x4Rgba::new(0.0, 0.0, 1.0, 1.0)];
+        let mut lcd = dst;
+        blend_subpixel(&src, &mut lcd, &[[0.5; 3]]);
+        let mut masked = dst;
+        blend_masked(&BlendMode::Source, &src, &mut masked, &[0.25]);
+        assert_eq!(lcd[0].r, masked[0].r);
+        assert_eq!(lcd[0].g, masked[0].g);
+        assert_eq!(lcd[0].b, masked[0].b);
+    }
+
+    #[test]
+    fn subpixel_coverage_is_per_channel() {
+        let out = subpixel_over(
+            F32x4Rgba::WHITE,
+            F32x4Rgba::new(0.0, 0.0, 0.0, 0.0),
+            [1.0, 0.5, 2.0],
+        );
+        assert_eq!(out, F32x4Rgba::new(1.0, 0.5, 1.0, 1.0));
+    }
+
+    #[test]
+    fn subpixel_over_translucent_destination() {
+        let dst = F32x4Rgba::new(1.0, 1.0, 1.0, 0.5);
+        let out = subpixel_over(F32x4Rgba::BLACK, dst, [0.5; 3]);
+        let third = 0.25 / 0.75;
+        assert_eq!(out, F32x4Rgba::new(third, third, third, 0.75));
+
+        // A red-only coverage leaves green and blue showing the destination.
+        let out = subpixel_over(F32x4Rgba::BLACK, dst, [1.0, 0.0, 0.0]);
+        assert_eq!(out, F32x4Rgba::new(0.0, 0.5, 0.5, 1.0));
+        assert_eq!(
+            subpixel_over(F32x4Rgba::WHITE, F32x4Rgba::TRANSPARENT, [0.0; 3]),
+            F32x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "one coverage value per pixel")]
+    fn blend_subpixel_panics_on_coverage_mismatch() {
+        blend_subpixel(&[F32x4Rgba::WHITE], &mut [F32x4Rgba::BLACK], &[]);
+    }
+}
@@ -1,14 +1,32 @@
-#![allow(unreachable_pub)]
+//! Low-level math primitives.
+//!
+//! These are the building blocks used by the crate's own kernels, exposed so that custom kernels
+//! can match its rounding behavior exactly.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::math;
+//!
+//! // 50% of 50% coverage, in 8-bit fixed point.
+//! assert_eq!(math::mul_div_255(128, 128), 64);
+//!
+//! assert_eq!(math::clamp01(1.5), 1.0);
+//! assert_eq!(math::lerp(0.0, 2.0, 0.25), 0.5);
+//! ```
 
 #[cfg(feature = "std")]
 extern crate std;
 
+use crate::rgba::U8x4Rgba;
+
 #[cfg(not(any(feature = "std", feature = "libm")))]
 compile_error!("Either the 'std' or 'libm' feature must be enabled for alpha-blend.");
 
 /// Implements rounding for `f32` values.
 ///
 /// If the `std` feature is enabled, it uses `f32::round`, otherwise it uses `libm::roundf`.
+#[must_use]
 pub fn round(f: f32) -> f32 {
     #[cfg(feature = "std")]
     return f32::round(f);
@@ -17,6 +35,176 @@ pub fn round(f: f32) -> f32 {
     return libm::roundf(f);
 }
 
+/// Implements the square root for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::sqrt`, otherwise it uses `libm::sqrtf`.
+#[must_use]
+pub fn sqrt(f: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return f32::sqrt(f);
+
+    #[cfg(not(feature = "std"))]
+    return libm::sqrtf(f);
+}
+
+/// Implements `2^f` for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::exp2`, otherwise it uses `libm::exp2f`.
+#[must_use]
+pub fn exp2(f: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return f32::exp2(f);
+
+    #[cfg(not(feature = "std"))]
+    return libm::exp2f(f);
+}
+
+/// Implements `base^exp` for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::powf`, otherwise it uses `libm::powf`.
+#[must_use]
+pub fn powf(base: f32, exp: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return f32::powf(base, exp);
+
+    #[cfg(not(feature = "std"))]
+    return libm::powf(base, exp);
+}
+
+/// Implements the cube root for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::cbrt`, otherwise it uses `libm::cbrtf`.
+#[must_use]
+pub fn cbrt(f: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return f32::cbrt(f);
+
+    #[cfg(not(feature = "std"))]
+    return libm::cbrtf(f);
+}
+
+/// Implements the four-quadrant arctangent of `y / x`, in radians, for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::atan2`, otherwise it uses `libm::atan2f`.
+#[must_use]
+pub fn atan2(y: f32, x: f32) -> f32 {
+    #[cfg(feature = "std")]
+    return f32::atan2(y, x);
+
+    #[cfg(not(feature = "std"))]
+    return libm::atan2f(y, x);
+}
+
+/// Implements the sine and cosine, in radians, for `f32` values.
+///
+/// If the `std` feature is enabled, it uses `f32::sin_cos`, otherwise it uses `libm::sincosf`.
+#[must_use]
+pub fn sin_cos(f: f32) -> (f32, f32) {
+    #[cfg(feature = "std")]
+    return f32::sin_cos(f);
+
+    #[cfg(not(feature = "std"))]
+    return libm::sincosf(f);
+}
+
+/// Clamps `v` to `[0.0, 1.0]`.
+///
+/// Unlike [`f32::clamp`], this is `const`; `NaN` is returned unchanged.
+#[must_use]
+pub const fn clamp01(v: f32) -> f32 {
+    if v < 0.0 {
+        0.0
+    } else if v > 1.0 {
+        1.0
+    } else {
+        v
+    }
+}
+
+/// Linearly interpolates from `a` to `b` by `t`, without clamping `t`.
+///
+/// `t = 0.0` returns `a`; `t = 1.0` returns `b`.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Divides `v` by `255`, rounding down, without a division.
+///
+/// Uses the `(v + (v >> 8) + 1) >> 8` identity, which is exact for every `v` up to
+/// `255 * 255`, the largest product of two 8-bit values.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn div_255(v: u16) -> u8 {
+    debug_assert!(v <= 255 * 255, "v must be at most 255 * 255");
+    ((v + (v >> 8) + 1) >> 8) as u8
+}
+
+/// Multiplies two 8-bit fixed-point values in `[0, 255]`, as in `a * b / 255` rounded down.
+#[must_use]
+pub const fn mul_div_255(a: u8, b: u8) -> u8 {
+    div_255(a as u16 * b as u16)
+}
+
+/// Multiplies two 8-bit fixed-point values in `[0, 255]`, as in `a * b / 255` rounded to
+/// nearest.
+///
+/// Computed as `(a * b + 127) / 255`, without any floating-point math.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn mul_div_255_round(a: u8, b: u8) -> u8 {
+    ((a as u16 * b as u16 + 127) / 255) as u8
+}
+
+#[cfg(feature = "mul-lut")]
+pub use lut::mul_div_255_lut;
+
+#[cfg(feature = "mul-lut")]
+mod lut {
+    use super::mul_div_255_round;
+
+    /// `MUL_DIV_255[a][b]` is `mul_div_255_round(a, b)`, computed at compile time.
+    #[allow(clippy::cast_possible_truncation)]
+    static MUL_DIV_255: [[u8; 256]; 256] = {
+        let mut table = [[0; 256]; 256];
+        let mut a = 0;
+        while a < 256 {
+            let mut b = 0;
+            while b < 256 {
+                table[a][b] = mul_div_255_round(a as u8, b as u8);
+                b += 1;
+            }
+            a += 1;
+        }
+        table
+    };
+
+    /// Multiplies two 8-bit fixed-point values in `[0, 255]` by table lookup.
+    ///
+    /// Identical to [`mul_div_255_round`], trading a 64 KiB read-only table for the multiply
+    /// and division, which is faster on microcontrollers without a fast hardware multiplier.
+    /// On CPUs with one the difference is small, and the table competes for cache; `cargo run
+    /// --release --example mul-lut --features mul-lut,std` compares the two.
+    ///
+    /// _This function requires the `mul-lut` feature._
+    #[must_use]
+    pub fn mul_div_255_lut(a: u8, b: u8) -> u8 {
+        MUL_DIV_255[a as usize][b as usize]
+    }
+}
+
+/// Adds two colors channel by channel, saturating at `255`.
+#[must_use]
+pub const fn saturating_add_u8x4(a: U8x4Rgba, b: U8x4Rgba) -> U8x4Rgba {
+    U8x4Rgba::new(
+        a.r.saturating_add(b.r),
+        a.g.saturating_add(b.g),
+        a.b.saturating_add(b.b),
+        a.a.saturating_add(b.a),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +220,92 @@ mod tests {
     fn round_down() {
         assert_eq!(round(1.4), 1.0);
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn sqrt_exact() {
+        assert_eq!(sqrt(9.0), 3.0);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn exp2_exact() {
+        assert_eq!(exp2(3.0), 8.0);
+        assert_eq!(exp2(-1.0), 0.5);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn cbrt_exact() {
+        assert_eq!(cbrt(27.0), 3.0);
+        assert_eq!(cbrt(-8.0), -2.0);
+    }
+
+    #[test]
+    fn trigonometry() {
+        let three_quarters = core::f32::consts::PI - core::f32::consts::FRAC_PI_4;
+        assert!((atan2(1.0, -1.0) - three_quarters).abs() < 1e-6);
+        let (sin, cos) = sin_cos(core::f32::consts::FRAC_PI_2);
+        assert!((sin - 1.0).abs() < 1e-6 && cos.abs() < 1e-6);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn clamp01_bounds() {
+        assert_eq!(clamp01(-1.0), 0.0);
+        assert_eq!(clamp01(0.5), 0.5);
+        assert_eq!(clamp01(2.0), 1.0);
+        assert!(clamp01(f32::NAN).is_nan());
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn lerp_does_not_clamp() {
+        assert_eq!(lerp(1.0, 3.0, 0.5), 2.0);
+        assert_eq!(lerp(1.0, 3.0, 2.0), 5.0);
+    }
+
+    #[test]
+    fn div_255_is_floor_division() {
+        for v in 0..=255 * 255 {
+            assert_eq!(u16::from(div_255(v)), v / 255, "{v}");
+        }
+    }
+
+    #[test]
+    fn mul_div_255_identities() {
+        for a in 0..=u8::MAX {
+            assert_eq!(mul_div_255(a, 255), a);
+            assert_eq!(mul_div_255(a, 0), 0);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn mul_div_255_round_is_nearest() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                let exact = f32::from(a) * f32::from(b) / 255.0;
+                assert_eq!(mul_div_255_round(a, b), round(exact) as u8, "{a} * {b}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mul-lut")]
+    fn mul_div_255_lut_matches_round() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                assert_eq!(mul_div_255_lut(a, b), mul_div_255_round(a, b), "{a} * {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn saturating_add_u8x4_saturates() {
+        assert_eq!(
+            saturating_add_u8x4(U8x4Rgba::new(200, 1, 0, 255), U8x4Rgba::new(100, 2, 0, 1)),
+            U8x4Rgba::new(255, 3, 0, 255)
+        );
+    }
 }
@@ -0,0 +1,331 @@
+//! Zero-sized blend modes, one type per [`BlendMode`].
+//!
+//! [`BlendMode::apply`] matches on the mode for every pixel. Each type here implements
+//! [`RgbaBlend`] for a single mode instead, with every coefficient and blend function known at
+//! compile time, so generic code such as [`RgbaBlend::apply_slice`] inlines the whole blend
+//! into its loop. Results are identical to the matching [`BlendMode`].
+//!
+//! When the mode is only known at runtime, [`BlendMode::dispatch`] selects the matching type
+//! once, and passes it to a [`Dispatch`] implementation that is monomorphized for each mode.
+//! Callers that cannot be generic can instead hoist the choice out of their loops with
+//! [`BlendMode::to_blender`], whose [`Blender`] calls a per-mode kernel through one function
+//! pointer, per pixel or per slice.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode, RgbaBlend,
+//!     modes::{Dispatch, SourceOver},
+//!     rgba::F32x4Rgba,
+//! };
+//!
+//! let mut dst = [F32x4Rgba::BLACK; 4];
+//! SourceOver.apply_slice(&[F32x4Rgba::new(1.0, 0.0, 0.0, 0.5); 4], &mut dst);
+//! assert_eq!(dst[0], F32x4Rgba::new(0.5, 0.0, 0.0, 0.75));
+//!
+//! // A loop that is compiled once per mode, chosen at runtime.
+//! struct Fill<'a>(F32x4Rgba, &'a mut [F32x4Rgba]);
+//!
+//! impl Dispatch for Fill<'_> {
+//!     type Output = ();
+//!
+//!     fn call<B: RgbaBlend<Channel = f32>>(self, blend: B) {
+//!         for d in self.1 {
+//!             *d = blend.apply(self.0, *d);
+//!         }
+//!     }
+//! }
+//!
+//! let mut dst = [F32x4Rgba::new(0.5, 0.0, 0.0, 1.0); 4];
+//! let mode = BlendMode::Screen;
+//! mode.dispatch(Fill(F32x4Rgba::new(0.5, 0.5, 0.5, 1.0), &mut dst));
+//! assert_eq!(dst[0], F32x4Rgba::new(0.75, 0.5, 0.5, 1.0));
+//! ```
+
+use crate::{
+    BlendMode, RgbaBlend, extended,
+    nonseparable::{self, NonSeparable},
+    porter_duff::{self, Coefficients},
+    rgba::F32x4Rgba,
+    separable::{self, Separable},
+};
+
+/// A computation generic over a blend mode, run by [`BlendMode::dispatch`].
+///
+/// Closures cannot be generic over their arguments, so the loop to monomorphize is written as
+/// an implementation of this trait instead.
+pub trait Dispatch {
+    /// What the computation returns.
+    type Output;
+
+    /// Runs the computation with `blend`, a [`modes`](self) type or a custom [`BlendMode`].
+    fn call<B: RgbaBlend<Channel = f32>>(self, blend: B) -> Self::Output;
+}
+
+/// A [`BlendMode`] resolved to its blend kernels, by [`BlendMode::to_blender`].
+///
+/// Blends exactly like its mode, without matching on the mode for each pixel:
+/// [`apply`](RgbaBlend::apply) is one indirect call to the mode's [`modes`](self) type, and
+/// [`apply_slice`](RgbaBlend::apply_slice) one indirect call to a loop specialized for it.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{BlendMode, RgbaBlend, rgba::F32x4Rgba};
+///
+/// let blender = BlendMode::Multiply.to_blender();
+/// let (src, dst) = (F32x4Rgba::new(0.5, 1.0, 0.0, 1.0), F32x4Rgba::WHITE);
+/// assert_eq!(blender.apply(src, dst), BlendMode::Multiply.apply(src, dst));
+/// assert_eq!(blender.mode(), BlendMode::Multiply);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Blender {
+    mode: BlendMode,
+    blend: fn(F32x4Rgba, F32x4Rgba) -> F32x4Rgba,
+    blend_slice: Option<SliceKernel>,
+}
+
+/// A loop blending a whole slice with one mode.
+type SliceKernel = fn(&[F32x4Rgba], &mut [F32x4Rgba]);
+
+impl Blender {
+    /// Creates a blender for the mode implemented by `B`.
+    fn of<B: RgbaBlend<Channel = f32> + Default + Into<BlendMode>>() -> Self {
+        Self {
+            mode: B::default().into(),
+            blend: |src, dst| B::default().apply(src, dst),
+            blend_slice: Some(|src, dst| B::default().apply_slice(src, dst)),
+        }
+    }
+
+    /// Returns the mode this blender was created from.
+    #[must_use]
+    pub const fn mode(&self) -> BlendMode {
+        self.mode
+    }
+}
+
+impl RgbaBlend for Blender {
+    type Channel = f32;
+
+    #[inline]
+    fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        (self.blend)(src, dst)
+    }
+
+    fn apply_slice(&self, src: &[F32x4Rgba], dst: &mut [F32x4Rgba]) {
+        if let Some(blend_slice) = self.blend_slice {
+            blend_slice(src, dst);
+        } else {
+            assert_eq!(
+                src.len(),
+                dst.len(),
+                "src and dst slices must have the same length"
+            );
+            for (s, d) in src.iter().zip(dst) {
+                *d = (self.blend)(*s, *d);
+            }
+        }
+    }
+}
+
+impl From<BlendMode> for Blender {
+    fn from(mode: BlendMode) -> Self {
+        mode.to_blender()
+    }
+}
+
+macro_rules! modes {
+    ($($(#[$doc:meta])* $name:ident($src:ident, $dst:ident) => $blend:expr;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl RgbaBlend for $name {
+                type Channel = f32;
+
+                #[inline]
+                fn apply(&self, $src: F32x4Rgba, $dst: F32x4Rgba) -> F32x4Rgba {
+                    $blend
+                }
+            }
+
+            impl From<$name> for BlendMode {
+                fn from(_: $name) -> Self {
+                    Self::$name
+                }
+            }
+        )*
+
+        impl BlendMode {
+            /// Runs `f` with the [`modes`](crate::modes) type matching this mode, so it is
+            /// monomorphized per mode; [`Custom`](BlendMode::Custom) modes pass themselves.
+            ///
+            /// See the [`modes`](crate::modes) module for an example.
+            pub fn dispatch<F: Dispatch>(self, f: F) -> F::Output {
+                match self {
+                    $(Self::$name => f.call($name),)*
+                    Self::Custom(_) => f.call(self),
+                }
+            }
+
+            /// Resolves this mode to a [`Blender`], so its kernels are chosen once rather than
+            /// for every pixel.
+            #[must_use]
+            pub fn to_blender(self) -> Blender {
+                match self {
+                    $(Self::$name => Blender::of::<$name>(),)*
+                    Self::Custom(blend) => Blender {
+                        mode: self,
+                        blend,
+                        blend_slice: None,
+                    },
+                }
+            }
+        }
+    };
+}
+
+modes! {
+    /// [`BlendMode::Clear`].
+    Clear(src, dst) => Coefficients::CLEAR.blend(src, dst);
+    /// [`BlendMode::Source`].
+    Source(src, dst) => Coefficients::SRC.blend(src, dst);
+    /// [`BlendMode::Destination`].
+    Destination(src, dst) => Coefficients::DST.blend(src, dst);
+    /// [`BlendMode::SourceOver`].
+    SourceOver(src, dst) => Coefficients::SRC_OVER.blend(src, dst);
+    /// [`BlendMode::DestinationOver`].
+    DestinationOver(src, dst) => Coefficients::DST_OVER.blend(src, dst);
+    /// [`BlendMode::SourceIn`].
+    SourceIn(src, dst) => Coefficients::SRC_IN.blend(src, dst);
+    /// [`BlendMode::DestinationIn`].
+    DestinationIn(src, dst) => Coefficients::DST_IN.blend(src, dst);
+    /// [`BlendMode::SourceOut`].
+    SourceOut(src, dst) => Coefficients::SRC_OUT.blend(src, dst);
+    /// [`BlendMode::DestinationOut`].
+    DestinationOut(src, dst) => Coefficients::DST_OUT.blend(src, dst);
+    /// [`BlendMode::SourceAtop`].
+    SourceAtop(src, dst) => Coefficients::SRC_ATOP.blend(src, dst);
+    /// [`BlendMode::DestinationAtop`].
+    DestinationAtop(src, dst) => Coefficients::DST_ATOP.blend(src, dst);
+    /// [`BlendMode::Xor`].
+    Xor(src, dst) => Coefficients::XOR.blend(src, dst);
+    /// [`BlendMode::Plus`].
+    Plus(src, dst) => Coefficients::PLUS.blend(src, dst);
+    /// [`BlendMode::Multiply`].
+    Multiply(src, dst) => Separable::new(separable::multiply).blend(src, dst);
+    /// [`BlendMode::Screen`].
+    Screen(src, dst) => Separable::new(separable::screen).blend(src, dst);
+    /// [`BlendMode::Overlay`].
+    Overlay(src, dst) => Separable::new(separable::overlay).blend(src, dst);
+    /// [`BlendMode::HardLight`].
+    HardLight(src, dst) => Separable::new(separable::hard_light).blend(src, dst);
+    /// [`BlendMode::SoftLight`].
+    SoftLight(src, dst) => Separable::new(separable::soft_light).blend(src, dst);
+    /// [`BlendMode::ColorDodge`].
+    ColorDodge(src, dst) => Separable::new(separable::color_dodge).blend(src, dst);
+    /// [`BlendMode::ColorBurn`].
+    ColorBurn(src, dst) => Separable::new(separable::color_burn).blend(src, dst);
+    /// [`BlendMode::Difference`].
+    Difference(src, dst) => Separable::new(separable::difference).blend(src, dst);
+    /// [`BlendMode::Exclusion`].
+    Exclusion(src, dst) => Separable::new(separable::exclusion).blend(src, dst);
+    /// [`BlendMode::Hue`].
+    Hue(src, dst) => NonSeparable::new(nonseparable::hue).blend(src, dst);
+    /// [`BlendMode::Saturation`].
+    Saturation(src, dst) => NonSeparable::new(nonseparable::saturation).blend(src, dst);
+    /// [`BlendMode::Color`].
+    Color(src, dst) => NonSeparable::new(nonseparable::color).blend(src, dst);
+    /// [`BlendMode::Luminosity`].
+    Luminosity(src, dst) => NonSeparable::new(nonseparable::luminosity).blend(src, dst);
+    /// [`BlendMode::LinearBurn`].
+    LinearBurn(src, dst) => Separable::new(extended::linear_burn).blend(src, dst);
+    /// [`BlendMode::LinearDodge`].
+    LinearDodge(src, dst) => Separable::new(extended::linear_dodge).blend(src, dst);
+    /// [`BlendMode::VividLight`].
+    VividLight(src, dst) => Separable::new(extended::vivid_light).blend(src, dst);
+    /// [`BlendMode::LinearLight`].
+    LinearLight(src, dst) => Separable::new(extended::linear_light).blend(src, dst);
+    /// [`BlendMode::PinLight`].
+    PinLight(src, dst) => Separable::new(extended::pin_light).blend(src, dst);
+    /// [`BlendMode::HardMix`].
+    HardMix(src, dst) => Separable::new(extended::hard_mix).blend(src, dst);
+    /// [`BlendMode::Subtract`].
+    Subtract(src, dst) => Separable::new(extended::subtract).blend(src, dst);
+    /// [`BlendMode::Divide`].
+    Divide(src, dst) => Separable::new(extended::divide).blend(src, dst);
+    /// [`BlendMode::Modulate`].
+    Modulate(src, dst) => src.modulate(dst);
+    /// [`BlendMode::PlusLighter`].
+    PlusLighter(src, dst) => porter_duff::plus_lighter(src, dst);
+    /// [`BlendMode::PlusDarker`].
+    PlusDarker(src, dst) => porter_duff::plus_darker(src, dst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Blends fixed colors with the dispatched mode.
+    struct Blend([F32x4Rgba; 5], [F32x4Rgba; 5]);
+
+    impl Dispatch for Blend {
+        type Output = [F32x4Rgba; 5];
+
+        fn call<B: RgbaBlend<Channel = f32>>(self, blend: B) -> Self::Output {
+            let mut dst = self.1;
+            blend.apply_slice(&self.0, &mut dst);
+            dst
+        }
+    }
+
+    #[test]
+    fn dispatch_matches_blend_mode() {
+        let src: [F32x4Rgba; 5] = core::array::from_fn(|i| {
+            let t = f32::from(u8::try_from(i).unwrap()) * 0.25;
+            F32x4Rgba::new(t, 1.0 - t, 0.5, 1.0 - t)
+        });
+        let dst: [F32x4Rgba; 5] = core::array::from_fn(|i| src[4 - i]);
+        for value in 0..=u8::MAX {
+            let Ok(mode) = BlendMode::try_from(value) else {
+                continue;
+            };
+            let expected: [F32x4Rgba; 5] = core::array::from_fn(|i| mode.apply(src[i], dst[i]));
+            assert_eq!(mode.dispatch(Blend(src, dst)), expected, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn blender_matches_blend_mode() {
+        fn invert(src: F32x4Rgba, _: F32x4Rgba) -> F32x4Rgba {
+            F32x4Rgba::new(1.0 - src.r, 1.0 - src.g, 1.0 - src.b, src.a)
+        }
+        let src: [F32x4Rgba; 5] = core::array::from_fn(|i| {
+            let t = f32::from(u8::try_from(i).unwrap()) * 0.25;
+            F32x4Rgba::new(t, 1.0 - t, 0.5, 1.0 - t)
+        });
+        let dst: [F32x4Rgba; 5] = core::array::from_fn(|i| src[4 - i]);
+        let modes = (0..=u8::MAX)
+            .filter_map(|value| BlendMode::try_from(value).ok())
+            .chain([BlendMode::Custom(invert)]);
+        for mode in modes {
+            let blender = Blender::from(mode);
+            assert_eq!(blender.mode(), mode);
+            let mut expected = dst;
+            mode.apply_slice(&src, &mut expected);
+            let mut batch = dst;
+            blender.apply_slice(&src, &mut batch);
+            assert_eq!(batch, expected, "{mode:?}");
+            assert_eq!(blender.apply(src[1], dst[1]), expected[1], "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn markers_convert_to_blend_mode() {
+        assert_eq!(BlendMode::from(SourceOver), BlendMode::SourceOver);
+        assert_eq!(BlendMode::from(PlusDarker), BlendMode::PlusDarker);
+    }
+}
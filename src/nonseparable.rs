@@ -0,0 +1,229 @@
+//! Non-separable blend modes.
+//!
+//! This module provides a [`BlendMode`] implementation for the non-separable blend modes of the
+//! [W3C Compositing and Blending] specification: [`Hue`](crate::BlendMode::Hue),
+//! [`Saturation`](crate::BlendMode::Saturation), [`Color`](crate::BlendMode::Color) and
+//! [`Luminosity`](crate::BlendMode::Luminosity). Unlike [separable] modes, their blend functions
+//! mix whole RGB triples, combining the hue, saturation and luminosity of the source and
+//! destination colors with the [`lum`], [`set_lum`], [`sat`] and [`set_sat`] helpers. Partially transparent pixels are composited as for separable modes.
+//!
+//! [`BlendMode`]: crate::BlendMode
+//! [W3C Compositing and Blending]: https://www.w3.org/TR/compositing-1/#blendingnonseparable
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, RgbaBlend, nonseparable, rgba::F32x4Rgba};
+//!
+//! // Recolor a gray destination with a red source, keeping its luminosity.
+//! let gray = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+//! let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+//! let out = BlendMode::Color.apply(red, gray);
+//! let lum = nonseparable::lum([out.r, out.g, out.b]);
+//! assert!((lum - 0.5).abs() < 1e-6);
+//! assert!(out.r > out.g && out.g == out.b);
+//! ```
+
+use crate::{
+    RgbaBlend,
+    rgba::{F32x4Rgba, Rgba},
+    separable,
+};
+
+/// A [`BlendMode`][] that mixes RGB triples with a blend function, and composites the result
+/// with source-over.
+///
+/// [`BlendMode`]: crate::BlendMode
+#[derive(Debug, Clone, Copy)]
+pub struct NonSeparable<F: Fn([f32; 3], [f32; 3]) -> [f32; 3]> {
+    /// Computes a mixed color given the source and destination colors.
+    blend: F,
+}
+
+impl<F: Fn([f32; 3], [f32; 3]) -> [f32; 3]> NonSeparable<F> {
+    /// Creates a new `NonSeparable` with the given blend function of `(src, dst)` colors.
+    pub const fn new(blend: F) -> Self {
+        Self { blend }
+    }
+
+    /// Returns the result of mixing `src` with `dst`, composited with source-over.
+    #[must_use]
+    pub fn blend(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        let mixed = (self.blend)([src.r, src.g, src.b], [dst.r, dst.g, dst.b]);
+        separable::composite_mixed(src, dst, mixed)
+    }
+}
+
+impl NonSeparable<fn([f32; 3], [f32; 3]) -> [f32; 3]> {
+    /// Hue of the source with the saturation and luminosity of the destination.
+    pub const HUE: Self = Self::new(hue);
+
+    /// Saturation of the source with the hue and luminosity of the destination.
+    pub const SATURATION: Self = Self::new(saturation);
+
+    /// Hue and saturation of the source with the luminosity of the destination.
+    pub const COLOR: Self = Self::new(color);
+
+    /// Luminosity of the source with the hue and saturation of the destination.
+    pub const LUMINOSITY: Self = Self::new(luminosity);
+}
+
+impl<F: Fn([f32; 3], [f32; 3]) -> [f32; 3]> RgbaBlend for NonSeparable<F> {
+    type Channel = f32;
+
+    fn apply(&self, src: Rgba<Self::Channel>, dst: Rgba<Self::Channel>) -> Rgba<Self::Channel> {
+        self.blend(src, dst)
+    }
+}
+
+/// Returns the luminosity of an RGB color (`Lum` in the specification).
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn lum([r, g, b]: [f32; 3]) -> f32 {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+/// Brings the channels of `color` within `[0.0, 1.0]` while preserving its luminosity
+/// (`ClipColor` in the specification).
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn clip_color(color: [f32; 3]) -> [f32; 3] {
+    let l = lum(color);
+    let min = color[0].min(color[1]).min(color[2]);
+    let max = color[0].max(color[1]).max(color[2]);
+    let mut out = color;
+    if min < 0.0 {
+        out = out.map(|c| l + (c - l) * l / (l - min));
+    }
+    if max > 1.0 {
+        out = out.map(|c| l + (c - l) * (1.0 - l) / (max - l));
+    }
+    out
+}
+
+/// Returns `color` shifted to luminosity `l`, clipped to `[0.0, 1.0]` (`SetLum` in the
+/// specification).
+#[must_use]
+pub fn set_lum(color: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(color);
+    clip_color(color.map(|c| c + d))
+}
+
+/// Returns the saturation of an RGB color, the difference of its largest and smallest channels
+/// (`Sat` in the specification).
+#[must_use]
+pub fn sat(color: [f32; 3]) -> f32 {
+    color[0].max(color[1]).max(color[2]) - color[0].min(color[1]).min(color[2])
+}
+
+/// Returns `color` with its saturation set to `s`, keeping its hue (`SetSat` in the
+/// specification).
+///
+/// Gray colors, which have no hue, become black.
+#[must_use]
+pub fn set_sat(color: [f32; 3], s: f32) -> [f32; 3] {
+    let min = color[0].min(color[1]).min(color[2]);
+    let max = color[0].max(color[1]).max(color[2]);
+    if max > min {
+        color.map(|c| (c - min) * s / (max - min))
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// Hue of `src` with the saturation and luminosity of `dst`.
+#[must_use]
+pub fn hue(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    set_lum(set_sat(src, sat(dst)), lum(dst))
+}
+
+/// Saturation of `src` with the hue and luminosity of `dst`.
+#[must_use]
+pub fn saturation(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    set_lum(set_sat(dst, sat(src)), lum(dst))
+}
+
+/// Hue and saturation of `src` with the luminosity of `dst`.
+#[must_use]
+pub fn color(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    set_lum(src, lum(dst))
+}
+
+/// Luminosity of `src` with the hue and saturation of `dst`.
+#[must_use]
+pub fn luminosity(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    set_lum(dst, lum(src))
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    const RED: [f32; 3] = [1.0, 0.0, 0.0];
+    const GRAY: [f32; 3] = [0.5, 0.5, 0.5];
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for (x, y) in a.into_iter().zip(b) {
+            assert!((x - y).abs() < 1e-6, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn lum_and_sat() {
+        assert_eq!(lum(RED), 0.3);
+        assert_eq!(lum([1.0; 3]), 1.0);
+        assert_eq!(sat([0.2, 0.9, 0.5]), 0.7);
+        assert_eq!(sat(GRAY), 0.0);
+    }
+
+    #[test]
+    fn set_sat_keeps_channel_order() {
+        assert_close(set_sat([0.2, 0.6, 1.0], 0.5), [0.0, 0.25, 0.5]);
+        assert_eq!(set_sat(GRAY, 1.0), [0.0; 3]);
+    }
+
+    #[test]
+    fn set_lum_clips_into_range() {
+        let out = set_lum(RED, 0.9);
+        assert!(out.iter().all(|c| (0.0..=1.0).contains(c)), "{out:?}");
+        assert!((lum(out) - 0.9).abs() < 1e-6);
+        assert_close(set_lum(GRAY, 0.25), [0.25; 3]);
+    }
+
+    #[test]
+    fn color_and_luminosity_are_inverses() {
+        let src = [0.8, 0.3, 0.1];
+        let dst = [0.2, 0.4, 0.6];
+        assert_close(color(src, dst), luminosity(dst, src));
+        assert!((lum(color(src, dst)) - lum(dst)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hue_and_saturation_of_gray() {
+        // A gray destination has no saturation, so any hue stays gray.
+        assert_close(hue(RED, GRAY), GRAY);
+        // A gray source removes the saturation of the destination.
+        let out = saturation(GRAY, [0.8, 0.3, 0.1]);
+        assert!(sat(out) < 1e-6);
+    }
+
+    #[test]
+    fn blend_modes_composite_with_source_over() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let dst = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+        let mixed = color([1.0, 0.0, 0.0], [0.5, 0.5, 0.5]);
+        let out = BlendMode::Color.apply(src, dst);
+        assert_eq!(out.a, 1.0);
+        assert!((out.r - f32::midpoint(mixed[0], 0.5)).abs() < 1e-6);
+        for mode in [
+            BlendMode::Hue,
+            BlendMode::Saturation,
+            BlendMode::Color,
+            BlendMode::Luminosity,
+        ] {
+            assert_eq!(mode.apply(src, F32x4Rgba::TRANSPARENT), src);
+        }
+    }
+}
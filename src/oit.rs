@@ -0,0 +1,280 @@
+//! Weighted blended order-independent transparency (OIT).
+//!
+//! A CPU reference implementation of [McGuire and Bavoil (2013)][paper], for validating GPU
+//! implementations. Transparent fragments are [accumulated](OitPixel::accumulate) in any order
+//! into a per-pixel [`OitPixel`], which is then [resolved](OitPixel::resolve) over the opaque
+//! scene behind it.
+//!
+//! Input fragments are straight-alpha [`F32x4Rgba`] colors; they are premultiplied and weighted
+//! during accumulation, as in the paper.
+//!
+//! [paper]: https://jcgt.org/published/0002/02/09/
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{oit::{OitPixel, Weight}, rgba::F32x4Rgba};
+//!
+//! let red = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+//! let blue = F32x4Rgba::new(0.0, 0.0, 1.0, 0.5);
+//!
+//! let mut a = OitPixel::CLEAR;
+//! a.accumulate(red, Weight::Constant.weight(red.a, 1.0));
+//! a.accumulate(blue, Weight::Constant.weight(blue.a, 2.0));
+//!
+//! let mut b = OitPixel::CLEAR;
+//! b.accumulate(blue, Weight::Constant.weight(blue.a, 2.0));
+//! b.accumulate(red, Weight::Constant.weight(red.a, 1.0));
+//!
+//! // The result does not depend on the order in which fragments were accumulated.
+//! assert_eq!(a.resolve(F32x4Rgba::BLACK), b.resolve(F32x4Rgba::BLACK));
+//! ```
+
+use crate::{rgba::F32x4Rgba, vec4::F32x4};
+
+/// Lower bound on the accumulated alpha during resolve, avoiding division by zero.
+const MIN_ACCUM_ALPHA: f32 = 1e-5;
+
+/// Depth weighting functions from the paper.
+///
+/// Each function returns `alpha * w(z)`, where `z` is the view-space distance of the fragment
+/// from the camera (positive), except for [`Depth`](Weight::Depth), where `z` is the
+/// window-space depth in `[0.0, 1.0]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Weight {
+    /// `w(z) = 1`: fragments are averaged by alpha only.
+    #[default]
+    Constant,
+
+    /// Equation 7: `clamp(10 / (1e-5 + (|z| / 5)^2 + (|z| / 200)^6), 1e-2, 3e3)`.
+    Distance7,
+
+    /// Equation 8: `clamp(10 / (1e-5 + (|z| / 10)^3 + (|z| / 200)^6), 1e-2, 3e3)`.
+    Distance8,
+
+    /// Equation 9: `clamp(0.03 / (1e-5 + (|z| / 200)^4), 1e-2, 3e3)`.
+    Distance9,
+
+    /// Equation 10: `max(1e-2, 3e3 * (1 - z)^3)`.
+    Depth,
+}
+
+impl Weight {
+    /// Returns the accumulation weight of a fragment with `alpha` at depth `z`.
+    #[must_use]
+    pub fn weight(self, alpha: f32, z: f32) -> f32 {
+        let z = z.abs();
+        let w = match self {
+            Self::Constant => 1.0,
+            Self::Distance7 => 10.0 / (1e-5 + pow(z / 5.0, 2) + pow(z / 200.0, 6)),
+            Self::Distance8 => 10.0 / (1e-5 + pow(z / 10.0, 3) + pow(z / 200.0, 6)),
+            Self::Distance9 => 0.03 / (1e-5 + pow(z / 200.0, 4)),
+            Self::Depth => 3e3 * pow(1.0 - z, 3),
+        };
+        let w = match self {
+            Self::Constant => w,
+            Self::Depth => w.max(1e-2),
+            _ => w.clamp(1e-2, 3e3),
+        };
+        alpha * w
+    }
+}
+
+/// Raises `base` to a small integer power, without requiring `std` or `libm`.
+fn pow(base: f32, exp: u32) -> f32 {
+    (0..exp).fold(1.0, |acc, _| acc * base)
+}
+
+/// Per-pixel accumulation and revealage targets of weighted blended OIT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OitPixel {
+    /// Sum of weighted, premultiplied fragment colors (`rgb`) and weighted alphas (`a`).
+    pub accum: F32x4Rgba,
+
+    /// Product of `1 - alpha` over all fragments; the fraction of the background still visible.
+    pub revealage: f32,
+}
+
+impl Default for OitPixel {
+    fn default() -> Self {
+        Self::CLEAR
+    }
+}
+
+impl OitPixel {
+    /// Cleared targets, as at the start of the accumulation pass.
+    pub const CLEAR: Self = Self {
+        accum: F32x4Rgba::TRANSPARENT,
+        revealage: 1.0,
+    };
+
+    /// Accumulates a straight-alpha fragment `color` with the given `weight`.
+    ///
+    /// `weight` is usually computed with [`Weight::weight`], and already includes the fragment
+    /// alpha.
+    pub fn accumulate(&mut self, color: F32x4Rgba, weight: f32) {
+        let premultiplied = F32x4::new(color.r, color.g, color.b, 1.0) * color.a;
+        self.accum = (F32x4::from(self.accum) + premultiplied * weight).into_rgba();
+        self.revealage *= 1.0 - color.a;
+    }
+
+    /// Resolves the accumulated fragments over a straight-alpha `background`.
+    ///
+    /// If no fragments were accumulated, `background` is returned unchanged.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn resolve(self, background: F32x4Rgba) -> F32x4Rgba {
+        if self.revealage == 1.0 {
+            return background;
+        }
+        let average = F32x4::from(self.accum) * (1.0 / self.accum.a.max(MIN_ACCUM_ALPHA));
+        let coverage = 1.0 - self.revealage;
+        let color = F32x4::new(average.w(), average.x(), average.y(), 1.0) * coverage;
+        (color + F32x4::from(background.premultiply()) * self.revealage)
+            .into_rgba()
+            .unpremultiply()
+    }
+}
+
+/// Accumulates one transparent layer of straight-alpha `fragments` at per-pixel depths `z`.
+///
+/// ## Panics
+///
+/// If `targets`, `fragments` and `z` do not all have the same length.
+pub fn accumulate_slice(
+    targets: &mut [OitPixel],
+    fragments: &[F32x4Rgba],
+    z: &[f32],
+    weight: Weight,
+) {
+    assert_eq!(
+        targets.len(),
+        fragments.len(),
+        "targets and fragments must have the same length"
+    );
+    assert_eq!(targets.len(), z.len(), "z must have one depth per pixel");
+    for ((target, &color), &z) in targets.iter_mut().zip(fragments).zip(z) {
+        target.accumulate(color, weight.weight(color.a, z));
+    }
+}
+
+/// Resolves `targets` over the opaque scene in `dst`, in place.
+///
+/// ## Panics
+///
+/// If `targets` and `dst` do not have the same length.
+pub fn resolve_slice(targets: &[OitPixel], dst: &mut [F32x4Rgba]) {
+    assert_eq!(
+        targets.len(),
+        dst.len(),
+        "targets and dst must have the same length"
+    );
+    for (target, d) in targets.iter().zip(dst) {
+        *d = target.resolve(*d);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    const RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+    const BLUE: F32x4Rgba = F32x4Rgba::new(0.0, 0.0, 1.0, 0.25);
+
+    fn assert_approx_eq(a: F32x4Rgba, b: F32x4Rgba) {
+        let close = a
+            .as_ref()
+            .iter()
+            .zip(b.as_ref())
+            .all(|(p, q)| (p - q).abs() < 1e-5);
+        assert!(close, "{a} != {b}");
+    }
+
+    #[test]
+    fn resolve_without_fragments_keeps_background() {
+        let bg = F32x4Rgba::new(0.1, 0.2, 0.3, 0.4);
+        assert_eq!(OitPixel::CLEAR.resolve(bg), bg);
+        assert_eq!(OitPixel::default(), OitPixel::CLEAR);
+    }
+
+    #[test]
+    fn single_fragment_matches_over() {
+        let mut pixel = OitPixel::CLEAR;
+        pixel.accumulate(RED, Weight::Distance7.weight(RED.a, 3.0));
+        assert_approx_eq(
+            pixel.resolve(F32x4Rgba::BLACK),
+            F32x4Rgba::new(0.5, 0.0, 0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn single_fragment_over_transparent_keeps_color() {
+        let mut pixel = OitPixel::CLEAR;
+        pixel.accumulate(RED, 1.0);
+        assert_approx_eq(pixel.resolve(F32x4Rgba::TRANSPARENT), RED);
+    }
+
+    #[test]
+    fn opaque_fragment_hides_background() {
+        let mut pixel = OitPixel::CLEAR;
+        pixel.accumulate(F32x4Rgba::WHITE, 1.0);
+        assert_eq!(pixel.revealage, 0.0);
+        assert_approx_eq(pixel.resolve(F32x4Rgba::BLACK), F32x4Rgba::WHITE);
+    }
+
+    #[test]
+    fn accumulation_is_order_independent() {
+        let (mut a, mut b) = (OitPixel::CLEAR, OitPixel::CLEAR);
+        a.accumulate(RED, 2.0);
+        a.accumulate(BLUE, 0.5);
+        b.accumulate(BLUE, 0.5);
+        b.accumulate(RED, 2.0);
+        assert_eq!(a, b);
+        assert_eq!(a.revealage, 0.375);
+    }
+
+    #[test]
+    fn nearer_fragments_dominate() {
+        let mut pixel = OitPixel::CLEAR;
+        for weight in [Weight::Distance7, Weight::Distance8, Weight::Distance9] {
+            assert!(
+                weight.weight(1.0, 1.0) > weight.weight(1.0, 100.0),
+                "{weight:?}"
+            );
+        }
+        pixel.accumulate(RED, Weight::Depth.weight(RED.a, 0.1));
+        pixel.accumulate(BLUE, Weight::Depth.weight(BLUE.a, 0.9));
+        let out = pixel.resolve(F32x4Rgba::BLACK);
+        assert!(out.r > out.b);
+    }
+
+    #[test]
+    fn weights_are_clamped() {
+        assert_eq!(Weight::Distance7.weight(1.0, 0.0), 3e3);
+        assert_eq!(Weight::Distance9.weight(1.0, 1e6), 1e-2);
+        assert_eq!(Weight::Depth.weight(0.5, 1.0), 0.5 * 1e-2);
+        assert_eq!(Weight::Constant.weight(0.5, 123.0), 0.5);
+    }
+
+    #[test]
+    fn slices_match_pixels() {
+        let mut targets = [OitPixel::CLEAR; 2];
+        accumulate_slice(&mut targets, &[RED, BLUE], &[1.0, 2.0], Weight::Distance8);
+        accumulate_slice(&mut targets, &[BLUE, RED], &[3.0, 4.0], Weight::Distance8);
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        resolve_slice(&targets, &mut dst);
+
+        let mut expected = OitPixel::CLEAR;
+        expected.accumulate(RED, Weight::Distance8.weight(RED.a, 1.0));
+        expected.accumulate(BLUE, Weight::Distance8.weight(BLUE.a, 3.0));
+        assert_eq!(dst[0], expected.resolve(F32x4Rgba::BLACK));
+    }
+
+    #[test]
+    #[should_panic(expected = "one depth per pixel")]
+    fn accumulate_slice_panics_on_depth_mismatch() {
+        let mut targets = [OitPixel::CLEAR; 2];
+        accumulate_slice(&mut targets, &[RED, BLUE], &[1.0], Weight::Constant);
+    }
+}
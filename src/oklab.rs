@@ -0,0 +1,270 @@
+//! The `OkLab` and `OkLch` perceptual color spaces.
+//!
+//! [`OkLab`](https://bottosson.github.io/posts/oklab/) is a perceptually uniform space: equal
+//! steps in it look like equal steps of lightness and color. Interpolating there, with
+//! [`mix_oklab`], avoids the muddy, darkened midpoints of interpolating sRGB-encoded channels
+//! directly. [`OkLch`] is the same space in polar form, with a hue angle and a chroma.
+//!
+//! Conversions take and return sRGB-encoded [`F32x4Rgba`] colors; alpha passes through
+//! unchanged.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{oklab, rgba::F32x4Rgba};
+//!
+//! let mid = oklab::mix_oklab(F32x4Rgba::BLACK, F32x4Rgba::WHITE, 0.5);
+//! // Perceptually halfway, which is darker than the sRGB midpoint of `0.5`.
+//! assert!((mid.r - 0.3885).abs() < 1e-3);
+//! ```
+
+use crate::{color_space::mul3, linear, math, rgba::F32x4Rgba};
+
+/// A color in `OkLab`: perceptual lightness `l` in `[0.0, 1.0]`, and the green–red `a` and
+/// blue–yellow `b` axes, roughly in `[-0.4, 0.4]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OkLab {
+    /// Perceptual lightness.
+    pub l: f32,
+
+    /// Green (negative) to red (positive).
+    pub a: f32,
+
+    /// Blue (negative) to yellow (positive).
+    pub b: f32,
+
+    /// Straight alpha.
+    pub alpha: f32,
+}
+
+/// A color in `OkLch`: [`OkLab`] lightness `l`, chroma `c` and hue `h` in degrees, in
+/// `[0.0, 360.0)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OkLch {
+    /// Perceptual lightness.
+    pub l: f32,
+
+    /// Chroma, the distance from the neutral axis.
+    pub c: f32,
+
+    /// Hue angle in degrees.
+    pub h: f32,
+
+    /// Straight alpha.
+    pub alpha: f32,
+}
+
+const LINEAR_SRGB_TO_LMS: [[f32; 3]; 3] = [
+    [0.412_221_46, 0.536_332_55, 0.051_445_995],
+    [0.211_903_5, 0.680_699_5, 0.107_396_96],
+    [0.088_302_46, 0.281_718_85, 0.629_978_7],
+];
+
+const LMS_TO_OKLAB: [[f32; 3]; 3] = [
+    [0.210_454_26, 0.793_617_8, -0.004_072_047],
+    [1.977_998_5, -2.428_592_2, 0.450_593_7],
+    [0.025_904_037, 0.782_771_77, -0.808_675_77],
+];
+
+const OKLAB_TO_LMS: [[f32; 3]; 3] = [
+    [1.0, 0.396_337_78, 0.215_803_76],
+    [1.0, -0.105_561_346, -0.063_854_17],
+    [1.0, -0.089_484_18, -1.291_485_5],
+];
+
+const LMS_TO_LINEAR_SRGB: [[f32; 3]; 3] = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+impl OkLab {
+    /// Converts an sRGB-encoded color.
+    #[must_use]
+    pub fn from_rgba(color: F32x4Rgba) -> Self {
+        let linear = [color.r, color.g, color.b].map(linear::srgb_to_linear);
+        let lms = mul3(LINEAR_SRGB_TO_LMS, linear).map(math::cbrt);
+        let [l, a, b] = mul3(LMS_TO_OKLAB, lms);
+        Self {
+            l,
+            a,
+            b,
+            alpha: color.a,
+        }
+    }
+
+    /// Converts to an sRGB-encoded color.
+    ///
+    /// Colors outside the sRGB gamut are not clipped: their channels fall outside
+    /// `[0.0, 1.0]`.
+    #[must_use]
+    pub fn to_rgba(self) -> F32x4Rgba {
+        let lms = mul3(OKLAB_TO_LMS, [self.l, self.a, self.b]).map(|c| c * c * c);
+        let [r, g, b] = mul3(LMS_TO_LINEAR_SRGB, lms).map(linear::linear_to_srgb);
+        F32x4Rgba::new(r, g, b, self.alpha)
+    }
+
+    /// Converts to polar form.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)]
+    pub fn to_lch(self) -> OkLch {
+        let h = math::atan2(self.b, self.a).to_degrees();
+        OkLch {
+            l: self.l,
+            c: math::sqrt(self.a * self.a + self.b * self.b),
+            h: if h < 0.0 { h + 360.0 } else { h },
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl OkLch {
+    /// Converts an sRGB-encoded color.
+    #[must_use]
+    pub fn from_rgba(color: F32x4Rgba) -> Self {
+        OkLab::from_rgba(color).to_lch()
+    }
+
+    /// Converts to an sRGB-encoded color; see [`OkLab::to_rgba`].
+    #[must_use]
+    pub fn to_rgba(self) -> F32x4Rgba {
+        self.to_lab().to_rgba()
+    }
+
+    /// Converts to rectangular form.
+    #[must_use]
+    pub fn to_lab(self) -> OkLab {
+        let (sin, cos) = math::sin_cos(self.h.to_radians());
+        OkLab {
+            l: self.l,
+            a: self.c * cos,
+            b: self.c * sin,
+            alpha: self.alpha,
+        }
+    }
+}
+
+impl From<F32x4Rgba> for OkLab {
+    fn from(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<OkLab> for F32x4Rgba {
+    fn from(color: OkLab) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl From<F32x4Rgba> for OkLch {
+    fn from(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<OkLch> for F32x4Rgba {
+    fn from(color: OkLch) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl From<OkLab> for OkLch {
+    fn from(color: OkLab) -> Self {
+        color.to_lch()
+    }
+}
+
+impl From<OkLch> for OkLab {
+    fn from(color: OkLch) -> Self {
+        color.to_lab()
+    }
+}
+
+/// Interpolates from `src` to `dst` by `t` in [`OkLab`], as CSS `color-mix(in oklab, ...)`.
+///
+/// `t = 0.0` returns `src` and `t = 1.0` returns `dst`. The color channels are interpolated
+/// premultiplied by alpha, so a transparent endpoint fades out without tinting the other
+/// color. The result is clipped to the sRGB gamut.
+#[must_use]
+pub fn mix_oklab(src: F32x4Rgba, dst: F32x4Rgba, t: f32) -> F32x4Rgba {
+    let (s, d) = (OkLab::from_rgba(src), OkLab::from_rgba(dst));
+    let alpha = math::lerp(s.alpha, d.alpha, t);
+    if alpha <= 0.0 {
+        return F32x4Rgba::TRANSPARENT;
+    }
+    let mix = |s: f32, d: f32| math::lerp(s * src.a, d * dst.a, t) / alpha;
+    let out = OkLab {
+        l: mix(s.l, d.l),
+        a: mix(s.a, d.a),
+        b: mix(s.b, d.b),
+        alpha,
+    }
+    .to_rgba();
+    F32x4Rgba::new(
+        math::clamp01(out.r),
+        math::clamp01(out.g),
+        math::clamp01(out.b),
+        out.a,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    const RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+
+    #[test]
+    fn reference_values() {
+        let white = OkLab::from_rgba(F32x4Rgba::WHITE);
+        assert!((white.l - 1.0).abs() < 1e-4);
+        assert!(white.a.abs() < 1e-4 && white.b.abs() < 1e-4);
+
+        let red = OkLab::from_rgba(RED);
+        assert!((red.l - 0.627_955).abs() < 1e-4);
+        assert!((red.a - 0.224_863).abs() < 1e-4);
+        assert!((red.b - 0.125_846).abs() < 1e-4);
+
+        let red = red.to_lch();
+        assert!((red.c - 0.257_683).abs() < 1e-4);
+        assert!((red.h - 29.234).abs() < 1e-2);
+    }
+
+    #[test]
+    fn round_trips() {
+        for color in [
+            RED,
+            F32x4Rgba::new(0.2, 0.7, 0.4, 0.5),
+            F32x4Rgba::new(0.0, 0.0, 1.0, 0.0),
+        ] {
+            assert_close(OkLab::from(color).into(), color, 1e-4);
+            assert_close(OkLch::from(color).into(), color, 1e-4);
+        }
+    }
+
+    #[test]
+    fn lch_hue_is_positive() {
+        let blue = OkLch::from_rgba(F32x4Rgba::new(0.0, 0.0, 1.0, 1.0));
+        assert!((blue.h - 264.052).abs() < 1e-2);
+    }
+
+    #[test]
+    fn mix_endpoints() {
+        let blue = F32x4Rgba::new(0.0, 0.0, 1.0, 0.5);
+        assert_close(mix_oklab(RED, blue, 0.0), RED, 1e-4);
+        assert_close(mix_oklab(RED, blue, 1.0), blue, 1e-4);
+        assert_eq!(mix_oklab(RED, blue, 0.5).a, 0.75);
+    }
+
+    #[test]
+    fn mix_with_transparent_keeps_color() {
+        let out = mix_oklab(RED, F32x4Rgba::TRANSPARENT, 0.5);
+        assert_close(out, F32x4Rgba::new(1.0, 0.0, 0.0, 0.5), 1e-4);
+        assert_eq!(
+            mix_oklab(F32x4Rgba::TRANSPARENT, F32x4Rgba::TRANSPARENT, 0.5),
+            F32x4Rgba::TRANSPARENT
+        );
+    }
+}
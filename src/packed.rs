@@ -0,0 +1,489 @@
+//! Blending framebuffers of packed `u32` and `u16` pixels.
+//!
+//! Many framebuffers hold one `u32` word per pixel rather than four bytes. [`PackedOrder`] names
+//! the common layouts of such a word, and [`blend_packed`] blends `&[u32]` buffers in place,
+//! unpacking and repacking each pixel in registers.
+//!
+//! Small embedded displays commonly use 16-bit [`Rgb565`] framebuffers, with [`Rgba4444`] for
+//! sprites that need alpha. [`blend_rgb565`] blends colors onto an opaque RGB565 framebuffer.
+//!
+//! HDR swapchains commonly use 10 bits per color channel and 2 bits of alpha, stored as
+//! [`Rgb10A2`] or [`Bgr10A2`]; these convert to and from [`F32x4Rgba`] without losing precision
+//! to 8-bit intermediates.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{packed::{self, PackedOrder}, porter_duff::U8PorterDuff};
+//!
+//! let sprite = [0x80FF_0000]; // Half-transparent red.
+//! let mut framebuffer = [0xFF00_00FF]; // Opaque blue.
+//! packed::blend_packed(&U8PorterDuff::SRC_OVER, PackedOrder::Argb, &sprite, &mut framebuffer);
+//! assert_eq!(framebuffer, [0xBF80_007F]);
+//! ```
+
+use crate::{
+    RgbaBlend,
+    format::Pixel,
+    math,
+    rgba::{F32x4Rgba, U8x4Rgba},
+};
+
+/// The layout of an 8-bit-per-channel color packed into a `u32`, most significant byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackedOrder {
+    /// `0xAARRGGBB`; see [`U8x4Rgba::from_argb_u32`].
+    Argb,
+
+    /// `0xRRGGBBAA`; see [`U8x4Rgba::from_rgba_u32`].
+    Rgba,
+
+    /// `0xAABBGGRR`; see [`U8x4Rgba::from_abgr_u32`].
+    Abgr,
+}
+
+impl PackedOrder {
+    /// Unpacks a word in this layout.
+    #[must_use]
+    pub const fn unpack(self, word: u32) -> U8x4Rgba {
+        match self {
+            Self::Argb => U8x4Rgba::from_argb_u32(word),
+            Self::Rgba => U8x4Rgba::from_rgba_u32(word),
+            Self::Abgr => U8x4Rgba::from_abgr_u32(word),
+        }
+    }
+
+    /// Packs a color into a word in this layout.
+    #[must_use]
+    pub const fn pack(self, color: U8x4Rgba) -> u32 {
+        match self {
+            Self::Argb => color.to_argb_u32(),
+            Self::Rgba => color.to_rgba_u32(),
+            Self::Abgr => color.to_abgr_u32(),
+        }
+    }
+}
+
+/// Blends packed `src` onto packed `dst` in place, both in the layout `order`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_packed<B>(blend: &B, order: PackedOrder, src: &[u32], dst: &mut [u32])
+where
+    B: RgbaBlend<Channel = u8> + ?Sized,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = order.pack(blend.apply(order.unpack(*s), order.unpack(*d)));
+    }
+}
+
+/// Widens a `bits`-bit channel to 8 bits, so that its maximum maps to `255`, rounding to nearest.
+#[allow(clippy::cast_possible_truncation)]
+const fn widen(value: u16, bits: u32) -> u8 {
+    let max = (1 << bits) - 1;
+    ((value as u32 * 255 + max / 2) / max) as u8
+}
+
+/// Narrows an 8-bit channel to `bits` bits, rounding to nearest.
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow(value: u8, bits: u32) -> u16 {
+    let max = (1 << bits) - 1;
+    ((value as u32 * max + 127) / 255) as u16
+}
+
+/// An opaque color packed into a `u16` as `0bRRRRRGGGGGGBBBBB`: 5 bits of red, 6 of green and
+/// 5 of blue.
+///
+/// Conversions round to nearest; alpha is discarded, and unpacked colors are opaque.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Rgb565(pub u16);
+
+impl Rgb565 {
+    /// Packs a color, discarding alpha.
+    #[must_use]
+    pub const fn from_rgba(color: U8x4Rgba) -> Self {
+        Self(narrow(color.r, 5) << 11 | narrow(color.g, 6) << 5 | narrow(color.b, 5))
+    }
+
+    /// Unpacks to an opaque color.
+    #[must_use]
+    pub const fn to_rgba(self) -> U8x4Rgba {
+        U8x4Rgba::new(
+            widen(self.0 >> 11, 5),
+            widen(self.0 >> 5 & 0x3F, 6),
+            widen(self.0 & 0x1F, 5),
+            u8::MAX,
+        )
+    }
+}
+
+/// A color packed into a `u16` as `0xRGBA`, with 4 bits per channel.
+///
+/// Conversions round to nearest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Rgba4444(pub u16);
+
+impl Rgba4444 {
+    /// Packs a color.
+    #[must_use]
+    pub const fn from_rgba(color: U8x4Rgba) -> Self {
+        Self(
+            narrow(color.r, 4) << 12
+                | narrow(color.g, 4) << 8
+                | narrow(color.b, 4) << 4
+                | narrow(color.a, 4),
+        )
+    }
+
+    /// Unpacks the color.
+    #[must_use]
+    pub const fn to_rgba(self) -> U8x4Rgba {
+        U8x4Rgba::new(
+            widen(self.0 >> 12, 4),
+            widen(self.0 >> 8 & 0xF, 4),
+            widen(self.0 >> 4 & 0xF, 4),
+            widen(self.0 & 0xF, 4),
+        )
+    }
+}
+
+impl From<U8x4Rgba> for Rgb565 {
+    fn from(color: U8x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<Rgb565> for U8x4Rgba {
+    fn from(color: Rgb565) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl From<U8x4Rgba> for Rgba4444 {
+    fn from(color: U8x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<Rgba4444> for U8x4Rgba {
+    fn from(color: Rgba4444) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl Pixel for Rgb565 {
+    fn to_rgba(self) -> F32x4Rgba {
+        Self::to_rgba(self).into()
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color.into())
+    }
+}
+
+impl Pixel for Rgba4444 {
+    fn to_rgba(self) -> F32x4Rgba {
+        Self::to_rgba(self).into()
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color.into())
+    }
+}
+
+// SAFETY: both are `#[repr(transparent)]` over `u16`, and every bit pattern is a valid color.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Rgb565 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Rgb565 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Rgba4444 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Rgba4444 {}
+
+/// Converts an unsigned-normalized field of `bits` bits to `[0.0, 1.0]`.
+#[allow(clippy::cast_precision_loss)]
+fn unorm_to_f32(word: u32, shift: u32, bits: u32) -> f32 {
+    let max = (1 << bits) - 1;
+    (word >> shift & max) as f32 / max as f32
+}
+
+/// Converts `value` to an unsigned-normalized field of `bits` bits, clamping and rounding to
+/// nearest, and shifts it into place.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn f32_to_unorm(value: f32, shift: u32, bits: u32) -> u32 {
+    let max = (1 << bits) - 1;
+    (math::round(value.clamp(0.0, 1.0) * max as f32) as u32) << shift
+}
+
+/// A color packed into a `u32` with 10 bits each of red (lowest bits), green and blue, and 2
+/// bits of alpha (highest bits).
+///
+/// This is `DXGI_FORMAT_R10G10B10A2_UNORM` and Vulkan's `A2B10G10R10_UNORM_PACK32`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Rgb10A2(pub u32);
+
+impl Rgb10A2 {
+    /// Packs a color, clamping each channel to `[0.0, 1.0]` and rounding to nearest.
+    #[must_use]
+    pub fn from_rgba(color: F32x4Rgba) -> Self {
+        Self(
+            f32_to_unorm(color.r, 0, 10)
+                | f32_to_unorm(color.g, 10, 10)
+                | f32_to_unorm(color.b, 20, 10)
+                | f32_to_unorm(color.a, 30, 2),
+        )
+    }
+
+    /// Unpacks the color.
+    #[must_use]
+    pub fn to_rgba(self) -> F32x4Rgba {
+        F32x4Rgba::new(
+            unorm_to_f32(self.0, 0, 10),
+            unorm_to_f32(self.0, 10, 10),
+            unorm_to_f32(self.0, 20, 10),
+            unorm_to_f32(self.0, 30, 2),
+        )
+    }
+}
+
+/// A color packed into a `u32` with 10 bits each of blue (lowest bits), green and red, and 2
+/// bits of alpha (highest bits).
+///
+/// This is Vulkan's `A2R10G10B10_UNORM_PACK32` and Metal's `bgr10a2Unorm`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Bgr10A2(pub u32);
+
+impl Bgr10A2 {
+    /// Packs a color, clamping each channel to `[0.0, 1.0]` and rounding to nearest.
+    #[must_use]
+    pub fn from_rgba(color: F32x4Rgba) -> Self {
+        Self(
+            f32_to_unorm(color.b, 0, 10)
+                | f32_to_unorm(color.g, 10, 10)
+                | f32_to_unorm(color.r, 20, 10)
+                | f32_to_unorm(color.a, 30, 2),
+        )
+    }
+
+    /// Unpacks the color.
+    #[must_use]
+    pub fn to_rgba(self) -> F32x4Rgba {
+        F32x4Rgba::new(
+            unorm_to_f32(self.0, 20, 10),
+            unorm_to_f32(self.0, 10, 10),
+            unorm_to_f32(self.0, 0, 10),
+            unorm_to_f32(self.0, 30, 2),
+        )
+    }
+}
+
+impl From<F32x4Rgba> for Rgb10A2 {
+    fn from(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<Rgb10A2> for F32x4Rgba {
+    fn from(color: Rgb10A2) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl From<F32x4Rgba> for Bgr10A2 {
+    fn from(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl From<Bgr10A2> for F32x4Rgba {
+    fn from(color: Bgr10A2) -> Self {
+        color.to_rgba()
+    }
+}
+
+impl Pixel for Rgb10A2 {
+    fn to_rgba(self) -> F32x4Rgba {
+        Self::to_rgba(self)
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+impl Pixel for Bgr10A2 {
+    fn to_rgba(self) -> F32x4Rgba {
+        Self::to_rgba(self)
+    }
+
+    fn from_rgba(color: F32x4Rgba) -> Self {
+        Self::from_rgba(color)
+    }
+}
+
+// SAFETY: both are `#[repr(transparent)]` over `u32`, and every bit pattern is a valid color.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Rgb10A2 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Rgb10A2 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Bgr10A2 {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Bgr10A2 {}
+
+/// Blends `src` onto the opaque RGB565 framebuffer `dst` in place.
+///
+/// Each destination pixel is unpacked as opaque, blended with integer math, and repacked; the
+/// alpha of the result is discarded. [`Rgba4444`] sources can be unpacked with
+/// [`Rgba4444::to_rgba`] first.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn blend_rgb565<B>(blend: &B, src: &[U8x4Rgba], dst: &mut [Rgb565])
+where
+    B: RgbaBlend<Channel = u8> + ?Sized,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = Rgb565::from_rgba(blend.apply(*s, d.to_rgba()));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::porter_duff::U8PorterDuff;
+
+    const COLOR: U8x4Rgba = U8x4Rgba::new(0x11, 0x22, 0x33, 0x44);
+
+    #[test]
+    fn packs_each_order() {
+        assert_eq!(PackedOrder::Argb.pack(COLOR), 0x4411_2233);
+        assert_eq!(PackedOrder::Rgba.pack(COLOR), 0x1122_3344);
+        assert_eq!(PackedOrder::Abgr.pack(COLOR), 0x4433_2211);
+    }
+
+    #[test]
+    fn unpack_round_trips() {
+        for order in [PackedOrder::Argb, PackedOrder::Rgba, PackedOrder::Abgr] {
+            assert_eq!(order.unpack(order.pack(COLOR)), COLOR);
+        }
+    }
+
+    #[test]
+    fn blend_packed_matches_unpacked() {
+        let src = U8x4Rgba::new(255, 128, 0, 100);
+        let dst = U8x4Rgba::new(0, 64, 255, 200);
+        let mut words = [PackedOrder::Abgr.pack(dst)];
+        blend_packed(
+            &U8PorterDuff::SRC_ATOP,
+            PackedOrder::Abgr,
+            &[PackedOrder::Abgr.pack(src)],
+            &mut words,
+        );
+        assert_eq!(
+            PackedOrder::Abgr.unpack(words[0]),
+            U8PorterDuff::SRC_ATOP.apply(src, dst)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn blend_packed_panics_on_length_mismatch() {
+        blend_packed(&U8PorterDuff::SRC, PackedOrder::Argb, &[0], &mut [0; 2]);
+    }
+
+    #[test]
+    fn rgb565_extremes_are_exact() {
+        assert_eq!(Rgb565::from(U8x4Rgba::WHITE), Rgb565(0xFFFF));
+        assert_eq!(Rgb565(0xFFFF).to_rgba(), U8x4Rgba::WHITE);
+        assert_eq!(Rgb565(0xF800).to_rgba(), U8x4Rgba::new(255, 0, 0, 255));
+        assert_eq!(Rgb565(0x07E0).to_rgba(), U8x4Rgba::new(0, 255, 0, 255));
+        assert_eq!(Rgb565(0x001F).to_rgba(), U8x4Rgba::new(0, 0, 255, 255));
+    }
+
+    #[test]
+    fn rgb565_round_trips() {
+        for bits in 0..=u16::MAX {
+            let color = Rgb565(bits);
+            assert_eq!(Rgb565::from(U8x4Rgba::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn rgba4444_round_trips() {
+        for bits in 0..=u16::MAX {
+            let color = Rgba4444(bits);
+            assert_eq!(Rgba4444::from(U8x4Rgba::from(color)), color);
+        }
+        assert_eq!(
+            Rgba4444(0xF00F).to_rgba(),
+            U8x4Rgba::new(255, 0, 0, 255),
+            "each nibble widens by 17"
+        );
+        assert_eq!(Rgba4444(0x1234).to_rgba(), U8x4Rgba::new(17, 34, 51, 68));
+    }
+
+    #[test]
+    fn blend_rgb565_treats_dst_as_opaque() {
+        let src = [U8x4Rgba::new(255, 0, 0, 128), U8x4Rgba::TRANSPARENT];
+        let mut dst = [Rgb565(0x001F); 2];
+        blend_rgb565(&U8PorterDuff::SRC_OVER, &src, &mut dst);
+        assert_eq!(dst[0].to_rgba(), U8x4Rgba::new(132, 0, 123, 255));
+        assert_eq!(dst[1], Rgb565(0x001F));
+    }
+
+    #[test]
+    fn rgb10a2_layout() {
+        let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+        assert_eq!(Rgb10A2::from(red), Rgb10A2(0xC000_03FF));
+        assert_eq!(Bgr10A2::from(red), Bgr10A2(0xFFF0_0000));
+        assert_eq!(F32x4Rgba::from(Rgb10A2(0xC000_03FF)), red);
+        assert_eq!(F32x4Rgba::from(Bgr10A2(0xFFF0_0000)), red);
+    }
+
+    #[test]
+    fn rgb10a2_round_trips() {
+        for bits in (0..=u32::MAX).step_by(65_537) {
+            assert_eq!(Rgb10A2::from_rgba(Rgb10A2(bits).to_rgba()), Rgb10A2(bits));
+            assert_eq!(Bgr10A2::from_rgba(Bgr10A2(bits).to_rgba()), Bgr10A2(bits));
+        }
+    }
+
+    #[test]
+    fn rgb10a2_clamps_and_rounds() {
+        let color = F32x4Rgba::new(2.0, -1.0, 0.5, 0.5);
+        let packed = Rgb10A2::from(color).to_rgba();
+        assert_eq!(packed.r, 1.0);
+        assert_eq!(packed.g, 0.0);
+        assert_eq!(packed.b, 512.0 / 1023.0);
+        assert_eq!(packed.a, 2.0 / 3.0);
+    }
+}
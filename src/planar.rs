@@ -0,0 +1,140 @@
+//! Conversion between interleaved and planar pixel layouts.
+//!
+//! Interleaved buffers store each pixel's channels together (`RGBA RGBA …`, as [`Rgba`] slices);
+//! planar buffers store each channel in its own plane (`RR… GG… BB… AA…`), a layout that suits
+//! structure-of-arrays kernels and some video and scientific formats.
+//!
+//! Planes are passed as `[r, g, b, a]` arrays of slices; a single buffer holding the four planes
+//! back to back can be split with [`planes`] and [`planes_mut`].
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{planar, rgba::Rgba};
+//!
+//! let pixels = [Rgba::new(1, 2, 3, 4), Rgba::new(5, 6, 7, 8)];
+//! let mut buffer = [0_u8; 8];
+//! planar::deinterleave(&pixels, planar::planes_mut(&mut buffer));
+//! assert_eq!(buffer, [1, 5, 2, 6, 3, 7, 4, 8]);
+//!
+//! let mut back = [Rgba::new(0, 0, 0, 0); 2];
+//! planar::interleave(planar::planes(&buffer), &mut back);
+//! assert_eq!(back, pixels);
+//! ```
+
+use crate::rgba::Rgba;
+
+/// Splits a buffer holding four equally sized planes back to back into `[r, g, b, a]`.
+///
+/// ## Panics
+///
+/// If the length of `buffer` is not a multiple of `4`.
+#[must_use]
+pub fn planes<C>(buffer: &[C]) -> [&[C]; 4] {
+    assert_eq!(buffer.len() % 4, 0, "buffer must hold 4 equal planes");
+    let len = buffer.len() / 4;
+    let (r, rest) = buffer.split_at(len);
+    let (g, rest) = rest.split_at(len);
+    let (b, a) = rest.split_at(len);
+    [r, g, b, a]
+}
+
+/// Splits a buffer holding four equally sized planes back to back into mutable `[r, g, b, a]`.
+///
+/// ## Panics
+///
+/// If the length of `buffer` is not a multiple of `4`.
+#[must_use]
+pub fn planes_mut<C>(buffer: &mut [C]) -> [&mut [C]; 4] {
+    assert_eq!(buffer.len() % 4, 0, "buffer must hold 4 equal planes");
+    let len = buffer.len() / 4;
+    let (r, rest) = buffer.split_at_mut(len);
+    let (g, rest) = rest.split_at_mut(len);
+    let (b, a) = rest.split_at_mut(len);
+    [r, g, b, a]
+}
+
+/// Copies interleaved `src` pixels into the `[r, g, b, a]` planes.
+///
+/// ## Panics
+///
+/// If any plane does not have the same length as `src`.
+pub fn deinterleave<C: Copy>(src: &[Rgba<C>], planes: [&mut [C]; 4]) {
+    let [r, g, b, a] = planes;
+    for plane in [&*r, &*g, &*b, &*a] {
+        assert_eq!(
+            plane.len(),
+            src.len(),
+            "planes must have one value per pixel"
+        );
+    }
+    for (i, p) in src.iter().enumerate() {
+        r[i] = p.r;
+        g[i] = p.g;
+        b[i] = p.b;
+        a[i] = p.a;
+    }
+}
+
+/// Copies the `[r, g, b, a]` planes into interleaved `dst` pixels.
+///
+/// ## Panics
+///
+/// If any plane does not have the same length as `dst`.
+pub fn interleave<C: Copy>(planes: [&[C]; 4], dst: &mut [Rgba<C>]) {
+    let [r, g, b, a] = planes;
+    for plane in planes {
+        assert_eq!(
+            plane.len(),
+            dst.len(),
+            "planes must have one value per pixel"
+        );
+    }
+    for (i, p) in dst.iter_mut().enumerate() {
+        *p = Rgba::new(r[i], g[i], b[i], a[i]);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::rgba::F32x4Rgba;
+
+    #[test]
+    fn round_trip_separate_planes() {
+        let pixels = [F32x4Rgba::new(0.1, 0.2, 0.3, 0.4), F32x4Rgba::WHITE];
+        let (mut r, mut g, mut b, mut a) = ([0.0; 2], [0.0; 2], [0.0; 2], [0.0; 2]);
+        deinterleave(&pixels, [&mut r, &mut g, &mut b, &mut a]);
+        assert_eq!(
+            (r, g, b, a),
+            ([0.1, 1.0], [0.2, 1.0], [0.3, 1.0], [0.4, 1.0])
+        );
+
+        let mut back = [F32x4Rgba::TRANSPARENT; 2];
+        interleave([&r, &g, &b, &a], &mut back);
+        assert_eq!(back, pixels);
+    }
+
+    #[test]
+    fn planes_split_evenly() {
+        let buffer = [0, 1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(planes(&buffer), [&[0, 1][..], &[2, 3], &[4, 5], &[6, 7]]);
+        let empty: [&[u8]; 4] = planes(&[]);
+        assert!(empty.iter().all(|p| p.is_empty()));
+    }
+
+    #[test]
+    #[should_panic(expected = "4 equal planes")]
+    fn planes_panics_on_uneven_buffer() {
+        let _ = planes_mut(&mut [0_u8; 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one value per pixel")]
+    fn deinterleave_panics_on_short_plane() {
+        let mut buffer = [0_u8; 4];
+        let [r, g, b, _] = planes_mut(&mut buffer);
+        deinterleave(&[Rgba::new(0, 0, 0, 0); 2], [r, g, b, &mut [0; 2]]);
+    }
+}
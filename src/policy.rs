@@ -0,0 +1,196 @@
+//! What to do with blend results outside `[0.0, 1.0]`.
+//!
+//! Floating-point blends are not clamped: `Plus` of two opaque colors has an alpha of `2.0`,
+//! and `Divide` or `ColorDodge` can brighten channels past `1.0`. That is what HDR pipelines
+//! want, but results headed for an 8-bit buffer or a display need bounding. An [`OutputPolicy`]
+//! makes that choice explicit, on a blend with [`PolicyBlend`] or
+//! [`BlendMode::apply_with_policy`], and when converting to `u8` with [`OutputPolicy::to_u8`].
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, policy::OutputPolicy, rgba::F32x4Rgba};
+//!
+//! let src = F32x4Rgba::new(1.0, 0.5, 0.0, 1.0);
+//! let dst = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+//! assert_eq!(BlendMode::Plus.apply_with_policy(src, dst, OutputPolicy::None).a, 2.0);
+//!
+//! let clamped = BlendMode::Plus.apply_with_policy(src, dst, OutputPolicy::Clamp);
+//! assert_eq!(clamped, F32x4Rgba::new(1.0, 0.5, 0.0, 1.0));
+//!
+//! // Saturating keeps the ratio of the color channels.
+//! let saturated = BlendMode::Plus.apply_with_policy(src, dst, OutputPolicy::Saturate);
+//! assert_eq!(saturated, F32x4Rgba::new(1.0, 0.25, 0.0, 1.0));
+//! ```
+
+use crate::{
+    BlendMode, RgbaBlend, math,
+    rgba::{F32x4Rgba, U8x4Rgba},
+};
+
+/// How blend results outside `[0.0, 1.0]` are brought back into range.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputPolicy {
+    /// Results are left as computed, for HDR pipelines.
+    ///
+    /// Integer conversions cannot represent out-of-range values, and clamp instead.
+    #[default]
+    None,
+
+    /// Every channel is clamped to `[0.0, 1.0]` independently; `NaN` becomes `0.0`.
+    ///
+    /// Overbright colors can shift hue, as their brightest channels are cut first.
+    Clamp,
+
+    /// Alpha is clamped, and color channels above `1.0` are scaled down together so the
+    /// brightest is `1.0`, keeping their ratios (and so the hue); negative channels and `NaN`
+    /// become `0.0`.
+    Saturate,
+}
+
+impl OutputPolicy {
+    /// Brings `color` into range according to this policy.
+    #[must_use]
+    pub fn apply(self, color: F32x4Rgba) -> F32x4Rgba {
+        match self {
+            Self::None => color,
+            Self::Clamp => color.clamp_unit(),
+            Self::Saturate => {
+                let [r, g, b] =
+                    [color.r, color.g, color.b].map(|c| if c.is_nan() { 0.0 } else { c.max(0.0) });
+                let max = r.max(g).max(b);
+                let scale = if max > 1.0 { max.recip() } else { 1.0 };
+                F32x4Rgba::new(r * scale, g * scale, b * scale, color.clamp_unit().a)
+            }
+        }
+    }
+
+    /// Brings every color in `colors` into range in place.
+    pub fn apply_slice(self, colors: &mut [F32x4Rgba]) {
+        if self != Self::None {
+            for c in colors {
+                *c = self.apply(*c);
+            }
+        }
+    }
+
+    /// Converts `color` to 8 bits, rounding to nearest after applying this policy.
+    ///
+    /// Whatever the policy, out-of-range channels are clamped rather than wrapped; `From` uses
+    /// [`OutputPolicy::Clamp`].
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn to_u8(self, color: F32x4Rgba) -> U8x4Rgba {
+        let policy = if self == Self::None {
+            Self::Clamp
+        } else {
+            self
+        };
+        let c = policy.apply(color);
+        let q = |v: f32| math::round(v * 255.0) as u8;
+        U8x4Rgba::new(q(c.r), q(c.g), q(c.b), q(c.a))
+    }
+}
+
+/// Applies an [`OutputPolicy`] to the results of the wrapped blend mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PolicyBlend<B>(pub B, pub OutputPolicy);
+
+impl<B: RgbaBlend<Channel = f32>> RgbaBlend for PolicyBlend<B> {
+    type Channel = f32;
+
+    fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        self.1.apply(self.0.apply(src, dst))
+    }
+
+    /// Blends with the wrapped mode's own [`apply_slice`](RgbaBlend::apply_slice), then applies
+    /// the policy in a second pass over `dst`.
+    fn apply_slice(&self, src: &[F32x4Rgba], dst: &mut [F32x4Rgba]) {
+        self.0.apply_slice(src, dst);
+        self.1.apply_slice(dst);
+    }
+}
+
+impl BlendMode {
+    /// Blends `src` with `dst`, then brings the result into range with `policy`.
+    #[must_use]
+    pub fn apply_with_policy(
+        self,
+        src: F32x4Rgba,
+        dst: F32x4Rgba,
+        policy: OutputPolicy,
+    ) -> F32x4Rgba {
+        PolicyBlend(self, policy).apply(src, dst)
+    }
+
+    /// Blends `src` onto `dst` in place, then brings the results into range with `policy`.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn apply_slice_with_policy(
+        self,
+        src: &[F32x4Rgba],
+        dst: &mut [F32x4Rgba],
+        policy: OutputPolicy,
+    ) {
+        PolicyBlend(self, policy).apply_slice(src, dst);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    const HOT: F32x4Rgba = F32x4Rgba::new(2.0, 1.0, -0.5, 1.5);
+
+    #[test]
+    fn none_keeps_values() {
+        assert_eq!(OutputPolicy::None.apply(HOT), HOT);
+    }
+
+    #[test]
+    fn clamp_is_per_channel() {
+        assert_eq!(
+            OutputPolicy::Clamp.apply(HOT),
+            F32x4Rgba::new(1.0, 1.0, 0.0, 1.0)
+        );
+        let nan = OutputPolicy::Clamp.apply(F32x4Rgba::new(f32::NAN, 0.5, 0.5, 0.5));
+        assert_eq!(nan, F32x4Rgba::new(0.0, 0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn saturate_keeps_ratios() {
+        assert_eq!(
+            OutputPolicy::Saturate.apply(HOT),
+            F32x4Rgba::new(1.0, 0.5, 0.0, 1.0)
+        );
+        let in_range = F32x4Rgba::new(0.25, 0.5, 0.75, 1.0);
+        assert_eq!(OutputPolicy::Saturate.apply(in_range), in_range);
+    }
+
+    #[test]
+    fn to_u8_never_wraps() {
+        for policy in [OutputPolicy::None, OutputPolicy::Clamp] {
+            assert_eq!(policy.to_u8(HOT), U8x4Rgba::new(255, 255, 0, 255));
+        }
+        assert_eq!(
+            OutputPolicy::Saturate.to_u8(HOT),
+            U8x4Rgba::new(255, 128, 0, 255)
+        );
+        assert_eq!(U8x4Rgba::from(HOT), OutputPolicy::Clamp.to_u8(HOT));
+    }
+
+    #[test]
+    fn slice_matches_individual() {
+        let src = [F32x4Rgba::WHITE, F32x4Rgba::new(0.5, 0.5, 0.5, 0.5)];
+        let mut dst = [F32x4Rgba::WHITE; 2];
+        let expected: [F32x4Rgba; 2] = core::array::from_fn(|i| {
+            BlendMode::Plus.apply_with_policy(src[i], dst[i], OutputPolicy::Clamp)
+        });
+        BlendMode::Plus.apply_slice_with_policy(&src, &mut dst, OutputPolicy::Clamp);
+        assert_eq!(dst, expected);
+        assert_eq!(dst[0], F32x4Rgba::WHITE);
+    }
+}
@@ -8,6 +8,7 @@ use core::marker::PhantomData;
 
 use crate::{
     RgbaBlend,
+    channel::Channel,
     rgba::{F32x4Rgba, Rgba},
     vec4::F32x4,
 };
@@ -28,6 +29,32 @@ pub struct PorterDuff<C, F: Fn(C, C) -> C> {
     _ty: PhantomData<C>,
 }
 
+/// A [`PorterDuff`] blending [`f32`] channels.
+pub type F32PorterDuff = PorterDuff<f32, fn(f32, f32) -> f32>;
+
+/// A [`PorterDuff`] blending [`f64`] channels, for double-precision accumulation.
+pub type F64PorterDuff = PorterDuff<f64, fn(f64, f64) -> f64>;
+
+/// A [`PorterDuff`] blending [`u8`] channels with fixed-point integer math.
+///
+/// Every constant, from [`CLEAR`](PorterDuff::CLEAR) to [`PLUS`](PorterDuff::PLUS), is
+/// available, so `RgbaBlend<Channel = u8>` works directly on decoded 8-bit images.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{RgbaBlend, porter_duff::U8PorterDuff, rgba::U8x4Rgba};
+///
+/// let sprite = [U8x4Rgba::new(255, 0, 0, 128); 2];
+/// let mut canvas = [U8x4Rgba::new(0, 0, 255, 255); 2];
+/// U8PorterDuff::SRC_OVER.apply_slice(&sprite, &mut canvas);
+/// assert_eq!(canvas, [U8x4Rgba::new(128, 0, 127, 191); 2]);
+/// ```
+pub type U8PorterDuff = PorterDuff<u8, fn(u8, u8) -> u8>;
+
+/// A [`PorterDuff`] blending [`u16`] channels with fixed-point integer math.
+pub type U16PorterDuff = PorterDuff<u16, fn(u16, u16) -> u16>;
+
 impl<C, F: Fn(C, C) -> C> PorterDuff<C, F> {
     /// Creates a new `PorterDuff` with the given source and destination coefficient functions.
     pub const fn new(src: F, dst: F) -> Self {
@@ -37,37 +64,87 @@ impl<C, F: Fn(C, C) -> C> PorterDuff<C, F> {
             _ty: PhantomData,
         }
     }
+
+    /// Returns the source and destination factors, `(Fa, Fb)`, for the given alpha values.
+    pub fn factors(&self, src_alpha: C, dst_alpha: C) -> (C, C)
+    where
+        C: Copy,
+    {
+        (
+            (self.src)(src_alpha, dst_alpha),
+            (self.dst)(src_alpha, dst_alpha),
+        )
+    }
 }
 
-impl PorterDuff<f32, fn(f32, f32) -> f32> {
+impl<C: Channel> PorterDuff<C, fn(C, C) -> C> {
+    /// Creates an operator from custom source and destination coefficient functions.
+    ///
+    /// Each receives the source and destination alpha and returns that side's factor, `Fa` or
+    /// `Fb`. Unlike [`new`](Self::new), the coefficients are function pointers, so the operator
+    /// has the same type as the built-in constants and works with [`RgbaBlend`] and the slice
+    /// APIs. Factors outside `[0, 1]` are allowed; integer channels saturate.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::{RgbaBlend, porter_duff::F32PorterDuff, rgba::F32x4Rgba};
+    ///
+    /// // A fixed 25% / 75% average of both colors, ignoring alpha.
+    /// let average = F32PorterDuff::custom(|_, _| 0.25, |_, _| 0.75);
+    /// let out = average.apply(F32x4Rgba::WHITE, F32x4Rgba::BLACK);
+    /// assert_eq!(out, F32x4Rgba::new(0.25, 0.25, 0.25, 1.0));
+    /// ```
+    #[must_use]
+    pub const fn custom(src_coeff: fn(C, C) -> C, dst_coeff: fn(C, C) -> C) -> Self {
+        Self::new(src_coeff, dst_coeff)
+    }
+
     /// Returns the result of the blend operation using source and destination alpha values.
+    ///
+    /// Each channel, including alpha, is `src * Fa + dst * Fb` computed with
+    /// [`Channel::weighted_sum`]: [`f32`] uses SIMD lanes, and [`u8`] uses only integer math,
+    /// rounding each product to nearest with [`math::mul_div_255_round`] (or a table, with the
+    /// `mul-lut` feature) and saturating the sum at `255`.
+    ///
+    /// [`math::mul_div_255_round`]: crate::math::mul_div_255_round
     #[must_use]
-    pub fn blend(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
-        let src_a = F32x4::splat((self.src)(src.alpha(), dst.alpha()));
-        let dst_a = F32x4::splat((self.dst)(src.alpha(), dst.alpha()));
-        let blend: F32x4 = src_a * F32x4::from(src) + dst_a * F32x4::from(dst);
-        blend.into_rgba()
+    pub fn blend(&self, src: Rgba<C>, dst: Rgba<C>) -> Rgba<C> {
+        let src_a = (self.src)(src.a, dst.a);
+        let dst_a = (self.dst)(src.a, dst.a);
+        C::weighted_sum(src, src_a, dst, dst_a)
     }
 
-    /// Always returns zero (`0.0`) regardless of the source and destination alpha values.
-    const FN_ZERO: fn(f32, f32) -> f32 = |_, _| 0.0;
+    /// Blends two pixels at once; identical to calling [`blend`](Self::blend) on each.
+    fn blend_pair(&self, src: [Rgba<C>; 2], dst: [Rgba<C>; 2]) -> [Rgba<C>; 2] {
+        let [s0, s1] = src;
+        let [d0, d1] = dst;
+        let src_a = [(self.src)(s0.a, d0.a), (self.src)(s1.a, d1.a)];
+        let dst_a = [(self.dst)(s0.a, d0.a), (self.dst)(s1.a, d1.a)];
+        C::weighted_sum_pair(src, src_a, dst, dst_a)
+    }
 
-    /// Always returns one (`1.0`) regardless of the source and destination alpha values.
-    const FN_ONE: fn(f32, f32) -> f32 = |_, _| 1.0;
+    /// Always returns zero ([`Channel::ZERO`]) regardless of the source and destination alpha
+    /// values.
+    const FN_ZERO: fn(C, C) -> C = |_, _| C::ZERO;
+
+    /// Always returns one ([`Channel::ONE`]) regardless of the source and destination alpha
+    /// values.
+    const FN_ONE: fn(C, C) -> C = |_, _| C::ONE;
 
     /// Returns the source alpha value, ignoring the destination alpha value.
-    const FN_SRC: fn(f32, f32) -> f32 = |src, _| src;
+    const FN_SRC: fn(C, C) -> C = |src, _| src;
 
     /// Returns the destination alpha value, ignoring the source alpha value.
-    const FN_DST: fn(f32, f32) -> f32 = |_, dst| dst;
+    const FN_DST: fn(C, C) -> C = |_, dst| dst;
 
-    /// Returns one minus the source alpha value (`1.0 - src`).
-    const FN_ONE_MINUS_SRC: fn(f32, f32) -> f32 = |src, _| 1.0 - src;
+    /// Returns one minus the source alpha value (`ONE - src`).
+    const FN_ONE_MINUS_SRC: fn(C, C) -> C = |src, _| C::ONE.sub(src);
 
-    /// Returns one minus the destination alpha value (`1.0 - dst`).
-    const FN_ONE_MINUS_DST: fn(f32, f32) -> f32 = |_, dst| 1.0 - dst;
+    /// Returns one minus the destination alpha value (`ONE - dst`).
+    const FN_ONE_MINUS_DST: fn(C, C) -> C = |_, dst| C::ONE.sub(dst);
 
-    /// Destination pixels covered by the source are cleared to `0.0`.
+    /// Destination pixels covered by the source are cleared to zero.
     pub const CLEAR: Self = Self::new(Self::FN_ZERO, Self::FN_ZERO);
 
     /// Destination pixels are replaced with the source pixels.
@@ -103,21 +180,240 @@ impl PorterDuff<f32, fn(f32, f32) -> f32> {
     /// Non-overlapping regions of the source and destination are combined.
     pub const XOR: Self = Self::new(Self::FN_ONE_MINUS_DST, Self::FN_ONE_MINUS_SRC);
 
-    /// Source and destination regions are added together.
+    /// Source and destination regions are added together; integer channels saturate.
     pub const PLUS: Self = Self::new(Self::FN_ONE, Self::FN_ONE);
 }
 
-impl RgbaBlend for PorterDuff<f32, fn(f32, f32) -> f32> {
-    type Channel = f32;
+impl<C: Channel> RgbaBlend for PorterDuff<C, fn(C, C) -> C> {
+    type Channel = C;
 
     fn apply(&self, src: Rgba<Self::Channel>, dst: Rgba<Self::Channel>) -> Rgba<Self::Channel> {
         self.blend(src, dst)
     }
+
+    /// Blends two pixels per step, then the last pixel of an odd-length slice on its own.
+    fn apply_slice(&self, src: &[Rgba<Self::Channel>], dst: &mut [Rgba<Self::Channel>]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        let mut src_pairs = src.chunks_exact(2);
+        let mut dst_pairs = dst.chunks_exact_mut(2);
+        for (s, d) in (&mut src_pairs).zip(&mut dst_pairs) {
+            let out = self.blend_pair([s[0], s[1]], [d[0], d[1]]);
+            d.copy_from_slice(&out);
+        }
+        for (s, d) in src_pairs.remainder().iter().zip(dst_pairs.into_remainder()) {
+            *d = self.blend(*s, *d);
+        }
+    }
+}
+
+/// A Porter-Duff factor, computed from the source and destination alpha.
+///
+/// Unlike the coefficient functions of a [`PorterDuff`], coefficients are plain data: they can
+/// be compared, hashed, stored and built in `const` contexts, and a match on them inlines into
+/// the blend loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Coefficient {
+    /// Always zero.
+    Zero,
+
+    /// Always one.
+    One,
+
+    /// The source alpha.
+    SrcAlpha,
+
+    /// The destination alpha.
+    DstAlpha,
+
+    /// One minus the source alpha.
+    OneMinusSrcAlpha,
+
+    /// One minus the destination alpha.
+    OneMinusDstAlpha,
+}
+
+impl Coefficient {
+    /// Returns the factor for the given source and destination alpha.
+    #[must_use]
+    #[inline]
+    pub fn eval<C: Channel>(self, src_alpha: C, dst_alpha: C) -> C {
+        match self {
+            Self::Zero => C::ZERO,
+            Self::One => C::ONE,
+            Self::SrcAlpha => src_alpha,
+            Self::DstAlpha => dst_alpha,
+            Self::OneMinusSrcAlpha => C::ONE.sub(src_alpha),
+            Self::OneMinusDstAlpha => C::ONE.sub(dst_alpha),
+        }
+    }
+
+    /// Returns the equivalent coefficient function of a [`PorterDuff`].
+    #[must_use]
+    pub const fn to_fn<C: Channel>(self) -> fn(C, C) -> C {
+        match self {
+            Self::Zero => PorterDuff::<C, fn(C, C) -> C>::FN_ZERO,
+            Self::One => PorterDuff::<C, fn(C, C) -> C>::FN_ONE,
+            Self::SrcAlpha => PorterDuff::<C, fn(C, C) -> C>::FN_SRC,
+            Self::DstAlpha => PorterDuff::<C, fn(C, C) -> C>::FN_DST,
+            Self::OneMinusSrcAlpha => PorterDuff::<C, fn(C, C) -> C>::FN_ONE_MINUS_SRC,
+            Self::OneMinusDstAlpha => PorterDuff::<C, fn(C, C) -> C>::FN_ONE_MINUS_DST,
+        }
+    }
+}
+
+/// A Porter-Duff operator described by a source and destination [`Coefficient`].
+///
+/// Blends exactly like the [`PorterDuff`] constant with the same coefficients, which
+/// [`to_porter_duff`](Self::to_porter_duff) returns, but is `PartialEq`, `Hash` and
+/// const-constructible by users.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::{
+///     RgbaBlend,
+///     porter_duff::{Coefficient, Coefficients, F32PorterDuff},
+///     rgba::F32x4Rgba,
+/// };
+///
+/// const OVER: Coefficients = Coefficients::new(Coefficient::SrcAlpha, Coefficient::OneMinusSrcAlpha);
+/// assert_eq!(OVER, Coefficients::SRC_OVER);
+///
+/// let (src, dst) = (F32x4Rgba::new(1.0, 0.0, 0.0, 0.5), F32x4Rgba::BLACK);
+/// assert_eq!(OVER.apply(src, dst), F32PorterDuff::SRC_OVER.apply(src, dst));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coefficients {
+    /// Factor of the source color, `Fa`.
+    pub src: Coefficient,
+
+    /// Factor of the destination color, `Fb`.
+    pub dst: Coefficient,
+}
+
+impl Coefficients {
+    /// Creates an operator from its source and destination coefficients.
+    #[must_use]
+    pub const fn new(src: Coefficient, dst: Coefficient) -> Self {
+        Self { src, dst }
+    }
+
+    /// Destination pixels covered by the source are cleared to zero.
+    pub const CLEAR: Self = Self::new(Coefficient::Zero, Coefficient::Zero);
+
+    /// Destination pixels are replaced with the source pixels.
+    pub const SRC: Self = Self::new(Coefficient::One, Coefficient::Zero);
+
+    /// Source pixels are replaced by the destination pixels.
+    pub const DST: Self = Self::new(Coefficient::Zero, Coefficient::One);
+
+    /// Source color is placed over the destination color.
+    pub const SRC_OVER: Self = Self::new(Coefficient::SrcAlpha, Coefficient::OneMinusSrcAlpha);
+
+    /// Destination color is placed over the source color.
+    pub const DST_OVER: Self = Self::new(Coefficient::OneMinusDstAlpha, Coefficient::DstAlpha);
+
+    /// Source that overlaps the destination replaces the destination.
+    pub const SRC_IN: Self = Self::new(Coefficient::DstAlpha, Coefficient::Zero);
+
+    /// Destination that overlaps the source replaces the source.
+    pub const DST_IN: Self = Self::new(Coefficient::Zero, Coefficient::SrcAlpha);
+
+    /// Source that does not overlap the destination replaces the destination.
+    pub const SRC_OUT: Self = Self::new(Coefficient::OneMinusDstAlpha, Coefficient::Zero);
+
+    /// Destination that does not overlap the source replaces the source.
+    pub const DST_OUT: Self = Self::new(Coefficient::Zero, Coefficient::OneMinusSrcAlpha);
+
+    /// Source that overlaps the destination is blended with the destination.
+    pub const SRC_ATOP: Self = Self::new(Coefficient::DstAlpha, Coefficient::OneMinusSrcAlpha);
+
+    /// Destination that overlaps the source is blended with the source.
+    pub const DST_ATOP: Self = Self::new(Coefficient::OneMinusDstAlpha, Coefficient::SrcAlpha);
+
+    /// Non-overlapping regions of the source and destination are combined.
+    pub const XOR: Self = Self::new(Coefficient::OneMinusDstAlpha, Coefficient::OneMinusSrcAlpha);
+
+    /// Source and destination regions are added together; integer channels saturate.
+    pub const PLUS: Self = Self::new(Coefficient::One, Coefficient::One);
+
+    /// Returns the source and destination factors, `(Fa, Fb)`, for the given alpha values.
+    #[must_use]
+    #[inline]
+    pub fn factors<C: Channel>(self, src_alpha: C, dst_alpha: C) -> (C, C) {
+        (
+            self.src.eval(src_alpha, dst_alpha),
+            self.dst.eval(src_alpha, dst_alpha),
+        )
+    }
+
+    /// Returns the result of the blend operation on colors with any [`Channel`].
+    ///
+    /// Identical to [`PorterDuff::blend`] with the same coefficients.
+    #[must_use]
+    pub fn blend<C: Channel>(self, src: Rgba<C>, dst: Rgba<C>) -> Rgba<C> {
+        let (fa, fb) = self.factors(src.a, dst.a);
+        C::weighted_sum(src, fa, dst, fb)
+    }
+
+    /// Returns the equivalent [`PorterDuff`] for channel type `C`.
+    #[must_use]
+    pub const fn to_porter_duff<C: Channel>(self) -> PorterDuff<C, fn(C, C) -> C> {
+        PorterDuff::new(self.src.to_fn(), self.dst.to_fn())
+    }
+}
+
+impl RgbaBlend for Coefficients {
+    type Channel = f32;
+
+    fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        self.blend(src, dst)
+    }
+}
+
+impl<C: Channel> From<Coefficients> for PorterDuff<C, fn(C, C) -> C> {
+    fn from(coefficients: Coefficients) -> Self {
+        coefficients.to_porter_duff()
+    }
+}
+
+/// Adds premultiplied `src` and `dst`, clamping colors and alpha to at most `1.0`.
+///
+/// This is the CSS Compositing Level 2 `plus-lighter` operator, [`BlendMode::PlusLighter`]. Unlike
+/// [`PLUS`](PorterDuff::PLUS), results stay within `[0.0, 1.0]`.
+///
+/// [`BlendMode::PlusLighter`]: crate::BlendMode::PlusLighter
+#[must_use]
+pub fn plus_lighter(src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+    let sum = F32x4::from(src.premultiply()) + F32x4::from(dst.premultiply());
+    let [r, g, b, a] = sum.into_rgba().into();
+    F32x4Rgba::new(r.min(1.0), g.min(1.0), b.min(1.0), a.min(1.0)).unpremultiply()
+}
+
+/// Darkens premultiplied `src` and `dst` by the sum of their distances from their alpha,
+/// clamping colors to at least `0.0`.
+///
+/// This is the CSS Compositing Level 2 `plus-darker` operator, [`BlendMode::PlusDarker`]: for
+/// opaque pixels, each channel is `max(0, 1 - ((1 - src) + (1 - dst)))`. The result alpha is
+/// `min(1, src.a + dst.a)`, and a transparent source leaves the destination unchanged.
+///
+/// [`BlendMode::PlusDarker`]: crate::BlendMode::PlusDarker
+#[must_use]
+pub fn plus_darker(src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+    let (s, d) = (src.premultiply(), dst.premultiply());
+    let a = (s.a + d.a).min(1.0);
+    let channel = |s_c: f32, d_c: f32| (a - ((s.a - s_c) + (d.a - d_c))).max(0.0);
+    F32x4Rgba::new(channel(s.r, d.r), channel(s.g, d.g), channel(s.b, d.b), a).unpremultiply()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rgba::{F64x4Rgba, U8x4Rgba};
 
     #[test]
     #[allow(clippy::float_cmp)]
@@ -302,4 +598,163 @@ mod tests {
         let result = blend.apply(src_c, dst_c);
         assert_eq!(result, F32x4Rgba::new(0.5, 0.7, 0.900_000_04, 2.0));
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn plus_lighter_clamps() {
+        let src_c = F32x4Rgba::new(0.5, 0.75, 0.25, 1.0);
+        let dst_c = F32x4Rgba::new(0.75, 0.5, 0.25, 1.0);
+        assert_eq!(
+            plus_lighter(src_c, dst_c),
+            F32x4Rgba::new(1.0, 1.0, 0.5, 1.0)
+        );
+        let half = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        assert_eq!(plus_lighter(half, half), F32x4Rgba::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn plus_darker_opaque() {
+        let src_c = F32x4Rgba::new(0.75, 0.5, 0.25, 1.0);
+        let dst_c = F32x4Rgba::new(0.75, 0.75, 1.0, 1.0);
+        assert_eq!(
+            plus_darker(src_c, dst_c),
+            F32x4Rgba::new(0.5, 0.25, 0.25, 1.0)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn plus_darker_transparent_source_is_identity() {
+        let dst_c = F32x4Rgba::new(0.5, 0.25, 1.0, 0.5);
+        assert_eq!(plus_darker(F32x4Rgba::TRANSPARENT, dst_c), dst_c);
+        assert_eq!(plus_lighter(F32x4Rgba::TRANSPARENT, dst_c), dst_c);
+    }
+
+    #[test]
+    fn custom_matches_builtin() {
+        let over = U8PorterDuff::custom(|src, _| src, |src, _| 255 - src);
+        let src = [U8x4Rgba::new(255, 0, 0, 128); 3];
+        let mut dst = [U8x4Rgba::new(0, 0, 255, 255); 3];
+        over.apply_slice(&src, &mut dst);
+        assert_eq!(
+            dst,
+            [U8PorterDuff::SRC_OVER.blend(src[0], U8x4Rgba::new(0, 0, 255, 255)); 3]
+        );
+    }
+
+    #[test]
+    fn coefficients_match_porter_duff() {
+        let pairs = [
+            (Coefficients::CLEAR, U8PorterDuff::CLEAR),
+            (Coefficients::SRC, U8PorterDuff::SRC),
+            (Coefficients::DST, U8PorterDuff::DST),
+            (Coefficients::SRC_OVER, U8PorterDuff::SRC_OVER),
+            (Coefficients::DST_OVER, U8PorterDuff::DST_OVER),
+            (Coefficients::SRC_IN, U8PorterDuff::SRC_IN),
+            (Coefficients::DST_IN, U8PorterDuff::DST_IN),
+            (Coefficients::SRC_OUT, U8PorterDuff::SRC_OUT),
+            (Coefficients::DST_OUT, U8PorterDuff::DST_OUT),
+            (Coefficients::SRC_ATOP, U8PorterDuff::SRC_ATOP),
+            (Coefficients::DST_ATOP, U8PorterDuff::DST_ATOP),
+            (Coefficients::XOR, U8PorterDuff::XOR),
+            (Coefficients::PLUS, U8PorterDuff::PLUS),
+        ];
+        let src = U8x4Rgba::new(255, 40, 0, 100);
+        let dst = U8x4Rgba::new(0, 90, 255, 200);
+        for (coefficients, porter_duff) in pairs {
+            let expected = porter_duff.blend(src, dst);
+            assert_eq!(coefficients.blend(src, dst), expected, "{coefficients:?}");
+            assert_eq!(
+                U8PorterDuff::from(coefficients).blend(src, dst),
+                expected,
+                "{coefficients:?}"
+            );
+            let (src, dst) = (F32x4Rgba::from(src), F32x4Rgba::from(dst));
+            assert_eq!(
+                coefficients.apply(src, dst),
+                F32PorterDuff::from(coefficients).apply(src, dst)
+            );
+        }
+    }
+
+    #[test]
+    fn u8_src_over_matches_f32() {
+        let src = U8x4Rgba::new(255, 0, 0, 128);
+        let dst = U8x4Rgba::new(0, 0, 255, 255);
+        assert_eq!(
+            U8PorterDuff::SRC_OVER.blend(src, dst),
+            U8x4Rgba::new(128, 0, 127, 191)
+        );
+        let f32_out = F32PorterDuff::SRC_OVER.blend(src.into(), dst.into());
+        assert_eq!(U8x4Rgba::from(f32_out), U8x4Rgba::new(128, 0, 127, 191));
+    }
+
+    #[test]
+    fn u8_plus_saturates() {
+        let c = U8x4Rgba::new(200, 100, 0, 200);
+        assert_eq!(
+            U8PorterDuff::PLUS.apply(c, c),
+            U8x4Rgba::new(255, 200, 0, 255)
+        );
+    }
+
+    #[test]
+    fn u8_constants() {
+        let src = U8x4Rgba::new(10, 20, 30, 40);
+        let dst = U8x4Rgba::new(50, 60, 70, 80);
+        assert_eq!(U8PorterDuff::CLEAR.apply(src, dst), U8x4Rgba::TRANSPARENT);
+        assert_eq!(U8PorterDuff::SRC.apply(src, dst), src);
+        assert_eq!(U8PorterDuff::DST.apply(src, dst), dst);
+        assert_eq!(
+            U8PorterDuff::SRC_OVER.apply(U8x4Rgba::TRANSPARENT, dst),
+            dst
+        );
+    }
+
+    #[test]
+    fn f64_matches_f32() {
+        let src = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+        let dst = F32x4Rgba::new(0.0, 0.0, 1.0, 1.0);
+        let out = F64PorterDuff::SRC_OVER.apply(src.into(), dst.into());
+        assert_eq!(
+            F32x4Rgba::from(out),
+            F32PorterDuff::SRC_OVER.blend(src, dst)
+        );
+    }
+
+    #[test]
+    fn f64_accumulates_many_layers() {
+        // Each layer moves red 1% closer to 1.0, so after n layers it is 1 - 0.99^n.
+        let layer = F64x4Rgba::new(1.0, 0.0, 0.0, 0.01);
+        let mut acc = F64x4Rgba::TRANSPARENT;
+        for _ in 0..500 {
+            acc = F64PorterDuff::SRC_OVER.blend(layer, acc);
+        }
+        assert!((acc.r - (1.0 - 0.99_f64.powi(500))).abs() < 1e-12);
+    }
+
+    #[test]
+    fn u16_src_over_matches_u8() {
+        let src = U8x4Rgba::new(255, 0, 0, 128);
+        let dst = U8x4Rgba::new(0, 0, 255, 255);
+        let out = U16PorterDuff::SRC_OVER.blend(src.into(), dst.into());
+        assert_eq!(U8x4Rgba::from(out), U8PorterDuff::SRC_OVER.blend(src, dst));
+    }
+
+    #[test]
+    fn apply_slice_matches_individual_for_every_channel() {
+        let src: [U8x4Rgba; 3] =
+            core::array::from_fn(|i| U8x4Rgba::new(255, 0, 0, u8::try_from(i * 100).unwrap()));
+        let mut dst = [U8x4Rgba::new(0, 0, 255, 200); 3];
+        let expected = src.map(|s| U8PorterDuff::XOR.blend(s, dst[0]));
+        U8PorterDuff::XOR.apply_slice(&src, &mut dst);
+        assert_eq!(dst, expected);
+
+        let src = src.map(|c| F64x4Rgba::from(F32x4Rgba::from(c)));
+        let mut dst = [F64x4Rgba::new(0.0, 0.0, 1.0, 0.75); 3];
+        let expected = src.map(|s| F64PorterDuff::XOR.blend(s, dst[0]));
+        F64PorterDuff::XOR.apply_slice(&src, &mut dst);
+        assert_eq!(dst, expected);
+    }
 }
@@ -0,0 +1,268 @@
+//! Premultiplied-alpha colors and blending.
+//!
+//! A [`PremulRgba`] holds a color whose red, green and blue channels are already multiplied by
+//! its alpha, the form most compositors and GPU textures use. Porter-Duff operators are defined
+//! on premultiplied colors, so [`PremulBlend`] applies them directly, without multiplying each
+//! pixel by its alpha first.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, premul::{F32x4PremulRgba, PremulBlend}, rgba::F32x4Rgba};
+//!
+//! let src = F32x4PremulRgba::from_straight(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5));
+//! let dst = F32x4PremulRgba::from_straight(F32x4Rgba::new(0.0, 0.0, 1.0, 1.0));
+//! let out = BlendMode::SourceOver.apply_premul(src, dst);
+//!
+//! assert_eq!(out.into_rgba(), F32x4Rgba::new(0.5, 0.0, 0.5, 1.0));
+//! assert_eq!(out.to_straight(), F32x4Rgba::new(0.5, 0.0, 0.5, 1.0));
+//! ```
+
+use crate::{
+    BlendMode, RgbaBlend, math,
+    rgba::{F32x4Rgba, Rgba, U8x4Rgba},
+    vec4::F32x4,
+};
+
+/// A color with premultiplied alpha.
+///
+/// Every channel is in the same range as alpha, so for example `r <= a`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(transparent)]
+pub struct PremulRgba<C: Copy>(Rgba<C>);
+
+/// A [`PremulRgba`] with [`f32`] channels in `[0.0, 1.0]`.
+pub type F32x4PremulRgba = PremulRgba<f32>;
+
+/// A [`PremulRgba`] with [`u8`] channels in `[0, 255]`.
+pub type U8x4PremulRgba = PremulRgba<u8>;
+
+impl<C: Copy> PremulRgba<C> {
+    /// Wraps a color whose channels are already premultiplied by its alpha.
+    #[must_use]
+    pub const fn from_premultiplied(color: Rgba<C>) -> Self {
+        Self(color)
+    }
+
+    /// Returns the premultiplied channels.
+    #[must_use]
+    pub const fn into_rgba(self) -> Rgba<C> {
+        self.0
+    }
+
+    /// Returns the alpha channel.
+    #[must_use]
+    pub const fn alpha(self) -> C {
+        self.0.a
+    }
+}
+
+impl F32x4PremulRgba {
+    /// Fully transparent black.
+    pub const TRANSPARENT: Self = Self(F32x4Rgba::TRANSPARENT);
+
+    /// Premultiplies a straight-alpha color.
+    #[must_use]
+    pub fn from_straight(color: F32x4Rgba) -> Self {
+        Self(color.premultiply())
+    }
+
+    /// Converts to straight alpha.
+    ///
+    /// A pixel with zero alpha becomes [`F32x4Rgba::TRANSPARENT`].
+    #[must_use]
+    pub fn to_straight(self) -> F32x4Rgba {
+        self.0.unpremultiply()
+    }
+}
+
+impl U8x4PremulRgba {
+    /// Fully transparent black.
+    pub const TRANSPARENT: Self = Self(U8x4Rgba::TRANSPARENT);
+
+    /// Premultiplies a straight-alpha color.
+    #[must_use]
+    pub const fn from_straight(color: U8x4Rgba) -> Self {
+        Self(color.premultiply())
+    }
+
+    /// Converts to straight alpha, rounding to nearest.
+    ///
+    /// A pixel with zero alpha becomes [`U8x4Rgba::TRANSPARENT`].
+    #[must_use]
+    pub const fn to_straight(self) -> U8x4Rgba {
+        self.0.unpremultiply()
+    }
+
+    /// Blends `self` (source) over `dst` (destination) using integer `SourceOver`.
+    ///
+    /// On premultiplied colors this is `out = src + dst * (1 - src.a)` for every channel.
+    #[must_use]
+    pub const fn source_over(self, dst: Self) -> Self {
+        let inv_a = 255 - self.0.a;
+        Self(U8x4Rgba::new(
+            over_u8(self.0.r, dst.0.r, inv_a),
+            over_u8(self.0.g, dst.0.g, inv_a),
+            over_u8(self.0.b, dst.0.b, inv_a),
+            over_u8(self.0.a, dst.0.a, inv_a),
+        ))
+    }
+}
+
+/// Returns `s + d * (255 - a) / 255` for a premultiplied channel, where `inv_a` is `255 - a`.
+const fn over_u8(s: u8, d: u8, inv_a: u8) -> u8 {
+    s.saturating_add(math::mul_div_255(d, inv_a))
+}
+
+/// Blends premultiplied pixel colors using alpha compositing.
+pub trait PremulBlend {
+    /// What type of channel this blend mode operates on.
+    type Channel: Copy;
+
+    /// Blends two premultiplied colors together using this blend mode.
+    fn apply_premul(
+        &self,
+        src: PremulRgba<Self::Channel>,
+        dst: PremulRgba<Self::Channel>,
+    ) -> PremulRgba<Self::Channel>;
+
+    /// Blend premultiplied `src` over `dst` in place, pixel by pixel.
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    fn apply_premul_slice(
+        &self,
+        src: &[PremulRgba<Self::Channel>],
+        dst: &mut [PremulRgba<Self::Channel>],
+    ) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        for (s, d) in src.iter().zip(dst.iter_mut()) {
+            *d = self.apply_premul(*s, *d);
+        }
+    }
+}
+
+/// Returns the Porter-Duff `(Fa, Fb)` coefficients of `mode` for premultiplied colors, or
+/// `None` if `mode` is not a Porter-Duff mode.
+///
+/// Unlike the [`PorterDuff`](crate::porter_duff::PorterDuff) constants, which fold the source
+/// alpha into `Fa` for straight colors, these are the coefficients of the original paper.
+fn coefficients(mode: BlendMode, sa: f32, da: f32) -> Option<(f32, f32)> {
+    Some(match mode {
+        BlendMode::Clear => (0.0, 0.0),
+        BlendMode::Source => (1.0, 0.0),
+        BlendMode::Destination => (0.0, 1.0),
+        BlendMode::SourceOver => (1.0, 1.0 - sa),
+        BlendMode::DestinationOver => (1.0 - da, 1.0),
+        BlendMode::SourceIn => (da, 0.0),
+        BlendMode::DestinationIn => (0.0, sa),
+        BlendMode::SourceOut => (1.0 - da, 0.0),
+        BlendMode::DestinationOut => (0.0, 1.0 - sa),
+        BlendMode::SourceAtop => (da, 1.0 - sa),
+        BlendMode::DestinationAtop => (1.0 - da, sa),
+        BlendMode::Xor => (1.0 - da, 1.0 - sa),
+        BlendMode::Plus => (1.0, 1.0),
+        _ => return None,
+    })
+}
+
+/// Porter-Duff modes blend premultiplied colors directly; other modes are blended in straight
+/// alpha, converting each pixel.
+impl PremulBlend for BlendMode {
+    type Channel = f32;
+
+    fn apply_premul(&self, src: F32x4PremulRgba, dst: F32x4PremulRgba) -> F32x4PremulRgba {
+        if let Some((fa, fb)) = coefficients(*self, src.0.a, dst.0.a) {
+            let out = F32x4::splat(fa) * F32x4::from(src.0) + F32x4::splat(fb) * F32x4::from(dst.0);
+            return PremulRgba(out.into_rgba());
+        }
+        F32x4PremulRgba::from_straight(self.apply(src.to_straight(), dst.to_straight()))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f32_round_trip() {
+        let c = F32x4Rgba::new(0.5, 0.25, 1.0, 0.5);
+        let p = F32x4PremulRgba::from_straight(c);
+        assert_eq!(p.into_rgba(), F32x4Rgba::new(0.25, 0.125, 0.5, 0.5));
+        assert_eq!(p.alpha(), 0.5);
+        assert_eq!(p.to_straight(), c);
+        assert_eq!(
+            F32x4PremulRgba::TRANSPARENT.to_straight(),
+            F32x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn u8_round_trip() {
+        let c = U8x4Rgba::new(255, 128, 0, 128);
+        let p = U8x4PremulRgba::from_straight(c);
+        assert_eq!(p.into_rgba(), U8x4Rgba::new(128, 64, 0, 128));
+        assert_eq!(p.to_straight(), U8x4Rgba::new(255, 128, 0, 128));
+        assert_eq!(
+            PremulRgba::from_premultiplied(U8x4Rgba::new(9, 9, 9, 0)).to_straight(),
+            U8x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn u8_source_over_matches_f32() {
+        let src = U8x4PremulRgba::from_straight(U8x4Rgba::new(255, 0, 0, 128));
+        let dst = U8x4PremulRgba::from_straight(U8x4Rgba::new(0, 0, 255, 255));
+        let out = src.source_over(dst).into_rgba();
+        assert_eq!(out, U8x4Rgba::new(128, 0, 127, 255));
+        assert_eq!(
+            U8x4PremulRgba::TRANSPARENT.source_over(dst),
+            dst,
+            "transparent source leaves destination unchanged"
+        );
+    }
+
+    #[test]
+    fn porter_duff_modes_blend_directly() {
+        let src = F32x4PremulRgba::from_straight(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5));
+        let dst = F32x4PremulRgba::from_straight(F32x4Rgba::new(0.0, 1.0, 0.0, 0.5));
+        let out = BlendMode::SourceOver.apply_premul(src, dst);
+        assert_eq!(out.into_rgba(), F32x4Rgba::new(0.5, 0.25, 0.0, 0.75));
+        assert_eq!(
+            BlendMode::DestinationOut.apply_premul(src, dst).into_rgba(),
+            F32x4Rgba::new(0.0, 0.25, 0.0, 0.25)
+        );
+    }
+
+    #[test]
+    fn other_modes_round_trip_through_straight() {
+        let src = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+        let dst = F32x4Rgba::new(0.5, 1.0, 0.0, 1.0);
+        let out = BlendMode::Multiply.apply_premul(
+            F32x4PremulRgba::from_straight(src),
+            F32x4PremulRgba::from_straight(dst),
+        );
+        assert_eq!(out.to_straight(), BlendMode::Multiply.apply(src, dst));
+    }
+
+    #[test]
+    fn apply_premul_slice_matches_individual() {
+        let src = [F32x4PremulRgba::from_straight(F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)); 2];
+        let mut dst = [
+            F32x4PremulRgba::TRANSPARENT,
+            F32x4PremulRgba::from_straight(F32x4Rgba::WHITE),
+        ];
+        let expected = [
+            BlendMode::SourceOver.apply_premul(src[0], dst[0]),
+            BlendMode::SourceOver.apply_premul(src[1], dst[1]),
+        ];
+        BlendMode::SourceOver.apply_premul_slice(&src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+}
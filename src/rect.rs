@@ -0,0 +1,106 @@
+//! Axis-aligned pixel rectangles.
+
+/// An axis-aligned rectangle of pixels, with its origin at the top-left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    /// Column of the left edge.
+    pub x: usize,
+
+    /// Row of the top edge.
+    pub y: usize,
+
+    /// Width, in pixels.
+    pub width: usize,
+
+    /// Height, in pixels.
+    pub height: usize,
+}
+
+impl Rect {
+    /// Creates a new `Rect`.
+    #[must_use]
+    pub const fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the column one past the right edge.
+    #[must_use]
+    pub const fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    /// Returns the row one past the bottom edge.
+    #[must_use]
+    pub const fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    /// Returns the number of pixels in the rectangle.
+    #[must_use]
+    pub const fn area(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Returns `true` if the rectangle contains no pixels.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns `true` if the pixel at `(x, y)` is inside the rectangle.
+    #[must_use]
+    pub const fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Returns the pixels inside both rectangles, or an empty rectangle if they do not overlap.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= x || bottom <= y {
+            return Self::default();
+        }
+        Self::new(x, y, right - x, bottom - y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_and_area() {
+        let r = Rect::new(1, 2, 3, 4);
+        assert_eq!((r.right(), r.bottom(), r.area()), (4, 6, 12));
+        assert!(!r.is_empty());
+        assert!(Rect::new(1, 2, 0, 4).is_empty());
+    }
+
+    #[test]
+    fn contains_is_half_open() {
+        let r = Rect::new(1, 1, 2, 2);
+        assert!(r.contains(1, 1));
+        assert!(r.contains(2, 2));
+        assert!(!r.contains(3, 2));
+        assert!(!r.contains(0, 1));
+    }
+
+    #[test]
+    fn intersection_clips_to_overlap() {
+        let a = Rect::new(0, 0, 4, 4);
+        assert_eq!(
+            a.intersection(&Rect::new(2, 1, 5, 2)),
+            Rect::new(2, 1, 2, 2)
+        );
+        assert!(a.intersection(&Rect::new(4, 0, 1, 1)).is_empty());
+        assert_eq!(a.intersection(&a), a);
+    }
+}
@@ -4,9 +4,11 @@
 extern crate std;
 
 use core::fmt;
+use core::ops::{Index, IndexMut};
 use core::ptr;
+use core::str::FromStr;
 
-use crate::math;
+use crate::{backend, channel::Channel, math, policy::OutputPolicy};
 
 /// Four-component vector type for representing RGBA colors.
 ///
@@ -56,17 +58,43 @@ where
 // `bytemuck` impls
 // ---------------------------------------------------------------------------
 
+// SAFETY: `Rgba<C>` is `#[repr(C)]` with four fields of the same type, so it has no padding,
+// and every bit pattern is valid whenever it is valid for `C`.
 #[cfg(feature = "bytemuck")]
-unsafe impl bytemuck::Zeroable for U8x4Rgba {}
+unsafe impl<C: bytemuck::Zeroable + Copy> bytemuck::Zeroable for Rgba<C> {}
 
 #[cfg(feature = "bytemuck")]
-unsafe impl bytemuck::Pod for U8x4Rgba {}
+unsafe impl<C: bytemuck::Pod> bytemuck::Pod for Rgba<C> {}
 
-#[cfg(feature = "bytemuck")]
-unsafe impl bytemuck::Zeroable for F32x4Rgba {}
+// ---------------------------------------------------------------------------
+// `arbitrary` impls
+// ---------------------------------------------------------------------------
 
-#[cfg(feature = "bytemuck")]
-unsafe impl bytemuck::Pod for F32x4Rgba {}
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for U8x4Rgba {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::from(<[u8; 4]>::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; 4]>::size_hint(depth)
+    }
+}
+
+/// Channels are in `[0.0, 1.0]`, in steps of `1 / (2^24 - 1)`, so both ends are reachable.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for F32x4Rgba {
+    #[allow(clippy::cast_precision_loss)]
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        const MAX: u32 = (1 << f32::MANTISSA_DIGITS) - 1;
+        let bits = <[u32; 4]>::arbitrary(u)?;
+        Ok(Self::from(bits.map(|b| (b >> 8) as f32 / MAX as f32)))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u32; 4]>::size_hint(depth)
+    }
+}
 
 // ---------------------------------------------------------------------------
 // Structural conversions
@@ -96,16 +124,137 @@ impl<C: Copy> From<Rgba<C>> for (C, C, C, C) {
     }
 }
 
+impl<C: Copy> Rgba<C> {
+    /// Returns the channels as an array, in `[r, g, b, a]` order, without copying.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::U8x4Rgba;
+    ///
+    /// let c = U8x4Rgba::new(1, 2, 3, 4);
+    /// assert_eq!(c.as_array(), &[1, 2, 3, 4]);
+    /// assert_eq!(c[3], 4);
+    /// assert_eq!(c.into_iter().map(u32::from).sum::<u32>(), 10);
+    /// ```
+    #[must_use]
+    pub const fn as_array(&self) -> &[C; 4] {
+        // SAFETY: `Rgba<C>` is `#[repr(C)]` with four fields of type `C` and no padding, so it has
+        // the layout of `[C; 4]`.
+        unsafe { &*ptr::from_ref(self).cast::<[C; 4]>() }
+    }
+
+    /// Returns the channels as a mutable array, in `[r, g, b, a]` order, without copying.
+    #[must_use]
+    pub const fn as_array_mut(&mut self) -> &mut [C; 4] {
+        // SAFETY: As in `as_array`.
+        unsafe { &mut *ptr::from_mut(self).cast::<[C; 4]>() }
+    }
+
+    /// Returns an iterator over the channels, in `[r, g, b, a]` order.
+    pub fn iter(&self) -> core::slice::Iter<'_, C> {
+        self.as_array().iter()
+    }
+
+    /// Returns an iterator over mutable references to the channels, in `[r, g, b, a]` order.
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, C> {
+        self.as_array_mut().iter_mut()
+    }
+
+    /// Reinterprets a slice of colors as a slice of `[r, g, b, a]` arrays, without copying.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::U8x4Rgba;
+    ///
+    /// let pixels = [U8x4Rgba::RED, U8x4Rgba::BLUE];
+    /// assert_eq!(U8x4Rgba::as_arrays(&pixels), &[[255, 0, 0, 255], [0, 0, 255, 255]]);
+    /// assert_eq!(U8x4Rgba::as_arrays(&pixels).as_flattened().len(), 8);
+    /// ```
+    #[must_use]
+    pub const fn as_arrays(pixels: &[Self]) -> &[[C; 4]] {
+        // SAFETY: `Rgba<C>` has the layout of `[C; 4]`, so the slices have the same layout.
+        unsafe { core::slice::from_raw_parts(pixels.as_ptr().cast(), pixels.len()) }
+    }
+
+    /// Reinterprets a mutable slice of colors as a slice of `[r, g, b, a]` arrays.
+    #[must_use]
+    pub const fn as_arrays_mut(pixels: &mut [Self]) -> &mut [[C; 4]] {
+        // SAFETY: As in `as_arrays`.
+        unsafe { core::slice::from_raw_parts_mut(pixels.as_mut_ptr().cast(), pixels.len()) }
+    }
+
+    /// Reinterprets a slice of `[r, g, b, a]` arrays as a slice of colors, without copying.
+    #[must_use]
+    pub const fn from_arrays(arrays: &[[C; 4]]) -> &[Self] {
+        // SAFETY: As in `as_arrays`.
+        unsafe { core::slice::from_raw_parts(arrays.as_ptr().cast(), arrays.len()) }
+    }
+
+    /// Reinterprets a mutable slice of `[r, g, b, a]` arrays as a slice of colors.
+    #[must_use]
+    pub const fn from_arrays_mut(arrays: &mut [[C; 4]]) -> &mut [Self] {
+        // SAFETY: As in `as_arrays`.
+        unsafe { core::slice::from_raw_parts_mut(arrays.as_mut_ptr().cast(), arrays.len()) }
+    }
+}
+
 impl<C: Copy> AsRef<[C]> for Rgba<C> {
     fn as_ref(&self) -> &[C] {
-        // Safety: Rgba<C> is repr(C) with 4 contiguous elements of type C.
-        unsafe { core::slice::from_raw_parts(ptr::from_ref(self).cast::<C>(), 4) }
+        self.as_array()
     }
 }
 
 impl<C: Copy> AsMut<[C]> for Rgba<C> {
     fn as_mut(&mut self) -> &mut [C] {
-        unsafe { core::slice::from_raw_parts_mut(ptr::from_mut(self).cast::<C>(), 4) }
+        self.as_array_mut()
+    }
+}
+
+/// Indexes the channels in `[r, g, b, a]` order.
+///
+/// ## Panics
+///
+/// If `index` is `4` or more.
+impl<C: Copy> Index<usize> for Rgba<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.as_array()[index]
+    }
+}
+
+impl<C: Copy> IndexMut<usize> for Rgba<C> {
+    fn index_mut(&mut self, index: usize) -> &mut C {
+        &mut self.as_array_mut()[index]
+    }
+}
+
+impl<C: Copy> IntoIterator for Rgba<C> {
+    type Item = C;
+    type IntoIter = core::array::IntoIter<C, 4>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        <[C; 4]>::from(self).into_iter()
+    }
+}
+
+impl<'a, C: Copy> IntoIterator for &'a Rgba<C> {
+    type Item = &'a C;
+    type IntoIter = core::slice::Iter<'a, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, C: Copy> IntoIterator for &'a mut Rgba<C> {
+    type Item = &'a mut C;
+    type IntoIter = core::slice::IterMut<'a, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
@@ -119,14 +268,215 @@ impl fmt::Display for Rgba<u8> {
     }
 }
 
-impl fmt::Display for Rgba<f32> {
+impl fmt::Display for Rgba<u16> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
     }
 }
 
+/// Formats as `rgba(r, g, b, a)`, or with the alternate flag (`{:#}`) as percentages to one
+/// decimal place (or the given precision), for logs and golden-test output.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::rgba::F32x4Rgba;
+///
+/// let c = F32x4Rgba::new(1.0, 0.5, 0.0, 0.25);
+/// assert_eq!(c.to_string(), "rgba(1, 0.5, 0, 0.25)");
+/// assert_eq!(format!("{c:#}"), "rgba(100.0%, 50.0%, 0.0%, 25.0%)");
+/// assert_eq!(format!("{c:#.0}"), "rgba(100%, 50%, 0%, 25%)");
+/// ```
+impl fmt::Display for Rgba<f32> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let precision = f.precision().unwrap_or(1);
+            f.write_str("rgba(")?;
+            for (i, channel) in <[f32; 4]>::from(*self).into_iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{:.*}%", precision, channel * 100.0)?;
+            }
+            f.write_str(")")
+        } else {
+            write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+/// Formats as `rrggbbaa`; the alternate flag (`{:#x}`) adds the `#` prefix that [`FromStr`]
+/// expects, as it adds `0x` for integers.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::rgba::U8x4Rgba;
+///
+/// let c = U8x4Rgba::new(255, 128, 0, 10);
+/// assert_eq!(format!("{c:x}"), "ff80000a");
+/// assert_eq!(format!("{c:#x}"), "#ff80000a");
+/// assert_eq!(format!("{c:#X}"), "#FF80000A");
+/// assert_eq!(format!("{c:#x}").parse(), Ok(c));
+/// ```
+impl fmt::LowerHex for Rgba<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("#")?;
+        }
+        write!(
+            f,
+            "{:02x}{:02x}{:02x}{:02x}",
+            self.r, self.g, self.b, self.a
+        )
+    }
+}
+
+/// Formats as `RRGGBBAA`; the alternate flag (`{:#X}`) adds the `#` prefix that [`FromStr`]
+/// expects.
+impl fmt::UpperHex for Rgba<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("#")?;
+        }
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}{:02X}",
+            self.r, self.g, self.b, self.a
+        )
+    }
+}
+
+/// Formats as `rgba(r, g, b, a)`, like [`Display`](fmt::Display).
+///
+/// _This implementation requires the `defmt` feature._
+#[cfg(feature = "defmt")]
+impl defmt::Format for Rgba<u8> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "rgba({=u8}, {=u8}, {=u8}, {=u8})",
+            self.r,
+            self.g,
+            self.b,
+            self.a
+        );
+    }
+}
+
+/// Formats as `rgba(r, g, b, a)`, like [`Display`](fmt::Display).
+///
+/// _This implementation requires the `defmt` feature._
+#[cfg(feature = "defmt")]
+impl defmt::Format for Rgba<f32> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "rgba({=f32}, {=f32}, {=f32}, {=f32})",
+            self.r,
+            self.g,
+            self.b,
+            self.a
+        );
+    }
+}
+
 // ---------------------------------------------------------------------------
-// Eq + Hash for u8
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// Error returned when parsing a color from a hex string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseColorError {
+    /// The string does not start with `#`.
+    MissingHash,
+
+    /// The number of hex digits after `#` is not 3, 4, 6 or 8.
+    InvalidLength(usize),
+
+    /// A character after `#` is not a hex digit.
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHash => f.write_str("hex color must start with '#'"),
+            Self::InvalidLength(len) => {
+                write!(f, "hex color must have 3, 4, 6 or 8 digits, found {len}")
+            }
+            Self::InvalidDigit(c) => write!(f, "invalid hex digit: {c:?}"),
+        }
+    }
+}
+
+impl core::error::Error for ParseColorError {}
+
+/// Parses a CSS-style hex color: `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, in either case.
+///
+/// Colors without an alpha component are opaque, and single digits are repeated, so `#f80`
+/// is `#ff8800`.
+///
+/// ## Errors
+///
+/// Returns a [`ParseColorError`] if the string is not one of the forms above.
+///
+/// ## Examples
+///
+/// ```rust
+/// use alpha_blend::rgba::{ParseColorError, U8x4Rgba};
+///
+/// assert_eq!("#ff8000".parse(), Ok(U8x4Rgba::new(255, 128, 0, 255)));
+/// assert_eq!("#F808".parse(), Ok(U8x4Rgba::new(255, 136, 0, 136)));
+/// assert_eq!("ff8000".parse::<U8x4Rgba>(), Err(ParseColorError::MissingHash));
+/// assert_eq!("#ff80".parse::<U8x4Rgba>(), Ok(U8x4Rgba::new(255, 255, 136, 0)));
+/// assert_eq!("#ff800".parse::<U8x4Rgba>(), Err(ParseColorError::InvalidLength(5)));
+/// ```
+impl FromStr for U8x4Rgba {
+    type Err = ParseColorError;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').ok_or(ParseColorError::MissingHash)?;
+        let mut digits = [0_u8; 8];
+        let mut len = 0;
+        for c in hex.chars() {
+            let digit = c.to_digit(16).ok_or(ParseColorError::InvalidDigit(c))?;
+            if let Some(slot) = digits.get_mut(len) {
+                *slot = digit as u8;
+            }
+            len += 1;
+        }
+        let channels: [u8; 4] = match len {
+            3 | 4 => core::array::from_fn(|i| if i < len { digits[i] * 17 } else { u8::MAX }),
+            6 | 8 => core::array::from_fn(|i| {
+                if i * 2 < len {
+                    digits[i * 2] << 4 | digits[i * 2 + 1]
+                } else {
+                    u8::MAX
+                }
+            }),
+            _ => return Err(ParseColorError::InvalidLength(len)),
+        };
+        Ok(Self::from(channels))
+    }
+}
+
+/// Parses a hex color as [`U8x4Rgba`] does, converting each channel to `[0.0, 1.0]`.
+///
+/// ## Errors
+///
+/// Returns a [`ParseColorError`] if the string is not a valid hex color.
+impl FromStr for F32x4Rgba {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U8x4Rgba::from_str(s).map(Self::from)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Eq + Hash for u8 and u16
 // ---------------------------------------------------------------------------
 
 impl Eq for Rgba<u8> {}
@@ -140,6 +490,17 @@ impl core::hash::Hash for Rgba<u8> {
     }
 }
 
+impl Eq for Rgba<u16> {}
+
+impl core::hash::Hash for Rgba<u16> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.r.hash(state);
+        self.g.hash(state);
+        self.b.hash(state);
+        self.a.hash(state);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Rgba<C> inherent methods
 // ---------------------------------------------------------------------------
@@ -172,6 +533,67 @@ where
     pub const fn alpha(&self) -> C {
         self.a
     }
+
+    /// Returns this color with its alpha component replaced by `a`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::U8x4Rgba;
+    ///
+    /// assert_eq!(U8x4Rgba::RED.with_alpha(128), U8x4Rgba::new(255, 0, 0, 128));
+    /// ```
+    #[must_use]
+    pub const fn with_alpha(self, a: C) -> Self {
+        Self { a, ..self }
+    }
+}
+
+impl<C: Channel> Rgba<C> {
+    /// Returns this color with full alpha, keeping its color channels.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::F32x4Rgba;
+    ///
+    /// let faint = F32x4Rgba::new(0.0, 0.5, 1.0, 0.25);
+    /// assert_eq!(faint.opaque(), F32x4Rgba::new(0.0, 0.5, 1.0, 1.0));
+    /// ```
+    #[must_use]
+    pub const fn opaque(self) -> Self {
+        self.with_alpha(C::ONE)
+    }
+
+    /// Composites this straight-alpha color over an opaque `background`, returning an opaque
+    /// color, as when exporting to a format without alpha such as JPEG.
+    ///
+    /// The alpha of `background` is ignored. Colors blend as [`PorterDuff::SRC_OVER`], so
+    /// `u8` channels use the same integer math.
+    ///
+    /// [`PorterDuff::SRC_OVER`]: crate::porter_duff::PorterDuff::SRC_OVER
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::U8x4Rgba;
+    ///
+    /// let shadow = U8x4Rgba::new(0, 0, 0, 64);
+    /// assert_eq!(shadow.flatten_over(U8x4Rgba::WHITE), U8x4Rgba::new(191, 191, 191, 255));
+    /// ```
+    #[must_use]
+    pub fn flatten_over(self, background: Self) -> Self {
+        let out = C::weighted_sum(self, self.a, background, C::ONE.sub(self.a));
+        Self::new(out.r, out.g, out.b, C::ONE)
+    }
+
+    /// Composites every pixel of `pixels` over an opaque `background` in place; see
+    /// [`flatten_over`](Self::flatten_over).
+    pub fn flatten_slice_over(pixels: &mut [Self], background: Self) {
+        for pixel in pixels {
+            *pixel = pixel.flatten_over(background);
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -181,9 +603,21 @@ where
 /// Four-component RGBA color with a component type of [`u8`].
 pub type U8x4Rgba = Rgba<u8>;
 
+/// Four-component RGBA color with a component type of [`u16`].
+pub type U16x4Rgba = Rgba<u16>;
+
 /// Four-component RGBA color with a component type of [`f32`].
 pub type F32x4Rgba = Rgba<f32>;
 
+/// Four-component RGBA color with a component type of [`f64`].
+pub type F64x4Rgba = Rgba<f64>;
+
+/// Four-component RGBA color with a component type of [`half::f16`], as in `RGBA16F` textures.
+///
+/// _This type requires the `half` feature._
+#[cfg(feature = "half")]
+pub type F16x4Rgba = Rgba<half::f16>;
+
 // ---------------------------------------------------------------------------
 // Constants
 // ---------------------------------------------------------------------------
@@ -197,6 +631,35 @@ impl U8x4Rgba {
 
     /// Fully opaque white (`(255, 255, 255, 255)`).
     pub const WHITE: Self = Self::new(255, 255, 255, 255);
+
+    /// Fully opaque red (`(255, 0, 0, 255)`).
+    pub const RED: Self = Self::new(255, 0, 0, 255);
+
+    /// Fully opaque green (`(0, 255, 0, 255)`).
+    pub const GREEN: Self = Self::new(0, 255, 0, 255);
+
+    /// Fully opaque blue (`(0, 0, 255, 255)`).
+    pub const BLUE: Self = Self::new(0, 0, 255, 255);
+
+    /// Fully opaque yellow (`(255, 255, 0, 255)`).
+    pub const YELLOW: Self = Self::new(255, 255, 0, 255);
+
+    /// Fully opaque cyan (`(0, 255, 255, 255)`).
+    pub const CYAN: Self = Self::new(0, 255, 255, 255);
+
+    /// Fully opaque magenta (`(255, 0, 255, 255)`).
+    pub const MAGENTA: Self = Self::new(255, 0, 255, 255);
+}
+
+impl U16x4Rgba {
+    /// Fully transparent black (`(0, 0, 0, 0)`).
+    pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+    /// Fully opaque black (`(0, 0, 0, 65535)`).
+    pub const BLACK: Self = Self::new(0, 0, 0, u16::MAX);
+
+    /// Fully opaque white (`(65535, 65535, 65535, 65535)`).
+    pub const WHITE: Self = Self::new(u16::MAX, u16::MAX, u16::MAX, u16::MAX);
 }
 
 impl F32x4Rgba {
@@ -208,6 +671,62 @@ impl F32x4Rgba {
 
     /// Fully opaque white (`(1.0, 1.0, 1.0, 1.0)`).
     pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+
+    /// Fully opaque red (`(1.0, 0.0, 0.0, 1.0)`).
+    pub const RED: Self = Self::new(1.0, 0.0, 0.0, 1.0);
+
+    /// Fully opaque green (`(0.0, 1.0, 0.0, 1.0)`).
+    pub const GREEN: Self = Self::new(0.0, 1.0, 0.0, 1.0);
+
+    /// Fully opaque blue (`(0.0, 0.0, 1.0, 1.0)`).
+    pub const BLUE: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+
+    /// Fully opaque yellow (`(1.0, 1.0, 0.0, 1.0)`).
+    pub const YELLOW: Self = Self::new(1.0, 1.0, 0.0, 1.0);
+
+    /// Fully opaque cyan (`(0.0, 1.0, 1.0, 1.0)`).
+    pub const CYAN: Self = Self::new(0.0, 1.0, 1.0, 1.0);
+
+    /// Fully opaque magenta (`(1.0, 0.0, 1.0, 1.0)`).
+    pub const MAGENTA: Self = Self::new(1.0, 0.0, 1.0, 1.0);
+}
+
+impl F64x4Rgba {
+    /// Fully transparent black (`(0.0, 0.0, 0.0, 0.0)`).
+    pub const TRANSPARENT: Self = Self::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Fully opaque black (`(0.0, 0.0, 0.0, 1.0)`).
+    pub const BLACK: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+
+    /// Fully opaque white (`(1.0, 1.0, 1.0, 1.0)`).
+    pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+}
+
+#[cfg(feature = "half")]
+impl F16x4Rgba {
+    /// Fully transparent black (`(0.0, 0.0, 0.0, 0.0)`).
+    pub const TRANSPARENT: Self = Self::new(
+        half::f16::ZERO,
+        half::f16::ZERO,
+        half::f16::ZERO,
+        half::f16::ZERO,
+    );
+
+    /// Fully opaque black (`(0.0, 0.0, 0.0, 1.0)`).
+    pub const BLACK: Self = Self::new(
+        half::f16::ZERO,
+        half::f16::ZERO,
+        half::f16::ZERO,
+        half::f16::ONE,
+    );
+
+    /// Fully opaque white (`(1.0, 1.0, 1.0, 1.0)`).
+    pub const WHITE: Self = Self::new(
+        half::f16::ONE,
+        half::f16::ONE,
+        half::f16::ONE,
+        half::f16::ONE,
+    );
 }
 
 // ---------------------------------------------------------------------------
@@ -216,171 +735,723 @@ impl F32x4Rgba {
 
 const MAX: f32 = 255.0;
 
-impl U8x4Rgba {
-    /// Creates a new `U8x4Rgba` instance with `0` for all components.
-    #[must_use]
-    pub const fn zeroed() -> Self {
-        Self::new(0, 0, 0, 0)
+impl U8x4Rgba {
+    /// Creates a new `U8x4Rgba` instance with `0` for all components.
+    #[must_use]
+    pub const fn zeroed() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Constructs an opaque `U8x4Rgba` from a `0x00RRGGBB` packed pixel.
+    ///
+    /// The alpha channel is set to 255 (fully opaque). The top byte of
+    /// `pixel` is ignored.
+    #[must_use]
+    pub const fn from_rgb_u32(pixel: u32) -> Self {
+        Self::new(
+            ((pixel >> 16) & 0xFF) as u8,
+            ((pixel >> 8) & 0xFF) as u8,
+            (pixel & 0xFF) as u8,
+            255,
+        )
+    }
+
+    /// Packs this color into a `0x00RRGGBB` `u32`, discarding the alpha channel.
+    #[must_use]
+    pub const fn to_rgb_u32(self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
+    }
+
+    /// Unpacks a `0xAARRGGBB` `u32`, as used by Windows, Cairo and Skia's native 32-bit format.
+    #[must_use]
+    pub const fn from_argb_u32(word: u32) -> Self {
+        let [a, r, g, b] = word.to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+
+    /// Packs this color into a `0xAARRGGBB` `u32`.
+    #[must_use]
+    pub const fn to_argb_u32(self) -> u32 {
+        u32::from_be_bytes([self.a, self.r, self.g, self.b])
+    }
+
+    /// Unpacks a `0xRRGGBBAA` `u32`, as used by CSS hex colors.
+    #[must_use]
+    pub const fn from_rgba_u32(word: u32) -> Self {
+        let [r, g, b, a] = word.to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+
+    /// Packs this color into a `0xRRGGBBAA` `u32`.
+    #[must_use]
+    pub const fn to_rgba_u32(self) -> u32 {
+        u32::from_be_bytes([self.r, self.g, self.b, self.a])
+    }
+
+    /// Unpacks a `0xAABBGGRR` `u32`, the layout of RGBA bytes read as a little-endian word.
+    #[must_use]
+    pub const fn from_abgr_u32(word: u32) -> Self {
+        let [a, b, g, r] = word.to_be_bytes();
+        Self::new(r, g, b, a)
+    }
+
+    /// Packs this color into a `0xAABBGGRR` `u32`.
+    #[must_use]
+    pub const fn to_abgr_u32(self) -> u32 {
+        u32::from_be_bytes([self.a, self.b, self.g, self.r])
+    }
+
+    /// Blends `self` (source) over `dst` (destination) using integer `SourceOver`.
+    ///
+    /// Equivalent to Porter-Duff `SRC_OVER`:
+    /// `out = src * src.a + dst * (1 - src.a)`
+    ///
+    /// Uses [`math::div_255`] for division by 255, which avoids floating-point and
+    /// is exact (rounding down) for all inputs in range.
+    #[must_use]
+    pub fn source_over(self, dst: Self) -> Self {
+        let a = u16::from(self.a);
+        let inv_a = 255 - a;
+
+        let blend_channel =
+            |s: u8, d: u8| -> u8 { math::div_255(u16::from(s) * a + u16::from(d) * inv_a) };
+
+        // Porter-Duff SRC_OVER: out_a = src_a + dst_a * (1 - src_a)
+        // In integer form: (a * 255 + dst.a * (255 - a)) / 255
+        let out_a = math::div_255(a * 255 + u16::from(dst.a) * inv_a);
+
+        Self::new(
+            blend_channel(self.r, dst.r),
+            blend_channel(self.g, dst.g),
+            blend_channel(self.b, dst.b),
+            out_a,
+        )
+    }
+
+    /// Converts from straight alpha to premultiplied alpha.
+    ///
+    /// `premultiplied.rgb = straight.rgb * straight.a / 255`, using [`math::mul_div_255`].
+    #[must_use]
+    pub const fn premultiply(self) -> Self {
+        Self::new(
+            math::mul_div_255(self.r, self.a),
+            math::mul_div_255(self.g, self.a),
+            math::mul_div_255(self.b, self.a),
+            self.a,
+        )
+    }
+
+    /// Converts from premultiplied alpha to straight alpha, rounding to nearest.
+    ///
+    /// Channels greater than alpha are clamped to `255`. A pixel with `alpha == 0` has no
+    /// recoverable color, so it becomes [`TRANSPARENT`](Self::TRANSPARENT) instead of dividing
+    /// by zero.
+    #[must_use]
+    pub const fn unpremultiply(self) -> Self {
+        if self.a == 0 {
+            return Self::TRANSPARENT;
+        }
+        Self::new(
+            unpremultiply_u8(self.r, self.a),
+            unpremultiply_u8(self.g, self.a),
+            unpremultiply_u8(self.b, self.a),
+            self.a,
+        )
+    }
+
+    /// Converts every pixel of `pixels` from straight alpha to premultiplied alpha, in place.
+    pub fn premultiply_slice(pixels: &mut [Self]) {
+        for pixel in pixels {
+            *pixel = pixel.premultiply();
+        }
+    }
+
+    /// Converts every pixel of `pixels` from premultiplied alpha to straight alpha, in place.
+    ///
+    /// Pixels with `alpha == 0` become [`TRANSPARENT`](Self::TRANSPARENT), as in
+    /// [`unpremultiply`](Self::unpremultiply).
+    pub fn unpremultiply_slice(pixels: &mut [Self]) {
+        for pixel in pixels {
+            *pixel = pixel.unpremultiply();
+        }
+    }
+
+    /// Linearly interpolates between `self` and `other` by the 8-bit weight `t`, rounding to
+    /// nearest.
+    ///
+    /// `t = 0` returns `self`; `t = 255` returns `other`.
+    #[must_use]
+    pub const fn lerp(self, other: Self, t: u8) -> Self {
+        Self::new(
+            lerp_u8(self.r, other.r, t),
+            lerp_u8(self.g, other.g, t),
+            lerp_u8(self.b, other.b, t),
+            lerp_u8(self.a, other.a, t),
+        )
+    }
+
+    /// Blends every pixel of `src` over the matching pixel of `dst` in place, as
+    /// [`source_over`](Self::source_over).
+    ///
+    /// With the `simd` feature, buffers of at least [`SIMD_THRESHOLD`] pixels are blended with
+    /// SSE2 or AVX2 kernels on x86-64; with the `wasm_simd` feature, with a `simd128` kernel on
+    /// wasm32. [`backend::set_backend`] selecting [`Backend::Scalar`] disables either. Results
+    /// are identical either way.
+    ///
+    /// [`SIMD_THRESHOLD`]: backend::SIMD_THRESHOLD
+    /// [`Backend::Scalar`]: backend::Backend::Scalar
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn source_over_slice(src: &[Self], dst: &mut [Self]) {
+        assert_eq!(
+            src.len(),
+            dst.len(),
+            "src and dst slices must have the same length"
+        );
+        let done = match backend::backend().resolve(dst.len()) {
+            #[cfg(any(feature = "simd", feature = "wasm_simd"))]
+            backend::Backend::Simd => crate::simd::source_over_prefix(src, dst),
+            _ => 0,
+        };
+        for (s, d) in src[done..].iter().zip(&mut dst[done..]) {
+            *d = s.source_over(*d);
+        }
+    }
+
+    /// Returns `true` if this pixel is fully transparent (`alpha == 0`).
+    #[must_use]
+    pub const fn is_transparent(self) -> bool {
+        self.a == 0
+    }
+
+    /// Returns `true` if this pixel is fully opaque (`alpha == 255`).
+    #[must_use]
+    pub const fn is_opaque(self) -> bool {
+        self.a == 255
+    }
+}
+
+/// Interpolates `a` to `b` by the 8-bit weight `t`, as `(a * (255 - t) + b * t) / 255` rounded
+/// to nearest.
+#[allow(clippy::cast_possible_truncation)]
+const fn lerp_u8(a: u8, b: u8, t: u8) -> u8 {
+    ((a as u16 * (255 - t as u16) + b as u16 * t as u16 + 127) / 255) as u8
+}
+
+/// Divides a premultiplied channel by a non-zero alpha, rounding to nearest.
+#[allow(clippy::cast_possible_truncation)]
+const fn unpremultiply_u8(c: u8, a: u8) -> u8 {
+    let a = a as u16;
+    let v = (c as u16 * 255 + a / 2) / a;
+    if v > 255 { 255 } else { v as u8 }
+}
+
+// ---------------------------------------------------------------------------
+// U16 helpers
+// ---------------------------------------------------------------------------
+
+const MAX_U16: f32 = 65535.0;
+
+impl U16x4Rgba {
+    /// Creates a new `U16x4Rgba` instance with `0` for all components.
+    #[must_use]
+    pub const fn zeroed() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+
+    /// Blends `self` (source) over `dst` (destination) using integer `SourceOver`.
+    ///
+    /// The 16-bit equivalent of [`U8x4Rgba::source_over`], dividing by `65535` and rounding
+    /// down. Other blend modes are available through the `f32` working depth with
+    /// [`bit_depth::blend_mixed`](crate::bit_depth::blend_mixed), which keeps 16-bit precision.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn source_over(self, dst: Self) -> Self {
+        const MAX: u32 = u16::MAX as u32;
+        let a = u32::from(self.a);
+        let inv_a = MAX - a;
+
+        let blend_channel =
+            |s: u16, d: u16| -> u16 { ((u32::from(s) * a + u32::from(d) * inv_a) / MAX) as u16 };
+
+        // Porter-Duff SRC_OVER: out_a = src_a + dst_a * (1 - src_a)
+        let out_a = ((a * MAX + u32::from(dst.a) * inv_a) / MAX) as u16;
+
+        Self::new(
+            blend_channel(self.r, dst.r),
+            blend_channel(self.g, dst.g),
+            blend_channel(self.b, dst.b),
+            out_a,
+        )
+    }
+
+    /// Returns `true` if this pixel is fully transparent (`alpha == 0`).
+    #[must_use]
+    pub const fn is_transparent(self) -> bool {
+        self.a == 0
+    }
+
+    /// Returns `true` if this pixel is fully opaque (`alpha == 65535`).
+    #[must_use]
+    pub const fn is_opaque(self) -> bool {
+        self.a == u16::MAX
+    }
+}
+
+// ---------------------------------------------------------------------------
+// F32 helpers
+// ---------------------------------------------------------------------------
+
+impl F32x4Rgba {
+    /// Creates a new `F32x4Rgba` instance with `0` for all components.
+    #[must_use]
+    pub const fn zeroed() -> Self {
+        Self::new(0.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Clamps all channels to `[0.0, 1.0]`.
+    ///
+    /// Necessary after [`crate::BlendMode::Plus`], which can produce values > 1.0.
+    #[must_use]
+    pub const fn clamp(self) -> Self {
+        Self::new(
+            math::clamp01(self.r),
+            math::clamp01(self.g),
+            math::clamp01(self.b),
+            math::clamp01(self.a),
+        )
+    }
+
+    /// Clamps all channels to `[0.0, 1.0]`, replacing `NaN` with `0.0`.
+    ///
+    /// Unlike [`clamp`](Self::clamp), the result is always a valid color, which makes it suitable
+    /// for sanitizing HDR or untrusted buffers before blending.
+    #[must_use]
+    pub const fn clamp_unit(self) -> Self {
+        const fn unit(v: f32) -> f32 {
+            if v.is_nan() { 0.0 } else { math::clamp01(v) }
+        }
+        Self::new(unit(self.r), unit(self.g), unit(self.b), unit(self.a))
+    }
+
+    /// Returns `true` if every channel is in `[0.0, 1.0]`; `NaN` is not.
+    #[must_use]
+    pub const fn is_normalized(self) -> bool {
+        const fn unit(v: f32) -> bool {
+            v >= 0.0 && v <= 1.0
+        }
+        unit(self.r) && unit(self.g) && unit(self.b) && unit(self.a)
+    }
+
+    /// Returns `true` if any channel is `NaN`.
+    #[must_use]
+    pub const fn has_nan(self) -> bool {
+        self.r.is_nan() || self.g.is_nan() || self.b.is_nan() || self.a.is_nan()
+    }
+
+    /// Applies [`clamp_unit`](Self::clamp_unit) to every pixel of `pixels`, in place.
+    pub fn clamp_unit_slice(pixels: &mut [Self]) {
+        for pixel in pixels {
+            *pixel = pixel.clamp_unit();
+        }
+    }
+
+    /// Returns `true` if every pixel of `pixels` [`is_normalized`](Self::is_normalized).
+    #[must_use]
+    pub fn is_normalized_slice(pixels: &[Self]) -> bool {
+        pixels.iter().all(|p| p.is_normalized())
+    }
+
+    /// Returns `true` if any pixel of `pixels` [`has_nan`](Self::has_nan).
+    #[must_use]
+    pub fn has_nan_slice(pixels: &[Self]) -> bool {
+        pixels.iter().any(|p| p.has_nan())
+    }
+
+    /// Converts from straight alpha to premultiplied alpha.
+    ///
+    /// `premultiplied.rgb = straight.rgb * straight.a`
+    #[must_use]
+    pub fn premultiply(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Converts from premultiplied alpha to straight alpha.
+    ///
+    /// A pixel with `alpha == 0` has no recoverable color, so it becomes
+    /// [`TRANSPARENT`](Self::TRANSPARENT) instead of dividing by zero.
+    #[must_use]
+    pub fn unpremultiply(self) -> Self {
+        if self.a == 0.0 {
+            return Self::TRANSPARENT;
+        }
+        Self::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    /// Converts every pixel of `pixels` from straight alpha to premultiplied alpha, in place.
+    pub fn premultiply_slice(pixels: &mut [Self]) {
+        for pixel in pixels {
+            *pixel = pixel.premultiply();
+        }
+    }
+
+    /// Converts every pixel of `pixels` from premultiplied alpha to straight alpha, in place.
+    ///
+    /// Pixels with `alpha == 0` become [`TRANSPARENT`](Self::TRANSPARENT), as in
+    /// [`unpremultiply`](Self::unpremultiply).
+    pub fn unpremultiply_slice(pixels: &mut [Self]) {
+        for pixel in pixels {
+            *pixel = pixel.unpremultiply();
+        }
+    }
+
+    /// Linearly interpolates between `self` and `other` by `t` (clamped to `[0.0, 1.0]`).
+    ///
+    /// `t = 0.0` returns `self`; `t = 1.0` returns `other`.
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self::new(
+            math::lerp(self.r, other.r, t),
+            math::lerp(self.g, other.g, t),
+            math::lerp(self.b, other.b, t),
+            math::lerp(self.a, other.a, t),
+        )
+    }
+
+    /// Interpolates between `self` and `other` by `t` (clamped to `[0.0, 1.0]`) in premultiplied
+    /// alpha, returning a straight-alpha color.
+    ///
+    /// Unlike [`lerp`](Self::lerp), a transparent color contributes no color of its own, so
+    /// fading to or from transparency does not darken (or otherwise tint) the visible color.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::F32x4Rgba;
+    ///
+    /// let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+    /// assert_eq!(red.lerp(F32x4Rgba::TRANSPARENT, 0.5), F32x4Rgba::new(0.5, 0.0, 0.0, 0.5));
+    /// assert_eq!(
+    ///     red.lerp_premultiplied(F32x4Rgba::TRANSPARENT, 0.5),
+    ///     F32x4Rgba::new(1.0, 0.0, 0.0, 0.5)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn lerp_premultiplied(self, other: Self, t: f32) -> Self {
+        self.premultiply()
+            .lerp(other.premultiply(), t)
+            .unpremultiply()
+    }
+
+    /// Crossfades from `from` to `to` by `t` into `dst`, as [`lerp`](Self::lerp) on each pixel.
+    ///
+    /// ## Panics
+    ///
+    /// If `from`, `to` and `dst` do not all have the same length.
+    pub fn lerp_slice(from: &[Self], to: &[Self], dst: &mut [Self], t: f32) {
+        Self::crossfade(from, to, dst, |a, b| a.lerp(b, t));
+    }
+
+    /// Crossfades from `from` to `to` by `t` into `dst`, as
+    /// [`lerp_premultiplied`](Self::lerp_premultiplied) on each pixel.
+    ///
+    /// ## Panics
+    ///
+    /// If `from`, `to` and `dst` do not all have the same length.
+    pub fn lerp_premultiplied_slice(from: &[Self], to: &[Self], dst: &mut [Self], t: f32) {
+        Self::crossfade(from, to, dst, |a, b| a.lerp_premultiplied(b, t));
+    }
+
+    /// Writes `mix` of each pair of pixels of `from` and `to` to `dst`.
+    fn crossfade(from: &[Self], to: &[Self], dst: &mut [Self], mix: impl Fn(Self, Self) -> Self) {
+        assert!(
+            from.len() == to.len() && to.len() == dst.len(),
+            "from, to and dst slices must have the same length"
+        );
+        for ((a, b), d) in from.iter().zip(to).zip(dst) {
+            *d = mix(*a, *b);
+        }
+    }
+
+    /// Multiplies all four channels, including alpha, by those of `other`.
+    ///
+    /// This is [`BlendMode::Modulate`](crate::BlendMode::Modulate); multiplying premultiplied
+    /// colors gives the same result, premultiplied.
+    #[must_use]
+    pub fn modulate(self, other: Self) -> Self {
+        Self::new(
+            self.r * other.r,
+            self.g * other.g,
+            self.b * other.b,
+            self.a * other.a,
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// u8 ↔ f32 conversion
+// ---------------------------------------------------------------------------
+
+impl From<U8x4Rgba> for F32x4Rgba {
+    fn from(rgba: U8x4Rgba) -> Self {
+        Self::new(
+            f32::from(rgba.red()) / MAX,
+            f32::from(rgba.green()) / MAX,
+            f32::from(rgba.blue()) / MAX,
+            f32::from(rgba.alpha()) / MAX,
+        )
+    }
+}
+
+/// Error returned by [`F32x4Rgba::to_u8_checked`] for a channel that is `NaN` or outside
+/// `[0.0, 1.0]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelOutOfRange {
+    /// Index of the first offending channel: `0` for red, `1` green, `2` blue and `3` alpha.
+    pub index: usize,
+
+    /// The offending value.
+    pub value: f32,
+}
+
+impl fmt::Display for ChannelOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = ["red", "green", "blue", "alpha"][self.index];
+        write!(f, "{name} channel out of range: {}", self.value)
+    }
+}
+
+impl core::error::Error for ChannelOutOfRange {}
+
+impl F32x4Rgba {
+    /// Converts to 8 bits, rounding to nearest, or fails if any channel is `NaN` or outside
+    /// `[0.0, 1.0]`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns the first out-of-range channel, in RGBA order.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use alpha_blend::rgba::{F32x4Rgba, U8x4Rgba};
+    ///
+    /// assert_eq!(F32x4Rgba::WHITE.to_u8_checked(), Ok(U8x4Rgba::WHITE));
+    ///
+    /// let err = F32x4Rgba::new(0.5, -0.1, 0.5, 1.0).to_u8_checked().unwrap_err();
+    /// assert_eq!(err.index, 1);
+    /// assert_eq!(err.to_string(), "green channel out of range: -0.1");
+    /// ```
+    pub fn to_u8_checked(self) -> Result<U8x4Rgba, ChannelOutOfRange> {
+        let channels: [f32; 4] = self.into();
+        if let Some(index) = channels.iter().position(|c| !(0.0..=1.0).contains(c)) {
+            return Err(ChannelOutOfRange {
+                index,
+                value: channels[index],
+            });
+        }
+        Ok(U8x4Rgba::from(self))
+    }
+}
+
+/// What [`unpremultiply_with`](F32x4Rgba::unpremultiply_with) returns for a pixel with
+/// `alpha == 0`, whose color cannot be recovered.
+///
+/// Dividing by a zero alpha produces `NaN` or infinite channels, which poison every later blend.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ZeroAlpha<C: Copy> {
+    /// The pixel becomes transparent black, as [`unpremultiply`](F32x4Rgba::unpremultiply) does.
+    #[default]
+    ZeroColor,
+
+    /// The pixel is returned unchanged, keeping whatever its color channels hold.
+    LeaveAsIs,
+
+    /// The pixel is replaced by the given color.
+    Replace(Rgba<C>),
+}
+
+impl<C: Copy + Default> ZeroAlpha<C> {
+    /// Returns the color for `pixel`, which has `alpha == 0`.
+    fn resolve(self, pixel: Rgba<C>) -> Rgba<C> {
+        match self {
+            Self::ZeroColor => Rgba::new(C::default(), C::default(), C::default(), C::default()),
+            Self::LeaveAsIs => pixel,
+            Self::Replace(color) => color,
+        }
     }
+}
 
-    /// Constructs an opaque `U8x4Rgba` from a `0x00RRGGBB` packed pixel.
-    ///
-    /// The alpha channel is set to 255 (fully opaque). The top byte of
-    /// `pixel` is ignored.
+impl U8x4Rgba {
+    /// Converts from premultiplied alpha to straight alpha, rounding to nearest, with
+    /// `zero_alpha` choosing the result for pixels with `alpha == 0`.
     #[must_use]
-    pub const fn from_rgb_u32(pixel: u32) -> Self {
-        Self::new(
-            ((pixel >> 16) & 0xFF) as u8,
-            ((pixel >> 8) & 0xFF) as u8,
-            (pixel & 0xFF) as u8,
-            255,
-        )
+    pub fn unpremultiply_with(self, zero_alpha: ZeroAlpha<u8>) -> Self {
+        if self.a == 0 {
+            zero_alpha.resolve(self)
+        } else {
+            self.unpremultiply()
+        }
     }
 
-    /// Packs this color into a `0x00RRGGBB` `u32`, discarding the alpha channel.
-    #[must_use]
-    pub const fn to_rgb_u32(self) -> u32 {
-        ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
+    /// Converts every pixel of `pixels` from premultiplied alpha to straight alpha, in place,
+    /// as [`unpremultiply_with`](Self::unpremultiply_with).
+    pub fn unpremultiply_slice_with(pixels: &mut [Self], zero_alpha: ZeroAlpha<u8>) {
+        for pixel in pixels {
+            *pixel = pixel.unpremultiply_with(zero_alpha);
+        }
     }
+}
 
-    /// Blends `self` (source) over `dst` (destination) using integer `SourceOver`.
+impl F32x4Rgba {
+    /// Converts from premultiplied alpha to straight alpha, with `zero_alpha` choosing the
+    /// result for pixels with `alpha == 0`.
     ///
-    /// Equivalent to Porter-Duff `SRC_OVER`:
-    /// `out = src * src.a + dst * (1 - src.a)`
+    /// ## Examples
     ///
-    /// Uses the `(x + (x >> 8) + 1) >> 8` approximation for division by 255,
-    /// which avoids floating-point and is exact for all inputs in range.
+    /// ```rust
+    /// use alpha_blend::rgba::{F32x4Rgba, ZeroAlpha};
+    ///
+    /// let hole = F32x4Rgba::new(0.0, 0.0, 0.0, 0.0);
+    /// let magenta = F32x4Rgba::new(1.0, 0.0, 1.0, 0.0);
+    /// assert_eq!(hole.unpremultiply_with(ZeroAlpha::Replace(magenta)), magenta);
+    /// assert_eq!(hole.unpremultiply_with(ZeroAlpha::ZeroColor), F32x4Rgba::TRANSPARENT);
+    /// ```
     #[must_use]
-    pub fn source_over(self, dst: Self) -> Self {
-        let a = u16::from(self.a);
-        let inv_a = 255 - a;
-
-        let blend_channel = |s: u8, d: u8| -> u8 {
-            let v = u16::from(s) * a + u16::from(d) * inv_a;
-            ((v + (v >> 8) + 1) >> 8) as u8
-        };
-
-        let out_a = {
-            // Porter-Duff SRC_OVER: out_a = src_a + dst_a * (1 - src_a)
-            // In integer form: (a * 255 + dst.a * (255 - a)) / 255
-            let v = a * 255 + u16::from(dst.a) * inv_a;
-            ((v + (v >> 8) + 1) >> 8) as u8
-        };
-
-        Self::new(
-            blend_channel(self.r, dst.r),
-            blend_channel(self.g, dst.g),
-            blend_channel(self.b, dst.b),
-            out_a,
-        )
+    #[allow(clippy::float_cmp)]
+    pub fn unpremultiply_with(self, zero_alpha: ZeroAlpha<f32>) -> Self {
+        if self.a == 0.0 {
+            zero_alpha.resolve(self)
+        } else {
+            self.unpremultiply()
+        }
     }
 
-    /// Returns `true` if this pixel is fully transparent (`alpha == 0`).
-    #[must_use]
-    pub const fn is_transparent(self) -> bool {
-        self.a == 0
+    /// Converts every pixel of `pixels` from premultiplied alpha to straight alpha, in place,
+    /// as [`unpremultiply_with`](Self::unpremultiply_with).
+    pub fn unpremultiply_slice_with(pixels: &mut [Self], zero_alpha: ZeroAlpha<f32>) {
+        for pixel in pixels {
+            *pixel = pixel.unpremultiply_with(zero_alpha);
+        }
     }
+}
 
-    /// Returns `true` if this pixel is fully opaque (`alpha == 255`).
-    #[must_use]
-    pub const fn is_opaque(self) -> bool {
-        self.a == 255
+/// Rounds to nearest, clamping out-of-range channels with [`OutputPolicy::Clamp`]; `NaN`
+/// becomes `0`. Use [`OutputPolicy::to_u8`] to choose another policy.
+///
+/// [`OutputPolicy::Clamp`]: crate::policy::OutputPolicy::Clamp
+/// [`OutputPolicy::to_u8`]: crate::policy::OutputPolicy::to_u8
+impl From<F32x4Rgba> for U8x4Rgba {
+    fn from(rgba: F32x4Rgba) -> Self {
+        OutputPolicy::Clamp.to_u8(rgba)
     }
 }
 
 // ---------------------------------------------------------------------------
-// F32 helpers
+// u16 ↔ f32 and u8 ↔ u16 conversion
 // ---------------------------------------------------------------------------
 
-impl F32x4Rgba {
-    /// Creates a new `F32x4Rgba` instance with `0` for all components.
-    #[must_use]
-    pub const fn zeroed() -> Self {
-        Self::new(0.0, 0.0, 0.0, 0.0)
+impl From<U16x4Rgba> for F32x4Rgba {
+    fn from(rgba: U16x4Rgba) -> Self {
+        Self::new(
+            f32::from(rgba.red()) / MAX_U16,
+            f32::from(rgba.green()) / MAX_U16,
+            f32::from(rgba.blue()) / MAX_U16,
+            f32::from(rgba.alpha()) / MAX_U16,
+        )
     }
+}
 
-    /// Clamps all channels to `[0.0, 1.0]`.
-    ///
-    /// Necessary after [`crate::BlendMode::Plus`], which can produce values > 1.0.
-    #[must_use]
-    pub const fn clamp(self) -> Self {
-        // f32::clamp is not const in stable Rust, so we use manual min/max.
-        #[inline]
-        const fn clamp_f32(v: f32, lo: f32, hi: f32) -> f32 {
-            if v < lo {
-                lo
-            } else if v > hi {
-                hi
-            } else {
-                v
-            }
-        }
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+impl From<F32x4Rgba> for U16x4Rgba {
+    fn from(rgba: F32x4Rgba) -> Self {
+        let r = math::round(rgba.red() * MAX_U16);
+        let g = math::round(rgba.green() * MAX_U16);
+        let b = math::round(rgba.blue() * MAX_U16);
+        let a = math::round(rgba.alpha() * MAX_U16);
+        Self::new(r as u16, g as u16, b as u16, a as u16)
+    }
+}
+
+/// Widens exactly: each channel `v` becomes `v * 257`, so `255` becomes `65535`.
+impl From<U8x4Rgba> for U16x4Rgba {
+    fn from(rgba: U8x4Rgba) -> Self {
+        let widen = |c: u8| u16::from(c) * 257;
+        Self::new(widen(rgba.r), widen(rgba.g), widen(rgba.b), widen(rgba.a))
+    }
+}
+
+/// Narrows each channel `v` to `v / 257`, rounded to nearest.
+#[allow(clippy::cast_possible_truncation)]
+impl From<U16x4Rgba> for U8x4Rgba {
+    fn from(rgba: U16x4Rgba) -> Self {
+        let narrow = |c: u16| ((u32::from(c) + 128) / 257) as u8;
         Self::new(
-            clamp_f32(self.r, 0.0, 1.0),
-            clamp_f32(self.g, 0.0, 1.0),
-            clamp_f32(self.b, 0.0, 1.0),
-            clamp_f32(self.a, 0.0, 1.0),
+            narrow(rgba.r),
+            narrow(rgba.g),
+            narrow(rgba.b),
+            narrow(rgba.a),
         )
     }
+}
 
-    /// Converts from straight alpha to premultiplied alpha.
-    ///
-    /// `premultiplied.rgb = straight.rgb * straight.a`
-    #[must_use]
-    pub fn premultiply(self) -> Self {
-        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
-    }
+// ---------------------------------------------------------------------------
+// f64 ↔ f32 conversion
+// ---------------------------------------------------------------------------
 
-    /// Converts from premultiplied alpha to straight alpha.
-    ///
-    /// No-op if `alpha == 0` (avoids division by zero).
-    #[must_use]
-    pub fn unpremultiply(self) -> Self {
-        if self.a == 0.0 {
-            return Self::TRANSPARENT;
-        }
-        Self::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+impl From<F32x4Rgba> for F64x4Rgba {
+    fn from(rgba: F32x4Rgba) -> Self {
+        Self::new(
+            f64::from(rgba.red()),
+            f64::from(rgba.green()),
+            f64::from(rgba.blue()),
+            f64::from(rgba.alpha()),
+        )
     }
+}
 
-    /// Linearly interpolates between `self` and `other` by `t` (clamped to `[0.0, 1.0]`).
-    ///
-    /// `t = 0.0` returns `self`; `t = 1.0` returns `other`.
-    #[must_use]
-    #[allow(clippy::suboptimal_flops)]
-    pub fn lerp(self, other: Self, t: f32) -> Self {
-        let t = t.clamp(0.0, 1.0);
+/// Rounds each channel to the nearest representable `f32`.
+#[allow(clippy::cast_possible_truncation)]
+impl From<F64x4Rgba> for F32x4Rgba {
+    fn from(rgba: F64x4Rgba) -> Self {
         Self::new(
-            self.r + (other.r - self.r) * t,
-            self.g + (other.g - self.g) * t,
-            self.b + (other.b - self.b) * t,
-            self.a + (other.a - self.a) * t,
+            rgba.red() as f32,
+            rgba.green() as f32,
+            rgba.blue() as f32,
+            rgba.alpha() as f32,
         )
     }
 }
 
 // ---------------------------------------------------------------------------
-// u8 ↔ f32 conversion
+// f16 ↔ f32 conversion
 // ---------------------------------------------------------------------------
 
-impl From<U8x4Rgba> for F32x4Rgba {
-    fn from(rgba: U8x4Rgba) -> Self {
+#[cfg(feature = "half")]
+impl From<F16x4Rgba> for F32x4Rgba {
+    fn from(rgba: F16x4Rgba) -> Self {
         Self::new(
-            f32::from(rgba.red()) / MAX,
-            f32::from(rgba.green()) / MAX,
-            f32::from(rgba.blue()) / MAX,
-            f32::from(rgba.alpha()) / MAX,
+            rgba.red().to_f32(),
+            rgba.green().to_f32(),
+            rgba.blue().to_f32(),
+            rgba.alpha().to_f32(),
         )
     }
 }
 
-#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-impl From<F32x4Rgba> for U8x4Rgba {
+/// Rounds each channel to the nearest representable `f16`.
+#[cfg(feature = "half")]
+impl From<F32x4Rgba> for F16x4Rgba {
     fn from(rgba: F32x4Rgba) -> Self {
-        let r = math::round(rgba.red() * MAX);
-        let g = math::round(rgba.green() * MAX);
-        let b = math::round(rgba.blue() * MAX);
-        let a = math::round(rgba.alpha() * MAX);
-        Self::new(r as u8, g as u8, b as u8, a as u8)
+        Self::new(
+            half::f16::from_f32(rgba.red()),
+            half::f16::from_f32(rgba.green()),
+            half::f16::from_f32(rgba.blue()),
+            half::f16::from_f32(rgba.alpha()),
+        )
     }
 }
 
@@ -392,6 +1463,7 @@ impl From<F32x4Rgba> for U8x4Rgba {
 #[allow(clippy::cast_lossless, clippy::float_cmp)]
 mod tests {
     use super::*;
+    use crate::{BlendMode, RgbaBlend};
 
     // --- Bug fix: field doc comments ---
 
@@ -591,6 +1663,35 @@ mod tests {
         assert_eq!(rgba.as_ref(), &[1u8, 2, 3, 4]);
     }
 
+    #[test]
+    fn index_and_iterate() {
+        let mut rgba = U8x4Rgba::new(1, 2, 3, 4);
+        assert_eq!([rgba[0], rgba[1], rgba[2], rgba[3]], [1, 2, 3, 4]);
+        rgba[2] = 30;
+        for c in &mut rgba {
+            *c += 1;
+        }
+        assert_eq!(rgba.iter().copied().collect::<Vec<_>>(), [2, 3, 31, 5]);
+        assert_eq!(rgba.into_iter().max(), Some(31));
+        rgba.as_array_mut()[3] = 0;
+        assert_eq!(rgba.as_array(), &[2, 3, 31, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn index_past_alpha_panics() {
+        let _ = F32x4Rgba::WHITE[4];
+    }
+
+    #[test]
+    fn slice_of_arrays() {
+        let mut arrays = [[1u8, 2, 3, 4], [5, 6, 7, 8]];
+        let pixels = U8x4Rgba::from_arrays_mut(&mut arrays);
+        pixels[1].a = 80;
+        assert_eq!(U8x4Rgba::as_arrays(pixels), &[[1, 2, 3, 4], [5, 6, 7, 80]]);
+        assert_eq!(U8x4Rgba::from_arrays(&arrays)[0], U8x4Rgba::new(1, 2, 3, 4));
+    }
+
     #[test]
     fn as_mut_slice() {
         let mut rgba = U8x4Rgba::new(1, 2, 3, 4);
@@ -674,18 +1775,176 @@ mod tests {
         assert_eq!(c, F32x4Rgba::TRANSPARENT);
     }
 
+    #[test]
+    fn unpremultiply_with_zero_alpha_policies() {
+        let hole = F32x4Rgba::new(0.25, 0.5, 0.75, 0.0);
+        assert_eq!(
+            hole.unpremultiply_with(ZeroAlpha::ZeroColor),
+            F32x4Rgba::TRANSPARENT
+        );
+        assert_eq!(hole.unpremultiply_with(ZeroAlpha::LeaveAsIs), hole);
+        assert_eq!(
+            hole.unpremultiply_with(ZeroAlpha::Replace(F32x4Rgba::WHITE)),
+            F32x4Rgba::WHITE
+        );
+        let half = F32x4Rgba::new(0.25, 0.5, 0.0, 0.5);
+        assert_eq!(
+            half.unpremultiply_with(ZeroAlpha::LeaveAsIs),
+            half.unpremultiply()
+        );
+
+        let mut pixels = [U8x4Rgba::new(9, 9, 9, 0), U8x4Rgba::new(64, 0, 0, 128)];
+        U8x4Rgba::unpremultiply_slice_with(&mut pixels, ZeroAlpha::LeaveAsIs);
+        assert_eq!(
+            pixels,
+            [U8x4Rgba::new(9, 9, 9, 0), U8x4Rgba::new(128, 0, 0, 128)]
+        );
+        let mut pixels = [F32x4Rgba::TRANSPARENT; 2];
+        F32x4Rgba::unpremultiply_slice_with(&mut pixels, ZeroAlpha::Replace(F32x4Rgba::BLACK));
+        assert_eq!(pixels, [F32x4Rgba::BLACK; 2]);
+    }
+
+    #[test]
+    fn flatten_over_is_opaque() {
+        let bg = F32x4Rgba::new(1.0, 1.0, 0.0, 0.25);
+        let mut pixels = [
+            F32x4Rgba::TRANSPARENT,
+            F32x4Rgba::new(0.0, 0.0, 1.0, 0.5),
+            F32x4Rgba::new(0.0, 0.0, 1.0, 1.0),
+        ];
+        F32x4Rgba::flatten_slice_over(&mut pixels, bg);
+        assert_eq!(
+            pixels,
+            [
+                F32x4Rgba::new(1.0, 1.0, 0.0, 1.0),
+                F32x4Rgba::new(0.5, 0.5, 0.5, 1.0),
+                F32x4Rgba::new(0.0, 0.0, 1.0, 1.0),
+            ]
+        );
+        assert_eq!(
+            U8x4Rgba::new(255, 0, 0, 128).flatten_over(U8x4Rgba::BLACK),
+            U8x4Rgba::new(128, 0, 0, 255)
+        );
+    }
+
+    #[test]
+    fn premultiply_slices_round_trip() {
+        let orig = [
+            F32x4Rgba::new(0.5, 1.0, 0.0, 0.5),
+            F32x4Rgba::WHITE,
+            F32x4Rgba::new(0.5, 0.5, 0.5, 0.0),
+        ];
+        let mut pixels = orig;
+        F32x4Rgba::premultiply_slice(&mut pixels);
+        assert_eq!(pixels[0], F32x4Rgba::new(0.25, 0.5, 0.0, 0.5));
+        F32x4Rgba::unpremultiply_slice(&mut pixels);
+        assert_eq!(pixels[..2], orig[..2]);
+        assert_eq!(
+            pixels[2],
+            F32x4Rgba::TRANSPARENT,
+            "zero alpha loses its color"
+        );
+    }
+
+    #[test]
+    fn source_over_slice_matches_source_over() {
+        let src: [U8x4Rgba; 51] = core::array::from_fn(|i| {
+            let a = u8::try_from(i * 5).unwrap();
+            U8x4Rgba::new(a, 255 - a, 90, a)
+        });
+        let mut dst: [U8x4Rgba; 51] = core::array::from_fn(|i| src[50 - i]);
+        let expected: [U8x4Rgba; 51] = core::array::from_fn(|i| src[i].source_over(dst[i]));
+        U8x4Rgba::source_over_slice(&src, &mut dst);
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn premultiply_u8() {
+        let c = U8x4Rgba::new(255, 128, 0, 128);
+        assert_eq!(c.premultiply(), U8x4Rgba::new(128, 64, 0, 128));
+        assert_eq!(c.premultiply().unpremultiply(), c);
+        assert_eq!(U8x4Rgba::WHITE.premultiply(), U8x4Rgba::WHITE);
+        assert_eq!(
+            U8x4Rgba::new(9, 9, 9, 0).unpremultiply(),
+            U8x4Rgba::TRANSPARENT
+        );
+        assert_eq!(
+            U8x4Rgba::new(200, 0, 0, 100).unpremultiply(),
+            U8x4Rgba::new(255, 0, 0, 100),
+            "channels above alpha are clamped"
+        );
+    }
+
+    #[test]
+    fn premultiply_slice_u8() {
+        let mut pixels = [U8x4Rgba::new(255, 255, 255, 51), U8x4Rgba::TRANSPARENT];
+        U8x4Rgba::premultiply_slice(&mut pixels);
+        assert_eq!(
+            pixels,
+            [U8x4Rgba::new(51, 51, 51, 51), U8x4Rgba::TRANSPARENT]
+        );
+        U8x4Rgba::unpremultiply_slice(&mut pixels);
+        assert_eq!(
+            pixels,
+            [U8x4Rgba::new(255, 255, 255, 51), U8x4Rgba::TRANSPARENT]
+        );
+    }
+
     #[test]
     fn lerp_identity() {
         let a = F32x4Rgba::new(0.2, 0.4, 0.6, 0.8);
         assert_eq!(a.lerp(F32x4Rgba::WHITE, 0.0), a);
     }
 
+    #[test]
+    fn modulate_multiplies_alpha() {
+        let tint = F32x4Rgba::new(1.0, 0.5, 0.25, 0.5);
+        let sprite = F32x4Rgba::new(0.5, 0.5, 1.0, 0.5);
+        let out = tint.modulate(sprite);
+        assert_eq!(out, F32x4Rgba::new(0.5, 0.25, 0.25, 0.25));
+        assert_eq!(BlendMode::Modulate.apply(tint, sprite), out);
+        assert_eq!(
+            tint.premultiply().modulate(sprite.premultiply()),
+            out.premultiply()
+        );
+        assert_ne!(BlendMode::Multiply.apply(tint, sprite), out);
+    }
+
     #[test]
     fn lerp_full() {
         let a = F32x4Rgba::new(0.2, 0.4, 0.6, 0.8);
         assert_eq!(a.lerp(F32x4Rgba::WHITE, 1.0), F32x4Rgba::WHITE);
     }
 
+    #[test]
+    fn lerp_premultiplied_keeps_color_when_fading_out() {
+        let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+        let from = [red, F32x4Rgba::TRANSPARENT];
+        let to = [F32x4Rgba::TRANSPARENT, F32x4Rgba::new(0.0, 1.0, 0.0, 0.5)];
+        let mut dst = [F32x4Rgba::BLACK; 2];
+        F32x4Rgba::lerp_premultiplied_slice(&from, &to, &mut dst, 0.25);
+        assert_eq!(dst[0], F32x4Rgba::new(1.0, 0.0, 0.0, 0.75));
+        assert_eq!(dst[1], F32x4Rgba::new(0.0, 1.0, 0.0, 0.125));
+
+        F32x4Rgba::lerp_slice(&from, &to, &mut dst, 0.25);
+        assert_eq!(dst[0], red.lerp(F32x4Rgba::TRANSPARENT, 0.25));
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the same length")]
+    fn lerp_slice_panics_on_mismatched_lengths() {
+        F32x4Rgba::lerp_slice(&[F32x4Rgba::WHITE; 2], &[F32x4Rgba::WHITE; 2], &mut [], 0.5);
+    }
+
+    #[test]
+    fn lerp_u8() {
+        let a = U8x4Rgba::new(0, 100, 255, 255);
+        let b = U8x4Rgba::new(255, 200, 0, 0);
+        assert_eq!(a.lerp(b, 0), a);
+        assert_eq!(a.lerp(b, 255), b);
+        assert_eq!(a.lerp(b, 128), U8x4Rgba::new(128, 150, 127, 127));
+    }
+
     #[test]
     fn lerp_midpoint() {
         let a = F32x4Rgba::new(0.0, 0.0, 0.0, 1.0);
@@ -696,4 +1955,175 @@ mod tests {
         assert!((mid.b - 0.5).abs() < 1e-6);
         assert!((mid.a - 1.0).abs() < 1e-6);
     }
+
+    // --- u16 channels ---
+
+    #[test]
+    fn u16_f32_round_trip() {
+        for v in [0, 1, 257, 32768, 65534, u16::MAX] {
+            let c = U16x4Rgba::new(v, v, v, v);
+            assert_eq!(U16x4Rgba::from(F32x4Rgba::from(c)), c);
+        }
+        assert_eq!(F32x4Rgba::from(U16x4Rgba::WHITE), F32x4Rgba::WHITE);
+    }
+
+    #[test]
+    fn u16_u8_conversions() {
+        assert_eq!(U16x4Rgba::from(U8x4Rgba::WHITE), U16x4Rgba::WHITE);
+        for v in 0..=u8::MAX {
+            let wide = U16x4Rgba::from(U8x4Rgba::new(v, v, v, v));
+            assert_eq!(wide.r, u16::from(v) * 257);
+            assert_eq!(U8x4Rgba::from(wide), U8x4Rgba::new(v, v, v, v));
+        }
+        assert_eq!(U8x4Rgba::from(U16x4Rgba::new(128, 129, 385, 386)).r, 0);
+        assert_eq!(U8x4Rgba::from(U16x4Rgba::new(129, 0, 0, 0)).r, 1);
+    }
+
+    #[test]
+    fn u16_source_over() {
+        let src = U16x4Rgba::new(u16::MAX, 0, 0, 32768);
+        let dst = U16x4Rgba::new(0, 0, u16::MAX, u16::MAX);
+        let out = src.source_over(dst);
+        assert_eq!(out, U16x4Rgba::new(32768, 0, 32767, u16::MAX));
+        assert_eq!(U16x4Rgba::TRANSPARENT.source_over(dst), dst);
+        assert_eq!(U16x4Rgba::WHITE.source_over(dst), U16x4Rgba::WHITE);
+        assert!(out.is_opaque() && !out.is_transparent());
+    }
+
+    // --- f64 channels ---
+
+    #[test]
+    fn f64_f32_conversions() {
+        let c = F32x4Rgba::new(0.1, 0.2, 0.3, 0.4);
+        assert_eq!(F32x4Rgba::from(F64x4Rgba::from(c)), c);
+        assert_eq!(F64x4Rgba::from(F32x4Rgba::WHITE), F64x4Rgba::WHITE);
+    }
+
+    // --- f16 channels ---
+
+    #[test]
+    #[cfg(feature = "half")]
+    fn f16_f32_conversions() {
+        let c = F32x4Rgba::new(0.5, 0.25, 1.0, 0.75);
+        assert_eq!(F32x4Rgba::from(F16x4Rgba::from(c)), c);
+        assert_eq!(F32x4Rgba::from(F16x4Rgba::WHITE), F32x4Rgba::WHITE);
+        assert_eq!(F16x4Rgba::from(F32x4Rgba::BLACK), F16x4Rgba::BLACK);
+        assert_eq!(
+            F16x4Rgba::from(F32x4Rgba::TRANSPARENT),
+            F16x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn validity_helpers() {
+        let hdr = F32x4Rgba::new(f32::NAN, -0.5, 2.0, 0.5);
+        assert!(hdr.has_nan());
+        assert!(!hdr.is_normalized());
+        assert_eq!(hdr.clamp_unit(), F32x4Rgba::new(0.0, 0.0, 1.0, 0.5));
+        assert!(hdr.clamp_unit().is_normalized());
+        assert!(F32x4Rgba::WHITE.is_normalized());
+        assert!(!F32x4Rgba::WHITE.has_nan());
+
+        let mut pixels = [F32x4Rgba::WHITE, hdr];
+        assert!(F32x4Rgba::has_nan_slice(&pixels));
+        assert!(!F32x4Rgba::is_normalized_slice(&pixels));
+        F32x4Rgba::clamp_unit_slice(&mut pixels);
+        assert!(!F32x4Rgba::has_nan_slice(&pixels));
+        assert!(F32x4Rgba::is_normalized_slice(&pixels));
+        assert!(F32x4Rgba::is_normalized_slice(&[]));
+    }
+
+    #[test]
+    fn to_u8_checked_rejects_out_of_range() {
+        assert_eq!(
+            F32x4Rgba::new(0.0, 0.5, 1.0, 1.0).to_u8_checked(),
+            Ok(U8x4Rgba::new(0, 128, 255, 255))
+        );
+        let err = F32x4Rgba::new(0.5, 0.5, 0.5, 1.5)
+            .to_u8_checked()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ChannelOutOfRange {
+                index: 3,
+                value: 1.5
+            }
+        );
+        let err = F32x4Rgba::new(f32::NAN, 2.0, 0.5, 1.0)
+            .to_u8_checked()
+            .unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(err.value.is_nan());
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn bytemuck_casts_any_pod_channel() {
+        let pixels = [U16x4Rgba::new(1, 2, 3, 4), U16x4Rgba::WHITE];
+        let raw: &[u16] = bytemuck::cast_slice(&pixels);
+        assert_eq!(raw, [1, 2, 3, 4, 65535, 65535, 65535, 65535]);
+        assert_eq!(bytemuck::cast_slice::<u16, U16x4Rgba>(raw), pixels);
+        assert_eq!(
+            <F64x4Rgba as bytemuck::Zeroable>::zeroed(),
+            F64x4Rgba::TRANSPARENT
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "bytemuck", feature = "half"))]
+    fn bytemuck_casts_f16() {
+        let pixels = [F16x4Rgba::WHITE];
+        let raw: &[half::f16] = bytemuck::cast_slice(&pixels);
+        assert_eq!(raw, [half::f16::ONE; 4]);
+    }
+
+    // --- Hex parsing ---
+
+    #[test]
+    fn parse_hex_forms() {
+        let orange = U8x4Rgba::new(0xff, 0x88, 0x00, 0xff);
+        for s in ["#f80", "#F80F", "#ff8800", "#FF8800ff"] {
+            assert_eq!(s.parse(), Ok(orange), "{s}");
+        }
+        assert_eq!(
+            "#12345678".parse(),
+            Ok(U8x4Rgba::new(0x12, 0x34, 0x56, 0x78))
+        );
+        assert_eq!(
+            "#ffffff80".parse(),
+            Ok(F32x4Rgba::from(U8x4Rgba::WHITE.with_alpha(0x80)))
+        );
+    }
+
+    #[test]
+    fn parse_hex_errors() {
+        let parse = str::parse::<U8x4Rgba>;
+        assert_eq!(parse(""), Err(ParseColorError::MissingHash));
+        assert_eq!(parse("#"), Err(ParseColorError::InvalidLength(0)));
+        assert_eq!(parse("#12345"), Err(ParseColorError::InvalidLength(5)));
+        assert_eq!(parse("#123456789"), Err(ParseColorError::InvalidLength(9)));
+        assert_eq!(parse("#12g"), Err(ParseColorError::InvalidDigit('g')));
+        assert_eq!(parse("# 123"), Err(ParseColorError::InvalidDigit(' ')));
+        assert_eq!(
+            ParseColorError::InvalidLength(5).to_string(),
+            "hex color must have 3, 4, 6 or 8 digits, found 5"
+        );
+    }
+
+    // --- Formatting ---
+
+    #[test]
+    fn hex_formatting_round_trips() {
+        for c in [
+            U8x4Rgba::TRANSPARENT,
+            U8x4Rgba::RED,
+            U8x4Rgba::new(1, 2, 3, 4),
+        ] {
+            assert_eq!(format!("{c:#x}").parse(), Ok(c));
+            assert_eq!(format!("{c:#X}").parse(), Ok(c));
+        }
+        assert_eq!(format!("{:x}", U8x4Rgba::new(1, 2, 3, 4)), "01020304");
+        assert_eq!(format!("{:#x}", U8x4Rgba::new(1, 2, 3, 4)), "#01020304");
+        assert_eq!(U8x4Rgba::WHITE.to_string(), "rgba(255, 255, 255, 255)");
+    }
 }
@@ -0,0 +1,375 @@
+//! Row-at-a-time compositing for streaming decoders.
+//!
+//! Image decoders (PNG, progressive JPEG, …) produce one row at a time. Instead of decoding the
+//! whole source image into an intermediate buffer before blending it, rows can be composited
+//! into the destination as they arrive, either by pushing them into a [`RowCompositor`], or by
+//! letting [`composite_rows`] pull them from a closure.
+//!
+//! Images too large to hold in memory, such as gigapixel scans, can stream the destination as
+//! well: [`stream_rows`] pulls a row of each image from a closure and hands each blended row to
+//! another, and [`blend_rows`] blends the rows of two iterators pairwise. Either way only one row
+//! of each image is held at a time. Rows can be any length, so the same functions blend
+//! fixed-size chunks of a flat buffer.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, rgba::F32x4Rgba, rows};
+//!
+//! let mut dst = [F32x4Rgba::BLACK; 9];
+//! let mut row = [F32x4Rgba::TRANSPARENT; 3];
+//!
+//! // A "decoder" producing white rows, that stops after two rows.
+//! let composited = rows::composite_rows(&BlendMode::SourceOver, &mut dst, 3, &mut row, |y, row| {
+//!     if y == 2 {
+//!         return false;
+//!     }
+//!     row.fill(F32x4Rgba::WHITE);
+//!     true
+//! });
+//!
+//! assert_eq!(composited, 2);
+//! assert_eq!(dst[..6], [F32x4Rgba::WHITE; 6]);
+//! assert_eq!(dst[6..], [F32x4Rgba::BLACK; 3]);
+//!
+//! // Blend rows from two decoders, passing each result on to an encoder.
+//! let src_rows = (0..3).map(|_| vec![F32x4Rgba::WHITE.with_alpha(0.0); 3]);
+//! let dst_rows = (0..3).map(|_| vec![F32x4Rgba::BLACK; 3]);
+//! for row in rows::blend_rows(&BlendMode::SourceOver, src_rows, dst_rows) {
+//!     assert_eq!(row, [F32x4Rgba::BLACK; 3]);
+//! }
+//! ```
+
+use crate::{RgbaBlend, rgba::F32x4Rgba};
+
+/// Composites source rows into a destination buffer as they arrive.
+///
+/// Rows are usually [pushed](RowCompositor::push_row) in order; interlaced decoders that produce
+/// rows out of order can [composite a row](RowCompositor::composite_row) at an explicit position.
+#[derive(Debug)]
+pub struct RowCompositor<'a, B: ?Sized> {
+    blend: &'a B,
+    dst: &'a mut [F32x4Rgba],
+    width: usize,
+    next: usize,
+}
+
+impl<'a, B> RowCompositor<'a, B>
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    /// Creates a new `RowCompositor` blending rows of `width` pixels into `dst`.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero, or the length of `dst` is not a multiple of `width`.
+    pub fn new(blend: &'a B, dst: &'a mut [F32x4Rgba], width: usize) -> Self {
+        assert!(width > 0, "width must be non-zero");
+        assert_eq!(
+            dst.len() % width,
+            0,
+            "buffer length must be a multiple of width"
+        );
+        Self {
+            blend,
+            dst,
+            width,
+            next: 0,
+        }
+    }
+
+    /// Returns the width of each row, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows in the destination.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.dst.len() / self.width
+    }
+
+    /// Returns the row the next [pushed](RowCompositor::push_row) row is composited into.
+    #[must_use]
+    pub const fn next_row(&self) -> usize {
+        self.next
+    }
+
+    /// Returns `true` once a row has been pushed for every destination row.
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.next >= self.height()
+    }
+
+    /// Composites `row` into the next destination row.
+    ///
+    /// ## Panics
+    ///
+    /// If every row was already pushed, or `row` is not `width` pixels long.
+    pub fn push_row(&mut self, row: &[F32x4Rgba]) {
+        assert!(!self.is_complete(), "all rows were already composited");
+        self.composite_row(self.next, row);
+        self.next += 1;
+    }
+
+    /// Composites `row` into destination row `y`.
+    ///
+    /// This does not affect [`next_row`](RowCompositor::next_row).
+    ///
+    /// ## Panics
+    ///
+    /// If `y` is out of bounds, or `row` is not `width` pixels long.
+    pub fn composite_row(&mut self, y: usize, row: &[F32x4Rgba]) {
+        assert!(y < self.height(), "row out of bounds");
+        assert_eq!(row.len(), self.width, "row must have width pixels");
+        let dst = &mut self.dst[y * self.width..][..self.width];
+        self.blend.apply_slice(row, dst);
+    }
+}
+
+/// Composites rows produced on demand by `source` into `dst`, one row at a time.
+///
+/// For each destination row `y`, from the top, `source(y, row)` is called to write the source
+/// row into `row`, a scratch buffer of `width` pixels; it returns `false` to stop early, for
+/// example when the decoder reaches the end of its data. Returns the number of rows composited.
+///
+/// ## Panics
+///
+/// If `width` is zero, `row` is not `width` pixels long, or the length of `dst` is not a
+/// multiple of `width`.
+pub fn composite_rows<B, F>(
+    blend: &B,
+    dst: &mut [F32x4Rgba],
+    width: usize,
+    row: &mut [F32x4Rgba],
+    mut source: F,
+) -> usize
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    F: FnMut(usize, &mut [F32x4Rgba]) -> bool,
+{
+    assert_eq!(row.len(), width, "row must have width pixels");
+    let mut compositor = RowCompositor::new(blend, dst, width);
+    while !compositor.is_complete() {
+        if !source(compositor.next_row(), row) {
+            break;
+        }
+        compositor.push_row(row);
+    }
+    compositor.next_row()
+}
+
+/// Blends two images streamed row by row, holding only one row of each.
+///
+/// For each row `y`, from the top, `read(y, src_row, dst_row)` is called to write the source and
+/// destination rows into the scratch buffers; it returns `false` to stop, for example when either
+/// decoder reaches the end of its data. The source row is then blended onto the destination row,
+/// and `write(y, dst_row)` receives the result. Returns the number of rows blended.
+///
+/// ## Panics
+///
+/// If `src_row` and `dst_row` do not have the same length.
+pub fn stream_rows<B, R, W>(
+    blend: &B,
+    src_row: &mut [F32x4Rgba],
+    dst_row: &mut [F32x4Rgba],
+    mut read: R,
+    mut write: W,
+) -> usize
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    R: FnMut(usize, &mut [F32x4Rgba], &mut [F32x4Rgba]) -> bool,
+    W: FnMut(usize, &[F32x4Rgba]),
+{
+    assert_eq!(
+        src_row.len(),
+        dst_row.len(),
+        "src and dst rows must have the same length"
+    );
+    let mut y = 0;
+    while read(y, src_row, dst_row) {
+        blend.apply_slice(src_row, dst_row);
+        write(y, dst_row);
+        y += 1;
+    }
+    y
+}
+
+/// Returns an iterator blending each row of `src` onto the matching row of `dst`, yielding the
+/// blended destination rows.
+///
+/// Rows are blended lazily, as the iterator is advanced, and it ends with the shorter of `src` and
+/// `dst`. Rows can be borrowed slices or owned buffers, such as the `Vec`s a decoder returns.
+///
+/// ## Panics
+///
+/// When advanced, if a source row and its destination row do not have the same length.
+pub fn blend_rows<B, S, D>(blend: &B, src: S, dst: D) -> BlendRows<'_, B, S::IntoIter, D::IntoIter>
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: IntoIterator,
+    S::Item: AsRef<[F32x4Rgba]>,
+    D: IntoIterator,
+    D::Item: AsMut<[F32x4Rgba]>,
+{
+    BlendRows {
+        blend,
+        src: src.into_iter(),
+        dst: dst.into_iter(),
+    }
+}
+
+/// An iterator blending rows of one iterator onto rows of another; see [`blend_rows`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct BlendRows<'a, B: ?Sized, S, D> {
+    blend: &'a B,
+    src: S,
+    dst: D,
+}
+
+impl<B, S, D> Iterator for BlendRows<'_, B, S, D>
+where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+    S: Iterator,
+    S::Item: AsRef<[F32x4Rgba]>,
+    D: Iterator,
+    D::Item: AsMut<[F32x4Rgba]>,
+{
+    type Item = D::Item;
+
+    fn next(&mut self) -> Option<D::Item> {
+        let src = self.src.next()?;
+        let mut dst = self.dst.next()?;
+        self.blend.apply_slice(src.as_ref(), dst.as_mut());
+        Some(dst)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (src_min, src_max) = self.src.size_hint();
+        let (dst_min, dst_max) = self.dst.size_hint();
+        let max = match (src_max, dst_max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (src_min.min(dst_min), max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    const HALF_RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+
+    #[test]
+    fn push_rows_matches_apply_slice() {
+        let src = [HALF_RED, F32x4Rgba::WHITE, F32x4Rgba::TRANSPARENT, HALF_RED];
+        let mut expected = [F32x4Rgba::BLACK; 4];
+        BlendMode::SourceOver.apply_slice(&src, &mut expected);
+
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        let mut compositor = RowCompositor::new(&BlendMode::SourceOver, &mut dst, 2);
+        assert_eq!(compositor.height(), 2);
+        for row in src.chunks_exact(2) {
+            assert!(!compositor.is_complete());
+            compositor.push_row(row);
+        }
+        assert!(compositor.is_complete());
+        assert_eq!(dst, expected);
+    }
+
+    #[test]
+    fn composite_row_out_of_order() {
+        let mut dst = [F32x4Rgba::BLACK; 3];
+        let mut compositor = RowCompositor::new(&BlendMode::Source, &mut dst, 1);
+        compositor.composite_row(2, &[F32x4Rgba::WHITE]);
+        assert_eq!(compositor.next_row(), 0);
+        assert_eq!(dst, [F32x4Rgba::BLACK, F32x4Rgba::BLACK, F32x4Rgba::WHITE]);
+    }
+
+    #[test]
+    fn composite_rows_pulls_every_row() {
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        let mut row = [F32x4Rgba::TRANSPARENT; 2];
+        let mut requested = [false; 2];
+        let n = composite_rows(&BlendMode::Source, &mut dst, 2, &mut row, |y, row| {
+            requested[y] = true;
+            row.fill(HALF_RED);
+            true
+        });
+        assert_eq!(n, 2);
+        assert_eq!(requested, [true; 2]);
+        assert_eq!(dst, [HALF_RED; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "already composited")]
+    fn push_row_panics_when_complete() {
+        let mut dst = [F32x4Rgba::BLACK; 1];
+        let mut compositor = RowCompositor::new(&BlendMode::Source, &mut dst, 1);
+        compositor.push_row(&[F32x4Rgba::WHITE]);
+        compositor.push_row(&[F32x4Rgba::WHITE]);
+    }
+
+    #[test]
+    #[should_panic(expected = "row must have width pixels")]
+    fn push_row_panics_on_wrong_width() {
+        let mut dst = [F32x4Rgba::BLACK; 4];
+        RowCompositor::new(&BlendMode::Source, &mut dst, 2).push_row(&[F32x4Rgba::WHITE]);
+    }
+
+    #[test]
+    fn stream_rows_writes_each_blended_row() {
+        let src = [HALF_RED, F32x4Rgba::WHITE, F32x4Rgba::TRANSPARENT, HALF_RED];
+        let mut expected = [F32x4Rgba::BLACK; 4];
+        BlendMode::SourceOver.apply_slice(&src, &mut expected);
+
+        let mut out = [F32x4Rgba::TRANSPARENT; 4];
+        let (mut src_row, mut dst_row) = ([F32x4Rgba::TRANSPARENT; 2], [F32x4Rgba::WHITE; 2]);
+        let n = stream_rows(
+            &BlendMode::SourceOver,
+            &mut src_row,
+            &mut dst_row,
+            |y, s, d| {
+                let Some(row) = src.chunks_exact(2).nth(y) else {
+                    return false;
+                };
+                s.copy_from_slice(row);
+                d.fill(F32x4Rgba::BLACK);
+                true
+            },
+            |y, row| out[y * 2..][..2].copy_from_slice(row),
+        );
+        assert_eq!(n, 2);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn blend_rows_ends_with_shorter_iterator() {
+        let src = [[HALF_RED; 2]; 3];
+        let mut dst = [[F32x4Rgba::BLACK; 2]; 2];
+        let mut expected = [F32x4Rgba::BLACK; 2];
+        BlendMode::SourceOver.apply_slice(&src[0], &mut expected);
+
+        let rows = blend_rows(&BlendMode::SourceOver, &src, dst.iter_mut());
+        assert_eq!(rows.size_hint(), (2, Some(2)));
+        assert_eq!(rows.count(), 2);
+        assert_eq!(dst, [expected; 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn stream_rows_panics_on_mismatched_rows() {
+        let (mut src, mut dst) = ([F32x4Rgba::WHITE; 2], [F32x4Rgba::WHITE; 3]);
+        stream_rows(
+            &BlendMode::Source,
+            &mut src,
+            &mut dst,
+            |_, _, _| false,
+            |_, _| {},
+        );
+    }
+}
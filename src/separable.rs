@@ -0,0 +1,356 @@
+//! Separable blend modes.
+//!
+//! This module provides a [`BlendMode`] implementation for the separable blend modes of the
+//! [W3C Compositing and Blending] specification, such as [`Multiply`](crate::BlendMode::Multiply) and
+//! [`Overlay`](crate::BlendMode::Overlay). A blend function `B(src, dst)` mixes each color channel
+//! independently; where the destination is transparent, the source color is used unchanged, and
+//! the mixed color is then composited with source-over. The result alpha is always
+//! `src.a + dst.a * (1 - src.a)`.
+//!
+//! [`BlendMode`]: crate::BlendMode
+//! [W3C Compositing and Blending]: https://www.w3.org/TR/compositing-1/#blending
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, RgbaBlend, rgba::F32x4Rgba, separable::Separable};
+//!
+//! let src = F32x4Rgba::new(0.5, 1.0, 0.0, 1.0);
+//! let dst = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+//! assert_eq!(BlendMode::Multiply.apply(src, dst), F32x4Rgba::new(0.25, 0.5, 0.0, 1.0));
+//!
+//! // Custom separable modes can be built from any blend function.
+//! let average = Separable::new(|s: f32, d: f32| (s + d) / 2.0);
+//! assert_eq!(average.apply(src, dst), F32x4Rgba::new(0.5, 0.75, 0.25, 1.0));
+//! ```
+
+use crate::{
+    RgbaBlend, math,
+    rgba::{F32x4Rgba, Rgba},
+};
+
+/// A [`BlendMode`][] that mixes colors channel by channel with a blend function, and composites
+/// the result with source-over.
+///
+/// [`BlendMode`]: crate::BlendMode
+#[derive(Debug, Clone, Copy)]
+pub struct Separable<F: Fn(f32, f32) -> f32> {
+    /// Computes a mixed channel given the source and destination channel values.
+    blend: F,
+}
+
+impl<F: Fn(f32, f32) -> f32> Separable<F> {
+    /// Creates a new `Separable` with the given blend function of `(src, dst)` channel values.
+    pub const fn new(blend: F) -> Self {
+        Self { blend }
+    }
+
+    /// Returns the result of mixing `src` with `dst`, composited with source-over.
+    #[must_use]
+    pub fn blend(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        let mixed = [
+            (self.blend)(src.r, dst.r),
+            (self.blend)(src.g, dst.g),
+            (self.blend)(src.b, dst.b),
+        ];
+        composite_mixed(src, dst, mixed)
+    }
+}
+
+/// Composites `src` onto `dst` with source-over, replacing the source color by `mixed` in
+/// proportion to the destination alpha.
+#[allow(clippy::suboptimal_flops)]
+pub(crate) fn composite_mixed(src: F32x4Rgba, dst: F32x4Rgba, mixed: [f32; 3]) -> F32x4Rgba {
+    let alpha = src.a + dst.a * (1.0 - src.a);
+    if alpha == 0.0 {
+        return F32x4Rgba::TRANSPARENT;
+    }
+    let channel = |s: f32, d: f32, m: f32| {
+        let s = (1.0 - dst.a) * s + dst.a * m;
+        (src.a * s + (1.0 - src.a) * dst.a * d) / alpha
+    };
+    F32x4Rgba::new(
+        channel(src.r, dst.r, mixed[0]),
+        channel(src.g, dst.g, mixed[1]),
+        channel(src.b, dst.b, mixed[2]),
+        alpha,
+    )
+}
+
+impl Separable<fn(f32, f32) -> f32> {
+    /// Source and destination colors are multiplied, darkening the result.
+    pub const MULTIPLY: Self = Self::new(multiply);
+
+    /// Complements of the source and destination colors are multiplied, lightening the result.
+    pub const SCREEN: Self = Self::new(screen);
+
+    /// Multiplies or screens the colors depending on the destination color.
+    pub const OVERLAY: Self = Self::new(overlay);
+
+    /// Multiplies or screens the colors depending on the source color.
+    pub const HARD_LIGHT: Self = Self::new(hard_light);
+
+    /// Darkens or lightens the colors depending on the source color.
+    pub const SOFT_LIGHT: Self = Self::new(soft_light);
+
+    /// Brightens the destination color to reflect the source color.
+    pub const COLOR_DODGE: Self = Self::new(color_dodge);
+
+    /// Darkens the destination color to reflect the source color.
+    pub const COLOR_BURN: Self = Self::new(color_burn);
+
+    /// Subtracts the darker of the source and destination colors from the lighter one.
+    pub const DIFFERENCE: Self = Self::new(difference);
+
+    /// Like [`DIFFERENCE`](Self::DIFFERENCE), but with lower contrast.
+    pub const EXCLUSION: Self = Self::new(exclusion);
+}
+
+impl<F: Fn(f32, f32) -> f32> RgbaBlend for Separable<F> {
+    type Channel = f32;
+
+    fn apply(&self, src: Rgba<Self::Channel>, dst: Rgba<Self::Channel>) -> Rgba<Self::Channel> {
+        self.blend(src, dst)
+    }
+}
+
+/// Multiplies the source and destination channels.
+#[must_use]
+pub fn multiply(src: f32, dst: f32) -> f32 {
+    src * dst
+}
+
+/// Multiplies the complements of the source and destination channels, and complements the result.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn screen(src: f32, dst: f32) -> f32 {
+    src + dst - src * dst
+}
+
+/// Hard light with the roles of the source and destination swapped.
+#[must_use]
+pub fn overlay(src: f32, dst: f32) -> f32 {
+    hard_light(dst, src)
+}
+
+/// Multiplies the destination by twice the source where the source is at most `0.5`, and screens
+/// it by `2 * src - 1` elsewhere.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn hard_light(src: f32, dst: f32) -> f32 {
+    if src <= 0.5 {
+        multiply(2.0 * src, dst)
+    } else {
+        screen(2.0 * src - 1.0, dst)
+    }
+}
+
+/// Darkens the destination where the source is at most `0.5` and lightens it elsewhere, by an
+/// amount that shrinks towards black and white destinations.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn soft_light(src: f32, dst: f32) -> f32 {
+    if src <= 0.5 {
+        dst - (1.0 - 2.0 * src) * dst * (1.0 - dst)
+    } else {
+        let d = if dst <= 0.25 {
+            ((16.0 * dst - 12.0) * dst + 4.0) * dst
+        } else {
+            math::sqrt(dst)
+        };
+        dst + (2.0 * src - 1.0) * (d - dst)
+    }
+}
+
+/// Divides the destination by the complement of the source.
+///
+/// A black destination stays black, and otherwise a white source gives white, instead of
+/// dividing by zero; results are clamped to at most `1.0`.
+#[must_use]
+pub fn color_dodge(src: f32, dst: f32) -> f32 {
+    if dst <= 0.0 {
+        0.0
+    } else if src >= 1.0 {
+        1.0
+    } else {
+        (dst / (1.0 - src)).min(1.0)
+    }
+}
+
+/// Divides the complement of the destination by the source, and complements the result.
+///
+/// A white destination stays white, and otherwise a black source gives black, instead of
+/// dividing by zero; results are clamped to at least `0.0`.
+#[must_use]
+pub fn color_burn(src: f32, dst: f32) -> f32 {
+    if dst >= 1.0 {
+        1.0
+    } else if src <= 0.0 {
+        0.0
+    } else {
+        1.0 - ((1.0 - dst) / src).min(1.0)
+    }
+}
+
+/// Returns the absolute difference of the source and destination channels.
+#[must_use]
+pub fn difference(src: f32, dst: f32) -> f32 {
+    (src - dst).abs()
+}
+
+/// Returns `src + dst - 2 * src * dst`.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn exclusion(src: f32, dst: f32) -> f32 {
+    src + dst - 2.0 * src * dst
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    const RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+    const GRAY: F32x4Rgba = F32x4Rgba::new(0.5, 0.5, 0.5, 1.0);
+
+    #[test]
+    fn multiply_opaque() {
+        assert_eq!(BlendMode::Multiply.apply(RED, F32x4Rgba::WHITE), RED);
+        assert_eq!(
+            BlendMode::Multiply.apply(GRAY, GRAY),
+            F32x4Rgba::new(0.25, 0.25, 0.25, 1.0)
+        );
+    }
+
+    #[test]
+    fn screen_opaque() {
+        assert_eq!(
+            BlendMode::Screen.apply(GRAY, GRAY),
+            F32x4Rgba::new(0.75, 0.75, 0.75, 1.0)
+        );
+        assert_eq!(BlendMode::Screen.apply(RED, F32x4Rgba::BLACK), RED);
+    }
+
+    #[test]
+    fn overlay_and_hard_light_swap_roles() {
+        let dark = F32x4Rgba::new(0.25, 0.25, 0.25, 1.0);
+        assert_eq!(
+            BlendMode::HardLight.apply(dark, GRAY),
+            F32x4Rgba::new(0.25, 0.25, 0.25, 1.0)
+        );
+        assert_eq!(
+            BlendMode::Overlay.apply(GRAY, dark),
+            BlendMode::HardLight.apply(dark, GRAY)
+        );
+        assert_eq!(hard_light(0.75, 0.5), 0.75);
+        assert_eq!(overlay(0.5, 0.75), 0.75);
+    }
+
+    #[test]
+    fn soft_light_reference_values() {
+        assert_eq!(soft_light(0.5, 0.3), 0.3);
+        assert_eq!(soft_light(0.0, 0.5), 0.25);
+        assert_eq!(soft_light(1.0, 0.25), 0.5);
+        assert_eq!(soft_light(1.0, 0.125), 0.343_75);
+        assert_eq!(
+            BlendMode::SoftLight.apply(F32x4Rgba::WHITE, GRAY).r,
+            math::sqrt(0.5)
+        );
+    }
+
+    #[test]
+    fn contrast_modes_fix_black_and_white() {
+        for mode in [BlendMode::Overlay, BlendMode::SoftLight] {
+            assert_eq!(mode.apply(GRAY, F32x4Rgba::BLACK), F32x4Rgba::BLACK);
+            assert_eq!(mode.apply(GRAY, F32x4Rgba::WHITE), F32x4Rgba::WHITE);
+        }
+    }
+
+    #[test]
+    fn dodge_and_burn_reference_values() {
+        assert_eq!(color_dodge(0.5, 0.25), 0.5);
+        assert_eq!(color_dodge(0.75, 0.5), 1.0);
+        assert_eq!(color_burn(0.5, 0.75), 0.5);
+        assert_eq!(color_burn(0.25, 0.5), 0.0);
+    }
+
+    #[test]
+    fn dodge_and_burn_singular_points() {
+        assert_eq!(color_dodge(1.0, 0.0), 0.0);
+        assert_eq!(color_dodge(1.0, 0.5), 1.0);
+        assert_eq!(color_burn(0.0, 1.0), 1.0);
+        assert_eq!(color_burn(0.0, 0.5), 0.0);
+        let out = BlendMode::ColorDodge.apply(F32x4Rgba::WHITE, GRAY);
+        assert_eq!(out, F32x4Rgba::WHITE);
+        let out = BlendMode::ColorBurn.apply(F32x4Rgba::BLACK, GRAY);
+        assert_eq!(out, F32x4Rgba::BLACK);
+    }
+
+    #[test]
+    fn difference_and_exclusion_opaque() {
+        let dst = F32x4Rgba::new(0.25, 0.75, 1.0, 1.0);
+        assert_eq!(
+            BlendMode::Difference.apply(GRAY, dst),
+            F32x4Rgba::new(0.25, 0.25, 0.5, 1.0)
+        );
+        assert_eq!(
+            BlendMode::Exclusion.apply(GRAY, dst),
+            F32x4Rgba::new(0.5, 0.5, 0.5, 1.0)
+        );
+        assert_eq!(BlendMode::Difference.apply(dst, dst).r, 0.0);
+        assert_eq!(exclusion(1.0, 0.25), 0.75);
+    }
+
+    #[test]
+    fn difference_composites_translucent_source() {
+        // Matches the canvas `globalCompositeOperation = "difference"` result.
+        let src = F32x4Rgba::new(1.0, 1.0, 1.0, 0.5);
+        let dst = F32x4Rgba::new(0.25, 0.25, 0.25, 1.0);
+        assert_eq!(
+            BlendMode::Difference.apply(src, dst),
+            F32x4Rgba::new(0.5, 0.5, 0.5, 1.0)
+        );
+        let dst = F32x4Rgba::new(0.25, 0.25, 0.25, 0.5);
+        let out = BlendMode::Difference.apply(src, dst);
+        assert_eq!(out.a, 0.75);
+        assert!((out.r - 0.5 / 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn translucent_source_mixes_with_destination() {
+        let src = F32x4Rgba::new(0.5, 0.5, 0.5, 0.5);
+        assert_eq!(
+            BlendMode::Multiply.apply(src, GRAY),
+            F32x4Rgba::new(0.375, 0.375, 0.375, 1.0)
+        );
+    }
+
+    #[test]
+    fn transparent_destination_keeps_source() {
+        let src = F32x4Rgba::new(0.2, 0.4, 0.6, 0.5);
+        for mode in [
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::HardLight,
+            BlendMode::SoftLight,
+            BlendMode::ColorDodge,
+            BlendMode::ColorBurn,
+            BlendMode::Difference,
+            BlendMode::Exclusion,
+        ] {
+            assert_eq!(mode.apply(src, F32x4Rgba::TRANSPARENT), src);
+        }
+    }
+
+    #[test]
+    fn transparent_source_keeps_destination() {
+        let dst = F32x4Rgba::new(0.2, 0.4, 0.6, 0.5);
+        assert_eq!(BlendMode::Screen.apply(F32x4Rgba::TRANSPARENT, dst), dst);
+        assert_eq!(
+            BlendMode::Multiply.apply(F32x4Rgba::TRANSPARENT, F32x4Rgba::TRANSPARENT),
+            F32x4Rgba::TRANSPARENT
+        );
+    }
+}
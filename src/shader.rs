@@ -0,0 +1,513 @@
+//! WGSL and GLSL source for the built-in blend modes.
+//!
+//! A renderer that previews on the CPU with this crate and composites on the GPU should use the
+//! same math in both places. [`source`] emits a shader function for a [`BlendMode`] that
+//! transcribes the crate's own implementation: straight-alpha `vec4` colors in, a straight-alpha
+//! `vec4` color out, with the same clamping and the same handling of transparent and
+//! divide-by-zero cases. GPU float rounding still differs from the CPU in the last bits.
+//!
+//! Each source is self-contained: it defines the blend function, named as
+//! [`function_name`] returns, after any helpers it calls, whose names start with `ab_`.
+//! Sources for several modes can be concatenated, but then define shared helpers more than once.
+//!
+//! _This module requires the `std` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, shader};
+//!
+//! let wgsl = BlendMode::SourceOver.to_wgsl().unwrap();
+//! assert!(wgsl.contains("fn blend_source_over(src: vec4<f32>, dst: vec4<f32>) -> vec4<f32>"));
+//!
+//! let glsl = BlendMode::Multiply.to_glsl().unwrap();
+//! assert!(glsl.contains("vec4 blend_multiply(vec4 src, vec4 dst)"));
+//! assert_eq!(shader::function_name(BlendMode::Multiply), Some("blend_multiply"));
+//! ```
+
+use std::{format, string::String, vec::Vec};
+
+use crate::{
+    BlendMode,
+    porter_duff::{Coefficient, Coefficients},
+};
+
+/// A shading language to emit blend functions in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// WebGPU Shading Language.
+    #[default]
+    Wgsl,
+
+    /// OpenGL Shading Language, in the subset shared by GLSL 3.30 and GLSL ES 3.00.
+    Glsl,
+}
+
+/// How a blend mode computes its result.
+enum Kind {
+    /// A weighted sum of the source and destination, with Porter-Duff coefficients.
+    PorterDuff(Coefficients),
+
+    /// A separable blend function of `(s, d)` channels, given as its body.
+    Separable(&'static str),
+
+    /// A non-separable blend function of `(s, d)` colors, given as a return expression.
+    NonSeparable(&'static str),
+
+    /// The body of the whole blend function.
+    Direct(&'static str),
+}
+
+/// Returns the function name of `mode`, and how it is computed; `None` for custom modes.
+#[allow(clippy::too_many_lines)]
+const fn describe(mode: BlendMode) -> Option<(&'static str, Kind)> {
+    use Kind::{Direct, NonSeparable, PorterDuff, Separable};
+
+    Some(match mode {
+        BlendMode::Clear => ("blend_clear", PorterDuff(Coefficients::CLEAR)),
+        BlendMode::Source => ("blend_source", PorterDuff(Coefficients::SRC)),
+        BlendMode::Destination => ("blend_destination", PorterDuff(Coefficients::DST)),
+        BlendMode::SourceOver => ("blend_source_over", PorterDuff(Coefficients::SRC_OVER)),
+        BlendMode::DestinationOver => {
+            ("blend_destination_over", PorterDuff(Coefficients::DST_OVER))
+        }
+        BlendMode::SourceIn => ("blend_source_in", PorterDuff(Coefficients::SRC_IN)),
+        BlendMode::DestinationIn => ("blend_destination_in", PorterDuff(Coefficients::DST_IN)),
+        BlendMode::SourceOut => ("blend_source_out", PorterDuff(Coefficients::SRC_OUT)),
+        BlendMode::DestinationOut => ("blend_destination_out", PorterDuff(Coefficients::DST_OUT)),
+        BlendMode::SourceAtop => ("blend_source_atop", PorterDuff(Coefficients::SRC_ATOP)),
+        BlendMode::DestinationAtop => {
+            ("blend_destination_atop", PorterDuff(Coefficients::DST_ATOP))
+        }
+        BlendMode::Xor => ("blend_xor", PorterDuff(Coefficients::XOR)),
+        BlendMode::Plus => ("blend_plus", PorterDuff(Coefficients::PLUS)),
+        BlendMode::Multiply => ("blend_multiply", Separable("return s * d;")),
+        BlendMode::Screen => ("blend_screen", Separable("return s + d - s * d;")),
+        BlendMode::Overlay => (
+            "blend_overlay",
+            Separable(
+                "if (d <= 0.5) {
+        return 2.0 * d * s;
+    }
+    {let_f} x = 2.0 * d - 1.0;
+    return x + s - x * s;",
+            ),
+        ),
+        BlendMode::HardLight => (
+            "blend_hard_light",
+            Separable(
+                "if (s <= 0.5) {
+        return 2.0 * s * d;
+    }
+    {let_f} x = 2.0 * s - 1.0;
+    return x + d - x * d;",
+            ),
+        ),
+        BlendMode::SoftLight => (
+            "blend_soft_light",
+            Separable(
+                "if (s <= 0.5) {
+        return d - (1.0 - 2.0 * s) * d * (1.0 - d);
+    }
+    {var_f} e = sqrt(d);
+    if (d <= 0.25) {
+        e = ((16.0 * d - 12.0) * d + 4.0) * d;
+    }
+    return d + (2.0 * s - 1.0) * (e - d);",
+            ),
+        ),
+        BlendMode::ColorDodge => (
+            "blend_color_dodge",
+            Separable(
+                "if (d <= 0.0) {
+        return 0.0;
+    }
+    if (s >= 1.0) {
+        return 1.0;
+    }
+    return min(d / (1.0 - s), 1.0);",
+            ),
+        ),
+        BlendMode::ColorBurn => (
+            "blend_color_burn",
+            Separable(
+                "if (d >= 1.0) {
+        return 1.0;
+    }
+    if (s <= 0.0) {
+        return 0.0;
+    }
+    return 1.0 - min((1.0 - d) / s, 1.0);",
+            ),
+        ),
+        BlendMode::Difference => ("blend_difference", Separable("return abs(s - d);")),
+        BlendMode::Exclusion => ("blend_exclusion", Separable("return s + d - 2.0 * s * d;")),
+        BlendMode::Hue => (
+            "blend_hue",
+            NonSeparable("ab_set_lum(ab_set_sat(s, ab_sat(d)), ab_lum(d))"),
+        ),
+        BlendMode::Saturation => (
+            "blend_saturation",
+            NonSeparable("ab_set_lum(ab_set_sat(d, ab_sat(s)), ab_lum(d))"),
+        ),
+        BlendMode::Color => ("blend_color", NonSeparable("ab_set_lum(s, ab_lum(d))")),
+        BlendMode::Luminosity => ("blend_luminosity", NonSeparable("ab_set_lum(d, ab_lum(s))")),
+        BlendMode::LinearBurn => (
+            "blend_linear_burn",
+            Separable("return max(s + d - 1.0, 0.0);"),
+        ),
+        BlendMode::LinearDodge => ("blend_linear_dodge", Separable("return min(s + d, 1.0);")),
+        BlendMode::VividLight => (
+            "blend_vivid_light",
+            Separable(
+                "if (s <= 0.5) {
+        {let_f} b = 2.0 * s;
+        if (d >= 1.0) {
+            return 1.0;
+        }
+        if (b <= 0.0) {
+            return 0.0;
+        }
+        return 1.0 - min((1.0 - d) / b, 1.0);
+    }
+    {let_f} t = 2.0 * s - 1.0;
+    if (d <= 0.0) {
+        return 0.0;
+    }
+    if (t >= 1.0) {
+        return 1.0;
+    }
+    return min(d / (1.0 - t), 1.0);",
+            ),
+        ),
+        BlendMode::LinearLight => (
+            "blend_linear_light",
+            Separable("return clamp(d + 2.0 * s - 1.0, 0.0, 1.0);"),
+        ),
+        BlendMode::PinLight => (
+            "blend_pin_light",
+            Separable(
+                "if (s <= 0.5) {
+        return min(d, 2.0 * s);
+    }
+    return max(d, 2.0 * s - 1.0);",
+            ),
+        ),
+        BlendMode::HardMix => (
+            "blend_hard_mix",
+            Separable(
+                "if (s + d >= 1.0) {
+        return 1.0;
+    }
+    return 0.0;",
+            ),
+        ),
+        BlendMode::Subtract => ("blend_subtract", Separable("return max(d - s, 0.0);")),
+        BlendMode::Divide => (
+            "blend_divide",
+            Separable(
+                "if (d <= 0.0) {
+        return 0.0;
+    }
+    if (s <= 0.0) {
+        return 1.0;
+    }
+    return min(d / s, 1.0);",
+            ),
+        ),
+        BlendMode::Modulate => ("blend_modulate", Direct("return src * dst;")),
+        BlendMode::PlusLighter => (
+            "blend_plus_lighter",
+            Direct(
+                "return ab_unpremultiply(min(ab_premultiply(src) + ab_premultiply(dst), {vec4}(1.0)));",
+            ),
+        ),
+        BlendMode::PlusDarker => (
+            "blend_plus_darker",
+            Direct(
+                "{let_v4} s = ab_premultiply(src);
+    {let_v4} d = ab_premultiply(dst);
+    {let_f} a = min(s.a + d.a, 1.0);
+    {let_v3} c = max(a - ((s.a - s.rgb) + (d.a - d.rgb)), {vec3}(0.0));
+    return ab_unpremultiply({vec4}(c, a));",
+            ),
+        ),
+        BlendMode::Custom(_) => return None,
+    })
+}
+
+/// Composites a mixed color `m` with source-over, as `separable::composite_mixed`.
+const COMPOSITE_MIXED: &str = "{fn ab_composite_mixed(src: v4, dst: v4, m: v3) -> v4} {
+    {let_f} alpha = src.a + dst.a * (1.0 - src.a);
+    if (alpha == 0.0) {
+        return {vec4}(0.0);
+    }
+    {let_v3} s = (1.0 - dst.a) * src.rgb + dst.a * m;
+    return {vec4}((src.a * s + (1.0 - src.a) * dst.a * dst.rgb) / alpha, alpha);
+}
+";
+
+/// The `Lum`, `ClipColor`, `SetLum`, `Sat` and `SetSat` functions of the specification, as in
+/// the `nonseparable` module.
+const NON_SEPARABLE_HELPERS: &str = "{fn ab_lum(c: v3) -> f} {
+    return 0.3 * c.r + 0.59 * c.g + 0.11 * c.b;
+}
+
+{fn ab_clip_color(c: v3) -> v3} {
+    {let_f} l = ab_lum(c);
+    {let_f} n = min(min(c.r, c.g), c.b);
+    {let_f} x = max(max(c.r, c.g), c.b);
+    {var_v3} o = c;
+    if (n < 0.0) {
+        o = l + (o - l) * l / (l - n);
+    }
+    if (x > 1.0) {
+        o = l + (o - l) * (1.0 - l) / (x - l);
+    }
+    return o;
+}
+
+{fn ab_set_lum(c: v3, l: f) -> v3} {
+    return ab_clip_color(c + (l - ab_lum(c)));
+}
+
+{fn ab_sat(c: v3) -> f} {
+    return max(max(c.r, c.g), c.b) - min(min(c.r, c.g), c.b);
+}
+
+{fn ab_set_sat(c: v3, s: f) -> v3} {
+    {let_f} n = min(min(c.r, c.g), c.b);
+    {let_f} x = max(max(c.r, c.g), c.b);
+    if (x > n) {
+        return (c - n) * s / (x - n);
+    }
+    return {vec3}(0.0);
+}
+";
+
+/// Conversions between straight and premultiplied alpha, as on `F32x4Rgba`.
+const PREMULTIPLY_HELPERS: &str = "{fn ab_premultiply(c: v4) -> v4} {
+    return {vec4}(c.rgb * c.a, c.a);
+}
+
+{fn ab_unpremultiply(c: v4) -> v4} {
+    if (c.a == 0.0) {
+        return {vec4}(0.0);
+    }
+    return {vec4}(c.rgb / c.a, c.a);
+}
+";
+
+/// Returns the shader expression for a Porter-Duff coefficient.
+const fn coefficient(c: Coefficient) -> &'static str {
+    match c {
+        Coefficient::Zero => "0.0",
+        Coefficient::One => "1.0",
+        Coefficient::SrcAlpha => "src.a",
+        Coefficient::DstAlpha => "dst.a",
+        Coefficient::OneMinusSrcAlpha => "(1.0 - src.a)",
+        Coefficient::OneMinusDstAlpha => "(1.0 - dst.a)",
+    }
+}
+
+/// Returns the name of the function that [`source`] defines for `mode`, or `None` for
+/// [`BlendMode::Custom`].
+#[must_use]
+pub const fn function_name(mode: BlendMode) -> Option<&'static str> {
+    match describe(mode) {
+        Some((name, _)) => Some(name),
+        None => None,
+    }
+}
+
+/// Returns the source of a `language` function blending straight-alpha `src` onto `dst` with
+/// `mode`, or `None` for [`BlendMode::Custom`], whose function is opaque.
+#[must_use]
+pub fn source(mode: BlendMode, language: Language) -> Option<String> {
+    let (name, kind) = describe(mode)?;
+    let mut out = String::new();
+    let main = match kind {
+        Kind::PorterDuff(c) => format!(
+            "return {} * src + {} * dst;",
+            coefficient(c.src),
+            coefficient(c.dst)
+        ),
+        Kind::Separable(body) => {
+            out.push_str(COMPOSITE_MIXED);
+            out.push('\n');
+            out.push_str("{fn ab_mix(s: f, d: f) -> f} {\n    ");
+            out.push_str(body);
+            out.push_str("\n}\n\n");
+            "return ab_composite_mixed(src, dst, {vec3}(ab_mix(src.r, dst.r), \
+             ab_mix(src.g, dst.g), ab_mix(src.b, dst.b)));"
+                .into()
+        }
+        Kind::NonSeparable(expr) => {
+            out.push_str(COMPOSITE_MIXED);
+            out.push('\n');
+            out.push_str(NON_SEPARABLE_HELPERS);
+            out.push('\n');
+            format!(
+                "{{let_v3}} s = src.rgb;\n    {{let_v3}} d = dst.rgb;\n    return ab_composite_mixed(src, dst, {expr});"
+            )
+        }
+        Kind::Direct(body) => {
+            if body.contains("ab_premultiply") {
+                out.push_str(PREMULTIPLY_HELPERS);
+                out.push('\n');
+            }
+            body.into()
+        }
+    };
+    out.push_str("{fn ");
+    out.push_str(name);
+    out.push_str("(src: v4, dst: v4) -> v4} {\n    ");
+    out.push_str(&main);
+    out.push_str("\n}\n");
+    Some(translate(&out, language))
+}
+
+/// Expands the placeholders of a source template for `language`.
+///
+/// Templates write expressions and statements in the common subset of WGSL and GLSL, and use
+/// `{fn name(arg: type, …) -> type}` signatures (with types `f`, `v3` and `v4`), `{let_*}` and
+/// `{var_*}` declarations and `{vec3}` / `{vec4}` constructors for the rest.
+fn translate(template: &str, language: Language) -> String {
+    let mut out = String::with_capacity(template.len() * 2);
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let token = &rest[start + 1..start + len];
+        out.push_str(&rest[..start]);
+        if let Some(signature) = token.strip_prefix("fn ") {
+            out.push_str(&expand_signature(signature, language));
+        } else if let Some(ty) = token
+            .strip_prefix("let_")
+            .or_else(|| token.strip_prefix("var_"))
+        {
+            out.push_str(match language {
+                Language::Wgsl if token.starts_with("let_") => "let",
+                Language::Wgsl => "var",
+                Language::Glsl => type_name(ty, language),
+            });
+        } else if let Some(size) = token
+            .strip_prefix("vec")
+            .filter(|s| matches!(*s, "3" | "4"))
+        {
+            out.push_str(type_name(if size == "3" { "v3" } else { "v4" }, language));
+        } else {
+            // A block brace of the shader itself.
+            out.push_str(&rest[start..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns the function signature `name(arg: type, …) -> type` in `language`.
+fn expand_signature(signature: &str, language: Language) -> String {
+    let (head, ret) = signature
+        .split_once(" -> ")
+        .expect("signature has a return type");
+    let (name, args) = head
+        .strip_suffix(')')
+        .and_then(|head| head.split_once('('))
+        .expect("signature has arguments");
+    let args = args.split(", ").map(|arg| {
+        let (arg, ty) = arg.split_once(": ").expect("argument has a type");
+        match language {
+            Language::Wgsl => format!("{arg}: {}", type_name(ty, language)),
+            Language::Glsl => format!("{} {arg}", type_name(ty, language)),
+        }
+    });
+    let args = args.collect::<Vec<_>>().join(", ");
+    let ret = type_name(ret, language);
+    match language {
+        Language::Wgsl => format!("fn {name}({args}) -> {ret}"),
+        Language::Glsl => format!("{ret} {name}({args})"),
+    }
+}
+
+/// Returns the name of the template type `f`, `v3` or `v4` in `language`.
+fn type_name(ty: &str, language: Language) -> &'static str {
+    match (ty, language) {
+        ("f", Language::Wgsl) => "f32",
+        ("v3", Language::Wgsl) => "vec3<f32>",
+        ("v4", Language::Wgsl) => "vec4<f32>",
+        ("f", Language::Glsl) => "float",
+        ("v3", Language::Glsl) => "vec3",
+        ("v4", Language::Glsl) => "vec4",
+        _ => unreachable!("unknown template type {ty}"),
+    }
+}
+
+impl BlendMode {
+    /// Returns the WGSL source of this blend mode; see [`shader::source`](source).
+    ///
+    /// _This method requires the `std` feature._
+    #[must_use]
+    pub fn to_wgsl(self) -> Option<String> {
+        source(self, Language::Wgsl)
+    }
+
+    /// Returns the GLSL source of this blend mode; see [`shader::source`](source).
+    ///
+    /// _This method requires the `std` feature._
+    #[must_use]
+    pub fn to_glsl(self) -> Option<String> {
+        source(self, Language::Glsl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_builtin_mode_has_a_source() {
+        for mode in (0..=u8::MAX).filter_map(|value| BlendMode::try_from(value).ok()) {
+            let name = function_name(mode).unwrap();
+            let wgsl = mode.to_wgsl().unwrap();
+            assert!(wgsl.contains(&format!("fn {name}(src: vec4<f32>, dst: vec4<f32>)")));
+            let glsl = mode.to_glsl().unwrap();
+            assert!(glsl.contains(&format!("vec4 {name}(vec4 src, vec4 dst)")));
+            for source in [wgsl, glsl] {
+                assert!(
+                    !source.contains("{let") && !source.contains("{fn"),
+                    "{source}"
+                );
+                assert_eq!(source.matches('{').count(), source.matches('}').count());
+            }
+        }
+    }
+
+    #[test]
+    fn custom_has_no_source() {
+        let mode = BlendMode::Custom(|src, _| src);
+        assert_eq!(function_name(mode), None);
+        assert_eq!(source(mode, Language::Glsl), None);
+    }
+
+    #[test]
+    fn porter_duff_coefficients() {
+        let wgsl = BlendMode::SourceOver.to_wgsl().unwrap();
+        assert_eq!(
+            wgsl,
+            "fn blend_source_over(src: vec4<f32>, dst: vec4<f32>) -> vec4<f32> {\n    \
+             return src.a * src + (1.0 - src.a) * dst;\n}\n"
+        );
+    }
+
+    #[test]
+    fn glsl_declarations() {
+        let glsl = BlendMode::SoftLight.to_glsl().unwrap();
+        assert!(glsl.contains("float ab_mix(float s, float d)"));
+        assert!(glsl.contains("float e = sqrt(d);"));
+        assert!(!glsl.contains("let ") && !glsl.contains("var ") && !glsl.contains("<f32>"));
+    }
+}
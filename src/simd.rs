@@ -0,0 +1,262 @@
+//! Vectorized `u8` kernels.
+//!
+//! On x86-64, with the `simd` feature, kernels are selected by runtime CPU feature detection:
+//! SSE2, which every x86-64 CPU supports, and AVX2 where the running CPU has it. On wasm32, with
+//! the `wasm_simd` feature, a `simd128` kernel is used when the module is compiled with that
+//! target feature, since WebAssembly has no runtime detection. On other targets no pixels are
+//! processed here, and callers fall back to their scalar loops.
+
+use crate::rgba::U8x4Rgba;
+
+/// Blends the longest vectorizable prefix of `src` over `dst` with integer `SourceOver`,
+/// returning its length.
+///
+/// Results are identical to [`U8x4Rgba::source_over`]; the caller blends the remaining pixels.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn source_over_prefix(src: &[U8x4Rgba], dst: &mut [U8x4Rgba]) -> usize {
+    debug_assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst must have the same length"
+    );
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if std::is_x86_feature_detected!("avx2") {
+            // Safety: the running CPU supports AVX2.
+            unsafe { x86_64::source_over_avx2(src, dst) }
+        } else {
+            // Safety: SSE2 is part of the x86-64 baseline.
+            unsafe { x86_64::source_over_sse2(src, dst) }
+        }
+    }
+    #[cfg(all(
+        feature = "wasm_simd",
+        target_arch = "wasm32",
+        target_feature = "simd128"
+    ))]
+    {
+        wasm32::source_over_simd128(src, dst)
+    }
+    #[cfg(not(any(
+        all(feature = "simd", target_arch = "x86_64"),
+        all(
+            feature = "wasm_simd",
+            target_arch = "wasm32",
+            target_feature = "simd128"
+        )
+    )))]
+    {
+        let _ = (src, dst);
+        0
+    }
+}
+
+// Most intrinsics are safe to call inside `#[target_feature]` functions from Rust 1.87, but not
+// on the minimum supported version. Loads and stores are unaligned, so casting pixel pointers to
+// vector pointers is fine.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[allow(unused_unsafe, clippy::cast_ptr_alignment)]
+mod x86_64 {
+    use core::arch::x86_64::{
+        __m128i, __m256i, _mm_add_epi16, _mm_and_si128, _mm_loadu_si128, _mm_mullo_epi16,
+        _mm_or_si128, _mm_packus_epi16, _mm_set_epi16, _mm_set1_epi16, _mm_setzero_si128,
+        _mm_shufflehi_epi16, _mm_shufflelo_epi16, _mm_srli_epi16, _mm_storeu_si128, _mm_sub_epi16,
+        _mm_unpackhi_epi8, _mm_unpacklo_epi8, _mm256_add_epi16, _mm256_and_si256,
+        _mm256_loadu_si256, _mm256_mullo_epi16, _mm256_or_si256, _mm256_packus_epi16,
+        _mm256_set_epi16, _mm256_set1_epi16, _mm256_setzero_si256, _mm256_shufflehi_epi16,
+        _mm256_shufflelo_epi16, _mm256_srli_epi16, _mm256_storeu_si256, _mm256_sub_epi16,
+        _mm256_unpackhi_epi8, _mm256_unpacklo_epi8,
+    };
+
+    use crate::rgba::U8x4Rgba;
+
+    /// Shuffle control broadcasting the alpha lane of each pixel to its four lanes.
+    const ALPHA: i32 = 0b11_11_11_11;
+
+    /// Blends the first `src.len() / 4 * 4` pixels, four at a time.
+    ///
+    /// ## Safety
+    ///
+    /// The running CPU must support SSE2.
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn source_over_sse2(src: &[U8x4Rgba], dst: &mut [U8x4Rgba]) -> usize {
+        let len = src.len().min(dst.len()) / 4 * 4;
+        for i in (0..len).step_by(4) {
+            // Safety: `i + 4 <= len`, so 16 bytes are in bounds of both slices.
+            unsafe {
+                let s = _mm_loadu_si128(src.as_ptr().add(i).cast::<__m128i>());
+                let d = _mm_loadu_si128(dst.as_ptr().add(i).cast::<__m128i>());
+                let zero = _mm_setzero_si128();
+                let lo = blend_sse2(_mm_unpacklo_epi8(s, zero), _mm_unpacklo_epi8(d, zero));
+                let hi = blend_sse2(_mm_unpackhi_epi8(s, zero), _mm_unpackhi_epi8(d, zero));
+                _mm_storeu_si128(
+                    dst.as_mut_ptr().add(i).cast::<__m128i>(),
+                    _mm_packus_epi16(lo, hi),
+                );
+            }
+        }
+        len
+    }
+
+    /// Blends two pixels widened to 16-bit lanes.
+    ///
+    /// ## Safety
+    ///
+    /// The running CPU must support SSE2.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    unsafe fn blend_sse2(s: __m128i, d: __m128i) -> __m128i {
+        // Safety: the caller guarantees SSE2 support.
+        unsafe {
+            let a = _mm_shufflehi_epi16(_mm_shufflelo_epi16(s, ALPHA), ALPHA);
+            let inv_a = _mm_sub_epi16(_mm_set1_epi16(255), a);
+            // Colors are scaled by the source alpha, the source alpha itself by 255.
+            let rgb = _mm_set_epi16(0, -1, -1, -1, 0, -1, -1, -1);
+            let opaque = _mm_set_epi16(255, 0, 0, 0, 255, 0, 0, 0);
+            let src_f = _mm_or_si128(_mm_and_si128(a, rgb), opaque);
+            let v = _mm_add_epi16(_mm_mullo_epi16(s, src_f), _mm_mullo_epi16(d, inv_a));
+            // `math::div_255`: (v + (v >> 8) + 1) >> 8.
+            let v = _mm_add_epi16(_mm_add_epi16(v, _mm_srli_epi16(v, 8)), _mm_set1_epi16(1));
+            _mm_srli_epi16(v, 8)
+        }
+    }
+
+    /// Blends the first `src.len() / 8 * 8` pixels, eight at a time.
+    ///
+    /// ## Safety
+    ///
+    /// The running CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn source_over_avx2(src: &[U8x4Rgba], dst: &mut [U8x4Rgba]) -> usize {
+        let len = src.len().min(dst.len()) / 8 * 8;
+        for i in (0..len).step_by(8) {
+            // Safety: `i + 8 <= len`, so 32 bytes are in bounds of both slices.
+            unsafe {
+                let s = _mm256_loadu_si256(src.as_ptr().add(i).cast::<__m256i>());
+                let d = _mm256_loadu_si256(dst.as_ptr().add(i).cast::<__m256i>());
+                let zero = _mm256_setzero_si256();
+                let lo = blend_avx2(_mm256_unpacklo_epi8(s, zero), _mm256_unpacklo_epi8(d, zero));
+                let hi = blend_avx2(_mm256_unpackhi_epi8(s, zero), _mm256_unpackhi_epi8(d, zero));
+                // Unpacking and packing both work within 128-bit lanes, so pixels keep their order.
+                _mm256_storeu_si256(
+                    dst.as_mut_ptr().add(i).cast::<__m256i>(),
+                    _mm256_packus_epi16(lo, hi),
+                );
+            }
+        }
+        // Safety: AVX2 implies SSE2.
+        len + unsafe { source_over_sse2(&src[len..], &mut dst[len..]) }
+    }
+
+    /// Blends four pixels widened to 16-bit lanes.
+    ///
+    /// ## Safety
+    ///
+    /// The running CPU must support AVX2.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn blend_avx2(s: __m256i, d: __m256i) -> __m256i {
+        // Safety: the caller guarantees AVX2 support.
+        unsafe {
+            let a = _mm256_shufflehi_epi16(_mm256_shufflelo_epi16(s, ALPHA), ALPHA);
+            let inv_a = _mm256_sub_epi16(_mm256_set1_epi16(255), a);
+            let rgb = _mm256_set_epi16(0, -1, -1, -1, 0, -1, -1, -1, 0, -1, -1, -1, 0, -1, -1, -1);
+            let opaque = _mm256_set_epi16(255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0);
+            let src_f = _mm256_or_si256(_mm256_and_si256(a, rgb), opaque);
+            let v = _mm256_add_epi16(_mm256_mullo_epi16(s, src_f), _mm256_mullo_epi16(d, inv_a));
+            let v = _mm256_add_epi16(
+                _mm256_add_epi16(v, _mm256_srli_epi16(v, 8)),
+                _mm256_set1_epi16(1),
+            );
+            _mm256_srli_epi16(v, 8)
+        }
+    }
+}
+
+// Loads and stores are unaligned, so casting pixel pointers to vector pointers is fine.
+#[cfg(all(
+    feature = "wasm_simd",
+    target_arch = "wasm32",
+    target_feature = "simd128"
+))]
+#[allow(clippy::cast_ptr_alignment)]
+mod wasm32 {
+    use core::arch::wasm32::{
+        i16x8_add, i16x8_mul, i16x8_shuffle, i16x8_sub, u8x16_narrow_i16x8, u16x8,
+        u16x8_extend_high_u8x16, u16x8_extend_low_u8x16, u16x8_shr, u16x8_splat, v128, v128_and,
+        v128_load, v128_or, v128_store,
+    };
+
+    use crate::rgba::U8x4Rgba;
+
+    /// Blends the first `src.len() / 4 * 4` pixels, four at a time.
+    pub(super) fn source_over_simd128(src: &[U8x4Rgba], dst: &mut [U8x4Rgba]) -> usize {
+        let len = src.len().min(dst.len()) / 4 * 4;
+        for i in (0..len).step_by(4) {
+            // Safety: `i + 4 <= len`, so 16 bytes are in bounds of both slices.
+            unsafe {
+                let s = v128_load(src.as_ptr().add(i).cast::<v128>());
+                let d = v128_load(dst.as_ptr().add(i).cast::<v128>());
+                let lo = blend(u16x8_extend_low_u8x16(s), u16x8_extend_low_u8x16(d));
+                let hi = blend(u16x8_extend_high_u8x16(s), u16x8_extend_high_u8x16(d));
+                v128_store(
+                    dst.as_mut_ptr().add(i).cast::<v128>(),
+                    u8x16_narrow_i16x8(lo, hi),
+                );
+            }
+        }
+        len
+    }
+
+    /// Blends two pixels widened to 16-bit lanes.
+    #[inline]
+    fn blend(s: v128, d: v128) -> v128 {
+        let a = i16x8_shuffle::<3, 3, 3, 3, 7, 7, 7, 7>(s, s);
+        let inv_a = i16x8_sub(u16x8_splat(255), a);
+        // Colors are scaled by the source alpha, the source alpha itself by 255.
+        let rgb = u16x8(0xffff, 0xffff, 0xffff, 0, 0xffff, 0xffff, 0xffff, 0);
+        let opaque = u16x8(0, 0, 0, 255, 0, 0, 0, 255);
+        let src_f = v128_or(v128_and(a, rgb), opaque);
+        let v = i16x8_add(i16x8_mul(s, src_f), i16x8_mul(d, inv_a));
+        // `math::div_255`: (v + (v >> 8) + 1) >> 8.
+        let v = i16x8_add(i16x8_add(v, u16x8_shr(v, 8)), u16x8_splat(1));
+        u16x8_shr(v, 8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [u8; 7] = [0, 1, 127, 128, 200, 254, 255];
+
+    /// Every combination of a few interesting channel values, as pixels.
+    fn pixels() -> [U8x4Rgba; 147] {
+        core::array::from_fn(|i| {
+            let (r, a, g) = (VALUES[i / 21], VALUES[i / 3 % 7], [0, 77, 255][i % 3]);
+            U8x4Rgba::new(r, g, 255 - r, a)
+        })
+    }
+
+    #[test]
+    fn matches_scalar_source_over() {
+        let src = pixels();
+        let mut dst: [U8x4Rgba; 147] = core::array::from_fn(|i| src[146 - i]);
+        let expected: [U8x4Rgba; 147] = core::array::from_fn(|i| src[i].source_over(dst[i]));
+
+        let done = source_over_prefix(&src, &mut dst);
+        assert_eq!(dst[..done], expected[..done]);
+        #[cfg(any(
+            all(feature = "simd", target_arch = "x86_64"),
+            all(
+                feature = "wasm_simd",
+                target_arch = "wasm32",
+                target_feature = "simd128"
+            )
+        ))]
+        assert!(
+            done >= src.len() - 3,
+            "only a short tail is left to the caller"
+        );
+    }
+}
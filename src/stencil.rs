@@ -0,0 +1,246 @@
+//! Stencil-tested compositing.
+//!
+//! Mirrors the GPU stencil test: an auxiliary buffer of [`u8`] stencil values, one per
+//! destination pixel, gates which destination pixels a blend writes. A typical masked UI effect
+//! first [marks](mark_visible) the stencil with a shape, then blends content only where the
+//! stencil is [`equal`](StencilState::equal) to the mark.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode,
+//!     rgba::F32x4Rgba,
+//!     stencil::{self, StencilState},
+//! };
+//!
+//! // Mark the pixels covered by a (here, single-pixel) shape.
+//! let mut stencil = [0; 3];
+//! let shape = [F32x4Rgba::TRANSPARENT, F32x4Rgba::WHITE, F32x4Rgba::TRANSPARENT];
+//! stencil::mark_visible(&mut stencil, &shape, 1);
+//!
+//! // Content is only drawn inside the shape.
+//! let mut dst = [F32x4Rgba::BLACK; 3];
+//! stencil::blend_stencil_tested(
+//!     &BlendMode::SourceOver,
+//!     &[F32x4Rgba::WHITE; 3],
+//!     &mut dst,
+//!     &stencil,
+//!     StencilState::equal(1),
+//! );
+//! assert_eq!(dst, [F32x4Rgba::BLACK, F32x4Rgba::WHITE, F32x4Rgba::BLACK]);
+//! ```
+
+use crate::{RgbaBlend, rgba::F32x4Rgba};
+
+/// Comparison between the masked reference value and the masked stencil value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StencilTest {
+    /// Always passes.
+    #[default]
+    Always,
+
+    /// Never passes.
+    Never,
+
+    /// Passes if `reference & mask == stencil & mask`.
+    Equal,
+
+    /// Passes if `reference & mask != stencil & mask`.
+    NotEqual,
+}
+
+/// Stencil test configuration for [`blend_stencil_tested`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StencilState {
+    /// Comparison each destination pixel's stencil value must pass.
+    pub test: StencilTest,
+
+    /// Reference value compared against the stencil buffer.
+    pub reference: u8,
+
+    /// Bits of the reference and stencil values taking part in the comparison.
+    pub mask: u8,
+}
+
+impl Default for StencilState {
+    /// Returns a state that always passes.
+    fn default() -> Self {
+        Self {
+            test: StencilTest::Always,
+            reference: 0,
+            mask: 0xFF,
+        }
+    }
+}
+
+impl StencilState {
+    /// Creates a state passing where the stencil value equals `reference`.
+    #[must_use]
+    pub const fn equal(reference: u8) -> Self {
+        Self {
+            test: StencilTest::Equal,
+            reference,
+            mask: 0xFF,
+        }
+    }
+
+    /// Creates a state passing where the stencil value does not equal `reference`.
+    #[must_use]
+    pub const fn not_equal(reference: u8) -> Self {
+        Self {
+            test: StencilTest::NotEqual,
+            reference,
+            mask: 0xFF,
+        }
+    }
+
+    /// Returns a copy of this state comparing only the bits set in `mask`.
+    ///
+    /// This allows a single stencil buffer to hold several independent masks, one per bit.
+    #[must_use]
+    pub const fn with_mask(self, mask: u8) -> Self {
+        Self {
+            test: self.test,
+            reference: self.reference,
+            mask,
+        }
+    }
+
+    /// Returns `true` if a destination pixel with the given `stencil` value passes the test.
+    #[must_use]
+    pub const fn passes(&self, stencil: u8) -> bool {
+        match self.test {
+            StencilTest::Always => true,
+            StencilTest::Never => false,
+            StencilTest::Equal => self.reference & self.mask == stencil & self.mask,
+            StencilTest::NotEqual => self.reference & self.mask != stencil & self.mask,
+        }
+    }
+}
+
+/// Blends `src` onto `dst` in place, only where the `stencil` value passes the stencil test.
+///
+/// ## Panics
+///
+/// If `src`, `dst` and `stencil` do not all have the same length.
+pub fn blend_stencil_tested<B>(
+    blend: &B,
+    src: &[F32x4Rgba],
+    dst: &mut [F32x4Rgba],
+    stencil: &[u8],
+    state: StencilState,
+) where
+    B: RgbaBlend<Channel = f32> + ?Sized,
+{
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    assert_eq!(
+        stencil.len(),
+        dst.len(),
+        "stencil must have one value per pixel"
+    );
+    for ((s, d), &st) in src.iter().zip(dst.iter_mut()).zip(stencil) {
+        if state.passes(st) {
+            *d = blend.apply(*s, *d);
+        }
+    }
+}
+
+/// Sets the `stencil` value to `value` wherever `shape` is not fully transparent.
+///
+/// ## Panics
+///
+/// If `stencil` and `shape` do not have the same length.
+pub fn mark_visible(stencil: &mut [u8], shape: &[F32x4Rgba], value: u8) {
+    assert_eq!(
+        stencil.len(),
+        shape.len(),
+        "stencil must have one value per pixel"
+    );
+    for (st, s) in stencil.iter_mut().zip(shape) {
+        if s.a > 0.0 {
+            *st = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlendMode;
+
+    #[test]
+    fn default_always_passes() {
+        let state = StencilState::default();
+        assert!((0..=u8::MAX).all(|st| state.passes(st)));
+    }
+
+    #[test]
+    fn never_fails() {
+        let state = StencilState {
+            test: StencilTest::Never,
+            ..StencilState::default()
+        };
+        assert!((0..=u8::MAX).all(|st| !state.passes(st)));
+    }
+
+    #[test]
+    fn equal_and_not_equal() {
+        assert!(StencilState::equal(3).passes(3));
+        assert!(!StencilState::equal(3).passes(4));
+        assert!(!StencilState::not_equal(3).passes(3));
+        assert!(StencilState::not_equal(3).passes(4));
+    }
+
+    #[test]
+    fn mask_selects_bits() {
+        let state = StencilState::equal(0b10).with_mask(0b10);
+        assert!(state.passes(0b10));
+        assert!(state.passes(0b11));
+        assert!(state.passes(0b1110));
+        assert!(!state.passes(0b01));
+        assert!(!StencilState::not_equal(0b10).with_mask(0b10).passes(0b11));
+    }
+
+    #[test]
+    fn blends_only_passing_pixels() {
+        let src = [F32x4Rgba::WHITE; 3];
+        let mut dst = [F32x4Rgba::BLACK; 3];
+        blend_stencil_tested(
+            &BlendMode::Source,
+            &src,
+            &mut dst,
+            &[0, 1, 2],
+            StencilState::not_equal(1),
+        );
+        assert_eq!(dst, [F32x4Rgba::WHITE, F32x4Rgba::BLACK, F32x4Rgba::WHITE]);
+    }
+
+    #[test]
+    fn mark_visible_skips_transparent() {
+        let mut stencil = [7; 3];
+        let shape = [
+            F32x4Rgba::TRANSPARENT,
+            F32x4Rgba::new(0.0, 0.0, 0.0, 0.1),
+            F32x4Rgba::WHITE,
+        ];
+        mark_visible(&mut stencil, &shape, 1);
+        assert_eq!(stencil, [7, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one value per pixel")]
+    fn panics_on_stencil_length_mismatch() {
+        blend_stencil_tested(
+            &BlendMode::Source,
+            &[F32x4Rgba::WHITE; 2],
+            &mut [F32x4Rgba::BLACK; 2],
+            &[0],
+            StencilState::default(),
+        );
+    }
+}
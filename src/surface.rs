@@ -0,0 +1,376 @@
+//! Owned two-dimensional pixel buffers.
+//!
+//! A [`Surface`] is a `width × height` buffer of straight-alpha [`F32x4Rgba`] pixels, stored
+//! row-major: a [`Canvas`] of `f32` channels. Besides pixel access, it provides positioned
+//! compositing, coverage statistics and trimming of transparent borders, for sprite packers and
+//! compositors culling layers.
+//!
+//! _This module requires the `alloc` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, rect::Rect, rgba::F32x4Rgba, surface::Surface};
+//!
+//! let mut sprite = Surface::new(4, 4);
+//! sprite.set(1, 2, F32x4Rgba::WHITE);
+//! sprite.set(2, 2, F32x4Rgba::WHITE);
+//!
+//! assert_eq!(sprite.coverage(), 2.0 / 16.0);
+//! assert_eq!(sprite.content_bounds(), Some(Rect::new(1, 2, 2, 1)));
+//!
+//! let (offset, trimmed) = sprite.trim().unwrap();
+//! assert_eq!((offset.x, offset.y), (1, 2));
+//! assert_eq!(trimmed.pixels(), &[F32x4Rgba::WHITE; 2]);
+//!
+//! // Composite the trimmed sprite onto a canvas, clipped to its edges.
+//! let mut canvas = Surface::filled(3, 3, F32x4Rgba::BLACK);
+//! canvas.composite(&trimmed, 2, 0, BlendMode::SourceOver);
+//! assert_eq!(canvas.get(2, 0), Some(F32x4Rgba::WHITE));
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{
+    BlendMode,
+    canvas::Canvas,
+    composite::{self, View, ViewMut},
+    compositor::Group,
+    rect::Rect,
+    rgba::F32x4Rgba,
+};
+
+/// A `width × height` buffer of straight-alpha pixels, stored row-major.
+///
+/// This is a [`Canvas`] of `f32` channels, so it has the same constructors and pixel access,
+/// and [`Canvas::composite_at`] blends raw pixel slices onto it.
+pub type Surface = Canvas<f32>;
+
+impl Surface {
+    /// Returns a [`View`] of the whole surface.
+    #[must_use]
+    pub fn view(&self) -> View<'_> {
+        View::new(self.pixels(), self.width(), self.height())
+    }
+
+    /// Returns a [`ViewMut`] of the whole surface.
+    #[must_use]
+    pub fn view_mut(&mut self) -> ViewMut<'_> {
+        let (width, height) = (self.width(), self.height());
+        ViewMut::new(self.pixels_mut(), width, height)
+    }
+
+    /// Composites every pixel over an opaque `background`, leaving the surface fully opaque;
+    /// see [`Rgba::flatten_over`](crate::rgba::Rgba::flatten_over).
+    pub fn flatten_over(&mut self, background: F32x4Rgba) {
+        F32x4Rgba::flatten_slice_over(self.pixels_mut(), background);
+    }
+
+    /// Returns the fraction of pixels that are not fully transparent, in `[0.0, 1.0]`.
+    ///
+    /// An empty surface has a coverage of `0.0`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn coverage(&self) -> f32 {
+        let pixels = self.pixels();
+        if pixels.is_empty() {
+            return 0.0;
+        }
+        let visible = pixels.iter().filter(|p| p.a > 0.0).count();
+        visible as f32 / pixels.len() as f32
+    }
+
+    /// Returns the mean alpha over all pixels.
+    ///
+    /// An empty surface has a mean alpha of `0.0`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean_alpha(&self) -> f32 {
+        let pixels = self.pixels();
+        if pixels.is_empty() {
+            return 0.0;
+        }
+        let sum: f32 = pixels.iter().map(|p| p.a).sum();
+        sum / pixels.len() as f32
+    }
+
+    /// Returns the tight bounding box of pixels that are not fully transparent.
+    ///
+    /// Returns `None` if every pixel is fully transparent.
+    #[must_use]
+    pub fn content_bounds(&self) -> Option<Rect> {
+        let (width, height) = (self.width(), self.height());
+        if width == 0 {
+            return None;
+        }
+        let visible = |row: &[F32x4Rgba]| row.iter().any(|p| p.a > 0.0);
+        let rows = || self.pixels().chunks_exact(width);
+        let top = rows().position(visible)?;
+        let bottom = height - rows().rev().position(visible)?;
+
+        let (mut left, mut right) = (width, 0);
+        for row in rows().take(bottom).skip(top) {
+            if let Some(first) = row.iter().position(|p| p.a > 0.0) {
+                left = left.min(first);
+            }
+            if let Some(last) = row.iter().rposition(|p| p.a > 0.0) {
+                right = right.max(last + 1);
+            }
+        }
+        Some(Rect::new(left, top, right - left, bottom - top))
+    }
+
+    /// Returns a copy of the pixels inside `rect`.
+    ///
+    /// ## Panics
+    ///
+    /// If `rect` does not fit within the surface.
+    #[must_use]
+    pub fn crop(&self, rect: Rect) -> Self {
+        assert!(
+            rect.right() <= self.width() && rect.bottom() <= self.height(),
+            "rect must fit within the surface"
+        );
+        let mut pixels = Vec::with_capacity(rect.area());
+        for y in rect.y..rect.bottom() {
+            pixels.extend_from_slice(&self.row(y)[rect.x..rect.right()]);
+        }
+        Self::from_pixels(rect.width, rect.height, pixels)
+    }
+
+    /// Composites `src` onto this surface with its top-left corner at `(x, y)`, using `mode`.
+    ///
+    /// Offsets may be negative, or place `src` partially or entirely outside this surface; only
+    /// the overlapping pixels are blended.
+    pub fn composite(&mut self, src: &Self, x: isize, y: isize, mode: BlendMode) {
+        composite::composite_at(&mode, src.view(), self.view_mut(), x, y);
+    }
+
+    /// Composites `group` onto this surface with its top-left corner at `(x, y)`, clipped like
+    /// [`composite`](Surface::composite); see [`Group`] for how its layers blend with this
+    /// surface.
+    pub fn composite_group(&mut self, group: &Group, x: isize, y: isize) {
+        let (width, height) = (group.stack().width(), group.stack().height());
+        let Some((src_rect, dst_rect)) =
+            composite::clip((width, height), (self.width(), self.height()), x, y)
+        else {
+            return;
+        };
+        for row in 0..dst_rect.height {
+            let start = (src_rect.y + row) * width + src_rect.x;
+            let dst = &mut self.row_mut(dst_rect.y + row)[dst_rect.x..dst_rect.right()];
+            group.composite_range(dst, start);
+        }
+    }
+
+    /// Composites `src` onto this surface like [`composite`](Surface::composite), blending rows
+    /// in parallel on the [`rayon`] thread pool.
+    ///
+    /// _This function requires the `rayon` feature._
+    #[cfg(feature = "rayon")]
+    pub fn par_composite(&mut self, src: &Self, x: isize, y: isize, mode: BlendMode) {
+        use rayon::prelude::*;
+
+        let Some((src_rect, dst_rect)) = composite::clip(
+            (src.width(), src.height()),
+            (self.width(), self.height()),
+            x,
+            y,
+        ) else {
+            return;
+        };
+        let width = self.width();
+        self.pixels_mut()
+            .par_chunks_mut(width)
+            .skip(dst_rect.y)
+            .zip(src.pixels().par_chunks(src.width()).skip(src_rect.y))
+            .take(dst_rect.height)
+            .for_each(|(dst, src)| {
+                crate::backend::blend_slice(
+                    &mode,
+                    &src[src_rect.x..src_rect.right()],
+                    &mut dst[dst_rect.x..dst_rect.right()],
+                );
+            });
+    }
+
+    /// Crops the surface to its [content bounds](Surface::content_bounds).
+    ///
+    /// Returns the bounds (the offset of the trimmed surface within this one) and the trimmed
+    /// surface, or `None` if every pixel is fully transparent.
+    #[must_use]
+    pub fn trim(&self) -> Option<(Rect, Self)> {
+        let bounds = self.content_bounds()?;
+        Some((bounds, self.crop(bounds)))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    const HALF: F32x4Rgba = F32x4Rgba::new(1.0, 1.0, 1.0, 0.5);
+
+    #[test]
+    fn new_is_transparent() {
+        let s = Surface::new(3, 2);
+        assert_eq!(s.pixels(), &[F32x4Rgba::TRANSPARENT; 6]);
+        assert_eq!(s.bounds(), Rect::new(0, 0, 3, 2));
+    }
+
+    #[test]
+    fn get_set_and_rows() {
+        let mut s = Surface::new(3, 2);
+        s.set(2, 1, F32x4Rgba::WHITE);
+        assert_eq!(s.get(2, 1), Some(F32x4Rgba::WHITE));
+        assert_eq!(s.get(3, 1), None);
+        assert_eq!(s.row(1)[2], F32x4Rgba::WHITE);
+        s.row_mut(0)[0] = F32x4Rgba::BLACK;
+        assert_eq!(s.pixels()[0], F32x4Rgba::BLACK);
+    }
+
+    #[test]
+    #[should_panic(expected = "width * height pixels")]
+    fn from_pixels_panics_on_wrong_length() {
+        let _ = Surface::from_pixels(2, 2, vec![F32x4Rgba::WHITE; 3]);
+    }
+
+    #[test]
+    fn flatten_over_makes_opaque() {
+        let mut s = Surface::from_pixels(2, 1, vec![HALF, F32x4Rgba::TRANSPARENT]);
+        s.flatten_over(F32x4Rgba::BLACK);
+        assert_eq!(
+            s.pixels(),
+            &[F32x4Rgba::new(0.5, 0.5, 0.5, 1.0), F32x4Rgba::BLACK]
+        );
+        assert_eq!(s.mean_alpha(), 1.0);
+    }
+
+    #[test]
+    fn coverage_and_mean_alpha() {
+        let s = Surface::from_pixels(
+            2,
+            2,
+            vec![
+                F32x4Rgba::WHITE,
+                HALF,
+                F32x4Rgba::TRANSPARENT,
+                F32x4Rgba::TRANSPARENT,
+            ],
+        );
+        assert_eq!(s.coverage(), 0.5);
+        assert_eq!(s.mean_alpha(), 0.375);
+        assert_eq!(Surface::new(0, 0).coverage(), 0.0);
+        assert_eq!(Surface::new(0, 0).mean_alpha(), 0.0);
+    }
+
+    #[test]
+    fn content_bounds_is_tight() {
+        let mut s = Surface::new(5, 4);
+        s.set(3, 1, HALF);
+        s.set(1, 2, HALF);
+        assert_eq!(s.content_bounds(), Some(Rect::new(1, 1, 3, 2)));
+        assert_eq!(Surface::new(5, 4).content_bounds(), None);
+        assert_eq!(Surface::new(0, 4).content_bounds(), None);
+        assert_eq!(
+            Surface::filled(2, 2, HALF).content_bounds(),
+            Some(Rect::new(0, 0, 2, 2))
+        );
+    }
+
+    #[test]
+    fn trim_crops_to_content() {
+        let mut s = Surface::new(4, 3);
+        s.set(1, 1, F32x4Rgba::WHITE);
+        s.set(2, 2, HALF);
+        let (bounds, trimmed) = s.trim().unwrap();
+        assert_eq!(bounds, Rect::new(1, 1, 2, 2));
+        assert_eq!(
+            trimmed.pixels(),
+            &[
+                F32x4Rgba::WHITE,
+                F32x4Rgba::TRANSPARENT,
+                F32x4Rgba::TRANSPARENT,
+                HALF
+            ]
+        );
+        assert!(Surface::new(4, 3).trim().is_none());
+    }
+
+    #[test]
+    fn composite_blends_at_offset() {
+        let mut dst = Surface::filled(3, 3, F32x4Rgba::BLACK);
+        let src = Surface::filled(2, 2, F32x4Rgba::WHITE);
+        dst.composite(&src, 1, 1, BlendMode::SourceOver);
+        assert_eq!(dst.get(0, 0), Some(F32x4Rgba::BLACK));
+        assert_eq!(dst.get(1, 0), Some(F32x4Rgba::BLACK));
+        assert_eq!(dst.get(1, 1), Some(F32x4Rgba::WHITE));
+        assert_eq!(dst.get(2, 2), Some(F32x4Rgba::WHITE));
+    }
+
+    #[test]
+    fn composite_clips_at_edges() {
+        let src = Surface::filled(2, 2, F32x4Rgba::WHITE);
+
+        let mut dst = Surface::filled(3, 3, F32x4Rgba::BLACK);
+        dst.composite(&src, -1, 2, BlendMode::Source);
+        let white = dst
+            .pixels()
+            .iter()
+            .filter(|&&p| p == F32x4Rgba::WHITE)
+            .count();
+        assert_eq!(white, 1);
+        assert_eq!(dst.get(0, 2), Some(F32x4Rgba::WHITE));
+
+        let before = dst.clone();
+        dst.composite(&src, 3, 0, BlendMode::Source);
+        dst.composite(&src, -2, -2, BlendMode::Source);
+        assert_eq!(dst, before, "no overlap leaves the surface unchanged");
+    }
+
+    #[test]
+    fn composite_group_at_offset() {
+        let mut stack = crate::compositor::LayerStack::new(2, 1);
+        stack.push(vec![F32x4Rgba::BLACK; 2], BlendMode::Multiply);
+        let group = Group::new(stack);
+        let mut dst = Surface::filled(2, 2, F32x4Rgba::WHITE);
+        dst.composite_group(&group, 1, 1);
+        assert_eq!(
+            dst.pixels(),
+            &[
+                F32x4Rgba::WHITE,
+                F32x4Rgba::WHITE,
+                F32x4Rgba::WHITE,
+                F32x4Rgba::BLACK
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_composite_matches_composite() {
+        let src = Surface::from_pixels(
+            3,
+            2,
+            (0..6_u8)
+                .map(|i| F32x4Rgba::new(1.0, 0.0, 0.0, f32::from(i) / 5.0))
+                .collect(),
+        );
+        for (x, y) in [(0, 0), (-1, 1), (2, -1), (4, 4), (-3, 0)] {
+            let mut expected = Surface::filled(4, 3, F32x4Rgba::BLACK);
+            expected.composite(&src, x, y, BlendMode::SourceOver);
+            let mut dst = Surface::filled(4, 3, F32x4Rgba::BLACK);
+            dst.par_composite(&src, x, y, BlendMode::SourceOver);
+            assert_eq!(dst, expected, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "fit within the surface")]
+    fn crop_panics_out_of_bounds() {
+        let _ = Surface::new(2, 2).crop(Rect::new(1, 1, 2, 1));
+    }
+}
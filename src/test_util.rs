@@ -0,0 +1,13 @@
+//! Assertions shared by the unit tests.
+
+use crate::rgba::F32x4Rgba;
+
+/// Asserts that every channel of `a` is within `tolerance` of the same channel of `b`.
+#[allow(clippy::redundant_pub_crate)]
+#[track_caller]
+pub(crate) fn assert_close(a: F32x4Rgba, b: F32x4Rgba, tolerance: f32) {
+    let (a, b): ([f32; 4], [f32; 4]) = (a.into(), b.into());
+    for (x, y) in a.into_iter().zip(b) {
+        assert!((x - y).abs() < tolerance, "{a:?} != {b:?}");
+    }
+}
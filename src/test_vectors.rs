@@ -0,0 +1,698 @@
+//! Reference vectors of every built-in blend mode, for validating other implementations.
+//!
+//! Each [`TestVector`] is a source and destination color and the result of blending them with a
+//! [`BlendMode`]. The sources and destinations ([`INPUTS`]) cover opaque, translucent and fully
+//! transparent pixels and the extremes of each channel, so SIMD, GPU or fixed-point ports of the
+//! blend modes can [`check`] that they agree before shipping. The vectors come in two sets:
+//!
+//! - [`w3c_vectors`]: the separable and non-separable modes, from [`Multiply`] to
+//!   [`Luminosity`]. Their expected results were not computed by this crate, but by evaluating
+//!   the formulas of [W3C Compositing and Blending Level 1][w3c] in double precision and
+//!   rounding to `f32`: the blend function of [Separable blend modes][separable] or
+//!   [Non-separable blend modes][non-separable], mixed into the source by the backdrop alpha as
+//!   described in [Blending][blending], and then composited with [source-over][source-over].
+//! - [`regression_vectors`]: every other mode, as this crate computes it. These only catch
+//!   changes in behavior: the Porter-Duff modes deliberately do not match Skia or other
+//!   references computed on premultiplied colors, since they apply their coefficients to
+//!   straight-alpha channels, alpha included, as described on [`BlendMode`].
+//!
+//! [`Multiply`]: BlendMode::Multiply
+//! [`Luminosity`]: BlendMode::Luminosity
+//! [w3c]: https://www.w3.org/TR/compositing-1/
+//! [blending]: https://www.w3.org/TR/compositing-1/#blending
+//! [separable]: https://www.w3.org/TR/compositing-1/#blendingseparable
+//! [non-separable]: https://www.w3.org/TR/compositing-1/#blendingnonseparable
+//! [source-over]: https://www.w3.org/TR/compositing-1/#porterduffcompositingoperators_srcover
+//!
+//! _This module requires the `test-vectors` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, modes, test_vectors};
+//!
+//! // A zero-sized `Multiply` must match the dynamic mode.
+//! assert_eq!(test_vectors::check(&modes::Multiply, BlendMode::Multiply, 1e-6), Ok(7));
+//!
+//! // Screen is not Multiply.
+//! let mismatch = test_vectors::check(&BlendMode::Screen, BlendMode::Multiply, 1e-6).unwrap_err();
+//! assert_eq!(mismatch.vector.mode, BlendMode::Multiply);
+//! ```
+
+use core::fmt;
+
+use crate::{BlendMode, RgbaBlend, rgba::F32x4Rgba};
+
+/// A source and destination color, and the result of blending them with a mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestVector {
+    /// The blend mode.
+    pub mode: BlendMode,
+
+    /// The straight-alpha source color.
+    pub src: F32x4Rgba,
+
+    /// The straight-alpha destination color.
+    pub dst: F32x4Rgba,
+
+    /// The result of blending `src` onto `dst` with `mode`.
+    pub expected: F32x4Rgba,
+}
+
+/// The source and destination colors blended with every mode, in the order of the vectors.
+pub const INPUTS: [(F32x4Rgba, F32x4Rgba); 7] = [
+    (
+        F32x4Rgba::new(1.0, 0.5, 0.0, 1.0),
+        F32x4Rgba::new(0.25, 0.5, 0.75, 1.0),
+    ),
+    (
+        F32x4Rgba::new(0.8, 0.2, 0.6, 0.5),
+        F32x4Rgba::new(0.3, 0.9, 0.1, 1.0),
+    ),
+    (
+        F32x4Rgba::new(0.2, 0.4, 0.9, 1.0),
+        F32x4Rgba::new(0.7, 0.1, 0.5, 0.5),
+    ),
+    (
+        F32x4Rgba::new(0.6, 0.7, 0.3, 0.25),
+        F32x4Rgba::new(0.4, 0.2, 0.8, 0.75),
+    ),
+    (F32x4Rgba::TRANSPARENT, F32x4Rgba::new(0.5, 0.6, 0.7, 0.8)),
+    (F32x4Rgba::new(0.9, 0.1, 0.3, 0.6), F32x4Rgba::TRANSPARENT),
+    (
+        F32x4Rgba::new(0.0, 1.0, 0.5, 1.0),
+        F32x4Rgba::new(1.0, 0.0, 0.5, 1.0),
+    ),
+];
+
+/// Returns the test vectors of the W3C separable and non-separable modes, grouped by mode in
+/// discriminant order.
+///
+/// Their expected results are independent of this crate: they are the formulas of W3C
+/// Compositing and Blending Level 1, with source-over compositing, evaluated in double precision
+/// and rounded to `f32`.
+pub fn w3c_vectors() -> impl Iterator<Item = TestVector> {
+    expand(&W3C)
+}
+
+/// Returns the test vectors of every mode without a W3C formula, grouped by mode in discriminant
+/// order.
+///
+/// Their expected results were generated by this crate, so they detect regressions rather than
+/// prove correctness.
+pub fn regression_vectors() -> impl Iterator<Item = TestVector> {
+    expand(&REGRESSION)
+}
+
+/// Returns every test vector: the [`w3c_vectors`], then the [`regression_vectors`].
+pub fn vectors() -> impl Iterator<Item = TestVector> {
+    w3c_vectors().chain(regression_vectors())
+}
+
+fn expand(table: &'static [(BlendMode, [[f32; 4]; 7])]) -> impl Iterator<Item = TestVector> {
+    table.iter().flat_map(|(mode, expected)| {
+        INPUTS
+            .iter()
+            .zip(expected)
+            .map(|(&(src, dst), &expected)| TestVector {
+                mode: *mode,
+                src,
+                dst,
+                expected: F32x4Rgba::from(expected),
+            })
+    })
+}
+
+/// Returns the test vectors of `mode`; none for [`BlendMode::Custom`].
+pub fn vectors_for(mode: BlendMode) -> impl Iterator<Item = TestVector> {
+    vectors().filter(move |vector| vector.mode == mode)
+}
+
+/// A test vector that an implementation did not reproduce, returned by [`check`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mismatch {
+    /// The test vector.
+    pub vector: TestVector,
+
+    /// The result of the implementation under test.
+    pub actual: F32x4Rgba,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TestVector {
+            mode,
+            src,
+            dst,
+            expected,
+        } = self.vector;
+        write!(
+            f,
+            "{mode:?}: blending {src} onto {dst} gave {}, expected {expected}",
+            self.actual
+        )
+    }
+}
+
+impl core::error::Error for Mismatch {}
+
+/// Blends each test vector of `mode` with `blend`, and checks that every channel of the result
+/// is within `tolerance` of the expected one.
+///
+/// Returns the number of vectors checked, or the first mismatch. `NaN` results never match.
+///
+/// ## Errors
+///
+/// If `blend` does not reproduce a test vector of `mode`.
+pub fn check<B: RgbaBlend<Channel = f32> + ?Sized>(
+    blend: &B,
+    mode: BlendMode,
+    tolerance: f32,
+) -> Result<usize, Mismatch> {
+    let mut count = 0;
+    for vector in vectors_for(mode) {
+        let actual = blend.apply(vector.src, vector.dst);
+        let close = <[f32; 4]>::from(actual)
+            .into_iter()
+            .zip(<[f32; 4]>::from(vector.expected))
+            .all(|(a, e)| (a - e).abs() <= tolerance);
+        if !close {
+            return Err(Mismatch { vector, actual });
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// The results of the W3C separable and non-separable blend modes for each of [`INPUTS`],
+/// evaluated outside this crate; see [`w3c_vectors`].
+const W3C: [(BlendMode, [[f32; 4]; 7]); 13] = [
+    (
+        BlendMode::Multiply,
+        [
+            [0.25, 0.25, 0.0, 1.0],
+            [0.27, 0.539_999_96, 0.080_000_006, 1.0],
+            [0.17, 0.22, 0.674_999_95, 1.0],
+            [0.378_461_54, 0.224_615_38, 0.632_307_7, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.0, 0.25, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Screen,
+        [
+            [1.0, 0.75, 0.75, 1.0],
+            [0.58, 0.909_999_97, 0.37, 1.0],
+            [0.48, 0.43, 0.924_999_95, 1.0],
+            [0.498_461_54, 0.367_692_32, 0.775_384_6, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 0.75, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Overlay,
+        [
+            [0.5, 0.5, 0.5, 1.0],
+            [0.390_000_02, 0.869_999_95, 0.110_000_01, 1.0],
+            [0.359_999_98, 0.240_000_01, 0.9, 1.0],
+            [0.433_846_18, 0.256_923_08, 0.743_076_9, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::HardLight,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.51, 0.63, 0.190_000_03, 1.0],
+            [0.24, 0.240_000_01, 0.9, 1.0],
+            [0.443_076_94, 0.312_307_7, 0.687_692_34, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::SoftLight,
+        [
+            [0.5, 0.5, 0.5625, 1.0],
+            [0.374_316_78, 0.872_999_97, 0.119_600_005, 1.0],
+            [0.387, 0.241_000_01, 0.782_842_7, 1.0],
+            [0.426_113_34, 0.261_353_85, 0.746_769_25, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::ColorDodge,
+        [
+            [1.0, 1.0, 0.75, 1.0],
+            [0.65, 0.95, 0.175_000_01, 1.0],
+            [0.5375, 0.283_333_33, 0.95, 1.0],
+            [0.553_846_2, 0.346_153_86, 0.807_692_3, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::ColorBurn,
+        [
+            [0.25, 0.0, 0.0, 1.0],
+            [0.212_500_02, 0.699_999_9, 0.05, 1.0],
+            [0.1, 0.2, 0.672_222_2, 1.0],
+            [0.323_076_93, 0.192_307_7, 0.653_846_2, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Difference,
+        [
+            [0.75, 0.0, 0.75, 1.0],
+            [0.4, 0.799_999_95, 0.3, 1.0],
+            [0.35, 0.35, 0.65, 1.0],
+            [0.369_230_78, 0.307_692_32, 0.692_307_7, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 0.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Exclusion,
+        [
+            [0.75, 0.5, 0.75, 1.0],
+            [0.46, 0.82, 0.34, 1.0],
+            [0.41, 0.41, 0.7, 1.0],
+            [0.443_076_94, 0.335_384_6, 0.72, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Hue,
+        [
+            [0.655, 0.405, 0.155, 1.0],
+            [0.65, 0.656_383, 0.452_127_67, 1.0],
+            [0.178_428_58, 0.364_142_86, 0.828_428_57, 1.0],
+            [0.392_307_7, 0.284_615_4, 0.576_923_1, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.550_387_6, 0.275_193_8, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Saturation,
+        [
+            [0.079_831_935, 0.539_916, 1.0, 1.0],
+            [0.3415, 0.866_499_96, 0.166_499_99, 1.0],
+            [0.481_333_32, 0.231_333_35, 0.714_666_66, 1.0],
+            [0.409_692_32, 0.248_153_85, 0.725_076_9, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Color,
+        [
+            [0.760_504_2, 0.380_252_1, -0., 1.0],
+            [0.65, 0.656_383, 0.452_127_67, 1.0],
+            [0.1645, 0.364_500_02, 0.8645, 1.0],
+            [0.392_307_7, 0.284_615_4, 0.576_923_1, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.550_387_6, 0.275_193_8, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Luminosity,
+        [
+            [0.3925, 0.6425, 0.8925, 1.0],
+            [0.229_699_27, 0.768_797, 0.05, 1.0],
+            [0.4855, 0.285_500_02, 0.7355, 1.0],
+            [0.481_012_67, 0.313_826_68, 0.807_692_3, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.449_612_4, 0.724_806_2, 1.0],
+        ],
+    ),
+];
+
+/// The results of every other built-in mode for each of [`INPUTS`], as computed by this crate;
+/// see [`regression_vectors`].
+const REGRESSION: [(BlendMode, [[f32; 4]; 7]); 24] = [
+    (
+        BlendMode::Clear,
+        [
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ],
+    ),
+    (
+        BlendMode::Source,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.8, 0.2, 0.6, 0.5],
+            [0.2, 0.4, 0.9, 1.0],
+            [0.6, 0.7, 0.3, 0.25],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Destination,
+        [
+            [0.25, 0.5, 0.75, 1.0],
+            [0.3, 0.9, 0.1, 1.0],
+            [0.7, 0.1, 0.5, 0.5],
+            [0.4, 0.2, 0.8, 0.75],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::SourceOver,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.55, 0.55, 0.350_000_02, 0.75],
+            [0.2, 0.4, 0.9, 1.0],
+            [0.450_000_02, 0.325, 0.675, 0.625],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.54, 0.060_000_002, 0.18, 0.36],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::DestinationOver,
+        [
+            [0.25, 0.5, 0.75, 1.0],
+            [0.3, 0.9, 0.1, 1.0],
+            [0.45, 0.25, 0.7, 0.75],
+            [0.450_000_02, 0.325, 0.675, 0.625],
+            [0.4, 0.480_000_02, 0.56, 0.640_000_05],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::SourceIn,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.8, 0.2, 0.6, 0.5],
+            [0.1, 0.2, 0.45, 0.5],
+            [0.450_000_02, 0.525, 0.225_000_01, 0.1875],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::DestinationIn,
+        [
+            [0.25, 0.5, 0.75, 1.0],
+            [0.15, 0.45, 0.05, 0.5],
+            [0.7, 0.1, 0.5, 0.5],
+            [0.1, 0.05, 0.2, 0.1875],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::SourceOut,
+        [
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.1, 0.2, 0.45, 0.5],
+            [0.15, 0.175, 0.075, 0.0625],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.0, 0.0, 0.0],
+        ],
+    ),
+    (
+        BlendMode::DestinationOut,
+        [
+            [0.0, 0.0, 0.0, 0.0],
+            [0.15, 0.45, 0.05, 0.5],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.3, 0.15, 0.6, 0.5625],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ],
+    ),
+    (
+        BlendMode::SourceAtop,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.950_000_05, 0.65, 0.650_000_04, 1.0],
+            [0.1, 0.2, 0.45, 0.5],
+            [0.75, 0.674_999_95, 0.825_000_05, 0.75],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::DestinationAtop,
+        [
+            [0.25, 0.5, 0.75, 1.0],
+            [0.15, 0.45, 0.05, 0.5],
+            [0.8, 0.3, 0.95, 1.0],
+            [0.25, 0.225, 0.275, 0.25],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Xor,
+        [
+            [0.0, 0.0, 0.0, 0.0],
+            [0.15, 0.45, 0.05, 0.5],
+            [0.1, 0.2, 0.45, 0.5],
+            [0.450_000_02, 0.325, 0.675, 0.625],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.0, 0.0, 0.0],
+        ],
+    ),
+    (
+        BlendMode::Plus,
+        [
+            [1.25, 1.0, 0.75, 2.0],
+            [1.1, 1.1, 0.700_000_05, 1.5],
+            [0.9, 0.5, 1.4, 1.5],
+            [1.0, 0.9, 1.1, 1.0],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 1.0, 2.0],
+        ],
+    ),
+    (
+        BlendMode::LinearBurn,
+        [
+            [0.25, 0.0, 0.0, 1.0],
+            [0.200_000_02, 0.5, 0.05, 1.0],
+            [0.1, 0.2, 0.65, 1.0],
+            [0.323_076_93, 0.192_307_7, 0.6, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::LinearDodge,
+        [
+            [1.0, 1.0, 0.75, 1.0],
+            [0.65, 0.95, 0.400_000_04, 1.0],
+            [0.55, 0.45, 0.95, 1.0],
+            [0.553_846_1, 0.399_999_98, 0.807_692_3, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::VividLight,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.525_000_04, 0.824_999_9, 0.112_500_01, 1.0],
+            [0.225, 0.2, 0.95, 1.0],
+            [0.438_461_6, 0.269_230_78, 0.730_769_2, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::LinearLight,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.6, 0.599_999_96, 0.200_000_03, 1.0],
+            [0.15, 0.2, 0.95, 1.0],
+            [0.461_538_46, 0.330_769_24, 0.669_230_8, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::PinLight,
+        [
+            [1.0, 0.5, 0.0, 1.0],
+            [0.450_000_02, 0.65, 0.150_000_02, 1.0],
+            [0.3, 0.25, 0.849_999_96, 1.0],
+            [0.415_384_62, 0.284_615_37, 0.715_384_6, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 1.0, 0.5, 1.0],
+        ],
+    ),
+    (
+        BlendMode::HardMix,
+        [
+            [1.0, 1.0, 0.0, 1.0],
+            [0.65, 0.95, 0.05, 1.0],
+            [0.1, 0.2, 0.95, 1.0],
+            [0.553_846_1, 0.192_307_7, 0.807_692_3, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Subtract,
+        [
+            [0.0, 0.0, 0.75, 1.0],
+            [0.15, 0.799_999_95, 0.05, 1.0],
+            [0.35, 0.2, 0.45, 1.0],
+            [0.323_076_93, 0.192_307_7, 0.692_307_7, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 0.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Divide,
+        [
+            [0.25, 1.0, 1.0, 1.0],
+            [0.3375, 0.95, 0.133_333_33, 1.0],
+            [0.6, 0.325, 0.727_777_8, 1.0],
+            [0.476_923_05, 0.258_241_77, 0.807_692_3, 0.8125],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 0.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::Modulate,
+        [
+            [0.25, 0.25, 0.0, 1.0],
+            [0.240_000_01, 0.179_999_99, 0.060_000_002, 0.5],
+            [0.14, 0.040_000_003, 0.45, 0.5],
+            [0.240_000_01, 0.14, 0.240_000_01, 0.1875],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.25, 1.0],
+        ],
+    ),
+    (
+        BlendMode::PlusLighter,
+        [
+            [1.0, 1.0, 0.75, 1.0],
+            [0.700_000_05, 1.0, 0.4, 1.0],
+            [0.55, 0.450_000_02, 1.0, 1.0],
+            [0.450_000_02, 0.325, 0.675, 1.0],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [1.0, 1.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        BlendMode::PlusDarker,
+        [
+            [0.25, 0.0, 0.0, 1.0],
+            [0.200_000_05, 0.5, 0.0, 1.0],
+            [0.049_999_952, 0.0, 0.65, 1.0],
+            [0.450_000_05, 0.325, 0.675, 1.0],
+            [0.5, 0.6, 0.7, 0.8],
+            [0.9, 0.1, 0.3, 0.6],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{modes, porter_duff::F32PorterDuff};
+
+    #[test]
+    fn every_builtin_mode_matches() {
+        let builtin = (0..=u8::MAX).filter_map(|value| BlendMode::try_from(value).ok());
+        assert_eq!(builtin.clone().count(), W3C.len() + REGRESSION.len());
+        for mode in builtin {
+            assert_eq!(check(&mode, mode, 1e-6), Ok(INPUTS.len()), "{mode:?}");
+        }
+        assert_eq!(
+            vectors().count(),
+            (W3C.len() + REGRESSION.len()) * INPUTS.len()
+        );
+    }
+
+    #[test]
+    fn w3c_set_is_separable_and_nonseparable_modes() {
+        let w3c: Vec<_> = W3C.iter().map(|(mode, _)| u8::from(*mode)).collect();
+        let multiply = u8::from(BlendMode::Multiply);
+        assert_eq!(
+            w3c,
+            (multiply..=u8::from(BlendMode::Luminosity)).collect::<Vec<_>>()
+        );
+        assert!(
+            REGRESSION
+                .iter()
+                .all(|(mode, _)| !w3c.contains(&u8::from(*mode)))
+        );
+        assert_eq!(vectors_for(BlendMode::Hue).count(), INPUTS.len());
+        assert!(w3c_vectors().all(|vector| w3c.contains(&u8::from(vector.mode))));
+    }
+
+    #[test]
+    fn other_implementations_match() {
+        assert_eq!(check(&modes::SoftLight, BlendMode::SoftLight, 1e-6), Ok(7));
+        assert_eq!(check(&F32PorterDuff::XOR, BlendMode::Xor, 1e-6), Ok(7));
+        let custom = BlendMode::Custom(|src, _| src);
+        assert_eq!(check(&custom, custom, 0.0), Ok(0));
+    }
+
+    #[test]
+    fn reports_first_mismatch() {
+        let mismatch = check(&BlendMode::Source, BlendMode::SourceOver, 1e-6).unwrap_err();
+        assert_eq!(
+            mismatch.vector,
+            vectors_for(BlendMode::SourceOver).nth(1).unwrap()
+        );
+        assert_eq!(mismatch.actual, INPUTS[1].0);
+        assert!(
+            mismatch
+                .to_string()
+                .starts_with("SourceOver: blending rgba(")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Compositing with binary (1-bit) alpha.
+//!
+//! GIF and some e-ink displays store a pixel as either fully opaque or fully transparent. Partial
+//! alpha blended into such a target shows up as halos once it is cut to one bit, so these
+//! helpers decide coverage up front: alpha at or above a threshold becomes `1.0`, anything below
+//! becomes `0.0`. [`ThresholdBlend`] and [`BlendMode::apply_with_threshold`] binarize both inputs
+//! and the result, so compositing binary layers stays binary.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, rgba::F32x4Rgba, threshold};
+//!
+//! let faint = F32x4Rgba::RED.with_alpha(0.25);
+//! let solid = F32x4Rgba::RED.with_alpha(0.75);
+//! assert_eq!(threshold::binarize(faint, 0.5).a, 0.0);
+//! assert_eq!(threshold::binarize(solid, 0.5).a, 1.0);
+//!
+//! // Faint source pixels drop out instead of tinting the destination.
+//! let dst = F32x4Rgba::BLUE;
+//! assert_eq!(BlendMode::SourceOver.apply_with_threshold(faint, dst, 0.5), dst);
+//! assert_eq!(
+//!     BlendMode::SourceOver.apply_with_threshold(solid, dst, 0.5),
+//!     F32x4Rgba::RED
+//! );
+//! ```
+
+use crate::{
+    BlendMode, RgbaBlend,
+    rgba::{F32x4Rgba, U8x4Rgba},
+};
+
+/// Returns `color` with an alpha of `1.0` if it is at least `threshold`, or `0.0` otherwise.
+///
+/// Color channels are kept, so a pixel cut to transparent can be raised again later. `NaN`
+/// alpha is below every threshold.
+#[must_use]
+pub fn binarize(color: F32x4Rgba, threshold: f32) -> F32x4Rgba {
+    let a = if color.a >= threshold { 1.0 } else { 0.0 };
+    F32x4Rgba { a, ..color }
+}
+
+/// Returns `color` with an alpha of `255` if it is at least `threshold`, or `0` otherwise.
+#[must_use]
+pub const fn binarize_u8(color: U8x4Rgba, threshold: u8) -> U8x4Rgba {
+    let a = if color.a >= threshold { u8::MAX } else { 0 };
+    U8x4Rgba { a, ..color }
+}
+
+/// Binarizes the alpha of every color in `colors` in place, as [`binarize`].
+pub fn binarize_slice(colors: &mut [F32x4Rgba], threshold: f32) {
+    for c in colors {
+        *c = binarize(*c, threshold);
+    }
+}
+
+/// Binarizes the alpha of every color in `colors` in place, as [`binarize_u8`].
+pub fn binarize_slice_u8(colors: &mut [U8x4Rgba], threshold: u8) {
+    for c in colors {
+        *c = binarize_u8(*c, threshold);
+    }
+}
+
+/// Blends with the wrapped mode after binarizing the alpha of both inputs at a threshold, then
+/// binarizes the result.
+///
+/// Binarizing the result as well keeps modes that can add coverage, such as `Plus`, within one
+/// bit.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ThresholdBlend<B>(pub B, pub f32);
+
+impl<B: RgbaBlend<Channel = f32>> RgbaBlend for ThresholdBlend<B> {
+    type Channel = f32;
+
+    fn apply(&self, src: F32x4Rgba, dst: F32x4Rgba) -> F32x4Rgba {
+        let threshold = self.1;
+        let result = self
+            .0
+            .apply(binarize(src, threshold), binarize(dst, threshold));
+        binarize(result, threshold)
+    }
+}
+
+impl BlendMode {
+    /// Blends `src` with `dst`, treating alpha at or above `threshold` as opaque and anything
+    /// below as transparent; see [`ThresholdBlend`].
+    #[must_use]
+    pub fn apply_with_threshold(self, src: F32x4Rgba, dst: F32x4Rgba, threshold: f32) -> F32x4Rgba {
+        ThresholdBlend(self, threshold).apply(src, dst)
+    }
+
+    /// Blends `src` onto `dst` in place with binary alpha at `threshold`; see [`ThresholdBlend`].
+    ///
+    /// ## Panics
+    ///
+    /// If `src` and `dst` do not have the same length.
+    pub fn apply_slice_with_threshold(
+        self,
+        src: &[F32x4Rgba],
+        dst: &mut [F32x4Rgba],
+        threshold: f32,
+    ) {
+        ThresholdBlend(self, threshold).apply_slice(src, dst);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binarize_at_threshold() {
+        let c = |a| F32x4Rgba::new(0.25, 0.5, 0.75, a);
+        assert_eq!(binarize(c(0.5), 0.5), c(1.0));
+        assert_eq!(binarize(c(0.499), 0.5), c(0.0));
+        assert_eq!(binarize(c(f32::NAN), 0.0), c(0.0));
+        assert_eq!(
+            binarize_u8(U8x4Rgba::new(1, 2, 3, 128), 128),
+            U8x4Rgba::new(1, 2, 3, 255)
+        );
+        assert_eq!(
+            binarize_u8(U8x4Rgba::new(1, 2, 3, 127), 128),
+            U8x4Rgba::new(1, 2, 3, 0)
+        );
+    }
+
+    #[test]
+    fn results_are_binary() {
+        let colors = [0.0, 0.3, 0.6, 1.0].map(|a| F32x4Rgba::new(0.5, 0.25, 1.0, a));
+        for mode in [BlendMode::SourceOver, BlendMode::Plus, BlendMode::Xor] {
+            for src in colors {
+                for dst in colors {
+                    let a = mode.apply_with_threshold(src, dst, 0.5).a;
+                    assert!(a == 0.0 || a == 1.0, "{mode:?}: {a}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slice_matches_individual() {
+        let src = [0.2, 0.8].map(|a| F32x4Rgba::new(1.0, 0.0, 0.0, a));
+        let mut dst = [F32x4Rgba::new(0.0, 1.0, 0.0, 0.4); 2];
+        let expected: [F32x4Rgba; 2] = core::array::from_fn(|i| {
+            BlendMode::SourceOver.apply_with_threshold(src[i], dst[i], 0.5)
+        });
+        BlendMode::SourceOver.apply_slice_with_threshold(&src, &mut dst, 0.5);
+        assert_eq!(dst, expected);
+        assert_eq!(dst[0].a, 0.0);
+        assert_eq!(dst[1], F32x4Rgba::new(1.0, 0.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,277 @@
+//! Tile-based compositing with dirty-rect tracking.
+//!
+//! A [`TiledCanvas`] owns a [`LayerStack`] and its flattened output, divided into square tiles
+//! of a configurable size. Edits go through [`TiledCanvas::layer_mut`], which marks the tiles
+//! touching the edited region as dirty; [`TiledCanvas::flatten`] then re-composites only those
+//! tiles, so an interactive canvas pays for what changed rather than for the whole image.
+//!
+//! _This module requires the `alloc` feature._
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{BlendMode, rect::Rect, rgba::F32x4Rgba, tiled::TiledCanvas};
+//!
+//! let mut canvas = TiledCanvas::new(4, 4, 2);
+//! canvas.push(vec![F32x4Rgba::BLACK; 16], BlendMode::SourceOver);
+//! let _ = canvas.flatten();
+//!
+//! // Painting a single pixel only dirties the tile containing it.
+//! canvas.layer_mut(0, Rect::new(3, 0, 1, 1)).unwrap().pixels_mut()[3] = F32x4Rgba::WHITE;
+//! assert_eq!(canvas.dirty_tiles().collect::<Vec<_>>(), [Rect::new(2, 0, 2, 2)]);
+//!
+//! assert_eq!(canvas.flatten()[3], F32x4Rgba::WHITE);
+//! assert_eq!(canvas.dirty_tiles().count(), 0);
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    BlendMode,
+    compositor::{self, Layer, LayerStack},
+    rect::Rect,
+    rgba::F32x4Rgba,
+};
+
+/// A [`LayerStack`] flattened tile by tile, re-compositing only tiles marked dirty.
+#[derive(Debug, Clone)]
+pub struct TiledCanvas {
+    stack: LayerStack,
+    tile_size: usize,
+    columns: usize,
+    dirty: Vec<bool>,
+    output: Vec<F32x4Rgba>,
+}
+
+impl TiledCanvas {
+    /// Creates an empty `width × height` canvas divided into `tile_size × tile_size` tiles.
+    ///
+    /// Tiles on the right and bottom edges are cropped to the canvas. Every tile starts dirty.
+    ///
+    /// ## Panics
+    ///
+    /// If `tile_size` is zero.
+    #[must_use]
+    pub fn new(width: usize, height: usize, tile_size: usize) -> Self {
+        assert!(tile_size > 0, "tile size must be non-zero");
+        let columns = width.div_ceil(tile_size);
+        let rows = height.div_ceil(tile_size);
+        Self {
+            stack: LayerStack::new(width, height),
+            tile_size,
+            columns,
+            dirty: vec![true; columns * rows],
+            output: vec![F32x4Rgba::TRANSPARENT; width * height],
+        }
+    }
+
+    /// Width of the canvas, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.stack.width()
+    }
+
+    /// Height of the canvas, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.stack.height()
+    }
+
+    /// Width and height of each tile, in pixels.
+    #[must_use]
+    pub const fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Returns the layer stack.
+    #[must_use]
+    pub const fn stack(&self) -> &LayerStack {
+        &self.stack
+    }
+
+    /// Returns the layer stack for modification, marking every tile dirty.
+    ///
+    /// Prefer [`layer_mut`](TiledCanvas::layer_mut) for edits confined to a region.
+    pub fn stack_mut(&mut self) -> &mut LayerStack {
+        self.mark_all_dirty();
+        &mut self.stack
+    }
+
+    /// Adds a layer on top of the stack, returning its index, and marks every tile dirty.
+    ///
+    /// ## Panics
+    ///
+    /// If `pixels.len() != width * height`.
+    pub fn push(&mut self, pixels: Vec<F32x4Rgba>, mode: BlendMode) -> usize {
+        self.stack_mut().push(pixels, mode)
+    }
+
+    /// Returns the layer at `index` for modification, or `None` if out of bounds.
+    ///
+    /// The tiles touching `region` are marked dirty; changes outside it are not re-composited
+    /// until those tiles are marked dirty by a later edit.
+    pub fn layer_mut(&mut self, index: usize, region: Rect) -> Option<&mut Layer> {
+        if index < self.stack.len() {
+            self.mark_dirty(region);
+        }
+        self.stack.layer_mut(index)
+    }
+
+    /// Marks the tiles touching `region` dirty. Parts outside the canvas are ignored.
+    pub fn mark_dirty(&mut self, region: Rect) {
+        let region = region.intersection(&self.bounds());
+        if region.is_empty() {
+            return;
+        }
+        let size = self.tile_size;
+        for row in region.y / size..region.bottom().div_ceil(size) {
+            for column in region.x / size..region.right().div_ceil(size) {
+                self.dirty[row * self.columns + column] = true;
+            }
+        }
+    }
+
+    /// Marks every tile dirty.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.fill(true);
+    }
+
+    /// Returns the bounds of each dirty tile, row by row.
+    pub fn dirty_tiles(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.dirty
+            .iter()
+            .enumerate()
+            .filter(|(_, dirty)| **dirty)
+            .map(|(index, _)| self.tile_bounds(index))
+    }
+
+    /// Re-composites the dirty tiles, marks them clean, and returns the flattened canvas.
+    ///
+    /// The result is identical to [`LayerStack::flatten`], as long as every edit was covered by
+    /// a dirty region.
+    pub fn flatten(&mut self) -> &[F32x4Rgba] {
+        let width = self.width();
+        for index in 0..self.dirty.len() {
+            if !self.dirty[index] {
+                continue;
+            }
+            let tile = self.tile_bounds(index);
+            for y in tile.y..tile.bottom() {
+                let start = y * width + tile.x;
+                let row = &mut self.output[start..start + tile.width];
+                row.fill(F32x4Rgba::TRANSPARENT);
+                compositor::composite_range(self.stack.layers(), row, start);
+            }
+            self.dirty[index] = false;
+        }
+        &self.output
+    }
+
+    /// Returns the bounds of the canvas, at the origin.
+    const fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width(), self.height())
+    }
+
+    /// Returns the bounds of the tile at `index`, cropped to the canvas.
+    fn tile_bounds(&self, index: usize) -> Rect {
+        let size = self.tile_size;
+        let tile = Rect::new(
+            index % self.columns * size,
+            index / self.columns * size,
+            size,
+            size,
+        );
+        tile.intersection(&self.bounds())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: F32x4Rgba = F32x4Rgba::new(1.0, 0.0, 0.0, 0.5);
+
+    fn canvas_5x3() -> TiledCanvas {
+        let mut canvas = TiledCanvas::new(5, 3, 2);
+        canvas.push(vec![F32x4Rgba::BLACK; 15], BlendMode::SourceOver);
+        canvas.push(vec![F32x4Rgba::TRANSPARENT; 15], BlendMode::SourceOver);
+        canvas
+    }
+
+    fn uncached(stack: &LayerStack) -> Vec<F32x4Rgba> {
+        let mut out = vec![F32x4Rgba::WHITE; stack.width() * stack.height()];
+        stack.flatten(&mut out);
+        out
+    }
+
+    #[test]
+    fn edge_tiles_are_cropped() {
+        let canvas = TiledCanvas::new(5, 3, 2);
+        let tiles: Vec<Rect> = canvas.dirty_tiles().collect();
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[2], Rect::new(4, 0, 1, 2));
+        assert_eq!(tiles[5], Rect::new(4, 2, 1, 1));
+    }
+
+    #[test]
+    fn flatten_matches_layer_stack() {
+        let mut canvas = canvas_5x3();
+        let expected = uncached(canvas.stack());
+        assert_eq!(canvas.flatten(), expected);
+
+        canvas
+            .layer_mut(1, Rect::new(1, 1, 3, 2))
+            .unwrap()
+            .pixels_mut()[8] = RED;
+        canvas
+            .layer_mut(1, Rect::new(4, 2, 1, 1))
+            .unwrap()
+            .pixels_mut()[14] = RED;
+        assert_eq!(canvas.dirty_tiles().count(), 5);
+        let expected = uncached(canvas.stack());
+        assert_eq!(canvas.flatten(), expected);
+    }
+
+    #[test]
+    fn only_dirty_tiles_are_recomposited() {
+        let mut canvas = canvas_5x3();
+        let _ = canvas.flatten();
+
+        // An edit outside the dirty region is not picked up until its tile is marked.
+        canvas.stack_mut().layer_mut(1).unwrap().pixels_mut()[0] = RED;
+        canvas.dirty.fill(false);
+        canvas.layer_mut(1, Rect::new(4, 0, 1, 1)).unwrap();
+        assert_eq!(canvas.flatten()[0], F32x4Rgba::BLACK);
+
+        canvas.mark_dirty(Rect::new(0, 0, 1, 1));
+        let expected = uncached(canvas.stack());
+        assert_eq!(canvas.flatten(), expected);
+    }
+
+    #[test]
+    fn mark_dirty_ignores_outside_regions() {
+        let mut canvas = canvas_5x3();
+        let _ = canvas.flatten();
+        canvas.mark_dirty(Rect::new(5, 0, 3, 3));
+        canvas.mark_dirty(Rect::new(0, 0, 0, 3));
+        assert!(canvas.layer_mut(2, Rect::new(0, 0, 5, 3)).is_none());
+        assert_eq!(canvas.dirty_tiles().count(), 0);
+
+        canvas.mark_dirty(Rect::new(3, 1, 10, 10));
+        assert_eq!(
+            canvas.dirty_tiles().collect::<Vec<_>>(),
+            [
+                Rect::new(2, 0, 2, 2),
+                Rect::new(4, 0, 1, 2),
+                Rect::new(2, 2, 2, 1),
+                Rect::new(4, 2, 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "tile size must be non-zero")]
+    fn new_panics_on_zero_tile_size() {
+        let _ = TiledCanvas::new(4, 4, 0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Transition effects (fades, wipes and iris reveals) as coverage masks.
+//!
+//! A [`Transition`] produces a coverage mask for a given progress `t` in `[0.0, 1.0]`, where
+//! `0.0` reveals nothing of the incoming image and `1.0` reveals all of it. Masks are composited
+//! with [`mask::blend_masked`](crate::mask::blend_masked).
+//!
+//! ## Examples
+//!
+//! Wiping from a black frame to a white one, halfway through:
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     BlendMode, mask,
+//!     rgba::F32x4Rgba,
+//!     transition::{Direction, Transition},
+//! };
+//!
+//! let incoming = [F32x4Rgba::WHITE; 4];
+//! let mut frame = [F32x4Rgba::BLACK; 4];
+//! let mut coverage = [0.0; 4];
+//!
+//! let wipe = Transition::Wipe { direction: Direction::Right, feather: 0.0 };
+//! wipe.fill_mask(&mut coverage, 4, 0.5);
+//! mask::blend_masked(&BlendMode::Source, &incoming, &mut frame, &coverage);
+//!
+//! assert_eq!(frame, [F32x4Rgba::WHITE, F32x4Rgba::WHITE, F32x4Rgba::BLACK, F32x4Rgba::BLACK]);
+//! ```
+
+use crate::math;
+
+/// Direction in which a [`Transition::Wipe`] edge travels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// From the right edge towards the left edge.
+    Left,
+
+    /// From the left edge towards the right edge.
+    #[default]
+    Right,
+
+    /// From the bottom edge towards the top edge.
+    Up,
+
+    /// From the top edge towards the bottom edge.
+    Down,
+
+    /// From the bottom-right corner towards the top-left corner.
+    UpLeft,
+
+    /// From the bottom-left corner towards the top-right corner.
+    UpRight,
+
+    /// From the top-right corner towards the bottom-left corner.
+    DownLeft,
+
+    /// From the top-left corner towards the bottom-right corner.
+    DownRight,
+}
+
+impl Direction {
+    /// Returns the (unnormalized) travel vector, with `y` pointing down.
+    const fn vector(self) -> (f32, f32) {
+        match self {
+            Self::Left => (-1.0, 0.0),
+            Self::Right => (1.0, 0.0),
+            Self::Up => (0.0, -1.0),
+            Self::Down => (0.0, 1.0),
+            Self::UpLeft => (-1.0, -1.0),
+            Self::UpRight => (1.0, -1.0),
+            Self::DownLeft => (-1.0, 1.0),
+            Self::DownRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// A transition between two images, expressed as a coverage mask over progress `t`.
+///
+/// `feather` widens hard edges into a linear ramp, as a fraction of the full travel distance;
+/// `0.0` produces a hard (aliased) edge.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Transition {
+    /// Uniform cross-fade; every pixel has coverage `t`.
+    #[default]
+    Fade,
+
+    /// A straight edge sweeping across the image in `direction`.
+    Wipe {
+        /// Direction in which the edge travels.
+        direction: Direction,
+
+        /// Width of the edge ramp, as a fraction of the travel distance.
+        feather: f32,
+    },
+
+    /// A circle growing from the center of the image until it covers the corners.
+    Iris {
+        /// Width of the edge ramp, as a fraction of the final radius.
+        feather: f32,
+    },
+}
+
+impl Transition {
+    /// Returns the coverage of the pixel at `(x, y)` in a `width` × `height` image at progress `t`.
+    ///
+    /// Pixels are sampled at their centers; `t` is clamped to `[0.0, 1.0]`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::suboptimal_flops)]
+    pub fn coverage(&self, x: usize, y: usize, width: usize, height: usize, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        let (width, height) = (width as f32, height as f32);
+        match *self {
+            Self::Fade => t,
+            Self::Wipe { direction, feather } => {
+                let (dx, dy) = direction.vector();
+                let min = (dx * width).min(0.0) + (dy * height).min(0.0);
+                let max = (dx * width).max(0.0) + (dy * height).max(0.0);
+                let distance = (px * dx + py * dy - min) / (max - min);
+                ramp(distance, t, feather)
+            }
+            Self::Iris { feather } => {
+                let (cx, cy) = (width / 2.0, height / 2.0);
+                let radius = math::sqrt(cx * cx + cy * cy);
+                let (ox, oy) = (px - cx, py - cy);
+                let distance = math::sqrt(ox * ox + oy * oy) / radius;
+                ramp(distance, t, feather)
+            }
+        }
+    }
+
+    /// Fills `mask` with the coverage of each pixel of a `width`-pixel-wide image at progress `t`.
+    ///
+    /// The image height is `mask.len() / width`.
+    ///
+    /// ## Panics
+    ///
+    /// If `width` is zero or `mask.len()` is not a multiple of `width`.
+    pub fn fill_mask(&self, mask: &mut [f32], width: usize, t: f32) {
+        assert!(
+            width != 0 && mask.len() % width == 0,
+            "mask length must be a multiple of width"
+        );
+        let height = mask.len() / width;
+        for (y, row) in mask.chunks_exact_mut(width).enumerate() {
+            for (x, m) in row.iter_mut().enumerate() {
+                *m = self.coverage(x, y, width, height, t);
+            }
+        }
+    }
+}
+
+/// Coverage of a point at normalized `distance` along the path of an edge at progress `t`.
+///
+/// The edge travels `1.0 + feather` so that coverage is exactly `0.0` at `t = 0.0` and `1.0` at
+/// `t = 1.0`, regardless of the feather width.
+#[allow(clippy::suboptimal_flops)]
+fn ramp(distance: f32, t: f32, feather: f32) -> f32 {
+    if feather <= 0.0 {
+        return if distance < t { 1.0 } else { 0.0 };
+    }
+    ((t * (1.0 + feather) - distance) / feather).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    fn mask_4x4(transition: Transition, t: f32) -> [f32; 16] {
+        let mut mask = [0.0; 16];
+        transition.fill_mask(&mut mask, 4, t);
+        mask
+    }
+
+    const HARD_WIPES: [Transition; 4] = [
+        Transition::Wipe {
+            direction: Direction::Right,
+            feather: 0.0,
+        },
+        Transition::Wipe {
+            direction: Direction::DownLeft,
+            feather: 0.0,
+        },
+        Transition::Iris { feather: 0.0 },
+        Transition::Iris { feather: 0.5 },
+    ];
+
+    #[test]
+    fn fade_is_uniform() {
+        assert_eq!(mask_4x4(Transition::Fade, 0.3), [0.3; 16]);
+    }
+
+    #[test]
+    fn start_and_end_are_empty_and_full() {
+        for transition in HARD_WIPES {
+            assert_eq!(mask_4x4(transition, 0.0), [0.0; 16], "{transition:?}");
+            assert_eq!(mask_4x4(transition, 1.0), [1.0; 16], "{transition:?}");
+        }
+    }
+
+    #[test]
+    fn progress_is_clamped() {
+        for transition in HARD_WIPES {
+            assert_eq!(mask_4x4(transition, -1.0), [0.0; 16], "{transition:?}");
+            assert_eq!(mask_4x4(transition, 2.0), [1.0; 16], "{transition:?}");
+        }
+    }
+
+    #[test]
+    fn wipe_left_reveals_right_edge_first() {
+        let wipe = Transition::Wipe {
+            direction: Direction::Left,
+            feather: 0.0,
+        };
+        let mask = mask_4x4(wipe, 0.25);
+        assert_eq!(&mask[0..4], &[0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn wipe_down_reveals_top_rows_first() {
+        let wipe = Transition::Wipe {
+            direction: Direction::Down,
+            feather: 0.0,
+        };
+        let mask = mask_4x4(wipe, 0.5);
+        assert_eq!(&mask[..8], &[1.0; 8]);
+        assert_eq!(&mask[8..], &[0.0; 8]);
+    }
+
+    #[test]
+    fn diagonal_wipe_reveals_corner_first() {
+        let wipe = Transition::Wipe {
+            direction: Direction::DownRight,
+            feather: 0.0,
+        };
+        let mask = mask_4x4(wipe, 0.2);
+        assert_eq!(mask[0], 1.0);
+        assert_eq!(mask[15], 0.0);
+    }
+
+    #[test]
+    fn feathered_wipe_ramps() {
+        let wipe = Transition::Wipe {
+            direction: Direction::Right,
+            feather: 1.0,
+        };
+        let mask = mask_4x4(wipe, 0.5);
+        assert!(mask[0] > mask[1] && mask[1] > mask[2] && mask[2] > mask[3]);
+        assert!(mask.iter().all(|m| (0.0..=1.0).contains(m)));
+    }
+
+    #[test]
+    fn iris_reveals_center_first() {
+        let mask = mask_4x4(Transition::Iris { feather: 0.0 }, 0.5);
+        assert_eq!(mask[5], 1.0);
+        assert_eq!(mask[10], 1.0);
+        assert_eq!(mask[0], 0.0);
+        assert_eq!(mask[15], 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of width")]
+    fn fill_mask_panics_on_bad_width() {
+        Transition::Fade.fill_mask(&mut [0.0; 5], 2, 0.5);
+    }
+}
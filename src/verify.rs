@@ -0,0 +1,57 @@
+//! [Kani] verification harnesses.
+//!
+//! Each harness is a bounded proof over the _whole_ input domain of a conversion or kernel,
+//! rather than a sample of it: `kani::any()` stands for every possible value at once. Run them
+//! with `cargo kani` (or `cargo just verify`); this module is only compiled under `cfg(kani)`.
+//!
+//! [Kani]: https://model-checking.github.io/kani/
+
+use crate::rgba::{F32x4Rgba, U8x4Rgba};
+
+fn any_u8x4() -> U8x4Rgba {
+    U8x4Rgba::new(kani::any(), kani::any(), kani::any(), kani::any())
+}
+
+/// Every `u8` color converts to an `f32` color with all channels in `[0.0, 1.0]`.
+#[kani::proof]
+fn u8_to_f32_is_in_range() {
+    let f = F32x4Rgba::from(any_u8x4());
+    for c in [f.r, f.g, f.b, f.a] {
+        assert!((0.0..=1.0).contains(&c));
+    }
+}
+
+/// Converting `u8 → f32 → u8` returns the original color.
+#[kani::proof]
+fn u8_f32_round_trip_is_identity() {
+    let c = any_u8x4();
+    assert_eq!(U8x4Rgba::from(F32x4Rgba::from(c)), c);
+}
+
+/// Converting any `f32` color to `u8`, including `NaN`, infinities and out-of-range values,
+/// never panics.
+#[kani::proof]
+fn f32_to_u8_never_panics() {
+    let f = F32x4Rgba::new(kani::any(), kani::any(), kani::any(), kani::any());
+    let _ = U8x4Rgba::from(f);
+}
+
+/// Converting an in-range `f32` channel to `u8` rounds to the nearest value.
+#[kani::proof]
+fn f32_to_u8_rounds_to_nearest() {
+    let v: f32 = kani::any();
+    kani::assume((0.0..=1.0).contains(&v));
+    let u = U8x4Rgba::from(F32x4Rgba::new(v, v, v, v)).r;
+    assert!((f32::from(u) - v * 255.0).abs() <= 0.5);
+}
+
+/// The `u8` `SourceOver` kernel never overflows, and never makes a pixel more transparent than
+/// either input.
+#[kani::proof]
+fn u8_source_over_is_in_range() {
+    let src = any_u8x4();
+    let dst = any_u8x4();
+    let out = src.source_over(dst);
+    assert!(out.a >= src.a);
+    assert!(out.a >= dst.a);
+}
@@ -0,0 +1,250 @@
+//! YCbCr conversions for compositing video frames.
+//!
+//! Decoded video is usually luma and chroma ([`YCbCrA`]) rather than RGB. The functions here
+//! convert between the two with the [`Standard`] BT.601 (SD) or BT.709 (HD) coefficients, in
+//! [`Range::Full`] (JPEG) or [`Range::Limited`] (broadcast, "TV") range, so frames can be blended
+//! as [`F32x4Rgba`] and converted back. As everywhere else in the crate, components are
+//! normalized: an 8-bit sample `v` is `v / 255`, so the neutral chroma is `128 / 255`.
+//!
+//! Alpha is not part of either standard, and passes through unchanged.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use alpha_blend::{
+//!     rgba::F32x4Rgba,
+//!     ycbcr::{self, Range, Standard},
+//! };
+//!
+//! let black = ycbcr::from_rgba(F32x4Rgba::BLACK, Standard::Bt709, Range::Limited);
+//! assert_eq!(black.y, 16.0 / 255.0);
+//! assert_eq!(black.cb, 128.0 / 255.0);
+//!
+//! let back = ycbcr::to_rgba(black, Standard::Bt709, Range::Limited);
+//! assert!(back.r.abs() < 1e-6);
+//! ```
+
+use crate::{math, rgba::F32x4Rgba};
+
+/// A color as luma, blue- and red-difference chroma, and straight alpha, each normalized to
+/// `[0.0, 1.0]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct YCbCrA {
+    /// Luma.
+    pub y: f32,
+
+    /// Blue-difference chroma, neutral at `128 / 255`.
+    pub cb: f32,
+
+    /// Red-difference chroma, neutral at `128 / 255`.
+    pub cr: f32,
+
+    /// Straight alpha.
+    pub a: f32,
+}
+
+/// The luma coefficients of a YCbCr standard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Standard {
+    /// ITU-R BT.601, for standard-definition video and JPEG.
+    #[default]
+    Bt601,
+
+    /// ITU-R BT.709, for high-definition video.
+    Bt709,
+}
+
+impl Standard {
+    /// Returns the red and blue luma coefficients, `(Kr, Kb)`.
+    #[must_use]
+    pub const fn coefficients(self) -> (f32, f32) {
+        match self {
+            Self::Bt601 => (0.299, 0.114),
+            Self::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+/// The range of encoded luma and chroma values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Range {
+    /// Luma and chroma use all of `[0, 255]`, as in JPEG.
+    #[default]
+    Full,
+
+    /// Luma uses `[16, 235]` and chroma `[16, 240]`, as in broadcast video.
+    Limited,
+}
+
+/// Neutral chroma.
+const CHROMA_ZERO: f32 = 128.0 / 255.0;
+
+impl Range {
+    /// Returns the offset and scale of luma, and the scale of chroma.
+    const fn scales(self) -> (f32, f32, f32) {
+        match self {
+            Self::Full => (0.0, 1.0, 1.0),
+            Self::Limited => (16.0 / 255.0, 219.0 / 255.0, 224.0 / 255.0),
+        }
+    }
+}
+
+/// Converts an RGB color to YCbCr, keeping alpha.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn from_rgba(color: F32x4Rgba, standard: Standard, range: Range) -> YCbCrA {
+    let (kr, kb) = standard.coefficients();
+    let (y_offset, y_scale, c_scale) = range.scales();
+    let F32x4Rgba { r, g, b, a } = color;
+    let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+    let cb = (b - y) / (2.0 * (1.0 - kb));
+    let cr = (r - y) / (2.0 * (1.0 - kr));
+    YCbCrA {
+        y: y_offset + y * y_scale,
+        cb: CHROMA_ZERO + cb * c_scale,
+        cr: CHROMA_ZERO + cr * c_scale,
+        a,
+    }
+}
+
+/// Converts a YCbCr color to RGB, keeping alpha.
+///
+/// Combinations of luma and chroma outside the RGB cube are clamped to `[0.0, 1.0]`.
+#[must_use]
+#[allow(clippy::suboptimal_flops)]
+pub fn to_rgba(color: YCbCrA, standard: Standard, range: Range) -> F32x4Rgba {
+    let (kr, kb) = standard.coefficients();
+    let (y_offset, y_scale, c_scale) = range.scales();
+    let y = (color.y - y_offset) / y_scale;
+    let cb = (color.cb - CHROMA_ZERO) / c_scale;
+    let cr = (color.cr - CHROMA_ZERO) / c_scale;
+    let r = y + 2.0 * (1.0 - kr) * cr;
+    let b = y + 2.0 * (1.0 - kb) * cb;
+    let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+    F32x4Rgba::new(
+        math::clamp01(r),
+        math::clamp01(g),
+        math::clamp01(b),
+        color.a,
+    )
+}
+
+/// Converts every color in `src` to YCbCr, writing them to `dst`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn from_rgba_slice(src: &[F32x4Rgba], dst: &mut [YCbCrA], standard: Standard, range: Range) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = from_rgba(*s, standard, range);
+    }
+}
+
+/// Converts every color in `src` to RGB, writing them to `dst`.
+///
+/// ## Panics
+///
+/// If `src` and `dst` do not have the same length.
+pub fn to_rgba_slice(src: &[YCbCrA], dst: &mut [F32x4Rgba], standard: Standard, range: Range) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "src and dst slices must have the same length"
+    );
+    for (s, d) in src.iter().zip(dst) {
+        *d = to_rgba(*s, standard, range);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    const STANDARDS: [Standard; 2] = [Standard::Bt601, Standard::Bt709];
+    const RANGES: [Range; 2] = [Range::Full, Range::Limited];
+
+    #[test]
+    fn luma_of_primaries() {
+        let red = F32x4Rgba::new(1.0, 0.0, 0.0, 1.0);
+        assert!((from_rgba(red, Standard::Bt601, Range::Full).y - 0.299).abs() < 1e-6);
+        assert!((from_rgba(red, Standard::Bt709, Range::Full).y - 0.2126).abs() < 1e-6);
+
+        let blue = from_rgba(
+            F32x4Rgba::new(0.0, 0.0, 1.0, 1.0),
+            Standard::Bt601,
+            Range::Full,
+        );
+        assert!((blue.cb - (CHROMA_ZERO + 0.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn grays_have_neutral_chroma() {
+        for standard in STANDARDS {
+            for range in RANGES {
+                let white = from_rgba(F32x4Rgba::WHITE, standard, range);
+                assert!((white.cb - CHROMA_ZERO).abs() < 1e-6);
+                assert!((white.cr - CHROMA_ZERO).abs() < 1e-6);
+            }
+        }
+        let white = from_rgba(F32x4Rgba::WHITE, Standard::Bt709, Range::Limited);
+        assert!((white.y - 235.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn round_trips() {
+        for standard in STANDARDS {
+            for range in RANGES {
+                for i in 0..=16_u8 {
+                    let t = f32::from(i) / 16.0;
+                    let color = F32x4Rgba::new(t, 1.0 - t, (t * 3.0) % 1.0, t);
+                    assert_close(
+                        to_rgba(from_rgba(color, standard, range), standard, range),
+                        color,
+                        1e-5,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn to_rgba_clamps() {
+        let out = to_rgba(
+            YCbCrA {
+                y: 1.0,
+                cb: 1.0,
+                cr: 1.0,
+                a: 1.0,
+            },
+            Standard::Bt601,
+            Range::Full,
+        );
+        assert_eq!((out.r, out.b), (1.0, 1.0));
+    }
+
+    #[test]
+    fn slices_match_scalar() {
+        let rgba = [
+            F32x4Rgba::new(0.1, 0.2, 0.3, 0.4),
+            F32x4Rgba::new(0.9, 0.5, 0.0, 1.0),
+        ];
+        let mut ycbcr = [YCbCrA::default(); 2];
+        from_rgba_slice(&rgba, &mut ycbcr, Standard::Bt709, Range::Limited);
+        assert_eq!(
+            ycbcr[1],
+            from_rgba(rgba[1], Standard::Bt709, Range::Limited)
+        );
+
+        let mut back = [F32x4Rgba::TRANSPARENT; 2];
+        to_rgba_slice(&ycbcr, &mut back, Standard::Bt709, Range::Limited);
+        assert_close(back[0], rgba[0], 1e-5);
+        assert_close(back[1], rgba[1], 1e-5);
+    }
+}